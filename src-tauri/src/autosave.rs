@@ -0,0 +1,548 @@
+//! Autosave with debounced snapshot commits
+//!
+//! Story edits are written to the database as soon as they arrive, but
+//! committing every keystroke-level save would flood the history. The
+//! `AutosaveService` remembers which stories have uncommitted edits and
+//! commits them once the writer goes idle, once enough words have changed,
+//! or when the app exits. With autosave disabled the edits are still
+//! remembered, but only committed when asked to (`flush_autosave`, or an
+//! operation that needs them committed first).
+
+use crate::db::Database;
+use crate::file_management::{sync_story_file, FileManagementError};
+use crate::models::Story;
+use crate::repositories::StoryRepository;
+use crate::word_diff::count_words;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+/// How often the background worker looks for idle stories
+const WORKER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Error type for autosave operations
+#[derive(Debug)]
+pub enum AutosaveError {
+    /// Loading the story from the database failed
+    Database(rusqlite::Error),
+    /// Writing or committing the story file failed
+    File(FileManagementError),
+}
+
+impl fmt::Display for AutosaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutosaveError::Database(e) => write!(f, "Database error: {e}"),
+            AutosaveError::File(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for AutosaveError {}
+
+impl From<rusqlite::Error> for AutosaveError {
+    fn from(err: rusqlite::Error) -> Self {
+        AutosaveError::Database(err)
+    }
+}
+
+impl From<FileManagementError> for AutosaveError {
+    fn from(err: FileManagementError) -> Self {
+        AutosaveError::File(err)
+    }
+}
+
+/// Result type alias for autosave operations
+pub type AutosaveResult<T> = Result<T, AutosaveError>;
+
+/// When pending edits get committed
+///
+/// The default only commits when the editor closes, like the frontend's
+/// default settings, so nothing else is committed before the app sends the
+/// user's own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct AutosaveConfig {
+    /// Whether edits are committed automatically at all (false = only on flush)
+    pub enabled: bool,
+    /// Commit after this many milliseconds without edits (0 = only on flush)
+    pub idle_ms: u32,
+    /// Commit once the word count moved this far from the last commit (0 = never)
+    pub max_words: u32,
+}
+
+impl Default for AutosaveConfig {
+    fn default() -> Self {
+        AutosaveConfig {
+            enabled: true,
+            idle_ms: 0,
+            max_words: 0,
+        }
+    }
+}
+
+/// Uncommitted edits to one story
+#[derive(Debug, Clone)]
+struct PendingEdit {
+    /// Repository the story file lives in
+    repo_path: PathBuf,
+    /// Filename of the story in the last commit
+    filename: String,
+    /// Word count in the last commit
+    base_words: u32,
+    /// Word count of the latest edit
+    words: u32,
+    /// When the latest edit arrived
+    last_edit: Instant,
+}
+
+/// Tracks uncommitted story edits and commits them in batches
+///
+/// Managed as Tauri state; `update_story` feeds it and a background worker
+/// started by `spawn_worker` commits stories that went idle.
+#[derive(Default)]
+pub struct AutosaveService {
+    config: Mutex<AutosaveConfig>,
+    pending: Mutex<HashMap<String, PendingEdit>>,
+}
+
+impl AutosaveService {
+    /// Get the current configuration
+    pub fn config(&self) -> AutosaveConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Replace the configuration
+    ///
+    /// Takes effect on the next edit or worker tick.
+    pub fn set_config(&self, config: AutosaveConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    /// Record an edit that has already been saved to the database
+    ///
+    /// # Arguments
+    /// * `story` - The story after the edit
+    /// * `repo_path` - Repository the story file lives in
+    /// * `filename` - Filename of the story in the repository
+    ///
+    /// # Returns
+    /// True if the edit volume threshold was reached and the story should be
+    /// flushed now; never while autosave is disabled
+    pub fn record_edit(&self, story: &Story, repo_path: &Path, filename: &str) -> bool {
+        let config = self.config();
+        let mut pending = self.pending.lock().unwrap();
+        let edit = pending.entry(story.id.clone()).or_insert_with(|| {
+            // The working tree only changes on commit, so the file on disk
+            // is the last committed version
+            let committed = fs::read_to_string(repo_path.join(filename)).unwrap_or_default();
+            let base_words = count_words(&committed);
+            PendingEdit {
+                repo_path: repo_path.to_path_buf(),
+                filename: filename.to_string(),
+                base_words,
+                words: base_words,
+                last_edit: Instant::now(),
+            }
+        });
+
+        edit.words = count_words(&story.content);
+        edit.last_edit = Instant::now();
+
+        config.enabled
+            && config.max_words > 0
+            && edit.words.abs_diff(edit.base_words) >= config.max_words
+    }
+
+    /// Forget a story's uncommitted edits without committing them
+    ///
+    /// Used when the story is deleted, or when another operation is about to
+    /// commit the story file anyway.
+    pub fn discard(&self, story_id: &str) {
+        self.pending.lock().unwrap().remove(story_id);
+    }
+
+    /// Get the stories whose last edit is at least the idle period before `now`
+    ///
+    /// None are due while autosave is disabled.
+    pub fn due(&self, now: Instant) -> Vec<String> {
+        let config = self.config();
+        if !config.enabled || config.idle_ms == 0 {
+            return Vec::new();
+        }
+        let idle = Duration::from_millis(u64::from(config.idle_ms));
+
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, edit)| now.saturating_duration_since(edit.last_edit) >= idle)
+            .map(|(story_id, _)| story_id.clone())
+            .collect()
+    }
+
+    /// Commit a story's pending edits
+    ///
+    /// The content is read back from the database, so the commit always
+    /// contains the latest save.
+    ///
+    /// # Arguments
+    /// * `db` - Database holding the story
+    /// * `story_id` - ID of the story to commit
+    ///
+    /// # Returns
+    /// The commit hash, or None if there was nothing to commit
+    ///
+    /// # Errors
+    /// Returns an error if the story cannot be loaded or committed. The edits
+    /// are dropped from the pending set either way; the content is still safe
+    /// in the database and goes into the next commit.
+    pub fn flush(&self, db: &Database, story_id: &str) -> AutosaveResult<Option<String>> {
+        let Some(edit) = self.pending.lock().unwrap().remove(story_id) else {
+            return Ok(None);
+        };
+
+        let story = StoryRepository::find_by_id(db, story_id)?;
+        let words = count_words(&story.content);
+        let message = autosave_message(edit.base_words, words, &story.title);
+
+        Ok(sync_story_file(
+            &edit.repo_path,
+            Some(&edit.filename),
+            &story,
+            Some(&message),
+        )?)
+    }
+
+    /// Commit every story that went idle
+    pub fn flush_due(&self, db: &Database) {
+        for story_id in self.due(Instant::now()) {
+            if let Err(e) = self.flush(db, &story_id) {
+                warn!("Autosave commit failed for story {story_id}: {e}");
+            }
+        }
+    }
+
+    /// Commit the pending edits of stories that are about to be trashed
+    ///
+    /// Errors are logged; the edits are dropped either way and the content
+    /// is still safe in the database.
+    pub fn flush_before_trash<'a>(
+        &self,
+        db: &Database,
+        story_ids: impl IntoIterator<Item = &'a str>,
+    ) {
+        for story_id in story_ids {
+            if let Err(e) = self.flush(db, story_id) {
+                warn!("Failed to commit pending edits for {story_id} before trashing: {e}");
+            }
+        }
+    }
+
+    /// Commit every story with pending edits
+    ///
+    /// Errors are logged and do not stop the remaining stories from being
+    /// committed.
+    ///
+    /// # Returns
+    /// The number of commits made
+    pub fn flush_all(&self, db: &Database) -> usize {
+        let story_ids: Vec<String> = self.pending.lock().unwrap().keys().cloned().collect();
+
+        story_ids
+            .iter()
+            .filter(|story_id| match self.flush(db, story_id) {
+                Ok(commit) => commit.is_some(),
+                Err(e) => {
+                    warn!("Autosave commit failed for story {story_id}: {e}");
+                    false
+                }
+            })
+            .count()
+    }
+}
+
+/// Build an autosave commit message, e.g. "Autosave: +312 words in Chapter 3"
+fn autosave_message(base_words: u32, words: u32, title: &str) -> String {
+    let delta = i64::from(words) - i64::from(base_words);
+    match delta {
+        0 => format!("Autosave: edits in {title}"),
+        1 => format!("Autosave: +1 word in {title}"),
+        -1 => format!("Autosave: -1 word in {title}"),
+        d if d > 0 => format!("Autosave: +{d} words in {title}"),
+        d => format!("Autosave: {d} words in {title}"),
+    }
+}
+
+/// Start the background thread that commits idle stories
+///
+/// Expects `Database` and `AutosaveService` to be managed by the app.
+pub fn spawn_worker(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WORKER_POLL_INTERVAL);
+        let db = app.state::<Database>();
+        app.state::<AutosaveService>().flush_due(&db);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::GitService;
+    use crate::models::{CreateStoryInput, StoryType, UpdateStoryInput};
+    use tempfile::TempDir;
+
+    struct Fixture {
+        db: Database,
+        story: Story,
+        repo_path: PathBuf,
+        _temp_dir: TempDir,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status) VALUES (?, ?, ?, ?, ?, ?)",
+            &[&"universe-1", &"Test Universe", &"Test", &"2024-01-01T00:00:00Z", &"2024-01-01T00:00:00Z", &"active"],
+        )
+        .unwrap();
+
+        let story = StoryRepository::create(
+            &db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: "Chapter 3".to_string(),
+                description: Some("Test".to_string()),
+                story_type: Some(StoryType::Chapter),
+                content: None,
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: None,
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), &story.id).unwrap();
+
+        Fixture {
+            db,
+            story,
+            repo_path,
+            _temp_dir: temp_dir,
+        }
+    }
+
+    /// Save content to the database and record the edit, like update_story
+    fn service_with(config: AutosaveConfig) -> AutosaveService {
+        let service = AutosaveService::default();
+        service.set_config(config);
+        service
+    }
+
+    fn edit(fixture: &Fixture, service: &AutosaveService, content: &str) -> bool {
+        let story = StoryRepository::update(
+            &fixture.db,
+            &fixture.story.id,
+            UpdateStoryInput {
+                title: None,
+                description: None,
+                story_type: None,
+                status: None,
+                content: Some(content.to_string()),
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                order: None,
+                tags: None,
+                color: None,
+                favorite: None,
+                related_element_ids: None,
+                series_name: None,
+            },
+        )
+        .unwrap();
+        service.record_edit(&story, &fixture.repo_path, "content.md")
+    }
+
+    #[test]
+    fn test_edits_are_coalesced_into_one_commit() {
+        let fixture = setup();
+        let service = AutosaveService::default();
+        let before = GitService::get_history(&fixture.repo_path, "original")
+            .unwrap()
+            .len();
+
+        assert!(!edit(&fixture, &service, "One"));
+        assert!(!edit(&fixture, &service, "One two"));
+        assert!(!edit(&fixture, &service, "One two three"));
+        assert!(service
+            .pending
+            .lock()
+            .unwrap()
+            .contains_key(&fixture.story.id));
+
+        let commit = service.flush(&fixture.db, &fixture.story.id).unwrap();
+
+        assert!(commit.is_some());
+        assert!(!service
+            .pending
+            .lock()
+            .unwrap()
+            .contains_key(&fixture.story.id));
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history.len(), before + 1);
+        assert_eq!(history[0].message, "Autosave: +3 words in Chapter 3");
+        assert_eq!(
+            fs::read_to_string(fixture.repo_path.join("content.md")).unwrap(),
+            "One two three"
+        );
+    }
+
+    #[test]
+    fn test_word_delta_is_relative_to_last_commit() {
+        let fixture = setup();
+        let service = AutosaveService::default();
+
+        edit(&fixture, &service, "one two three four five");
+        service.flush(&fixture.db, &fixture.story.id).unwrap();
+        edit(&fixture, &service, "one two");
+        service.flush(&fixture.db, &fixture.story.id).unwrap();
+
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history[0].message, "Autosave: -3 words in Chapter 3");
+    }
+
+    #[test]
+    fn test_edit_volume_threshold_requests_flush() {
+        let fixture = setup();
+        let service = service_with(AutosaveConfig {
+            max_words: 3,
+            ..AutosaveConfig::default()
+        });
+
+        assert!(!edit(&fixture, &service, "one two"));
+        assert!(edit(&fixture, &service, "one two three"));
+    }
+
+    #[test]
+    fn test_due_respects_idle_period() {
+        let fixture = setup();
+        let service = service_with(AutosaveConfig {
+            idle_ms: 1_000,
+            ..AutosaveConfig::default()
+        });
+
+        edit(&fixture, &service, "Some words");
+
+        assert!(service.due(Instant::now()).is_empty());
+        let later = Instant::now() + Duration::from_secs(2);
+        assert_eq!(service.due(later), vec![fixture.story.id.clone()]);
+
+        // An idle period of zero disables idle commits
+        service.set_config(AutosaveConfig {
+            idle_ms: 0,
+            ..AutosaveConfig::default()
+        });
+        assert!(service.due(later).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_service_only_commits_on_flush() {
+        let fixture = setup();
+        let service = service_with(AutosaveConfig {
+            enabled: false,
+            idle_ms: 1_000,
+            max_words: 2,
+        });
+        let before = GitService::get_history(&fixture.repo_path, "original")
+            .unwrap()
+            .len();
+
+        assert!(!edit(&fixture, &service, "Some more words"));
+        let later = Instant::now() + Duration::from_secs(2);
+        assert!(service.due(later).is_empty());
+        assert_eq!(
+            GitService::get_history(&fixture.repo_path, "original")
+                .unwrap()
+                .len(),
+            before
+        );
+
+        assert!(service
+            .flush(&fixture.db, &fixture.story.id)
+            .unwrap()
+            .is_some());
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history.len(), before + 1);
+        assert_eq!(history[0].message, "Autosave: +3 words in Chapter 3");
+    }
+
+    #[test]
+    fn test_flush_before_trash_commits_and_forgets_edits() {
+        let fixture = setup();
+        let service = AutosaveService::default();
+
+        edit(&fixture, &service, "Last words before deleting");
+        service.flush_before_trash(&fixture.db, [fixture.story.id.as_str()]);
+
+        assert!(!service
+            .pending
+            .lock()
+            .unwrap()
+            .contains_key(&fixture.story.id));
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history[0].message, "Autosave: +4 words in Chapter 3");
+    }
+
+    #[test]
+    fn test_flush_all_commits_pending_stories() {
+        let fixture = setup();
+        let service = AutosaveService::default();
+
+        edit(&fixture, &service, "Unsaved work before exit");
+
+        assert_eq!(service.flush_all(&fixture.db), 1);
+        assert_eq!(service.flush_all(&fixture.db), 0);
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history[0].message, "Autosave: +4 words in Chapter 3");
+    }
+
+    #[test]
+    fn test_autosave_message_wording() {
+        assert_eq!(
+            autosave_message(10, 10, "Prologue"),
+            "Autosave: edits in Prologue"
+        );
+        assert_eq!(
+            autosave_message(10, 11, "Prologue"),
+            "Autosave: +1 word in Prologue"
+        );
+        assert_eq!(
+            autosave_message(10, 9, "Prologue"),
+            "Autosave: -1 word in Prologue"
+        );
+        assert_eq!(
+            autosave_message(0, 312, "Chapter 3"),
+            "Autosave: +312 words in Chapter 3"
+        );
+    }
+}
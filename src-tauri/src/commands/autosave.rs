@@ -0,0 +1,44 @@
+use crate::autosave::{AutosaveConfig, AutosaveService};
+use crate::commands::error::{CommandError, CommandResult};
+use crate::db::Database;
+use tauri::State;
+
+/// Get the autosave configuration
+#[tauri::command]
+pub fn get_autosave_config(autosave: State<AutosaveService>) -> AutosaveConfig {
+    autosave.config()
+}
+
+/// Set when autosave commits pending edits
+///
+/// The frontend pushes the user's auto-commit settings here on startup and
+/// whenever they change.
+#[tauri::command]
+pub fn set_autosave_config(autosave: State<AutosaveService>, config: AutosaveConfig) {
+    autosave.set_config(config);
+}
+
+/// Commit pending autosave edits now
+///
+/// Call this before operations that need a clean working tree (switching
+/// variations, merging) or when the editor closes.
+///
+/// # Arguments
+/// * `story_id` - Story to commit, or None to commit every pending story
+///
+/// # Returns
+/// The number of commits made
+#[tauri::command]
+pub fn flush_autosave(
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    story_id: Option<String>,
+) -> CommandResult<usize> {
+    match story_id {
+        Some(story_id) => autosave
+            .flush(&db, &story_id)
+            .map(|commit| usize::from(commit.is_some()))
+            .map_err(CommandError::from),
+        None => Ok(autosave.flush_all(&db)),
+    }
+}
@@ -1,7 +1,16 @@
+use crate::autosave::AutosaveService;
+use crate::commands::error::{CommandError, CommandResult, ErrorCode};
+use crate::commands::trash::app_data_dir;
 use crate::db::Database;
+use crate::duplicate::{self, HistoryMode};
+use crate::file_management::write_container_metadata_file;
 use crate::git::GitService;
-use crate::models::{Container, ContainerChildren, CreateContainerInput, UpdateContainerInput};
+use crate::models::{
+    Container, ContainerChildren, ContainerStats, CreateContainerInput, UpdateContainerInput,
+};
 use crate::repositories::{ContainerRepository, StoryRepository};
+use crate::trash::TrashEntityType;
+use crate::undo::{self, UndoOperation, UndoStep};
 use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
@@ -9,21 +18,30 @@ pub fn create_container(
     _app: AppHandle,
     db: State<Database>,
     input: CreateContainerInput,
-) -> Result<Container, String> {
+) -> CommandResult<Container> {
     // Validate inputs
     let trimmed_title = input.title.trim();
     if trimmed_title.is_empty() {
-        return Err("Title cannot be empty".to_string());
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            "Title cannot be empty",
+        ));
     }
     if input.title.len() > 255 {
-        return Err("Title too long (max 255 characters)".to_string());
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            "Title too long (max 255 characters)",
+        ));
     }
 
     const VALID_TYPES: &[&str] = &["novel", "series", "collection"];
     if !VALID_TYPES.contains(&input.container_type.as_str()) {
-        return Err(format!(
-            "Invalid container type: {}. Must be one of: novel, series, collection",
-            input.container_type
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!(
+                "Invalid container type: {}. Must be one of: novel, series, collection",
+                input.container_type
+            ),
         ));
     }
 
@@ -36,8 +54,7 @@ pub fn create_container(
         input.title,
         input.description,
         input.order.unwrap_or(0),
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     // If this is a leaf container (has no parent or will contain stories),
     // initialize a git repository for it
@@ -49,27 +66,25 @@ pub fn create_container(
 }
 
 #[tauri::command]
-pub fn get_container(db: State<Database>, id: String) -> Result<Container, String> {
-    ContainerRepository::find_by_id(&db, &id).map_err(|e| e.to_string())
+pub fn get_container(db: State<Database>, id: String) -> CommandResult<Container> {
+    ContainerRepository::find_by_id(&db, &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn list_containers(db: State<Database>, universe_id: String) -> Result<Vec<Container>, String> {
-    ContainerRepository::list_by_universe(&db, &universe_id).map_err(|e| e.to_string())
+pub fn list_containers(db: State<Database>, universe_id: String) -> CommandResult<Vec<Container>> {
+    ContainerRepository::list_by_universe(&db, &universe_id).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn list_container_children(
     db: State<Database>,
     container_id: String,
-) -> Result<ContainerChildren, String> {
+) -> CommandResult<ContainerChildren> {
     // Get child containers
-    let containers =
-        ContainerRepository::list_children(&db, &container_id).map_err(|e| e.to_string())?;
+    let containers = ContainerRepository::list_children(&db, &container_id)?;
 
     // Get stories in this container
-    let stories =
-        StoryRepository::list_by_container(&db, &container_id).map_err(|e| e.to_string())?;
+    let stories = StoryRepository::list_by_container(&db, &container_id)?;
 
     Ok(ContainerChildren {
         containers,
@@ -77,41 +92,226 @@ pub fn list_container_children(
     })
 }
 
+/// Get word counts and other totals for a container and each container below it
+#[tauri::command]
+pub fn get_container_stats(
+    db: State<Database>,
+    container_id: String,
+) -> CommandResult<Vec<ContainerStats>> {
+    ContainerRepository::get_subtree_stats(&db, &container_id).map_err(CommandError::from)
+}
+
+/// Update a container, refreshing the metadata.json of leaf containers
+/// that already have a git repository
 #[tauri::command]
 pub fn update_container(
     db: State<Database>,
     id: String,
     input: UpdateContainerInput,
-) -> Result<Container, String> {
-    ContainerRepository::update(
+) -> CommandResult<Container> {
+    let container = ContainerRepository::update(
         &db,
         &id,
         input.title,
         input.description,
         input.container_type,
         input.order,
-    )
-    .map_err(|e| e.to_string())
+    )?;
+
+    if let Some(ref git_repo_path) = container.git_repo_path {
+        let path = std::path::Path::new(git_repo_path);
+        if !git_repo_path.is_empty() && path.exists() {
+            write_container_metadata_file(path, &container).map_err(|e| {
+                CommandError::from(e).context("Container saved but metadata not updated")
+            })?;
+        }
+    }
+
+    Ok(container)
 }
 
+/// Move a container and everything in it to the trash
+///
+/// Pending autosave edits of the stories inside are committed first. The
+/// whole subtree is one undoable change.
+///
+/// # Returns
+/// The IDs of the container and every container below it
 #[tauri::command]
 pub fn delete_container(
-    _app: AppHandle,
+    app: AppHandle,
     db: State<Database>,
+    autosave: State<AutosaveService>,
     id: String,
-) -> Result<Vec<String>, String> {
-    // Delete the container and all its children
-    // The repository handles cascade deletion and git repo cleanup
-    ContainerRepository::delete(&db, &id).map_err(|e| e.to_string())
+) -> CommandResult<Vec<String>> {
+    let container = ContainerRepository::find_by_id(&db, &id)?;
+    let container_ids: Vec<String> = ContainerRepository::get_subtree(&db, &id, None)?
+        .into_iter()
+        .map(|container| container.id)
+        .collect();
+
+    let mut story_ids = Vec::new();
+    for container_id in &container_ids {
+        story_ids.extend(
+            StoryRepository::list_by_container(&db, container_id)?
+                .into_iter()
+                .map(|story| story.id),
+        );
+    }
+    autosave.flush_before_trash(&db, story_ids.iter().map(String::as_str));
+
+    undo::perform(
+        &db,
+        &app_data_dir(&app)?,
+        &container.universe_id,
+        &format!("Delete {}", container.title),
+        vec![UndoStep::new(
+            UndoOperation::Trash {
+                entity_type: TrashEntityType::Container,
+                id: id.clone(),
+            },
+            UndoOperation::Restore {
+                entity_type: TrashEntityType::Container,
+                id,
+            },
+        )],
+    )?;
+
+    Ok(container_ids)
 }
 
+/// Reorder a container's child containers and stories
+///
+/// Story files in the container's repository are renamed to match their
+/// new positions. Both reorders are one undoable change.
+///
+/// # Arguments
+/// * `container_id` - Parent container
+/// * `container_ids` - Child containers in their new order (may be empty)
+/// * `story_ids` - Stories in their new order (may be empty)
 #[tauri::command]
 pub fn reorder_container_children(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    container_id: String,
+    container_ids: Vec<String>,
+    story_ids: Vec<String>,
+) -> CommandResult<()> {
+    let container = ContainerRepository::find_by_id(&db, &container_id)?;
+    let mut steps = Vec::new();
+
+    if !container_ids.is_empty() {
+        let previous = ContainerRepository::list_children(&db, &container_id)?
+            .into_iter()
+            .map(|child| child.id)
+            .collect();
+        steps.push(UndoStep::new(
+            UndoOperation::ReorderContainers {
+                parent_id: container_id.clone(),
+                container_ids,
+            },
+            UndoOperation::ReorderContainers {
+                parent_id: container_id.clone(),
+                container_ids: previous,
+            },
+        ));
+    }
+    if !story_ids.is_empty() {
+        // Renames would leave pending edits pointing at the old filenames
+        autosave.flush_all(&db);
+        let previous = StoryRepository::list_by_container(&db, &container_id)?
+            .into_iter()
+            .map(|story| story.id)
+            .collect();
+        steps.push(UndoStep::new(
+            UndoOperation::ReorderStories {
+                container_id: container_id.clone(),
+                story_ids,
+            },
+            UndoOperation::ReorderStories {
+                container_id: container_id.clone(),
+                story_ids: previous,
+            },
+        ));
+    }
+    if steps.is_empty() {
+        return Ok(());
+    }
+
+    undo::perform(
+        &db,
+        &app_data_dir(&app)?,
+        &container.universe_id,
+        &format!("Reorder {}", container.title),
+        steps,
+    )
+    .map_err(CommandError::from)
+}
+
+/// Move a container, with everything below it, to a new parent
+///
+/// The nesting depth limit and leaf rules apply as when creating a
+/// container. Moving to another universe takes all containers and stories
+/// below along; those stories lose their related elements. The move can
+/// be undone from the universe the container came from.
+///
+/// # Arguments
+/// * `parent_container_id` - New parent, or None for a top-level container
+/// * `universe_id` - Destination universe (defaults to the parent's, or the
+///   container's current universe)
+#[tauri::command]
+pub fn move_container(
+    app: AppHandle,
     db: State<Database>,
-    parent_id: String,
-    child_ids: Vec<String>,
-) -> Result<(), String> {
-    ContainerRepository::reorder_children(&db, &parent_id, child_ids).map_err(|e| e.to_string())
+    id: String,
+    parent_container_id: Option<String>,
+    universe_id: Option<String>,
+) -> CommandResult<Container> {
+    let container = ContainerRepository::find_by_id(&db, &id)?;
+
+    undo::perform(
+        &db,
+        &app_data_dir(&app)?,
+        &container.universe_id,
+        &format!("Move {}", container.title),
+        vec![UndoStep::new(
+            UndoOperation::MoveContainer {
+                container_id: id.clone(),
+                universe_id,
+                parent_id: parent_container_id,
+                order: None,
+            },
+            UndoOperation::MoveContainer {
+                container_id: id.clone(),
+                universe_id: Some(container.universe_id.clone()),
+                parent_id: container.parent_container_id.clone(),
+                order: Some(container.order),
+            },
+        )],
+    )?;
+
+    ContainerRepository::find_by_id(&db, &id).map_err(CommandError::from)
+}
+
+/// Copy a container with everything below it, after its last sibling
+///
+/// Pending autosave edits are committed first, so the copy has the latest
+/// text.
+///
+/// # Arguments
+/// * `history` - Whether the copied repositories keep their history
+#[tauri::command]
+pub fn duplicate_container(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+    history: HistoryMode,
+) -> CommandResult<Container> {
+    autosave.flush_all(&db);
+    duplicate::duplicate_container(&db, &app_data_dir(&app)?, &id, history)
+        .map_err(CommandError::from)
 }
 
 /// Ensure a container has a git repository initialized.
@@ -129,18 +329,20 @@ pub fn ensure_container_git_repo(
     app: AppHandle,
     db: State<Database>,
     id: String,
-) -> Result<Container, String> {
+) -> CommandResult<Container> {
     // Get the container
-    let container = ContainerRepository::find_by_id(&db, &id).map_err(|e| e.to_string())?;
+    let container = ContainerRepository::find_by_id(&db, &id)?;
 
     // Verify this container can have a git repo (should not have child containers)
-    let child_containers =
-        ContainerRepository::list_children(&db, &id).map_err(|e| e.to_string())?;
+    let child_containers = ContainerRepository::list_children(&db, &id)?;
     if !child_containers.is_empty() {
-        return Err(format!(
-            "Container '{}' has child containers and cannot have its own git repository. \
-             Only leaf containers (those containing stories) should have git repositories.",
-            container.title
+        return Err(CommandError::new(
+            ErrorCode::InvalidOperation,
+            format!(
+                "Container '{}' has child containers and cannot have its own git repository. \
+                 Only leaf containers (those containing stories) should have git repositories.",
+                container.title
+            ),
         ));
     }
 
@@ -150,24 +352,30 @@ pub fn ensure_container_git_repo(
         if path.exists() && path.join(".git").exists() {
             // Validate repository integrity
             if let Err(e) = GitService::validate_repo_integrity(path) {
-                return Err(format!(
-                    "Git repository corruption detected at {}: {}. \
-                     To fix this, you can either manually repair the repository or \
-                     delete it and reinitialize by setting git_repo_path to null and calling this command again.",
-                    git_repo_path, e
-                ));
+                return Err(CommandError::new(
+                    ErrorCode::RepositoryCorrupted,
+                    format!(
+                        "Git repository corruption detected at {}: {}. \
+                         To fix this, you can either manually repair the repository or \
+                         delete it and reinitialize by setting git_repo_path to null and calling this command again.",
+                        git_repo_path, e
+                    ),
+                )
+                .with_detail("path", git_repo_path.clone()));
             }
 
             // Sync the current branch name from the actual repo
             let actual_branch = GitService::get_current_branch(path)
-                .map_err(|e| format!("Failed to get current branch: {e}"))?;
+                .map_err(|e| CommandError::from(e).context("Failed to get current branch"))?;
 
             if let Some(ref current_branch) = container.current_branch {
                 if actual_branch != *current_branch {
                     ContainerRepository::set_current_branch(&db, &container.id, &actual_branch)
-                        .map_err(|e| format!("Failed to update current branch: {e}"))?;
+                        .map_err(|e| {
+                            CommandError::from(e).context("Failed to update current branch")
+                        })?;
                     return ContainerRepository::find_by_id(&db, &container.id)
-                        .map_err(|e| e.to_string());
+                        .map_err(CommandError::from);
                 }
             }
 
@@ -179,11 +387,15 @@ pub fn ensure_container_git_repo(
     let app_data_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+        .map_err(|e| CommandError::from(e).context("Failed to get app data directory"))?;
 
     // STEP 2: Initialize git repository for the container
     let git_repo_path = GitService::init_repo(&app_data_dir, &container.id)
-        .map_err(|e| format!("Failed to initialize git repository: {e}"))?;
+        .map_err(|e| CommandError::from(e).context("Failed to initialize git repository"))?;
+    if let Err(e) = write_container_metadata_file(&git_repo_path, &container) {
+        let _ = std::fs::remove_dir_all(&git_repo_path);
+        return Err(CommandError::from(e).context("Failed to write container metadata"));
+    }
 
     // STEP 3: Update the container with the git repo path
     let git_repo_path_str = git_repo_path.to_string_lossy().to_string();
@@ -192,7 +404,7 @@ pub fn ensure_container_git_repo(
         if git_repo_path.exists() {
             let _ = std::fs::remove_dir_all(&git_repo_path);
         }
-        return Err(format!("Failed to update git repo path: {e}"));
+        return Err(CommandError::from(e).context("Failed to update git repo path"));
     }
 
     // Get current branch from the newly created repo
@@ -202,7 +414,7 @@ pub fn ensure_container_git_repo(
             let _ = std::fs::remove_dir_all(&git_repo_path);
         }
         let _ = ContainerRepository::set_git_repo_path(&db, &container.id, "");
-        format!("Failed to get current branch: {e}")
+        CommandError::from(e).context("Failed to get current branch")
     })?;
 
     // Update the current branch
@@ -212,11 +424,11 @@ pub fn ensure_container_git_repo(
             let _ = std::fs::remove_dir_all(&git_repo_path);
         }
         let _ = ContainerRepository::set_git_repo_path(&db, &container.id, "");
-        return Err(format!("Failed to update current branch: {e}"));
+        return Err(CommandError::from(e).context("Failed to update current branch"));
     }
 
     // Return the updated container
-    ContainerRepository::find_by_id(&db, &container.id).map_err(|e| e.to_string())
+    ContainerRepository::find_by_id(&db, &container.id).map_err(CommandError::from)
 }
 
 /// Check if a container is an empty non-leaf container (edge case detection)
@@ -229,11 +441,8 @@ pub fn ensure_container_git_repo(
 /// This edge case occurs when a container had child containers (became non-leaf),
 /// then all children were deleted, leaving it unable to have stories without git initialization.
 #[tauri::command]
-pub fn check_empty_non_leaf_container(
-    db: State<Database>,
-    id: String,
-) -> Result<bool, String> {
-    ContainerRepository::is_empty_non_leaf(&db, &id).map_err(|e| e.to_string())
+pub fn check_empty_non_leaf_container(db: State<Database>, id: String) -> CommandResult<bool> {
+    ContainerRepository::is_empty_non_leaf(&db, &id).map_err(CommandError::from)
 }
 
 /// Convert an empty non-leaf container to a leaf container by initializing a git repository
@@ -253,29 +462,36 @@ pub fn convert_to_leaf_container(
     app: AppHandle,
     db: State<Database>,
     id: String,
-) -> Result<Container, String> {
+) -> CommandResult<Container> {
     // First check if this is actually an empty non-leaf container
-    let is_empty_non_leaf = ContainerRepository::is_empty_non_leaf(&db, &id)
-        .map_err(|e| e.to_string())?;
+    let is_empty_non_leaf = ContainerRepository::is_empty_non_leaf(&db, &id)?;
 
     if !is_empty_non_leaf {
-        let container = ContainerRepository::find_by_id(&db, &id).map_err(|e| e.to_string())?;
+        let container = ContainerRepository::find_by_id(&db, &id)?;
 
         if container.git_repo_path.is_some() {
-            return Err("Container already has a git repository (is already a leaf container)".to_string());
+            return Err(CommandError::new(
+                ErrorCode::InvalidOperation,
+                "Container already has a git repository (is already a leaf container)",
+            ));
         }
 
-        let child_count = ContainerRepository::get_child_container_count(&db, &id)
-            .map_err(|e| e.to_string())?;
+        let child_count = ContainerRepository::get_child_container_count(&db, &id)?;
         if child_count > 0 {
-            return Err(format!(
-                "Container has {} child containers and cannot be converted to a leaf container. \
-                Only empty containers can be converted.",
-                child_count
+            return Err(CommandError::new(
+                ErrorCode::InvalidOperation,
+                format!(
+                    "Container has {} child containers and cannot be converted to a leaf container. \
+                    Only empty containers can be converted.",
+                    child_count
+                ),
             ));
         }
 
-        return Err("Container does not meet criteria for conversion (unknown state)".to_string());
+        return Err(CommandError::new(
+            ErrorCode::InvalidOperation,
+            "Container does not meet criteria for conversion (unknown state)",
+        ));
     }
 
     // Container is an empty non-leaf, we can safely initialize git repo
@@ -1,24 +1,27 @@
+use crate::commands::error::{CommandError, CommandResult};
+use crate::commands::trash::app_data_dir;
 use crate::db::Database;
 use crate::models::{CreateElementInput, Element, ElementType, UpdateElementInput};
 use crate::repositories::ElementRepository;
-use tauri::State;
+use crate::undo::{self, UndoOperation, UndoStep};
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub fn create_element(db: State<Database>, input: CreateElementInput) -> Result<Element, String> {
-    ElementRepository::create(&db, input).map_err(|e| e.to_string())
+pub fn create_element(db: State<Database>, input: CreateElementInput) -> CommandResult<Element> {
+    ElementRepository::create(&db, input).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn get_element(db: State<Database>, id: String) -> Result<Element, String> {
-    ElementRepository::find_by_id(&db, &id).map_err(|e| e.to_string())
+pub fn get_element(db: State<Database>, id: String) -> CommandResult<Element> {
+    ElementRepository::find_by_id(&db, &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn list_elements_by_universe(
     db: State<Database>,
     universe_id: String,
-) -> Result<Vec<Element>, String> {
-    ElementRepository::list_by_universe(&db, &universe_id).map_err(|e| e.to_string())
+) -> CommandResult<Vec<Element>> {
+    ElementRepository::list_by_universe(&db, &universe_id).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -26,28 +29,75 @@ pub fn list_elements_by_type(
     db: State<Database>,
     universe_id: String,
     element_type: ElementType,
-) -> Result<Vec<Element>, String> {
-    ElementRepository::list_by_type(&db, &universe_id, element_type).map_err(|e| e.to_string())
+) -> CommandResult<Vec<Element>> {
+    ElementRepository::list_by_type(&db, &universe_id, element_type).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn get_related_elements(
     db: State<Database>,
     element_id: String,
-) -> Result<Vec<(String, Element)>, String> {
-    ElementRepository::get_related_elements(&db, &element_id).map_err(|e| e.to_string())
+) -> CommandResult<Vec<(String, Element)>> {
+    ElementRepository::get_related_elements(&db, &element_id).map_err(CommandError::from)
 }
 
+/// Update an element
+///
+/// Changes to its relationships can be undone.
 #[tauri::command]
 pub fn update_element(
     db: State<Database>,
     id: String,
     input: UpdateElementInput,
-) -> Result<Element, String> {
-    ElementRepository::update(&db, &id, input).map_err(|e| e.to_string())
+) -> CommandResult<Element> {
+    let Some(relationships) = input.relationships.clone() else {
+        return ElementRepository::update(&db, &id, input).map_err(CommandError::from);
+    };
+
+    let before = ElementRepository::find_by_id(&db, &id)?;
+    let element = ElementRepository::update(&db, &id, input)?;
+    undo::record(
+        &db,
+        &element.universe_id,
+        &format!("Edit relationships of {}", element.name),
+        &[UndoStep::new(
+            UndoOperation::SetRelationships {
+                element_id: id.clone(),
+                relationships,
+            },
+            UndoOperation::SetRelationships {
+                element_id: id,
+                relationships: before.relationships.unwrap_or_default(),
+            },
+        )],
+    )?;
+
+    Ok(element)
 }
 
+/// Delete an element and its relationships
+///
+/// The deletion can be undone, bringing back relationships from other
+/// elements as well.
 #[tauri::command]
-pub fn delete_element(db: State<Database>, id: String) -> Result<(), String> {
-    ElementRepository::delete(&db, &id).map_err(|e| e.to_string())
+pub fn delete_element(app: AppHandle, db: State<Database>, id: String) -> CommandResult<()> {
+    let element = ElementRepository::find_by_id(&db, &id)?;
+    let incoming = ElementRepository::list_incoming_relationships(&db, &id)?;
+    let universe_id = element.universe_id.clone();
+    let label = format!("Delete {}", element.name);
+
+    undo::perform(
+        &db,
+        &app_data_dir(&app)?,
+        &universe_id,
+        &label,
+        vec![UndoStep::new(
+            UndoOperation::DeleteElement { element_id: id },
+            UndoOperation::RestoreElement {
+                element: Box::new(element),
+                incoming,
+            },
+        )],
+    )
+    .map_err(CommandError::from)
 }
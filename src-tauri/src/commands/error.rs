@@ -0,0 +1,440 @@
+//! Error type returned by all Tauri commands
+//!
+//! Commands used to return `Result<_, String>`, which left the frontend
+//! string-matching messages. `CommandError` carries a stable `code` to branch
+//! on, the human-readable `message`, and structured `details` (paths, branch
+//! names, underlying library codes) for anything that needs more context.
+
+use crate::autosave::AutosaveError;
+use crate::consistency::ConsistencyError;
+use crate::duplicate::DuplicateError;
+use crate::file_management::FileManagementError;
+use crate::git::GitServiceError;
+use crate::moves::MoveError;
+use crate::recovery::RecoveryError;
+use crate::restructure::RestructureError;
+use crate::trash::TrashError;
+use crate::undo::UndoError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use ts_rs::TS;
+
+/// Stable error codes exposed to the frontend
+///
+/// Codes are part of the IPC contract: add new ones freely, but never rename
+/// or repurpose existing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub enum ErrorCode {
+    /// The requested entity, file, branch, commit or repository does not exist
+    NotFound,
+    /// An entity, file or branch with the same name already exists
+    AlreadyExists,
+    /// The input failed validation
+    InvalidInput,
+    /// The operation is not allowed in the current state
+    InvalidOperation,
+    /// The operation is blocked by uncommitted changes in the working directory
+    UncommittedChanges,
+    /// The operation ran into a merge conflict or a conflicting write
+    Conflict,
+    /// The Git repository failed its integrity checks
+    RepositoryCorrupted,
+    /// A database constraint (foreign key, uniqueness, check) was violated
+    ConstraintViolation,
+    /// Any other database error
+    Database,
+    /// Any other Git error
+    Git,
+    /// Any other filesystem error
+    Io,
+}
+
+/// Error returned by Tauri commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct CommandError {
+    /// Stable code the frontend can branch on
+    pub code: ErrorCode,
+    /// Human-readable description, suitable for display
+    pub message: String,
+    /// Structured context, e.g. `path`, `branch`, `git_code`
+    pub details: BTreeMap<String, String>,
+}
+
+impl CommandError {
+    /// Create an error with no details
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        CommandError {
+            code,
+            message: message.into(),
+            details: BTreeMap::new(),
+        }
+    }
+
+    /// Attach a detail to the error
+    pub fn with_detail(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Prefix the message with what was being attempted, keeping code and details
+    pub fn context(mut self, context: &str) -> Self {
+        self.message = format!("{context}: {}", self.message);
+        self
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Result type alias for Tauri commands
+pub type CommandResult<T> = Result<T, CommandError>;
+
+fn code_for_git(err: &git2::Error) -> ErrorCode {
+    match err.code() {
+        git2::ErrorCode::NotFound => ErrorCode::NotFound,
+        git2::ErrorCode::Exists => ErrorCode::AlreadyExists,
+        git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict => ErrorCode::Conflict,
+        git2::ErrorCode::Uncommitted | git2::ErrorCode::IndexDirty => ErrorCode::UncommittedChanges,
+        git2::ErrorCode::InvalidSpec | git2::ErrorCode::Invalid => ErrorCode::InvalidInput,
+        _ => ErrorCode::Git,
+    }
+}
+
+fn code_for_io(err: &std::io::Error) -> ErrorCode {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+        std::io::ErrorKind::AlreadyExists => ErrorCode::AlreadyExists,
+        _ => ErrorCode::Io,
+    }
+}
+
+impl From<GitServiceError> for CommandError {
+    fn from(err: GitServiceError) -> Self {
+        let message = err.to_string();
+        match err {
+            GitServiceError::Git(e) => CommandError::new(code_for_git(&e), message)
+                .with_detail("git_code", format!("{:?}", e.code()))
+                .with_detail("git_class", format!("{:?}", e.class())),
+            GitServiceError::Io(e) => CommandError::new(code_for_io(&e), message),
+            GitServiceError::RepositoryNotFound(path) => {
+                CommandError::new(ErrorCode::NotFound, message)
+                    .with_detail("path", path.to_string_lossy())
+            }
+            GitServiceError::InvalidOperation(_) => {
+                CommandError::new(ErrorCode::InvalidOperation, message)
+            }
+            GitServiceError::NotFound(_) => CommandError::new(ErrorCode::NotFound, message),
+            GitServiceError::AlreadyExists(_) => {
+                CommandError::new(ErrorCode::AlreadyExists, message)
+            }
+            GitServiceError::UncommittedChanges(_) => {
+                CommandError::new(ErrorCode::UncommittedChanges, message)
+            }
+            GitServiceError::InvalidInput(_) => CommandError::new(ErrorCode::InvalidInput, message),
+            GitServiceError::Conflict(_) => CommandError::new(ErrorCode::Conflict, message),
+            GitServiceError::RepositoryCorrupted(_) => {
+                CommandError::new(ErrorCode::RepositoryCorrupted, message)
+            }
+        }
+    }
+}
+
+impl From<FileManagementError> for CommandError {
+    fn from(err: FileManagementError) -> Self {
+        let message = err.to_string();
+        match err {
+            FileManagementError::FileExists(path) => {
+                CommandError::new(ErrorCode::AlreadyExists, message)
+                    .with_detail("path", path.to_string_lossy())
+            }
+            FileManagementError::Io(e) => CommandError::new(code_for_io(&e), message),
+            FileManagementError::Git(e) => CommandError {
+                message,
+                ..CommandError::from(e)
+            },
+            FileManagementError::UncommittedChanges(_) => {
+                CommandError::new(ErrorCode::UncommittedChanges, message)
+            }
+        }
+    }
+}
+
+impl From<rusqlite::Error> for CommandError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err {
+            rusqlite::Error::QueryReturnedNoRows => {
+                CommandError::new(ErrorCode::NotFound, "Record not found")
+            }
+            // Repositories report validation failures (nesting depth, leaf
+            // rules) as InvalidParameterName carrying a readable message
+            rusqlite::Error::InvalidParameterName(msg) => {
+                CommandError::new(ErrorCode::InvalidInput, msg)
+            }
+            rusqlite::Error::SqliteFailure(ref e, _) => {
+                let code = match e.code {
+                    rusqlite::ErrorCode::ConstraintViolation => ErrorCode::ConstraintViolation,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked => {
+                        ErrorCode::Conflict
+                    }
+                    _ => ErrorCode::Database,
+                };
+                CommandError::new(code, err.to_string())
+                    .with_detail("sqlite_code", e.extended_code.to_string())
+            }
+            other => CommandError::new(ErrorCode::Database, other.to_string()),
+        }
+    }
+}
+
+impl From<AutosaveError> for CommandError {
+    fn from(err: AutosaveError) -> Self {
+        match err {
+            AutosaveError::Database(e) => CommandError::from(e),
+            AutosaveError::File(e) => CommandError::from(e),
+        }
+    }
+}
+
+impl From<RecoveryError> for CommandError {
+    fn from(err: RecoveryError) -> Self {
+        match err {
+            RecoveryError::Database(e) => CommandError::from(e),
+            RecoveryError::Io(e) => CommandError::from(e),
+        }
+    }
+}
+
+impl From<ConsistencyError> for CommandError {
+    fn from(err: ConsistencyError) -> Self {
+        match err {
+            ConsistencyError::Database(e) => CommandError::from(e),
+            ConsistencyError::Io(e) => CommandError::from(e),
+            ConsistencyError::Git(e) => CommandError::from(e),
+            ConsistencyError::NotFixable(msg) => CommandError::new(
+                ErrorCode::InvalidOperation,
+                format!("Cannot fix automatically: {msg}"),
+            ),
+        }
+    }
+}
+
+impl From<TrashError> for CommandError {
+    fn from(err: TrashError) -> Self {
+        match err {
+            TrashError::Database(e) => CommandError::from(e),
+            TrashError::Io(e) => CommandError::from(e),
+            TrashError::AlreadyInTrash(_) | TrashError::NotInTrash(_) => {
+                CommandError::new(ErrorCode::InvalidOperation, err.to_string())
+            }
+            TrashError::ParentInTrash(ref id) => {
+                CommandError::new(ErrorCode::InvalidOperation, err.to_string())
+                    .with_detail("id", id)
+            }
+            TrashError::DestinationExists(ref path) => {
+                CommandError::new(ErrorCode::AlreadyExists, err.to_string())
+                    .with_detail("path", path.display().to_string())
+            }
+        }
+    }
+}
+
+impl From<UndoError> for CommandError {
+    fn from(err: UndoError) -> Self {
+        match err {
+            UndoError::Database(e) => CommandError::from(e),
+            UndoError::Trash(e) => CommandError::from(e),
+            UndoError::Files(e) => CommandError::from(e),
+            UndoError::Move(e) => CommandError::from(e),
+            UndoError::PartiallyApplied { applied, source } => CommandError::from(*source)
+                .context("Partially applied and could not be rolled back")
+                .with_detail("applied", applied.to_string()),
+        }
+    }
+}
+
+impl From<MoveError> for CommandError {
+    fn from(err: MoveError) -> Self {
+        match err {
+            MoveError::Database(e) => CommandError::from(e),
+            MoveError::Git(e) => CommandError::from(e),
+            MoveError::Files(e) => CommandError::from(e),
+        }
+    }
+}
+
+impl From<RestructureError> for CommandError {
+    fn from(err: RestructureError) -> Self {
+        match err {
+            RestructureError::Database(e) => CommandError::from(e),
+            RestructureError::Trash(e) => CommandError::from(e),
+            RestructureError::Files(e) => CommandError::from(e),
+            RestructureError::Git(e) => CommandError::from(e),
+            RestructureError::Invalid(msg) => CommandError::new(ErrorCode::InvalidInput, msg),
+        }
+    }
+}
+
+impl From<DuplicateError> for CommandError {
+    fn from(err: DuplicateError) -> Self {
+        match err {
+            DuplicateError::Database(e) => CommandError::from(e),
+            DuplicateError::Git(e) => CommandError::from(e),
+            DuplicateError::Files(e) => CommandError::from(e),
+            DuplicateError::Invalid(msg) => CommandError::new(ErrorCode::InvalidInput, msg),
+        }
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(err: std::io::Error) -> Self {
+        CommandError::new(code_for_io(&err), format!("IO error: {err}"))
+    }
+}
+
+impl From<tauri::Error> for CommandError {
+    fn from(err: tauri::Error) -> Self {
+        CommandError::new(ErrorCode::Io, err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_repository_not_found_maps_to_not_found_with_path() {
+        let err: CommandError =
+            GitServiceError::RepositoryNotFound(PathBuf::from("/tmp/missing")).into();
+
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(err.details.get("path"), Some(&"/tmp/missing".to_string()));
+        assert!(err.message.contains("/tmp/missing"));
+    }
+
+    #[test]
+    fn test_git_error_variants_map_to_codes() {
+        let cases = [
+            (
+                GitServiceError::NotFound("Branch 'x' not found".to_string()),
+                ErrorCode::NotFound,
+            ),
+            (
+                GitServiceError::AlreadyExists("Branch 'x' already exists".to_string()),
+                ErrorCode::AlreadyExists,
+            ),
+            (
+                GitServiceError::UncommittedChanges(
+                    "Cannot checkout: uncommitted changes exist".to_string(),
+                ),
+                ErrorCode::UncommittedChanges,
+            ),
+            (
+                GitServiceError::InvalidInput("Invalid commit hash: xyz".to_string()),
+                ErrorCode::InvalidInput,
+            ),
+            // The message mentions a conflict, but the code follows the variant
+            (
+                GitServiceError::InvalidOperation(
+                    "File 'a.md' does not have a conflict".to_string(),
+                ),
+                ErrorCode::InvalidOperation,
+            ),
+        ];
+
+        for (err, code) in cases {
+            let message = err.to_string();
+            let err: CommandError = err.into();
+            assert_eq!(err.code, code, "message: {message}");
+        }
+    }
+
+    #[test]
+    fn test_corruption_maps_to_repository_corrupted() {
+        let err: CommandError =
+            GitServiceError::RepositoryCorrupted("missing HEAD".to_string()).into();
+
+        assert_eq!(err.code, ErrorCode::RepositoryCorrupted);
+    }
+
+    #[test]
+    fn test_git2_not_found_keeps_library_code() {
+        let git_err = git2::Error::new(
+            git2::ErrorCode::NotFound,
+            git2::ErrorClass::Reference,
+            "reference not found",
+        );
+        let err: CommandError = GitServiceError::Git(git_err).into();
+
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(err.details.get("git_code"), Some(&"NotFound".to_string()));
+    }
+
+    #[test]
+    fn test_file_management_errors_map_to_codes() {
+        let err: CommandError =
+            FileManagementError::UncommittedChanges("branch is dirty".to_string()).into();
+        assert_eq!(err.code, ErrorCode::UncommittedChanges);
+
+        let err: CommandError = FileManagementError::FileExists(PathBuf::from("a.md")).into();
+        assert_eq!(err.code, ErrorCode::AlreadyExists);
+        assert_eq!(err.details.get("path"), Some(&"a.md".to_string()));
+
+        // Wrapped git errors keep the git code but the outer message
+        let err: CommandError =
+            FileManagementError::Git(GitServiceError::NotFound("Tag 'v1' not found".to_string()))
+                .into();
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert!(err.message.starts_with("Git error"));
+    }
+
+    #[test]
+    fn test_rusqlite_errors_map_to_codes() {
+        let err: CommandError = rusqlite::Error::QueryReturnedNoRows.into();
+        assert_eq!(err.code, ErrorCode::NotFound);
+
+        let err: CommandError =
+            rusqlite::Error::InvalidParameterName("Maximum nesting depth exceeded".to_string())
+                .into();
+        assert_eq!(err.code, ErrorCode::InvalidInput);
+        assert_eq!(err.message, "Maximum nesting depth exceeded");
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (id TEXT PRIMARY KEY); INSERT INTO t VALUES ('a');")
+            .unwrap();
+        let sql_err = conn.execute("INSERT INTO t VALUES ('a')", []).unwrap_err();
+        let err: CommandError = sql_err.into();
+        assert_eq!(err.code, ErrorCode::ConstraintViolation);
+        assert!(err.details.contains_key("sqlite_code"));
+    }
+
+    #[test]
+    fn test_serializes_with_code_message_and_details() {
+        let err = CommandError::new(ErrorCode::Conflict, "Merge has conflicts")
+            .with_detail("branch", "alt");
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "Conflict");
+        assert_eq!(json["message"], "Merge has conflicts");
+        assert_eq!(json["details"]["branch"], "alt");
+    }
+
+    #[test]
+    fn test_context_prefixes_message_and_keeps_code() {
+        let err = CommandError::new(ErrorCode::NotFound, "Record not found")
+            .with_detail("id", "42")
+            .context("Failed to load story");
+
+        assert_eq!(err.message, "Failed to load story: Record not found");
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(err.details.get("id"), Some(&"42".to_string()));
+    }
+}
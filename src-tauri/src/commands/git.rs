@@ -1,8 +1,16 @@
+use crate::commands::error::{CommandError, CommandResult, ErrorCode};
 use crate::file_management::{
-    get_variation_display_name, list_variations, save_variation_mapping, VariationInfo,
+    get_variation_display_name, list_archived_variations, list_milestones, list_variations,
+    remove_milestone_mapping, remove_variation_mapping, rename_variation_mapping,
+    resolve_milestone, save_milestone_mapping, save_variation_mapping, search_history,
+    MilestoneInfo, VariationInfo,
 };
 use crate::file_naming::slugify_unique_variation;
-use crate::git::{CommitInfo, DiffResult, GitService, MergeResult};
+use crate::git::{
+    CommitInfo, ConflictFile, DiffResult, FileHistoryEntry, GitService, HistoryMatch, HunkChoice,
+    MergePreview, MergeResult, ParagraphBlame, SnapshotFile,
+};
+use crate::prose_merge::ProseMergeResult;
 use std::path::PathBuf;
 
 /// Initialize a Git repository for a story
@@ -14,11 +22,11 @@ use std::path::PathBuf;
 /// # Returns
 /// The path to the created repository
 #[tauri::command]
-pub fn git_init_repo(base_path: String, story_id: String) -> Result<String, String> {
+pub fn git_init_repo(base_path: String, story_id: String) -> CommandResult<String> {
     let base = PathBuf::from(base_path);
     GitService::init_repo(&base, &story_id)
         .map(|path| path.to_string_lossy().to_string())
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// Commit a specific file with content
@@ -37,9 +45,9 @@ pub fn git_commit_file(
     file_path: String,
     content: String,
     message: String,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let path = PathBuf::from(repo_path);
-    GitService::commit_file(&path, &file_path, &content, &message).map_err(|e| e.to_string())
+    GitService::commit_file(&path, &file_path, &content, &message).map_err(CommandError::from)
 }
 
 /// Commit all changes in the repository
@@ -51,9 +59,9 @@ pub fn git_commit_file(
 /// # Returns
 /// The commit hash (OID) as a string
 #[tauri::command]
-pub fn git_commit_all(repo_path: String, message: String) -> Result<String, String> {
+pub fn git_commit_all(repo_path: String, message: String) -> CommandResult<String> {
     let path = PathBuf::from(repo_path);
-    GitService::commit_all(&path, &message).map_err(|e| e.to_string())
+    GitService::commit_all(&path, &message).map_err(CommandError::from)
 }
 
 /// Create a new branch (variation) from a parent branch
@@ -73,20 +81,20 @@ pub fn git_create_branch(
     repo_path: String,
     parent_branch: String,
     display_name: String,
-) -> Result<VariationInfo, String> {
+) -> CommandResult<VariationInfo> {
     let path = PathBuf::from(repo_path);
 
     // Get list of existing branches
-    let existing_branches = GitService::list_branches(&path).map_err(|e| e.to_string())?;
+    let existing_branches = GitService::list_branches(&path)?;
 
     // Generate unique slug from display name
     let slug = slugify_unique_variation(&display_name, &existing_branches);
 
     // Create the Git branch
-    GitService::create_branch(&path, &parent_branch, &slug).map_err(|e| e.to_string())?;
+    GitService::create_branch(&path, &parent_branch, &slug)?;
 
     // Save the variation mapping
-    save_variation_mapping(&path, &slug, &display_name).map_err(|e| e.to_string())?;
+    save_variation_mapping(&path, &slug, &display_name)?;
 
     // Return VariationInfo for the new branch
     Ok(VariationInfo {
@@ -108,9 +116,9 @@ pub fn git_create_branch(
 /// # Errors
 /// Returns an error if there are uncommitted changes
 #[tauri::command]
-pub fn git_checkout_branch(repo_path: String, branch: String) -> Result<(), String> {
+pub fn git_checkout_branch(repo_path: String, branch: String) -> CommandResult<()> {
     let path = PathBuf::from(repo_path);
-    GitService::checkout_branch(&path, &branch).map_err(|e| e.to_string())
+    GitService::checkout_branch(&path, &branch).map_err(CommandError::from)
 }
 
 /// Get the diff between two branches
@@ -127,9 +135,58 @@ pub fn git_diff_branches(
     repo_path: String,
     branch_a: String,
     branch_b: String,
-) -> Result<DiffResult, String> {
+) -> CommandResult<DiffResult> {
+    let path = PathBuf::from(repo_path);
+    GitService::diff_branches(&path, &branch_a, &branch_b).map_err(CommandError::from)
+}
+
+/// Get the diff between two commits (snapshots)
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `from_commit` - Hash of the older commit
+/// * `to_commit` - Hash of the newer commit
+///
+/// # Returns
+/// A DiffResult containing all file changes between the commits
+#[tauri::command]
+pub fn git_diff_commits(
+    repo_path: String,
+    from_commit: String,
+    to_commit: String,
+) -> CommandResult<DiffResult> {
+    let path = PathBuf::from(repo_path);
+    GitService::diff_commits(&path, &from_commit, &to_commit).map_err(CommandError::from)
+}
+
+/// Get the diff between a commit (snapshot) and the current working tree
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `commit_hash` - Hash of the commit to compare against
+///
+/// # Returns
+/// A DiffResult containing all changes made since the commit, including unsaved ones
+#[tauri::command]
+pub fn git_diff_commit_to_workdir(
+    repo_path: String,
+    commit_hash: String,
+) -> CommandResult<DiffResult> {
+    let path = PathBuf::from(repo_path);
+    GitService::diff_commit_to_workdir(&path, &commit_hash).map_err(CommandError::from)
+}
+
+/// Get the diff between the index and the working tree (unsaved changes)
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// A DiffResult containing changes that have not been committed yet
+#[tauri::command]
+pub fn git_diff_index_to_workdir(repo_path: String) -> CommandResult<DiffResult> {
     let path = PathBuf::from(repo_path);
-    GitService::diff_branches(&path, &branch_a, &branch_b).map_err(|e| e.to_string())
+    GitService::diff_index_to_workdir(&path).map_err(CommandError::from)
 }
 
 /// Merge one branch (variation) into another
@@ -148,18 +205,21 @@ pub fn git_merge_branches(
     repo_path: String,
     from_branch: String,
     into_branch: String,
-) -> Result<MergeResult, String> {
+) -> CommandResult<MergeResult> {
     let path = PathBuf::from(repo_path);
 
     // Perform the merge
     let result = GitService::merge_branches(&path, &from_branch, &into_branch).map_err(|e| {
         // Make error messages more user-friendly
-        let err_str = e.to_string();
-        if err_str.contains("Branch") && err_str.contains("not found") {
-            "Variation not found. Please refresh the variation list.".to_string()
+        let err = CommandError::from(e);
+        if err.code == ErrorCode::NotFound && err.message.contains("Branch") {
+            CommandError {
+                message: "Variation not found. Please refresh the variation list.".to_string(),
+                ..err
+            }
         } else {
             // Keep other errors as is
-            err_str
+            err
         }
     })?;
 
@@ -183,18 +243,98 @@ pub fn git_merge_branches(
     })
 }
 
+/// Preview merging one branch (variation) into another
+///
+/// Does not modify the repository, so it is safe to call before asking the
+/// user to confirm a merge.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `from_branch` - Slug of the branch to merge from
+/// * `into_branch` - Slug of the branch to merge into
+///
+/// # Returns
+/// A MergePreview with the expected outcome, conflicting files and change stats
+#[tauri::command]
+pub fn git_preview_merge(
+    repo_path: String,
+    from_branch: String,
+    into_branch: String,
+) -> CommandResult<MergePreview> {
+    let path = PathBuf::from(repo_path);
+    GitService::preview_merge(&path, &from_branch, &into_branch).map_err(CommandError::from)
+}
+
 /// Get commit history for a branch
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `branch` - Name of the branch
+/// * `branch` - Name of the branch, or of a history from `git_list_imported_history`
 ///
 /// # Returns
 /// A vector of CommitInfo structs ordered from newest to oldest
 #[tauri::command]
-pub fn git_get_history(repo_path: String, branch: String) -> Result<Vec<CommitInfo>, String> {
+pub fn git_get_history(repo_path: String, branch: String) -> CommandResult<Vec<CommitInfo>> {
+    let path = PathBuf::from(repo_path);
+    GitService::get_history(&path, &branch).map_err(CommandError::from)
+}
+
+/// Get the commit history of a single file, following renames
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `branch` - Name of the branch
+/// * `file_path` - Relative path to the file within the repository
+///
+/// # Returns
+/// A vector of FileHistoryEntry structs ordered from newest to oldest
+#[tauri::command]
+pub fn git_get_file_history(
+    repo_path: String,
+    branch: String,
+    file_path: String,
+) -> CommandResult<Vec<FileHistoryEntry>> {
+    let path = PathBuf::from(repo_path);
+    GitService::get_file_history(&path, &branch, &file_path).map_err(CommandError::from)
+}
+
+/// Get the commit that last changed each paragraph of a file
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `branch` - Name of the branch
+/// * `file_path` - Relative path to the file within the repository
+///
+/// # Returns
+/// A vector of ParagraphBlame structs in document order
+#[tauri::command]
+pub fn git_blame_paragraphs(
+    repo_path: String,
+    branch: String,
+    file_path: String,
+) -> CommandResult<Vec<ParagraphBlame>> {
     let path = PathBuf::from(repo_path);
-    GitService::get_history(&path, &branch).map_err(|e| e.to_string())
+    GitService::blame_paragraphs(&path, &branch, &file_path).map_err(CommandError::from)
+}
+
+/// Search every commit and variation of a repository for a phrase or pattern
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `pattern` - Phrase (matched ignoring case) or regular expression
+/// * `is_regex` - Treat `pattern` as a regular expression
+///
+/// # Returns
+/// A vector of HistoryMatch structs ordered from newest to oldest; pass one
+/// to `restore_story_passage` to bring the passage back
+#[tauri::command]
+pub fn git_search_history(
+    repo_path: String,
+    pattern: String,
+    is_regex: bool,
+) -> CommandResult<Vec<HistoryMatch>> {
+    let path = PathBuf::from(repo_path);
+    search_history(&path, &pattern, is_regex).map_err(CommandError::from)
 }
 
 /// Restore the repository to a specific commit
@@ -206,9 +346,73 @@ pub fn git_get_history(repo_path: String, branch: String) -> Result<Vec<CommitIn
 /// # Errors
 /// Returns an error if there are uncommitted changes
 #[tauri::command]
-pub fn git_restore_commit(repo_path: String, commit_hash: String) -> Result<(), String> {
+pub fn git_restore_commit(repo_path: String, commit_hash: String) -> CommandResult<()> {
+    let path = PathBuf::from(repo_path);
+    GitService::restore_commit(&path, &commit_hash).map_err(CommandError::from)
+}
+
+/// Restore a snapshot as a new commit on the current branch
+///
+/// Keeps HEAD on the current variation, so later saves continue the branch
+/// instead of creating orphaned commits.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `commit_hash` - The commit hash of the snapshot to restore
+///
+/// # Returns
+/// The hash of the "Restored from" commit
+///
+/// # Errors
+/// Returns an error if there are uncommitted changes
+#[tauri::command]
+pub fn git_restore_snapshot(repo_path: String, commit_hash: String) -> CommandResult<String> {
+    let path = PathBuf::from(repo_path);
+    GitService::restore_snapshot(&path, &commit_hash).map_err(CommandError::from)
+}
+
+/// Read the files of a snapshot without checking it out
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `commit_hash` - The commit hash of the snapshot
+///
+/// # Returns
+/// A vector of SnapshotFile structs with each file's path and content
+#[tauri::command]
+pub fn git_peek_snapshot(
+    repo_path: String,
+    commit_hash: String,
+) -> CommandResult<Vec<SnapshotFile>> {
     let path = PathBuf::from(repo_path);
-    GitService::restore_commit(&path, &commit_hash).map_err(|e| e.to_string())
+    GitService::peek_snapshot(&path, &commit_hash).map_err(CommandError::from)
+}
+
+/// Check whether the repository is stuck in detached HEAD state
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+#[tauri::command]
+pub fn git_is_head_detached(repo_path: String) -> CommandResult<bool> {
+    let path = PathBuf::from(repo_path);
+    GitService::is_head_detached(&path).map_err(CommandError::from)
+}
+
+/// Reattach a detached HEAD to a branch (variation), keeping its content
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `branch` - Branch slug to reattach to, or None to pick it automatically
+///
+/// # Returns
+/// The slug of the branch HEAD now points to
+#[tauri::command]
+pub fn git_recover_detached_head(
+    repo_path: String,
+    branch: Option<String>,
+) -> CommandResult<String> {
+    let path = PathBuf::from(repo_path);
+    GitService::recover_detached_head(&path, branch.as_deref()).map_err(CommandError::from)
 }
 
 /// List all local branches (variations) in the repository
@@ -221,9 +425,9 @@ pub fn git_restore_commit(repo_path: String, commit_hash: String) -> Result<(),
 /// # Returns
 /// A vector of VariationInfo structs with display names and metadata
 #[tauri::command]
-pub fn git_list_branches(repo_path: String) -> Result<Vec<VariationInfo>, String> {
+pub fn git_list_branches(repo_path: String) -> CommandResult<Vec<VariationInfo>> {
     let path = PathBuf::from(repo_path);
-    list_variations(&path).map_err(|e| e.to_string())
+    list_variations(&path).map_err(CommandError::from)
 }
 
 /// Get information about the current branch (variation)
@@ -236,11 +440,11 @@ pub fn git_list_branches(repo_path: String) -> Result<Vec<VariationInfo>, String
 /// # Returns
 /// VariationInfo for the current branch
 #[tauri::command]
-pub fn git_get_current_branch(repo_path: String) -> Result<VariationInfo, String> {
+pub fn git_get_current_branch(repo_path: String) -> CommandResult<VariationInfo> {
     let path = PathBuf::from(repo_path);
 
     // Get current branch name (slug)
-    let slug = GitService::get_current_branch(&path).map_err(|e| e.to_string())?;
+    let slug = GitService::get_current_branch(&path)?;
 
     // Get display name from metadata
     let display_name = get_variation_display_name(&path, &slug).unwrap_or_else(|_| slug.clone()); // Fallback to slug if metadata not found
@@ -258,6 +462,134 @@ pub fn git_get_current_branch(repo_path: String) -> Result<VariationInfo, String
     })
 }
 
+/// Rename a branch (variation)
+///
+/// Generates a new unique slug from the display name, renames the Git branch
+/// and updates the display name mapping. If the slug does not change, only
+/// the display name is updated.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - Current slug of the variation
+/// * `display_name` - New user-friendly display name
+///
+/// # Returns
+/// VariationInfo for the renamed branch
+#[tauri::command]
+pub fn git_rename_branch(
+    repo_path: String,
+    slug: String,
+    display_name: String,
+) -> CommandResult<VariationInfo> {
+    let path = PathBuf::from(repo_path);
+
+    // Slugs must stay unique across live and archived branches
+    let mut existing_branches = GitService::list_branches(&path)?;
+    existing_branches.extend(GitService::list_archived_branches(&path)?);
+    existing_branches.retain(|branch| branch != &slug);
+
+    let new_slug = slugify_unique_variation(&display_name, &existing_branches);
+
+    if new_slug != slug {
+        GitService::rename_branch(&path, &slug, &new_slug)?;
+    }
+
+    rename_variation_mapping(&path, &slug, &new_slug, &display_name)?;
+
+    let is_current = GitService::get_current_branch(&path)? == new_slug;
+
+    Ok(VariationInfo {
+        slug: new_slug,
+        display_name,
+        is_current,
+        is_original: false, // The original branch cannot be renamed
+    })
+}
+
+/// Delete a branch (variation)
+///
+/// Refuses to delete the current or original branch. Unless `force` is set,
+/// also refuses if the branch has commits that no other branch contains;
+/// use `git_count_unmerged_commits` to warn the user beforehand.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - Slug of the variation to delete
+/// * `force` - Delete even if commits would be lost
+#[tauri::command]
+pub fn git_delete_branch(repo_path: String, slug: String, force: bool) -> CommandResult<()> {
+    let path = PathBuf::from(repo_path);
+
+    GitService::delete_branch(&path, &slug, force)?;
+
+    remove_variation_mapping(&path, &slug).map_err(CommandError::from)
+}
+
+/// Count commits that would be lost by deleting a branch (variation)
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - Slug of the variation
+///
+/// # Returns
+/// Number of commits not contained in any other branch
+#[tauri::command]
+pub fn git_count_unmerged_commits(repo_path: String, slug: String) -> CommandResult<usize> {
+    let path = PathBuf::from(repo_path);
+    GitService::count_unmerged_commits(&path, &slug).map_err(CommandError::from)
+}
+
+/// Archive a branch (variation)
+///
+/// The variation is hidden from `git_list_branches` but keeps its commits
+/// and display name, and can be brought back with `git_restore_archived_branch`.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - Slug of the variation to archive
+#[tauri::command]
+pub fn git_archive_branch(repo_path: String, slug: String) -> CommandResult<()> {
+    let path = PathBuf::from(repo_path);
+    GitService::archive_branch(&path, &slug).map_err(CommandError::from)
+}
+
+/// List archived branches (variations)
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// A vector of VariationInfo structs for the archived variations
+#[tauri::command]
+pub fn git_list_archived_branches(repo_path: String) -> CommandResult<Vec<VariationInfo>> {
+    let path = PathBuf::from(repo_path);
+    list_archived_variations(&path).map_err(CommandError::from)
+}
+
+/// List histories that moved into a repository along with a story
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// Names usable as the branch argument of `git_get_history`
+#[tauri::command]
+pub fn git_list_imported_history(repo_path: String) -> CommandResult<Vec<String>> {
+    let path = PathBuf::from(repo_path);
+    GitService::list_imported_history(&path).map_err(CommandError::from)
+}
+
+/// Restore an archived branch (variation)
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - Slug of the archived variation
+#[tauri::command]
+pub fn git_restore_archived_branch(repo_path: String, slug: String) -> CommandResult<()> {
+    let path = PathBuf::from(repo_path);
+    GitService::restore_archived_branch(&path, &slug).map_err(CommandError::from)
+}
+
 /// Resolve a file conflict by choosing ours or theirs version
 ///
 /// # Arguments
@@ -269,9 +601,9 @@ pub fn git_resolve_conflict(
     repo_path: String,
     file_path: String,
     take_theirs: bool,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let path = PathBuf::from(repo_path);
-    GitService::resolve_conflict(&path, &file_path, take_theirs).map_err(|e| e.to_string())
+    GitService::resolve_conflict(&path, &file_path, take_theirs).map_err(CommandError::from)
 }
 
 /// Abort a merge in progress
@@ -279,9 +611,9 @@ pub fn git_resolve_conflict(
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
 #[tauri::command]
-pub fn git_abort_merge(repo_path: String) -> Result<(), String> {
+pub fn git_abort_merge(repo_path: String) -> CommandResult<()> {
     let path = PathBuf::from(repo_path);
-    GitService::abort_merge(&path).map_err(|e| e.to_string())
+    GitService::abort_merge(&path).map_err(CommandError::from)
 }
 
 /// Get the content of a conflicted file with conflict markers
@@ -293,7 +625,181 @@ pub fn git_abort_merge(repo_path: String) -> Result<(), String> {
 /// # Returns
 /// File content with conflict markers
 #[tauri::command]
-pub fn git_get_conflict_content(repo_path: String, file_path: String) -> Result<String, String> {
+pub fn git_get_conflict_content(repo_path: String, file_path: String) -> CommandResult<String> {
+    let path = PathBuf::from(repo_path);
+    GitService::get_conflict_content(&path, &file_path).map_err(CommandError::from)
+}
+
+/// Get the conflict regions of a conflicted file
+///
+/// Unlike `git_get_conflict_content`, this returns structured regions with the
+/// base, ours and theirs text of each conflicting passage.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `file_path` - Relative path to the conflicted file
+///
+/// # Returns
+/// A ProseMergeResult with resolved and conflicting regions in document order
+#[tauri::command]
+pub fn git_get_conflict_regions(
+    repo_path: String,
+    file_path: String,
+) -> CommandResult<ProseMergeResult> {
+    let path = PathBuf::from(repo_path);
+    GitService::get_conflict_regions(&path, &file_path).map_err(CommandError::from)
+}
+
+/// List every conflicted file with its conflict hunks
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// A vector of ConflictFile structs, each with hunks in document order
+#[tauri::command]
+pub fn git_get_conflict_files(repo_path: String) -> CommandResult<Vec<ConflictFile>> {
+    let path = PathBuf::from(repo_path);
+    GitService::get_conflict_files(&path).map_err(CommandError::from)
+}
+
+/// Resolve a conflicted file hunk by hunk
+///
+/// Writes the merged file and stages it, marking the conflict as resolved.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `file_path` - Relative path to the conflicted file
+/// * `resolutions` - The chosen resolution for every conflict hunk
+#[tauri::command]
+pub fn git_resolve_conflict_hunks(
+    repo_path: String,
+    file_path: String,
+    resolutions: Vec<HunkChoice>,
+) -> CommandResult<()> {
+    let path = PathBuf::from(repo_path);
+    GitService::resolve_conflict_hunks(&path, &file_path, &resolutions).map_err(CommandError::from)
+}
+
+/// Create a milestone (annotated tag) on a commit
+///
+/// Takes a display name, generates a unique slug for the tag name, creates
+/// the tag, and saves the display name and note mapping.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `display_name` - User-friendly name, e.g. "Draft 1 sent to editor"
+/// * `note` - Free-form note for the milestone
+/// * `commit_hash` - Commit to mark, or None for the current snapshot
+///
+/// # Returns
+/// MilestoneInfo for the new milestone
+#[tauri::command]
+pub fn git_create_milestone(
+    repo_path: String,
+    display_name: String,
+    note: String,
+    commit_hash: Option<String>,
+) -> CommandResult<MilestoneInfo> {
+    let path = PathBuf::from(repo_path);
+
+    // Generate unique slug from display name
+    let existing_tags: Vec<String> = GitService::list_tags(&path)?
+        .into_iter()
+        .map(|tag| tag.name)
+        .collect();
+    let slug = slugify_unique_variation(&display_name, &existing_tags);
+
+    // Create the annotated tag
+    let message = if note.is_empty() {
+        display_name.clone()
+    } else {
+        format!("{display_name}\n\n{note}")
+    };
+    GitService::create_tag(&path, &slug, commit_hash.as_deref(), &message)?;
+
+    // Save the milestone mapping
+    save_milestone_mapping(&path, &slug, &display_name, &note)?;
+
+    list_milestones(&path)?
+        .into_iter()
+        .find(|milestone| milestone.slug == slug)
+        .ok_or_else(|| {
+            CommandError::new(
+                ErrorCode::NotFound,
+                format!("Milestone '{slug}' not found after creation"),
+            )
+            .with_detail("milestone", slug.clone())
+        })
+}
+
+/// List all milestones in the repository
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// A vector of MilestoneInfo structs ordered from newest to oldest
+#[tauri::command]
+pub fn git_list_milestones(repo_path: String) -> CommandResult<Vec<MilestoneInfo>> {
+    let path = PathBuf::from(repo_path);
+    list_milestones(&path).map_err(CommandError::from)
+}
+
+/// Delete a milestone
+///
+/// Removes the tag and its display name mapping. The tagged commit is kept.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - Tag name of the milestone (from MilestoneInfo)
+#[tauri::command]
+pub fn git_delete_milestone(repo_path: String, slug: String) -> CommandResult<()> {
+    let path = PathBuf::from(repo_path);
+    GitService::delete_tag(&path, &slug)?;
+    remove_milestone_mapping(&path, &slug).map_err(CommandError::from)
+}
+
+/// Get the diff from a milestone to another milestone or to the working tree
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `milestone` - Slug or display name of the milestone to compare from
+/// * `to_milestone` - Slug or display name to compare to, or None for the working tree
+///
+/// # Returns
+/// A DiffResult containing all file changes since the milestone
+#[tauri::command]
+pub fn git_diff_milestone(
+    repo_path: String,
+    milestone: String,
+    to_milestone: Option<String>,
+) -> CommandResult<DiffResult> {
+    let path = PathBuf::from(repo_path);
+    let from_commit = resolve_milestone(&path, &milestone)?;
+
+    match to_milestone {
+        Some(to_milestone) => {
+            let to_commit = resolve_milestone(&path, &to_milestone)?;
+            GitService::diff_commits(&path, &from_commit, &to_commit).map_err(CommandError::from)
+        }
+        None => GitService::diff_commit_to_workdir(&path, &from_commit).map_err(CommandError::from),
+    }
+}
+
+/// Restore a milestone as a new commit on the current branch
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `milestone` - Slug or display name of the milestone
+///
+/// # Errors
+/// Returns an error if there are uncommitted changes
+#[tauri::command]
+pub fn git_restore_milestone(repo_path: String, milestone: String) -> CommandResult<()> {
     let path = PathBuf::from(repo_path);
-    GitService::get_conflict_content(&path, &file_path).map_err(|e| e.to_string())
+    let commit_hash = resolve_milestone(&path, &milestone)?;
+    GitService::restore_snapshot(&path, &commit_hash)
+        .map(|_| ())
+        .map_err(CommandError::from)
 }
@@ -0,0 +1,156 @@
+use crate::autosave::AutosaveService;
+use crate::commands::container::update_container;
+use crate::commands::element::update_element;
+use crate::commands::error::{CommandError, CommandResult, ErrorCode};
+use crate::commands::story::update_story;
+use crate::commands::universe::update_universe;
+use crate::db::Database;
+use crate::models::{JournalEntityType, JournalEntry};
+use crate::repositories::JournalRepository;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tauri::State;
+
+/// Universe fields that can be set through `update_universe`
+const UNIVERSE_FIELDS: &[&str] = &[
+    "name",
+    "description",
+    "genre",
+    "tone",
+    "worldbuildingNotes",
+    "themes",
+    "status",
+    "color",
+    "icon",
+    "tags",
+];
+
+/// Container fields that can be set through `update_container`
+const CONTAINER_FIELDS: &[&str] = &["title", "description", "containerType", "order"];
+
+/// Element fields that can be set through `update_element`
+const ELEMENT_FIELDS: &[&str] = &[
+    "name",
+    "description",
+    "elementType",
+    "customTypeName",
+    "details",
+    "attributes",
+    "imageUrl",
+    "tags",
+    "relationships",
+    "color",
+    "icon",
+    "favorite",
+    "order",
+];
+
+/// Story fields that can be set through `update_story`
+const STORY_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "storyType",
+    "status",
+    "notes",
+    "outline",
+    "targetWordCount",
+    "order",
+    "tags",
+    "color",
+    "favorite",
+    "relatedElementIds",
+    "seriesName",
+];
+
+/// Build an update input that sets a single field
+fn single_field_input<T: DeserializeOwned>(field: &str, value: Value) -> CommandResult<T> {
+    serde_json::from_value(json!({ field: value })).map_err(|e| {
+        CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Recorded value can't be restored: {e}"),
+        )
+        .with_detail("field", field)
+    })
+}
+
+/// Get the recorded changes to an entity, newest first
+///
+/// # Arguments
+/// * `entity_type` - Kind of entity
+/// * `entity_id` - ID of the entity
+/// * `limit` - Maximum number of entries to return (all if omitted)
+#[tauri::command]
+pub fn get_change_timeline(
+    db: State<Database>,
+    entity_type: JournalEntityType,
+    entity_id: String,
+    limit: Option<u32>,
+) -> CommandResult<Vec<JournalEntry>> {
+    JournalRepository::timeline(&db, entity_type, &entity_id, limit).map_err(CommandError::from)
+}
+
+/// Set one field of an entity back to its value before a journaled change
+///
+/// The revert goes through the regular update command, so files are renamed
+/// and metadata refreshed as for any other edit, and it's journaled itself.
+/// Fields that were unset before the change can't be reverted, since the
+/// update commands have no way to clear a field.
+///
+/// # Arguments
+/// * `entry_id` - Journal entry containing the change
+/// * `field` - Changed field to revert (camelCase, as in the entry)
+#[tauri::command]
+pub fn revert_field_change(
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    entry_id: i64,
+    field: String,
+) -> CommandResult<()> {
+    let entry = JournalRepository::find_by_id(&db, entry_id)?;
+    let Some(change) = entry.changes.get(&field) else {
+        return Err(
+            CommandError::new(ErrorCode::NotFound, "Field was not changed by this entry")
+                .with_detail("entry_id", entry_id.to_string())
+                .with_detail("field", &field),
+        );
+    };
+
+    let revertable = match entry.entity_type {
+        JournalEntityType::Universe => UNIVERSE_FIELDS,
+        JournalEntityType::Container => CONTAINER_FIELDS,
+        JournalEntityType::Element => ELEMENT_FIELDS,
+        JournalEntityType::Story => STORY_FIELDS,
+    };
+    if !revertable.contains(&field.as_str()) {
+        return Err(
+            CommandError::new(ErrorCode::InvalidOperation, "Field can't be reverted")
+                .with_detail("field", &field),
+        );
+    }
+    if change.before.is_null() {
+        return Err(CommandError::new(
+            ErrorCode::InvalidOperation,
+            "Field had no value before this change",
+        )
+        .with_detail("field", &field));
+    }
+
+    let before = change.before.clone();
+    let id = entry.entity_id;
+    match entry.entity_type {
+        JournalEntityType::Universe => {
+            update_universe(db, id, single_field_input(&field, before)?)?;
+        }
+        JournalEntityType::Container => {
+            update_container(db, id, single_field_input(&field, before)?)?;
+        }
+        JournalEntityType::Element => {
+            update_element(db, id, single_field_input(&field, before)?)?;
+        }
+        JournalEntityType::Story => {
+            update_story(db, autosave, id, single_field_input(&field, before)?, None)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::commands::error::{CommandError, CommandResult};
+use crate::consistency::{self, ConsistencyIssue, ConsistencyReport};
+use crate::db::Database;
+use crate::recovery::{rebuild_from_repos, RecoveryReport};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+fn git_repos_dir(app: &AppHandle) -> CommandResult<PathBuf> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::from(e).context("Failed to get app data directory"))?;
+    Ok(app_data_dir.join("git-repos"))
+}
+
+/// Recreate database rows from the story and container repositories in the
+/// app data directory (disaster recovery import)
+///
+/// Entities that already exist are left untouched and listed as conflicts.
+#[tauri::command]
+pub fn rebuild_database_from_repos(
+    app: AppHandle,
+    db: State<Database>,
+) -> CommandResult<RecoveryReport> {
+    rebuild_from_repos(&db, &git_repos_dir(&app)?).map_err(CommandError::from)
+}
+
+/// List inconsistencies between the database and the git repositories
+#[tauri::command]
+pub fn check_consistency(app: AppHandle, db: State<Database>) -> CommandResult<ConsistencyReport> {
+    consistency::check_consistency(&db, &git_repos_dir(&app)?).map_err(CommandError::from)
+}
+
+/// Apply the automatic fix for one issue from `check_consistency`
+#[tauri::command]
+pub fn fix_consistency_issue(
+    app: AppHandle,
+    db: State<Database>,
+    issue: ConsistencyIssue,
+) -> CommandResult<()> {
+    consistency::apply_fix(&db, &git_repos_dir(&app)?, &issue).map_err(CommandError::from)
+}
+
+/// Apply every available automatic fix and return the issues that remain
+#[tauri::command]
+pub fn fix_all_consistency_issues(
+    app: AppHandle,
+    db: State<Database>,
+) -> CommandResult<ConsistencyReport> {
+    consistency::fix_all(&db, &git_repos_dir(&app)?).map_err(CommandError::from)
+}
@@ -1,11 +1,26 @@
+pub mod autosave;
 pub mod container;
 pub mod element;
+pub mod error;
 pub mod git;
+pub mod journal;
+pub mod maintenance;
+pub mod search;
+pub mod session;
 pub mod story;
+pub mod trash;
+pub mod undo;
 pub mod universe;
 
+pub use autosave::*;
 pub use container::*;
 pub use element::*;
 pub use git::*;
+pub use journal::*;
+pub use maintenance::*;
+pub use search::*;
+pub use session::*;
 pub use story::*;
+pub use trash::*;
+pub use undo::*;
 pub use universe::*;
@@ -0,0 +1,17 @@
+use crate::commands::error::{CommandError, CommandResult};
+use crate::db::Database;
+use crate::models::{SearchFilters, SearchHit};
+use crate::repositories::SearchRepository;
+use tauri::State;
+
+/// Full-text search across stories, elements and universes
+///
+/// Returns ranked hits with snippets and highlight offsets.
+#[tauri::command]
+pub fn search(
+    db: State<Database>,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> CommandResult<Vec<SearchHit>> {
+    SearchRepository::search(&db, &query, &filters.unwrap_or_default()).map_err(CommandError::from)
+}
@@ -0,0 +1,100 @@
+use crate::commands::error::{CommandError, CommandResult, ErrorCode};
+use crate::db::Database;
+use crate::models::{
+    CreateWritingGoalInput, GoalProgress, WritingDay, WritingGoal, WritingScope, WritingStreak,
+};
+use crate::repositories::SessionRepository;
+use chrono::{Local, NaiveDate};
+use tauri::State;
+
+/// Parse a `YYYY-MM-DD` command argument
+fn parse_day(name: &str, value: &str) -> CommandResult<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        CommandError::new(ErrorCode::InvalidInput, format!("Invalid {name} date"))
+            .with_detail(name, value)
+    })
+}
+
+/// Get words written per day
+///
+/// # Arguments
+/// * `scope` - Which writing to count (defaults to all)
+/// * `from` - First day (`YYYY-MM-DD`, inclusive)
+/// * `to` - Last day (`YYYY-MM-DD`, inclusive); defaults to today
+///
+/// # Returns
+/// Days with writing activity, oldest first
+#[tauri::command]
+pub fn get_writing_history(
+    db: State<Database>,
+    scope: Option<WritingScope>,
+    from: String,
+    to: Option<String>,
+) -> CommandResult<Vec<WritingDay>> {
+    let from = parse_day("from", &from)?;
+    let to = match to {
+        Some(to) => parse_day("to", &to)?,
+        None => Local::now().date_naive(),
+    };
+    SessionRepository::daily_history(&db, &scope.unwrap_or_default(), from, to)
+        .map_err(CommandError::from)
+}
+
+/// Get the current and longest writing streaks
+///
+/// A day counts towards the streak when it meets the daily goal that applies
+/// to the scope's universe (or the global one), or when any words were added
+/// if there is no daily goal.
+#[tauri::command]
+pub fn get_writing_streak(
+    db: State<Database>,
+    scope: Option<WritingScope>,
+) -> CommandResult<WritingStreak> {
+    let scope = scope.unwrap_or_default();
+    let threshold = SessionRepository::daily_target(&db, scope.universe_id.as_deref())?;
+    SessionRepository::streak(
+        &db,
+        &scope,
+        threshold.unwrap_or(1),
+        Local::now().date_naive(),
+    )
+    .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn create_writing_goal(
+    db: State<Database>,
+    input: CreateWritingGoalInput,
+) -> CommandResult<WritingGoal> {
+    SessionRepository::create_goal(&db, input).map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn list_writing_goals(db: State<Database>) -> CommandResult<Vec<WritingGoal>> {
+    SessionRepository::list_goals(&db).map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn delete_writing_goal(db: State<Database>, id: String) -> CommandResult<()> {
+    SessionRepository::delete_goal(&db, &id).map_err(CommandError::from)
+}
+
+/// Get today's progress towards a goal, with the projected completion date
+/// for deadline goals
+#[tauri::command]
+pub fn get_goal_progress(db: State<Database>, id: String) -> CommandResult<GoalProgress> {
+    let goal = SessionRepository::find_goal(&db, &id)?;
+    SessionRepository::goal_progress(&db, goal, Local::now().date_naive())
+        .map_err(CommandError::from)
+}
+
+/// Get progress for every goal
+#[tauri::command]
+pub fn list_goal_progress(db: State<Database>) -> CommandResult<Vec<GoalProgress>> {
+    let today = Local::now().date_naive();
+    SessionRepository::list_goals(&db)?
+        .into_iter()
+        .map(|goal| SessionRepository::goal_progress(&db, goal, today))
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(CommandError::from)
+}
@@ -1,7 +1,19 @@
+use crate::autosave::AutosaveService;
+use crate::commands::error::{CommandError, CommandResult, ErrorCode};
+use crate::commands::trash::app_data_dir;
 use crate::db::Database;
+use crate::duplicate::{self, HistoryMode};
+use crate::file_management::{
+    story_filename, sync_story_file, update_chapter_record, update_metadata_file,
+    write_metadata_file,
+};
 use crate::git::GitService;
-use crate::models::{CreateStoryInput, Story, UpdateStoryInput};
-use crate::repositories::StoryRepository;
+use crate::models::{ChangeOrigin, CreateStoryInput, Story, UpdateStoryInput};
+use crate::repositories::{JournalRepository, StoryRepository};
+use crate::restructure;
+use crate::trash::TrashEntityType;
+use crate::undo::{self, UndoOperation, UndoStep};
+use std::path::PathBuf;
 use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
@@ -9,17 +21,17 @@ pub fn create_story(
     app: AppHandle,
     db: State<Database>,
     input: CreateStoryInput,
-) -> Result<Story, String> {
+) -> CommandResult<Story> {
     // Transaction sequence:
     // 1. Insert DB row (story)
     // 2. If entity should have git repo (standalone story):
-    //    - Initialize git repo
+    //    - Initialize git repo and write its metadata.json
     //    - If git init fails → rollback DB insert, return error
     //    - Update git_repo_path in DB
     //    - If path update fails → delete git repo dir, rollback DB, return error
 
     // STEP 1: Create the story in the database
-    let story = StoryRepository::create(&db, input).map_err(|e| e.to_string())?;
+    let story = StoryRepository::create(&db, input)?;
 
     // Only initialize git repository for standalone stories (container_id = None)
     // Stories within containers share their container's git repo
@@ -28,16 +40,24 @@ pub fn create_story(
         let app_data_dir = app.path().app_data_dir().map_err(|e| {
             // Rollback: Delete the story from database
             let _ = StoryRepository::delete(&db, &story.id);
-            format!("Failed to get app data directory: {e}")
+            CommandError::from(e).context("Failed to get app data directory")
         })?;
 
         // STEP 2: Initialize git repository for the story
         let git_repo_path = GitService::init_repo(&app_data_dir, &story.id).map_err(|e| {
             // Rollback: Delete the story from database
             let _ = StoryRepository::delete(&db, &story.id);
-            format!("Failed to initialize git repository: {e}")
+            CommandError::from(e).context("Failed to initialize git repository")
         })?;
 
+        // Record the story in metadata.json so the repo can be re-imported later
+        if let Err(e) = write_metadata_file(&git_repo_path, &story) {
+            // Rollback: Delete the git repo directory and the story
+            let _ = std::fs::remove_dir_all(&git_repo_path);
+            let _ = StoryRepository::delete(&db, &story.id);
+            return Err(CommandError::from(e).context("Failed to write story metadata"));
+        }
+
         // STEP 3: Update the story with the git repo path
         let git_repo_path_str = git_repo_path.to_string_lossy().to_string();
         if let Err(e) = StoryRepository::set_git_repo_path(&db, &story.id, &git_repo_path_str) {
@@ -47,7 +67,7 @@ pub fn create_story(
             }
             // Rollback: Delete the story from database
             let _ = StoryRepository::delete(&db, &story.id);
-            return Err(format!("Failed to update git repo path: {e}"));
+            return Err(CommandError::from(e).context("Failed to update git repo path"));
         }
 
         // Get current branch from the newly created repo
@@ -57,7 +77,7 @@ pub fn create_story(
                 let _ = std::fs::remove_dir_all(&git_repo_path);
             }
             let _ = StoryRepository::delete(&db, &story.id);
-            format!("Failed to get current branch: {e}")
+            CommandError::from(e).context("Failed to get current branch")
         })?;
 
         // Update the current branch
@@ -67,11 +87,11 @@ pub fn create_story(
                 let _ = std::fs::remove_dir_all(&git_repo_path);
             }
             let _ = StoryRepository::delete(&db, &story.id);
-            return Err(format!("Failed to update current branch: {e}"));
+            return Err(CommandError::from(e).context("Failed to update current branch"));
         }
 
         // Return the updated story
-        return StoryRepository::find_by_id(&db, &story.id).map_err(|e| e.to_string());
+        return StoryRepository::find_by_id(&db, &story.id).map_err(CommandError::from);
     }
 
     // For stories within containers, just return the created story
@@ -79,57 +99,294 @@ pub fn create_story(
 }
 
 #[tauri::command]
-pub fn get_story(db: State<Database>, id: String) -> Result<Story, String> {
-    StoryRepository::find_by_id(&db, &id).map_err(|e| e.to_string())
+pub fn get_story(db: State<Database>, id: String) -> CommandResult<Story> {
+    StoryRepository::find_by_id(&db, &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn list_stories_by_universe(
     db: State<Database>,
     universe_id: String,
-) -> Result<Vec<Story>, String> {
-    StoryRepository::list_by_universe(&db, &universe_id).map_err(|e| e.to_string())
+) -> CommandResult<Vec<Story>> {
+    StoryRepository::list_by_universe(&db, &universe_id).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn list_story_variations(
     db: State<Database>,
     variation_group_id: String,
-) -> Result<Vec<Story>, String> {
-    StoryRepository::list_by_variation_group(&db, &variation_group_id).map_err(|e| e.to_string())
+) -> CommandResult<Vec<Story>> {
+    StoryRepository::list_by_variation_group(&db, &variation_group_id).map_err(CommandError::from)
 }
 
+/// Update a story and mirror its content into its git repository
+///
+/// The database is updated right away. Content edits are handed to the
+/// autosave service, which commits the story's markdown file once the writer
+/// goes idle or enough words changed; with autosave disabled they are
+/// committed immediately. Title and order changes rename the file and are
+/// committed immediately, together with any pending edits. Stories
+/// whose repo hasn't been initialized yet are only saved to the database.
+/// Standalone stories also refresh their metadata.json when their
+/// descriptive fields change.
+///
+/// `origin` attributes the change in the journal and defaults to the UI;
+/// the editor's background saves pass `autosave`.
 #[tauri::command]
 pub fn update_story(
     db: State<Database>,
+    autosave: State<AutosaveService>,
     id: String,
     input: UpdateStoryInput,
-) -> Result<Story, String> {
-    StoryRepository::update(&db, &id, input).map_err(|e| e.to_string())
+    origin: Option<ChangeOrigin>,
+) -> CommandResult<Story> {
+    JournalRepository::with_origin(origin.unwrap_or_default(), || {
+        save_story(&db, &autosave, &id, input)
+    })
 }
 
-#[tauri::command]
-pub fn delete_story(app: AppHandle, db: State<Database>, id: String) -> Result<(), String> {
-    // Delete the story
-    StoryRepository::delete(&db, &id).map_err(|e| e.to_string())?;
+fn save_story(
+    db: &Database,
+    autosave: &AutosaveService,
+    id: &str,
+    input: UpdateStoryInput,
+) -> CommandResult<Story> {
+    let content_changed = input.content.is_some();
+    let metadata_changed = input.title.is_some()
+        || input.description.is_some()
+        || input.story_type.is_some()
+        || input.status.is_some()
+        || input.target_word_count.is_some();
+    let previous = StoryRepository::find_by_id(db, id)?;
 
-    // Get app data directory for git repos
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
-
-    // Delete git repository for the story (if it exists)
-    let git_repos_dir = app_data_dir.join("git-repos");
-    let repo_path = git_repos_dir.join(&id);
-    if repo_path.exists() {
-        std::fs::remove_dir_all(&repo_path)
-            .map_err(|e| format!("Failed to delete git repo for {id}: {e}"))?;
+    let story = StoryRepository::update(db, id, input)?;
+
+    let Some(repo_path) = StoryRepository::find_git_repo_path(db, &story)? else {
+        return Ok(story);
+    };
+    let repo_path = PathBuf::from(repo_path);
+    let previous_filename = story_filename(&previous);
+
+    let renamed = story_filename(&story) != previous_filename;
+    if renamed {
+        // The commit carries the latest content, so pending edits are covered
+        autosave.discard(&story.id);
+        sync_story_file(&repo_path, Some(&previous_filename), &story, None)
+            .map_err(|e| CommandError::from(e).context("Story saved but not committed"))?;
+    } else if content_changed && autosave.record_edit(&story, &repo_path, &previous_filename) {
+        autosave
+            .flush(db, &story.id)
+            .map_err(|e| CommandError::from(e).context("Story saved but not committed"))?;
     }
 
+    if metadata_changed && story.should_have_git_repo() {
+        update_metadata_file(&repo_path, &story)
+            .map_err(|e| CommandError::from(e).context("Story saved but metadata not updated"))?;
+    } else if metadata_changed && story.container_id.is_some() {
+        update_chapter_record(&repo_path, &story)
+            .map_err(|e| CommandError::from(e).context("Story saved but metadata not updated"))?;
+    }
+
+    Ok(story)
+}
+
+/// Bring back one passage of a story from an older commit
+///
+/// Pending autosave edits are committed first, so the restore lands on top
+/// of them. The passage is inserted near its old neighbours and committed,
+/// and the story's content is updated to match the file.
+///
+/// # Arguments
+/// * `story_id` - Story to restore the passage into
+/// * `commit_hash` - Commit containing the passage (from `git_search_history`)
+/// * `file_path` - Path of the file at that commit
+/// * `paragraph_index` - Position of the paragraph in that file (0-based)
+#[tauri::command]
+pub fn restore_story_passage(
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    story_id: String,
+    commit_hash: String,
+    file_path: String,
+    paragraph_index: u32,
+) -> CommandResult<Story> {
+    let story = StoryRepository::find_by_id(&db, &story_id)?;
+    let Some(repo_path) = StoryRepository::find_git_repo_path(&db, &story)? else {
+        return Err(
+            CommandError::new(ErrorCode::NotFound, "Story has no Git repository")
+                .with_detail("story_id", &story.id),
+        );
+    };
+    let repo_path = PathBuf::from(repo_path);
+    let filename = story_filename(&story);
+
+    autosave.flush(&db, &story.id)?;
+    GitService::restore_passage(
+        &repo_path,
+        &commit_hash,
+        &file_path,
+        paragraph_index,
+        &filename,
+    )?;
+
+    let content = std::fs::read_to_string(repo_path.join(&filename))
+        .map_err(|e| CommandError::from(e).context("Passage restored but story not updated"))?;
+    let input = UpdateStoryInput {
+        content: Some(content),
+        ..Default::default()
+    };
+    StoryRepository::update(&db, &story.id, input).map_err(CommandError::from)
+}
+
+/// Move a story to the trash
+///
+/// Pending autosave edits are committed first so a restored story comes
+/// back exactly as it was left. The deletion can be undone.
+#[tauri::command]
+pub fn delete_story(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+) -> CommandResult<()> {
+    let story = StoryRepository::find_by_id(&db, &id)?;
+    autosave.flush_before_trash(&db, [id.as_str()]);
+
+    undo::perform(
+        &db,
+        &app_data_dir(&app)?,
+        &story.universe_id,
+        &format!("Delete {}", story.title),
+        vec![UndoStep::new(
+            UndoOperation::Trash {
+                entity_type: TrashEntityType::Story,
+                id: id.clone(),
+            },
+            UndoOperation::Restore {
+                entity_type: TrashEntityType::Story,
+                id,
+            },
+        )],
+    )?;
+
     Ok(())
 }
 
+/// Move a story into a container, or out of one to stand alone
+///
+/// The story's file moves to the destination repository, and the commits
+/// made before the move stay reachable there under
+/// `refs/history/<story id>/`. Moving to another universe drops its related
+/// elements. The move can be undone from the universe it came from.
+///
+/// # Arguments
+/// * `container_id` - Destination container, or None to stand alone
+/// * `universe_id` - Destination universe (defaults to the container's, or
+///   the story's current universe)
+#[tauri::command]
+pub fn move_story(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+    container_id: Option<String>,
+    universe_id: Option<String>,
+) -> CommandResult<Story> {
+    let story = StoryRepository::find_by_id(&db, &id)?;
+    // Pending edits would otherwise be committed to the old filename
+    if let Err(e) = autosave.flush(&db, &id) {
+        log::warn!("Failed to commit pending edits for {id} before moving: {e}");
+    }
+    autosave.discard(&id);
+
+    undo::perform(
+        &db,
+        &app_data_dir(&app)?,
+        &story.universe_id,
+        &format!("Move {}", story.title),
+        vec![UndoStep::new(
+            UndoOperation::MoveStory {
+                story_id: id.clone(),
+                universe_id,
+                container_id,
+                order: None,
+            },
+            UndoOperation::MoveStory {
+                story_id: id.clone(),
+                universe_id: Some(story.universe_id.clone()),
+                container_id: story.container_id.clone(),
+                order: story.order,
+            },
+        )],
+    )?;
+
+    StoryRepository::find_by_id(&db, &id).map_err(CommandError::from)
+}
+
+/// Split a story in two at a paragraph
+///
+/// The story keeps the paragraphs before `paragraph_index` and a new story
+/// with the rest is placed right after it. Pending autosave edits in the
+/// container are committed first, since later stories' files are renamed.
+///
+/// # Arguments
+/// * `paragraph_index` - First paragraph of the new story (0-based)
+/// * `new_title` - Title of the new story
+///
+/// # Returns
+/// The shortened story followed by the new one
+#[tauri::command]
+pub fn split_story(
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+    paragraph_index: u32,
+    new_title: String,
+) -> CommandResult<Vec<Story>> {
+    autosave.flush_all(&db);
+    let (original, created) =
+        restructure::split_story(&db, &id, paragraph_index as usize, &new_title)?;
+    Ok(vec![original, created])
+}
+
+/// Merge sibling stories into the first of them, in container order
+///
+/// The other stories are moved to the trash. Pending autosave edits are
+/// committed first, since the merge renames files.
+///
+/// # Returns
+/// The merged story
+#[tauri::command]
+pub fn merge_stories(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    story_ids: Vec<String>,
+) -> CommandResult<Story> {
+    autosave.flush_all(&db);
+    restructure::merge_stories(&db, &app_data_dir(&app)?, &story_ids).map_err(CommandError::from)
+}
+
+/// Copy a story
+///
+/// A story in a container is copied after the container's last story; a
+/// standalone story gets a repository of its own. Pending autosave edits
+/// are committed first, so the copy has the latest text.
+///
+/// # Arguments
+/// * `history` - Whether a standalone copy's repository keeps its history
+#[tauri::command]
+pub fn duplicate_story(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+    history: HistoryMode,
+) -> CommandResult<Story> {
+    autosave.flush_all(&db);
+    duplicate::duplicate_story(&db, &app_data_dir(&app)?, &id, history).map_err(CommandError::from)
+}
+
 /// Ensure a story has a git repository initialized.
 /// This is useful for stories created before git integration was added.
 /// Also syncs the current_branch field with the actual git branch.
@@ -142,15 +399,18 @@ pub fn ensure_story_git_repo(
     app: AppHandle,
     db: State<Database>,
     id: String,
-) -> Result<Story, String> {
+) -> CommandResult<Story> {
     // Get the story
-    let story = StoryRepository::find_by_id(&db, &id).map_err(|e| e.to_string())?;
+    let story = StoryRepository::find_by_id(&db, &id)?;
 
     // Only standalone stories should have their own git repos
     if !story.should_have_git_repo() {
-        return Err(format!(
-            "Story '{}' is within a container and shares the container's git repo",
-            story.id
+        return Err(CommandError::new(
+            ErrorCode::InvalidOperation,
+            format!(
+                "Story '{}' is within a container and shares the container's git repo",
+                story.id
+            ),
         ));
     }
 
@@ -160,22 +420,27 @@ pub fn ensure_story_git_repo(
         if path.exists() && path.join(".git").exists() {
             // Validate repository integrity
             if let Err(e) = GitService::validate_repo_integrity(path) {
-                return Err(format!(
-                    "Git repository corruption detected at {}: {}. \
-                     To fix this, you can either manually repair the repository or \
-                     delete it and reinitialize by setting git_repo_path to empty string and calling this command again.",
-                    story.git_repo_path, e
-                ));
+                return Err(CommandError::new(
+                    ErrorCode::RepositoryCorrupted,
+                    format!(
+                        "Git repository corruption detected at {}: {}. \
+                         To fix this, you can either manually repair the repository or \
+                         delete it and reinitialize by setting git_repo_path to empty string and calling this command again.",
+                        story.git_repo_path, e
+                    ),
+                )
+                .with_detail("path", story.git_repo_path.clone()));
             }
 
             // Sync the current branch name from the actual repo
             let actual_branch = GitService::get_current_branch(path)
-                .map_err(|e| format!("Failed to get current branch: {e}"))?;
+                .map_err(|e| CommandError::from(e).context("Failed to get current branch"))?;
 
             if actual_branch != story.current_branch {
-                StoryRepository::set_current_branch(&db, &story.id, &actual_branch)
-                    .map_err(|e| format!("Failed to update current branch: {e}"))?;
-                return StoryRepository::find_by_id(&db, &story.id).map_err(|e| e.to_string());
+                StoryRepository::set_current_branch(&db, &story.id, &actual_branch).map_err(
+                    |e| CommandError::from(e).context("Failed to update current branch"),
+                )?;
+                return StoryRepository::find_by_id(&db, &story.id).map_err(CommandError::from);
             }
 
             return Ok(story);
@@ -186,17 +451,111 @@ pub fn ensure_story_git_repo(
     let app_data_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+        .map_err(|e| CommandError::from(e).context("Failed to get app data directory"))?;
 
     // Initialize git repository for the story
     let git_repo_path = GitService::init_repo(&app_data_dir, &story.id)
-        .map_err(|e| format!("Failed to initialize git repository: {e}"))?;
+        .map_err(|e| CommandError::from(e).context("Failed to initialize git repository"))?;
+    if let Err(e) = write_metadata_file(&git_repo_path, &story) {
+        // Rollback: Delete the git repo directory so the next call starts fresh
+        let _ = std::fs::remove_dir_all(&git_repo_path);
+        return Err(CommandError::from(e).context("Failed to write story metadata"));
+    }
 
     // Update the story with the git repo path
     let git_repo_path_str = git_repo_path.to_string_lossy().to_string();
-    StoryRepository::set_git_repo_path(&db, &story.id, &git_repo_path_str)
-        .map_err(|e| format!("Failed to update git repo path: {e}"))?;
+    if let Err(e) = StoryRepository::set_git_repo_path(&db, &story.id, &git_repo_path_str) {
+        // Rollback: Delete the git repo directory
+        let _ = std::fs::remove_dir_all(&git_repo_path);
+        return Err(CommandError::from(e).context("Failed to update git repo path"));
+    }
 
     // Return the updated story
-    StoryRepository::find_by_id(&db, &story.id).map_err(|e| e.to_string())
+    StoryRepository::find_by_id(&db, &story.id).map_err(CommandError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autosave::AutosaveConfig;
+    use crate::models::StoryType;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn setup(temp_dir: &TempDir) -> (Database, Story) {
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+             VALUES ('universe-1', 'Test Universe', 'A test universe', datetime('now'), datetime('now'), 'active')",
+            &[],
+        )
+        .unwrap();
+
+        let story = StoryRepository::create(
+            &db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: "Chapter 3".to_string(),
+                description: Some(String::new()),
+                story_type: Some(StoryType::Chapter),
+                content: None,
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: None,
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), &story.id).unwrap();
+        StoryRepository::set_git_repo_path(&db, &story.id, &repo_path.to_string_lossy()).unwrap();
+        let story = StoryRepository::find_by_id(&db, &story.id).unwrap();
+        (db, story)
+    }
+
+    fn commit_count(repo_path: &Path) -> usize {
+        GitService::get_history(repo_path, "original")
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_saves_with_autosave_disabled_are_not_committed() {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, story) = setup(&temp_dir);
+        let repo_path = PathBuf::from(&story.git_repo_path);
+        let autosave = AutosaveService::default();
+        autosave.set_config(AutosaveConfig {
+            enabled: false,
+            idle_ms: 1_000,
+            max_words: 1,
+        });
+        let commits = commit_count(&repo_path);
+
+        for content in ["One", "One two", "One two three"] {
+            let input = UpdateStoryInput {
+                content: Some(content.to_string()),
+                ..Default::default()
+            };
+            save_story(&db, &autosave, &story.id, input).unwrap();
+        }
+        assert_eq!(commit_count(&repo_path), commits);
+
+        // Committing by hand picks up the latest save
+        autosave.flush(&db, &story.id).unwrap();
+        assert_eq!(commit_count(&repo_path), commits + 1);
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("content.md")).unwrap(),
+            "One two three"
+        );
+    }
 }
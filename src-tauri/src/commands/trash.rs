@@ -0,0 +1,70 @@
+use crate::commands::error::{CommandError, CommandResult};
+use crate::db::Database;
+use crate::trash::{self, TrashEntityType, TrashItem};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+/// The app data directory, which holds `git-repos` and the trash
+pub(crate) fn app_data_dir(app: &AppHandle) -> CommandResult<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| CommandError::from(e).context("Failed to get app data directory"))
+}
+
+/// Directory trashed repositories are kept in
+pub(crate) fn trash_dir(app: &AppHandle) -> CommandResult<PathBuf> {
+    Ok(trash::trash_dir(&app_data_dir(app)?))
+}
+
+/// List the trash
+///
+/// # Arguments
+/// * `universe_id` - List containers and stories deleted from this universe,
+///   or None to list deleted universes
+#[tauri::command]
+pub fn list_trash(
+    db: State<Database>,
+    universe_id: Option<String>,
+) -> CommandResult<Vec<TrashItem>> {
+    trash::list_trash(&db, universe_id.as_deref()).map_err(CommandError::from)
+}
+
+/// Restore a trashed universe, container or story with everything that was
+/// deleted along with it
+#[tauri::command]
+pub fn restore_from_trash(
+    app: AppHandle,
+    db: State<Database>,
+    entity_type: TrashEntityType,
+    id: String,
+) -> CommandResult<TrashItem> {
+    trash::restore(&db, &trash_dir(&app)?, entity_type, &id).map_err(CommandError::from)
+}
+
+/// Permanently delete a trashed universe, container or story
+#[tauri::command]
+pub fn purge_from_trash(
+    app: AppHandle,
+    db: State<Database>,
+    entity_type: TrashEntityType,
+    id: String,
+) -> CommandResult<()> {
+    trash::purge(&db, &trash_dir(&app)?, entity_type, &id).map_err(CommandError::from)
+}
+
+/// Permanently delete everything that has been in the trash for longer than
+/// the retention period
+///
+/// The frontend calls this on startup when the user has set a retention
+/// period; without one, the trash is only emptied explicitly.
+///
+/// # Returns
+/// The entries that were purged
+#[tauri::command]
+pub fn purge_expired_trash(
+    app: AppHandle,
+    db: State<Database>,
+    retention_days: u32,
+) -> CommandResult<Vec<TrashItem>> {
+    trash::purge_expired(&db, &trash_dir(&app)?, retention_days).map_err(CommandError::from)
+}
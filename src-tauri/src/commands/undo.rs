@@ -0,0 +1,45 @@
+use crate::autosave::AutosaveService;
+use crate::commands::error::{CommandError, CommandResult};
+use crate::commands::trash::app_data_dir;
+use crate::db::Database;
+use crate::undo::{self, UndoEntry, UndoState};
+use tauri::{AppHandle, State};
+
+/// Undo the latest structural change in a universe
+///
+/// Pending autosave edits are committed first, since undoing a reorder
+/// renames story files.
+///
+/// # Returns
+/// The undone change, or None if there was nothing to undo
+#[tauri::command]
+pub fn undo(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    universe_id: String,
+) -> CommandResult<Option<UndoEntry>> {
+    autosave.flush_all(&db);
+    undo::undo(&db, &app_data_dir(&app)?, &universe_id).map_err(CommandError::from)
+}
+
+/// Redo the latest undone structural change in a universe
+///
+/// # Returns
+/// The redone change, or None if there was nothing to redo
+#[tauri::command]
+pub fn redo(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    universe_id: String,
+) -> CommandResult<Option<UndoEntry>> {
+    autosave.flush_all(&db);
+    undo::redo(&db, &app_data_dir(&app)?, &universe_id).map_err(CommandError::from)
+}
+
+/// Get the changes undo and redo would apply next in a universe
+#[tauri::command]
+pub fn get_undo_state(db: State<Database>, universe_id: String) -> CommandResult<UndoState> {
+    undo::undo_state(&db, &universe_id).map_err(CommandError::from)
+}
@@ -1,24 +1,26 @@
+use crate::autosave::AutosaveService;
+use crate::commands::error::{CommandError, CommandResult};
+use crate::commands::trash::{app_data_dir, trash_dir};
 use crate::db::Database;
-use crate::models::{CreateUniverseInput, Universe, UpdateUniverseInput};
-use crate::repositories::UniverseRepository;
-use tauri::State;
+use crate::duplicate::{self, HistoryMode};
+use crate::models::{CreateUniverseInput, Universe, UniverseStatus, UpdateUniverseInput};
+use crate::repositories::{StoryRepository, UniverseRepository};
+use crate::trash::{self, TrashEntityType};
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub fn create_universe(
-    db: State<Database>,
-    input: CreateUniverseInput,
-) -> Result<Universe, String> {
-    UniverseRepository::create(&db, input).map_err(|e| e.to_string())
+pub fn create_universe(db: State<Database>, input: CreateUniverseInput) -> CommandResult<Universe> {
+    UniverseRepository::create(&db, input).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn get_universe(db: State<Database>, id: String) -> Result<Universe, String> {
-    UniverseRepository::find_by_id(&db, &id).map_err(|e| e.to_string())
+pub fn get_universe(db: State<Database>, id: String) -> CommandResult<Universe> {
+    UniverseRepository::find_by_id(&db, &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn list_universes(db: State<Database>) -> Result<Vec<Universe>, String> {
-    UniverseRepository::list_all(&db).map_err(|e| e.to_string())
+pub fn list_universes(db: State<Database>) -> CommandResult<Vec<Universe>> {
+    UniverseRepository::list_all(&db).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -26,11 +28,69 @@ pub fn update_universe(
     db: State<Database>,
     id: String,
     input: UpdateUniverseInput,
-) -> Result<Universe, String> {
-    UniverseRepository::update(&db, &id, input).map_err(|e| e.to_string())
+) -> CommandResult<Universe> {
+    UniverseRepository::update(&db, &id, input).map_err(CommandError::from)
+}
+
+/// Move a universe and everything in it to the trash
+///
+/// Pending autosave edits of the stories inside are committed first.
+#[tauri::command]
+pub fn delete_universe(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+) -> CommandResult<()> {
+    let stories = StoryRepository::list_by_universe(&db, &id)?;
+    autosave.flush_before_trash(&db, stories.iter().map(|story| story.id.as_str()));
+
+    trash::move_to_trash(&db, &trash_dir(&app)?, TrashEntityType::Universe, &id)?;
+    Ok(())
 }
 
+/// Copy a universe with its elements, containers and stories
+///
+/// Pending autosave edits are committed first, so the copy has the latest
+/// text.
+///
+/// # Arguments
+/// * `name` - Name of the copy ("<name> (copy)" if None)
+/// * `status` - Status of the copy, e.g. `template` to save it as one
+///   (the original's, if None)
+/// * `history` - Whether the copied repositories keep their history
 #[tauri::command]
-pub fn delete_universe(db: State<Database>, id: String) -> Result<(), String> {
-    UniverseRepository::delete(&db, &id).map_err(|e| e.to_string())
+pub fn duplicate_universe(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    id: String,
+    name: Option<String>,
+    status: Option<UniverseStatus>,
+    history: HistoryMode,
+) -> CommandResult<Universe> {
+    autosave.flush_all(&db);
+    duplicate::duplicate_universe(
+        &db,
+        &app_data_dir(&app)?,
+        &id,
+        name.as_deref(),
+        status,
+        history,
+    )
+    .map_err(CommandError::from)
+}
+
+/// Create an active universe from a template, without its history
+#[tauri::command]
+pub fn create_universe_from_template(
+    app: AppHandle,
+    db: State<Database>,
+    autosave: State<AutosaveService>,
+    template_id: String,
+    name: String,
+) -> CommandResult<Universe> {
+    autosave.flush_all(&db);
+    duplicate::create_universe_from_template(&db, &app_data_dir(&app)?, &template_id, &name)
+        .map_err(CommandError::from)
 }
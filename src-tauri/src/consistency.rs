@@ -0,0 +1,738 @@
+//! Consistency checks between the database and the git repositories
+//!
+//! Rows and repositories are created and deleted in separate steps, so a
+//! crash or a failed rollback can leave them out of sync: a repo directory
+//! with no row, a row pointing at a repo that is gone, a stale
+//! `current_branch`, or relationship lists naming deleted entities.
+//! `check_consistency` finds these problems and `apply_fix` repairs the ones
+//! that have a safe automatic fix.
+
+use crate::db::Database;
+use crate::file_management::read_metadata_file;
+use crate::git::{GitService, GitServiceError};
+use crate::recovery::{import_repo, RecoveryError};
+use crate::repositories::{ContainerRepository, StoryRepository};
+use log::warn;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Error type for consistency checks and fixes
+#[derive(Debug)]
+pub enum ConsistencyError {
+    /// Reading or writing the database failed
+    Database(rusqlite::Error),
+    /// Reading the git-repos directory failed
+    Io(std::io::Error),
+    /// Reading a repository failed
+    Git(GitServiceError),
+    /// The issue has no automatic fix, or no longer applies
+    NotFixable(String),
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsistencyError::Database(e) => write!(f, "Database error: {e}"),
+            ConsistencyError::Io(e) => write!(f, "IO error: {e}"),
+            ConsistencyError::Git(e) => write!(f, "{e}"),
+            ConsistencyError::NotFixable(msg) => write!(f, "Cannot fix automatically: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ConsistencyError {}
+
+impl From<rusqlite::Error> for ConsistencyError {
+    fn from(err: rusqlite::Error) -> Self {
+        ConsistencyError::Database(err)
+    }
+}
+
+impl From<std::io::Error> for ConsistencyError {
+    fn from(err: std::io::Error) -> Self {
+        ConsistencyError::Io(err)
+    }
+}
+
+impl From<GitServiceError> for ConsistencyError {
+    fn from(err: GitServiceError) -> Self {
+        ConsistencyError::Git(err)
+    }
+}
+
+impl From<RecoveryError> for ConsistencyError {
+    fn from(err: RecoveryError) -> Self {
+        match err {
+            RecoveryError::Database(e) => ConsistencyError::Database(e),
+            RecoveryError::Io(e) => ConsistencyError::Io(e),
+        }
+    }
+}
+
+/// Result type alias for consistency operations
+pub type ConsistencyResult<T> = Result<T, ConsistencyError>;
+
+/// The kind of inconsistency found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub enum ConsistencyIssueKind {
+    /// A directory in git-repos that no story or container refers to
+    OrphanedRepo,
+    /// A row whose git_repo_path does not exist on disk
+    MissingRepo,
+    /// A row whose repository fails the integrity check
+    CorruptedRepo,
+    /// A row whose current_branch differs from the repository HEAD
+    BranchMismatch,
+    /// A story's related_element_ids names a deleted element
+    DanglingElementReference,
+    /// An element's related_story_ids names a deleted story
+    DanglingStoryReference,
+}
+
+/// The kind of database row an issue is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum ConsistencyEntity {
+    Story,
+    Container,
+    Element,
+}
+
+/// A single inconsistency between the database and the filesystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyIssue {
+    pub kind: ConsistencyIssueKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_type: Option<ConsistencyEntity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<String>,
+    /// Repository path the issue is about, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub message: String,
+    /// What `apply_fix` would do, or None if the issue needs manual attention
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+/// Result of a consistency check
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    pub issues: Vec<ConsistencyIssue>,
+}
+
+/// A story or container row that may own a repository
+struct RepoRow {
+    entity_type: ConsistencyEntity,
+    id: String,
+    git_repo_path: String,
+    current_branch: Option<String>,
+}
+
+/// Compare the database with the repositories under `git_repos_dir`
+///
+/// # Arguments
+/// * `db` - The application database
+/// * `git_repos_dir` - The app data `git-repos` directory
+///
+/// # Returns
+/// Every inconsistency found, each with a description of its automatic fix
+/// when one is available
+pub fn check_consistency(
+    db: &Database,
+    git_repos_dir: &Path,
+) -> ConsistencyResult<ConsistencyReport> {
+    let mut issues = Vec::new();
+    let repo_rows = load_repo_rows(db)?;
+
+    // Repos without rows
+    let known_ids: HashSet<String> = all_ids(db, "stories")?
+        .into_iter()
+        .chain(all_ids(db, "containers")?)
+        .collect();
+    let referenced_paths: HashSet<PathBuf> = repo_rows
+        .iter()
+        .map(|row| PathBuf::from(&row.git_repo_path))
+        .collect();
+    if git_repos_dir.exists() {
+        let mut repo_dirs = fs::read_dir(git_repos_dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        repo_dirs.sort();
+
+        for repo_path in repo_dirs {
+            let dir_id = repo_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if known_ids.contains(&dir_id) || referenced_paths.contains(&repo_path) {
+                continue;
+            }
+            let importable = GitService::validate_repo_integrity(&repo_path).is_ok()
+                && read_metadata_file(&repo_path).is_ok();
+            issues.push(ConsistencyIssue {
+                kind: ConsistencyIssueKind::OrphanedRepo,
+                entity_type: None,
+                entity_id: Some(dir_id),
+                path: Some(repo_path.to_string_lossy().to_string()),
+                message: "Repository has no matching story or container".to_string(),
+                fix: importable.then(|| "Import it into the database".to_string()),
+            });
+        }
+    }
+
+    // Rows whose repos are missing, corrupted or on another branch
+    for row in &repo_rows {
+        let repo_path = Path::new(&row.git_repo_path);
+        if !repo_path.exists() {
+            let fix = match convention_repo_path(git_repos_dir, &row.id) {
+                Some(path) => format!("Point it at {}", path.display()),
+                None => "Clear the repository path so it is re-created on next open".to_string(),
+            };
+            issues.push(row_issue(
+                row,
+                ConsistencyIssueKind::MissingRepo,
+                "Repository does not exist".to_string(),
+                Some(fix),
+            ));
+            continue;
+        }
+
+        if let Err(e) = GitService::validate_repo_integrity(repo_path) {
+            issues.push(row_issue(
+                row,
+                ConsistencyIssueKind::CorruptedRepo,
+                e.to_string(),
+                None,
+            ));
+            continue;
+        }
+
+        // A repo whose HEAD can't be read is reported without ending the check
+        let head = match head_branch(repo_path) {
+            Ok(Some(head)) => head,
+            // A detached HEAD means a snapshot is being viewed, not a branch switch
+            Ok(None) => continue,
+            Err(e) => {
+                issues.push(row_issue(
+                    row,
+                    ConsistencyIssueKind::CorruptedRepo,
+                    format!("Cannot read HEAD: {e}"),
+                    None,
+                ));
+                continue;
+            }
+        };
+        if row.current_branch.as_deref() != Some(head.as_str()) {
+            issues.push(row_issue(
+                row,
+                ConsistencyIssueKind::BranchMismatch,
+                format!(
+                    "Recorded branch is '{}' but the repository is on '{head}'",
+                    row.current_branch.as_deref().unwrap_or("")
+                ),
+                Some(format!("Set the current branch to '{head}'")),
+            ));
+        }
+    }
+
+    // Relationship lists naming deleted entities
+    let element_ids: HashSet<String> = all_ids(db, "elements")?.into_iter().collect();
+    let story_ids: HashSet<String> = all_ids(db, "stories")?.into_iter().collect();
+    for (story_id, related) in load_id_lists(db, "stories", "related_element_ids")? {
+        let missing = missing_ids(&related, &element_ids);
+        if !missing.is_empty() {
+            issues.push(dangling_issue(
+                ConsistencyIssueKind::DanglingElementReference,
+                ConsistencyEntity::Story,
+                story_id,
+                &missing,
+                "element",
+            ));
+        }
+    }
+    for (element_id, related) in load_id_lists(db, "elements", "related_story_ids")? {
+        let missing = missing_ids(&related, &story_ids);
+        if !missing.is_empty() {
+            issues.push(dangling_issue(
+                ConsistencyIssueKind::DanglingStoryReference,
+                ConsistencyEntity::Element,
+                element_id,
+                &missing,
+                "story",
+            ));
+        }
+    }
+
+    Ok(ConsistencyReport { issues })
+}
+
+/// Apply the automatic fix for an issue returned by `check_consistency`
+///
+/// The situation is re-examined before anything is changed, so applying a
+/// fix that is no longer needed is harmless.
+///
+/// # Arguments
+/// * `db` - The application database
+/// * `git_repos_dir` - The app data `git-repos` directory
+/// * `issue` - The issue to fix
+///
+/// # Errors
+/// Returns `ConsistencyError::NotFixable` for issues without an automatic fix
+/// (corrupted repos, repos without readable metadata)
+pub fn apply_fix(
+    db: &Database,
+    git_repos_dir: &Path,
+    issue: &ConsistencyIssue,
+) -> ConsistencyResult<()> {
+    let entity_id = issue
+        .entity_id
+        .as_deref()
+        .ok_or_else(|| ConsistencyError::NotFixable("The issue names no entity".to_string()))?;
+
+    match issue.kind {
+        ConsistencyIssueKind::OrphanedRepo => {
+            let repo_path = git_repos_dir.join(entity_id);
+            let report = import_repo(db, &repo_path)?;
+            if let Some(problem) = report.orphaned_repos.iter().chain(&report.conflicts).next() {
+                return Err(ConsistencyError::NotFixable(problem.reason.clone()));
+            }
+        }
+        ConsistencyIssueKind::MissingRepo => {
+            let replacement = convention_repo_path(git_repos_dir, entity_id)
+                .map(|path| path.to_string_lossy().to_string());
+            match (issue.entity_type, replacement) {
+                (Some(ConsistencyEntity::Story), Some(path)) => {
+                    StoryRepository::set_git_repo_path(db, entity_id, &path)?
+                }
+                (Some(ConsistencyEntity::Story), None) => {
+                    StoryRepository::set_git_repo_path(db, entity_id, "")?
+                }
+                (Some(ConsistencyEntity::Container), Some(path)) => {
+                    ContainerRepository::set_git_repo_path(db, entity_id, &path)?
+                }
+                (Some(ConsistencyEntity::Container), None) => {
+                    ContainerRepository::clear_git_repo_path(db, entity_id)?
+                }
+                _ => return Err(not_fixable(issue)),
+            }
+        }
+        ConsistencyIssueKind::BranchMismatch => {
+            let repo_path = issue.path.as_deref().ok_or_else(|| not_fixable(issue))?;
+            let head = GitService::get_current_branch(Path::new(repo_path))?;
+            match issue.entity_type {
+                Some(ConsistencyEntity::Story) => {
+                    StoryRepository::set_current_branch(db, entity_id, &head)?
+                }
+                Some(ConsistencyEntity::Container) => {
+                    ContainerRepository::set_current_branch(db, entity_id, &head)?
+                }
+                _ => return Err(not_fixable(issue)),
+            }
+        }
+        ConsistencyIssueKind::DanglingElementReference => {
+            let element_ids: HashSet<String> = all_ids(db, "elements")?.into_iter().collect();
+            prune_id_list(
+                db,
+                "stories",
+                "related_element_ids",
+                entity_id,
+                &element_ids,
+            )?;
+        }
+        ConsistencyIssueKind::DanglingStoryReference => {
+            let story_ids: HashSet<String> = all_ids(db, "stories")?.into_iter().collect();
+            prune_id_list(db, "elements", "related_story_ids", entity_id, &story_ids)?;
+        }
+        ConsistencyIssueKind::CorruptedRepo => return Err(not_fixable(issue)),
+    }
+
+    Ok(())
+}
+
+/// Apply every available automatic fix, then check again
+///
+/// Fixes that fail are logged and skipped; their issues remain in the
+/// returned report along with the issues that need manual attention.
+pub fn fix_all(db: &Database, git_repos_dir: &Path) -> ConsistencyResult<ConsistencyReport> {
+    let report = check_consistency(db, git_repos_dir)?;
+    for issue in report.issues.iter().filter(|issue| issue.fix.is_some()) {
+        if let Err(e) = apply_fix(db, git_repos_dir, issue) {
+            warn!("Consistency fix failed for {:?}: {e}", issue.entity_id);
+        }
+    }
+    check_consistency(db, git_repos_dir)
+}
+
+fn not_fixable(issue: &ConsistencyIssue) -> ConsistencyError {
+    ConsistencyError::NotFixable(issue.message.clone())
+}
+
+/// The branch HEAD is on, or None if HEAD is detached
+fn head_branch(repo_path: &Path) -> Result<Option<String>, GitServiceError> {
+    if GitService::is_head_detached(repo_path)? {
+        return Ok(None);
+    }
+    GitService::get_current_branch(repo_path).map(Some)
+}
+
+fn row_issue(
+    row: &RepoRow,
+    kind: ConsistencyIssueKind,
+    message: String,
+    fix: Option<String>,
+) -> ConsistencyIssue {
+    ConsistencyIssue {
+        kind,
+        entity_type: Some(row.entity_type),
+        entity_id: Some(row.id.clone()),
+        path: Some(row.git_repo_path.clone()),
+        message,
+        fix,
+    }
+}
+
+fn dangling_issue(
+    kind: ConsistencyIssueKind,
+    entity_type: ConsistencyEntity,
+    entity_id: String,
+    missing: &[String],
+    target: &str,
+) -> ConsistencyIssue {
+    ConsistencyIssue {
+        kind,
+        entity_type: Some(entity_type),
+        entity_id: Some(entity_id),
+        path: None,
+        message: format!("References deleted {target}(s): {}", missing.join(", ")),
+        fix: Some(format!(
+            "Remove {} missing {target} reference(s)",
+            missing.len()
+        )),
+    }
+}
+
+/// The `git-repos/<id>` directory for an entity, if it holds a valid repo
+fn convention_repo_path(git_repos_dir: &Path, id: &str) -> Option<PathBuf> {
+    let path = git_repos_dir.join(id);
+    GitService::validate_repo_integrity(&path)
+        .ok()
+        .map(|_| path)
+}
+
+fn missing_ids(ids: &[String], existing: &HashSet<String>) -> Vec<String> {
+    ids.iter()
+        .filter(|id| !existing.contains(*id))
+        .cloned()
+        .collect()
+}
+
+/// Standalone stories and containers that record a repository path
+fn load_repo_rows(db: &Database) -> rusqlite::Result<Vec<RepoRow>> {
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    let mut stmt = conn.prepare(
+        "SELECT id, git_repo_path, current_branch FROM stories
+         WHERE container_id IS NULL AND git_repo_path != '' AND deleted_at IS NULL
+         ORDER BY id",
+    )?;
+    let mut rows = stmt
+        .query_map([], |row| {
+            Ok(RepoRow {
+                entity_type: ConsistencyEntity::Story,
+                id: row.get(0)?,
+                git_repo_path: row.get(1)?,
+                current_branch: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, git_repo_path, current_branch FROM containers
+         WHERE git_repo_path IS NOT NULL AND git_repo_path != '' AND deleted_at IS NULL
+         ORDER BY id",
+    )?;
+    rows.extend(
+        stmt.query_map([], |row| {
+            Ok(RepoRow {
+                entity_type: ConsistencyEntity::Container,
+                id: row.get(0)?,
+                git_repo_path: row.get(1)?,
+                current_branch: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?,
+    );
+
+    Ok(rows)
+}
+
+fn all_ids(db: &Database, table: &str) -> rusqlite::Result<Vec<String>> {
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    let mut stmt = conn.prepare(&format!("SELECT id FROM {table}"))?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(ids)
+}
+
+/// Read a JSON array column of IDs for every row that has one
+fn load_id_lists(
+    db: &Database,
+    table: &str,
+    column: &str,
+) -> rusqlite::Result<Vec<(String, Vec<String>)>> {
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, {column} FROM {table} WHERE {column} IS NOT NULL ORDER BY id"
+    ))?;
+    let lists = stmt
+        .query_map([], |row| {
+            let id: String = row.get(0)?;
+            let json: String = row.get(1)?;
+            Ok((id, serde_json::from_str(&json).unwrap_or_default()))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(lists)
+}
+
+/// Remove IDs that are not in `existing` from a JSON array column
+fn prune_id_list(
+    db: &Database,
+    table: &str,
+    column: &str,
+    id: &str,
+    existing: &HashSet<String>,
+) -> rusqlite::Result<()> {
+    let Some((_, ids)) = load_id_lists(db, table, column)?
+        .into_iter()
+        .find(|(row_id, _)| row_id == id)
+    else {
+        return Ok(());
+    };
+
+    let kept: Vec<String> = ids.into_iter().filter(|i| existing.contains(i)).collect();
+    db.execute(
+        &format!("UPDATE {table} SET {column} = ?1 WHERE id = ?2"),
+        params![serde_json::to_string(&kept).unwrap(), id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_management::write_metadata_file;
+    use crate::models::{CreateElementInput, CreateStoryInput, ElementType, StoryType};
+    use crate::repositories::ElementRepository;
+    use tempfile::TempDir;
+
+    fn setup_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+            conn.execute(
+                "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+                 VALUES ('universe-1', 'Test Universe', 'Test', '2024-01-01', '2024-01-01', 'active')",
+                [],
+            )
+            .unwrap();
+        }
+        (db, temp_dir)
+    }
+
+    /// Create a standalone story with a repo under `base/git-repos`
+    fn create_story_with_repo(db: &Database, base: &Path, title: &str) -> (String, PathBuf) {
+        let story = StoryRepository::create(
+            db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: title.to_string(),
+                description: Some("Test".to_string()),
+                story_type: Some(StoryType::ShortStory),
+                content: None,
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: None,
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap();
+        let repo_path = GitService::init_repo(base, &story.id).unwrap();
+        StoryRepository::set_git_repo_path(db, &story.id, &repo_path.to_string_lossy()).unwrap();
+        StoryRepository::set_current_branch(db, &story.id, "original").unwrap();
+        (story.id, repo_path)
+    }
+
+    fn kinds(report: &ConsistencyReport) -> Vec<ConsistencyIssueKind> {
+        report.issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_check_consistency_clean() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        create_story_with_repo(&db, &base, "Clean");
+
+        let report = check_consistency(&db, &base.join("git-repos")).unwrap();
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_orphaned_repo_is_imported() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let git_repos = base.join("git-repos");
+        let (story_id, repo_path) = create_story_with_repo(&db, &base, "Left Behind");
+        let story = StoryRepository::find_by_id(&db, &story_id).unwrap();
+        write_metadata_file(&repo_path, &story).unwrap();
+        StoryRepository::delete(&db, &story_id).unwrap();
+
+        let report = check_consistency(&db, &git_repos).unwrap();
+        assert_eq!(kinds(&report), vec![ConsistencyIssueKind::OrphanedRepo]);
+        assert!(report.issues[0].fix.is_some());
+
+        apply_fix(&db, &git_repos, &report.issues[0]).unwrap();
+
+        let restored = StoryRepository::find_by_id(&db, &story_id).unwrap();
+        assert_eq!(restored.title, "Left Behind");
+        assert!(check_consistency(&db, &git_repos)
+            .unwrap()
+            .issues
+            .is_empty());
+    }
+
+    #[test]
+    fn test_missing_and_corrupted_repos() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let git_repos = base.join("git-repos");
+        let (missing_id, missing_path) = create_story_with_repo(&db, &base, "Missing");
+        let (_, corrupted_path) = create_story_with_repo(&db, &base, "Corrupted");
+        fs::remove_dir_all(&missing_path).unwrap();
+        fs::remove_dir_all(corrupted_path.join(".git").join("refs")).unwrap();
+
+        let report = check_consistency(&db, &git_repos).unwrap();
+        let mut found = kinds(&report);
+        found.sort_by_key(|kind| format!("{kind:?}"));
+        assert_eq!(
+            found,
+            vec![
+                ConsistencyIssueKind::CorruptedRepo,
+                ConsistencyIssueKind::MissingRepo
+            ]
+        );
+
+        let corrupted = report
+            .issues
+            .iter()
+            .find(|i| i.kind == ConsistencyIssueKind::CorruptedRepo)
+            .unwrap();
+        assert!(corrupted.fix.is_none());
+        assert!(matches!(
+            apply_fix(&db, &git_repos, corrupted),
+            Err(ConsistencyError::NotFixable(_))
+        ));
+
+        let missing = report
+            .issues
+            .iter()
+            .find(|i| i.kind == ConsistencyIssueKind::MissingRepo)
+            .unwrap();
+        apply_fix(&db, &git_repos, missing).unwrap();
+        let story = StoryRepository::find_by_id(&db, &missing_id).unwrap();
+        assert_eq!(story.git_repo_path, "");
+    }
+
+    #[test]
+    fn test_branch_mismatch_is_synced() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let git_repos = base.join("git-repos");
+        let (story_id, repo_path) = create_story_with_repo(&db, &base, "Branchy");
+        GitService::create_branch(&repo_path, "original", "rewrite").unwrap();
+        GitService::checkout_branch(&repo_path, "rewrite").unwrap();
+
+        let report = fix_all(&db, &git_repos).unwrap();
+
+        assert!(report.issues.is_empty());
+        let story = StoryRepository::find_by_id(&db, &story_id).unwrap();
+        assert_eq!(story.current_branch, "rewrite");
+    }
+
+    #[test]
+    fn test_dangling_references_are_pruned() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let git_repos = base.join("git-repos");
+        let (story_id, _) = create_story_with_repo(&db, &base, "Related");
+        let element = ElementRepository::create(
+            &db,
+            CreateElementInput {
+                universe_id: "universe-1".to_string(),
+                name: "Sarah".to_string(),
+                description: Some("Protagonist".to_string()),
+                element_type: Some(ElementType::Character),
+                custom_type_name: None,
+                details: None,
+                attributes: None,
+                image_url: None,
+                tags: None,
+                color: None,
+                icon: None,
+                relationships: None,
+            },
+        )
+        .unwrap();
+        db.execute(
+            "UPDATE stories SET related_element_ids = ?1 WHERE id = ?2",
+            params![format!("[\"{}\", \"gone-element\"]", element.id), &story_id],
+        )
+        .unwrap();
+        db.execute(
+            "UPDATE elements SET related_story_ids = ?1 WHERE id = ?2",
+            params![format!("[\"{story_id}\", \"gone-story\"]"), &element.id],
+        )
+        .unwrap();
+
+        let report = check_consistency(&db, &git_repos).unwrap();
+        assert_eq!(
+            kinds(&report),
+            vec![
+                ConsistencyIssueKind::DanglingElementReference,
+                ConsistencyIssueKind::DanglingStoryReference
+            ]
+        );
+        assert!(report.issues[0].message.contains("gone-element"));
+
+        let report = fix_all(&db, &git_repos).unwrap();
+        assert!(report.issues.is_empty());
+        let story = StoryRepository::find_by_id(&db, &story_id).unwrap();
+        assert_eq!(story.related_element_ids, Some(vec![element.id]));
+    }
+}
@@ -76,4 +76,59 @@ impl Database {
             Ok(None)
         }
     }
+
+    /// Run several writes as one transaction
+    ///
+    /// Repositories lock the connection for each call, so `f` uses them as
+    /// usual: everything written while it runs is committed if it succeeds
+    /// and rolled back if it fails or panics. Transactions the repositories
+    /// open themselves are savepoints, so they nest inside this one. Writes
+    /// from other threads share the connection and would land in the same
+    /// transaction, so keep `f` to a single command's work.
+    pub fn transaction<T, E>(
+        &self,
+        f: impl FnOnce() -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, E>
+    where
+        E: From<rusqlite::Error>,
+    {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute_batch("SAVEPOINT db_transaction")?;
+        let mut guard = TransactionGuard {
+            conn: &self.conn,
+            finished: false,
+        };
+
+        let value = f()?;
+        self.conn
+            .lock()
+            .unwrap()
+            .execute_batch("RELEASE db_transaction")?;
+        guard.finished = true;
+        Ok(value)
+    }
+}
+
+/// Rolls back a `Database::transaction` that didn't finish
+struct TransactionGuard<'a> {
+    conn: &'a Mutex<Connection>,
+    finished: bool,
+}
+
+impl Drop for TransactionGuard<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        // A poisoned lock means a repository panicked mid-write; roll back anyway
+        let conn = self
+            .conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(e) = conn.execute_batch("ROLLBACK TO db_transaction; RELEASE db_transaction") {
+            log::error!("Failed to roll back transaction: {e}");
+        }
+    }
 }
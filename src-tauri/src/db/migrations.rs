@@ -1,12 +1,181 @@
-use rusqlite::{Connection, Result};
+//! Versioned schema migrations
+//!
+//! Each entry in `MIGRATIONS` upgrades the schema by one version and runs in
+//! its own transaction together with the `schema_version` row that records
+//! it, so a failed step leaves the database at the previous version. Steps
+//! must carry existing rows forward: alter or copy tables rather than
+//! dropping them. Append new steps to the end of the registry; never edit or
+//! reorder one that has shipped.
 
-/// Current database schema version
-#[allow(dead_code)]
-const SCHEMA_VERSION: i32 = 1;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::fmt;
+use std::path::{Path, PathBuf};
 
-/// Run all database migrations
-pub fn run_migrations(conn: &Connection) -> Result<()> {
-    // Create schema_version table if it doesn't exist
+mod v4_text_stats;
+
+/// A single schema upgrade step
+struct Migration {
+    /// Version the schema is at after this step
+    version: i32,
+    description: &'static str,
+    up: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// All migrations, in the order they are applied
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        up: migrate_v1,
+    },
+    Migration {
+        version: 2,
+        description: "Backfill container order and index variation groups",
+        up: migrate_v2,
+    },
+    Migration {
+        version: 3,
+        description: "Full-text search index",
+        up: migrate_v3,
+    },
+    Migration {
+        version: 4,
+        description: "Story content statistics",
+        up: migrate_v4,
+    },
+    Migration {
+        version: 5,
+        description: "Writing sessions and goals",
+        up: migrate_v5,
+    },
+    Migration {
+        version: 6,
+        description: "Soft delete",
+        up: migrate_v6,
+    },
+    Migration {
+        version: 7,
+        description: "Change journal",
+        up: migrate_v7,
+    },
+    Migration {
+        version: 8,
+        description: "Undo log",
+        up: migrate_v8,
+    },
+];
+
+/// Current database schema version (the version of the last migration)
+pub const SCHEMA_VERSION: i32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Error type for database migrations
+#[derive(Debug)]
+pub enum MigrationError {
+    /// A migration step or version query failed
+    Database(rusqlite::Error),
+    /// Copying the database before the upgrade failed
+    Backup(String),
+    /// The database was written by a newer version of the app
+    NewerSchema { found: i32, supported: i32 },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Database(e) => write!(f, "Database error: {e}"),
+            MigrationError::Backup(msg) => write!(f, "Database backup failed: {msg}"),
+            MigrationError::NewerSchema { found, supported } => write!(
+                f,
+                "Database schema version {found} is newer than this app supports ({supported}). \
+                 Please update the app."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<rusqlite::Error> for MigrationError {
+    fn from(err: rusqlite::Error) -> Self {
+        MigrationError::Database(err)
+    }
+}
+
+/// Result type alias for migrations
+pub type MigrationResult<T> = Result<T, MigrationError>;
+
+/// Run all pending database migrations
+///
+/// # Errors
+/// Returns `MigrationError::NewerSchema` if the database is at a version this
+/// binary doesn't know about, or the error of the first migration that fails
+/// (earlier migrations stay applied).
+pub fn run_migrations(conn: &Connection) -> MigrationResult<()> {
+    apply_migrations(conn, MIGRATIONS)
+}
+
+/// Back up the database if it needs upgrading, then run migrations
+///
+/// A brand-new (empty) database is not backed up. The backup is a consistent
+/// copy written next to the database as
+/// `<name>.v<old version>-<timestamp>.bak`.
+///
+/// # Arguments
+/// * `conn` - Connection to the database at `db_path`
+/// * `db_path` - Path of the database file
+///
+/// # Returns
+/// The path of the backup, if one was taken
+pub fn upgrade_database(conn: &Connection, db_path: &Path) -> MigrationResult<Option<PathBuf>> {
+    ensure_version_table(conn)?;
+    let current = current_version(conn)?;
+    check_supported(current, SCHEMA_VERSION)?;
+
+    let backup = if current > 0 && current < SCHEMA_VERSION {
+        Some(backup_database(conn, db_path, current)?)
+    } else {
+        None
+    };
+
+    run_migrations(conn)?;
+    Ok(backup)
+}
+
+/// Read the schema version recorded in the database (0 if none)
+pub fn current_version(conn: &Connection) -> rusqlite::Result<i32> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )
+}
+
+fn apply_migrations(conn: &Connection, migrations: &[Migration]) -> MigrationResult<()> {
+    ensure_version_table(conn)?;
+    let current = current_version(conn)?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+    check_supported(current, latest)?;
+
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let tx = conn.unchecked_transaction()?;
+        (migration.up)(&tx)?;
+        tx.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            params![migration.version],
+        )?;
+        tx.commit()?;
+        log::info!(
+            "Applied database migration v{}: {}",
+            migration.version,
+            migration.description
+        );
+    }
+
+    Ok(())
+}
+
+fn ensure_version_table(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS schema_version (
             version INTEGER PRIMARY KEY,
@@ -14,29 +183,38 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         )",
         [],
     )?;
+    Ok(())
+}
 
-    // Get current version
-    let current_version: i32 = conn
-        .query_row(
-            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
-
-    // Apply migrations sequentially
-    if current_version < 1 {
-        migrate_v1(conn)?;
-        conn.execute("INSERT INTO schema_version (version) VALUES (1)", [])?;
+fn check_supported(found: i32, supported: i32) -> MigrationResult<()> {
+    if found > supported {
+        return Err(MigrationError::NewerSchema { found, supported });
     }
-
     Ok(())
 }
 
+/// Write a consistent copy of the database next to it
+fn backup_database(conn: &Connection, db_path: &Path, version: i32) -> MigrationResult<PathBuf> {
+    let file_name = db_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "bright.db".to_string());
+    let timestamp = Utc::now().format("%Y%m%dT%H%M%S");
+    let backup_path = db_path.with_file_name(format!("{file_name}.v{version}-{timestamp}.bak"));
+
+    conn.execute(
+        "VACUUM INTO ?1",
+        params![backup_path.to_string_lossy().to_string()],
+    )
+    .map_err(|e| MigrationError::Backup(format!("{}: {e}", backup_path.display())))?;
+
+    Ok(backup_path)
+}
+
 /// Initial schema - Version 1
-/// Creates tables for Universe and Element domain models
-/// NOTE: Stories table removed - will be recreated in container/story refactor
-fn migrate_v1(conn: &Connection) -> Result<()> {
+/// Creates tables for universes, containers, stories, elements and element
+/// relationships
+fn migrate_v1(conn: &Connection) -> rusqlite::Result<()> {
     // Universes table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS universes (
@@ -187,3 +365,464 @@ fn migrate_v1(conn: &Connection) -> Result<()> {
 
     Ok(())
 }
+
+/// Version 2
+/// Containers created before `order` was always set have NULL there, which
+/// the Container model can't represent; give them order 0. Also index the
+/// variation group lookup used when listing a story's variations.
+fn migrate_v2(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE containers SET \"order\" = 0 WHERE \"order\" IS NULL",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_stories_variation_group ON stories(variation_group_id)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 3
+/// Full-text search over stories, elements and universes. A single FTS5
+/// table holds one row per entity (title plus searchable body text) so hits
+/// from different entity types are ranked together; triggers keep it in sync
+/// with the source tables.
+fn migrate_v3(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+            entity_type UNINDEXED,
+            entity_id UNINDEXED,
+            universe_id UNINDEXED,
+            title,
+            body,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        -- Stories: title, content, notes and outline
+        CREATE TRIGGER IF NOT EXISTS stories_search_insert AFTER INSERT ON stories BEGIN
+            INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+            VALUES ('story', new.id, new.universe_id, new.title,
+                    concat_ws(char(10), nullif(new.content, ''), new.notes, new.outline));
+        END;
+        CREATE TRIGGER IF NOT EXISTS stories_search_update
+        AFTER UPDATE OF title, content, notes, outline, universe_id ON stories BEGIN
+            DELETE FROM search_index WHERE entity_type = 'story' AND entity_id = old.id;
+            INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+            VALUES ('story', new.id, new.universe_id, new.title,
+                    concat_ws(char(10), nullif(new.content, ''), new.notes, new.outline));
+        END;
+        CREATE TRIGGER IF NOT EXISTS stories_search_delete AFTER DELETE ON stories BEGIN
+            DELETE FROM search_index WHERE entity_type = 'story' AND entity_id = old.id;
+        END;
+
+        -- Elements: name, description, details and attributes as `key: value` lines
+        CREATE TRIGGER IF NOT EXISTS elements_search_insert AFTER INSERT ON elements BEGIN
+            INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+            VALUES ('element', new.id, new.universe_id, new.name,
+                    concat_ws(char(10), nullif(new.description, ''), new.details,
+                        (SELECT group_concat(key || ': ' || value, char(10))
+                         FROM json_each(CASE WHEN json_valid(new.attributes)
+                                             THEN new.attributes END))));
+        END;
+        CREATE TRIGGER IF NOT EXISTS elements_search_update
+        AFTER UPDATE OF name, description, details, attributes, universe_id ON elements BEGIN
+            DELETE FROM search_index WHERE entity_type = 'element' AND entity_id = old.id;
+            INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+            VALUES ('element', new.id, new.universe_id, new.name,
+                    concat_ws(char(10), nullif(new.description, ''), new.details,
+                        (SELECT group_concat(key || ': ' || value, char(10))
+                         FROM json_each(CASE WHEN json_valid(new.attributes)
+                                             THEN new.attributes END))));
+        END;
+        CREATE TRIGGER IF NOT EXISTS elements_search_delete AFTER DELETE ON elements BEGIN
+            DELETE FROM search_index WHERE entity_type = 'element' AND entity_id = old.id;
+        END;
+
+        -- Universes: name and worldbuilding notes
+        CREATE TRIGGER IF NOT EXISTS universes_search_insert AFTER INSERT ON universes BEGIN
+            INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+            VALUES ('universe', new.id, new.id, new.name, coalesce(new.worldbuilding_notes, ''));
+        END;
+        CREATE TRIGGER IF NOT EXISTS universes_search_update
+        AFTER UPDATE OF name, worldbuilding_notes ON universes BEGIN
+            DELETE FROM search_index WHERE entity_type = 'universe' AND entity_id = old.id;
+            INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+            VALUES ('universe', new.id, new.id, new.name, coalesce(new.worldbuilding_notes, ''));
+        END;
+        CREATE TRIGGER IF NOT EXISTS universes_search_delete AFTER DELETE ON universes BEGIN
+            DELETE FROM search_index WHERE entity_type = 'universe' AND entity_id = old.id;
+        END;
+
+        -- Index existing rows
+        INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+        SELECT 'story', id, universe_id, title, concat_ws(char(10), nullif(content, ''), notes, outline)
+        FROM stories;
+        INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+        SELECT 'element', id, universe_id, name,
+               concat_ws(char(10), nullif(description, ''), details,
+                   (SELECT group_concat(key || ': ' || value, char(10))
+                    FROM json_each(CASE WHEN json_valid(attributes) THEN attributes END)))
+        FROM elements;
+        INSERT INTO search_index (entity_type, entity_id, universe_id, title, body)
+        SELECT 'universe', id, id, name, coalesce(worldbuilding_notes, '')
+        FROM universes;",
+    )
+}
+
+/// Version 4
+/// Character, paragraph, sentence and reading-time counts for stories, kept
+/// up to date on save. Existing rows are counted here with the rules frozen
+/// in `v4_text_stats`, which also corrects word counts that were never
+/// recalculated after edits.
+fn migrate_v4(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE stories ADD COLUMN character_count INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE stories ADD COLUMN character_count_no_spaces INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE stories ADD COLUMN paragraph_count INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE stories ADD COLUMN sentence_count INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE stories ADD COLUMN reading_time_seconds INTEGER NOT NULL DEFAULT 0;",
+    )?;
+
+    let stories = conn
+        .prepare("SELECT id, content FROM stories")?
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut update = conn.prepare(
+        "UPDATE stories SET word_count = ?1, character_count = ?2,
+            character_count_no_spaces = ?3, paragraph_count = ?4, sentence_count = ?5,
+            reading_time_seconds = ?6
+         WHERE id = ?7",
+    )?;
+    for (id, content) in stories {
+        let stats = v4_text_stats::compute(&content);
+        update.execute(params![
+            stats.word_count,
+            stats.character_count,
+            stats.character_count_no_spaces,
+            stats.paragraph_count,
+            stats.sentence_count,
+            stats.reading_time_seconds,
+            id,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Version 5
+/// Per-day writing activity and writing goals. Sessions have no foreign key
+/// to stories so deleting a story doesn't rewrite the writer's history.
+fn migrate_v5(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS writing_sessions (
+            story_id TEXT NOT NULL,
+            universe_id TEXT NOT NULL,
+            day TEXT NOT NULL,
+            words_added INTEGER NOT NULL DEFAULT 0,
+            words_removed INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (story_id, day),
+            FOREIGN KEY (universe_id) REFERENCES universes(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_writing_sessions_day ON writing_sessions(day);
+        CREATE INDEX IF NOT EXISTS idx_writing_sessions_universe
+            ON writing_sessions(universe_id, day);
+
+        CREATE TABLE IF NOT EXISTS writing_goals (
+            id TEXT PRIMARY KEY,
+            goal_type TEXT NOT NULL CHECK (goal_type IN ('daily', 'deadline')),
+            target_words INTEGER NOT NULL,
+            universe_id TEXT,
+            container_id TEXT,
+            story_id TEXT,
+            deadline TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (universe_id) REFERENCES universes(id) ON DELETE CASCADE,
+            FOREIGN KEY (container_id) REFERENCES containers(id) ON DELETE CASCADE,
+            FOREIGN KEY (story_id) REFERENCES stories(id) ON DELETE CASCADE
+        );",
+    )
+}
+
+/// Version 6
+/// Soft delete: trashed universes, containers and stories keep their rows
+/// with `deleted_at` set until they are restored or purged.
+fn migrate_v6(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE universes ADD COLUMN deleted_at TEXT;
+        ALTER TABLE containers ADD COLUMN deleted_at TEXT;
+        ALTER TABLE stories ADD COLUMN deleted_at TEXT;
+        CREATE INDEX IF NOT EXISTS idx_universes_deleted_at ON universes(deleted_at);
+        CREATE INDEX IF NOT EXISTS idx_containers_deleted_at ON containers(deleted_at);
+        CREATE INDEX IF NOT EXISTS idx_stories_deleted_at ON stories(deleted_at);",
+    )
+}
+
+/// Version 7
+/// Append-only journal of field-level changes to universes, containers,
+/// elements and story metadata. Triggers reject edits to past entries.
+/// Entries have no foreign keys so they outlive the entities they describe.
+fn migrate_v7(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS change_journal (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity_type TEXT NOT NULL
+                CHECK (entity_type IN ('universe', 'container', 'element', 'story')),
+            entity_id TEXT NOT NULL,
+            universe_id TEXT NOT NULL,
+            action TEXT NOT NULL CHECK (action IN ('create', 'update', 'delete')),
+            changes TEXT NOT NULL,
+            origin TEXT NOT NULL CHECK (origin IN ('ui', 'import', 'autosave')),
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_change_journal_entity
+            ON change_journal(entity_type, entity_id, id);
+        CREATE INDEX IF NOT EXISTS idx_change_journal_universe
+            ON change_journal(universe_id, id);
+
+        CREATE TRIGGER IF NOT EXISTS change_journal_no_update
+        BEFORE UPDATE ON change_journal
+        BEGIN
+            SELECT RAISE(ABORT, 'change_journal is append-only');
+        END;
+        CREATE TRIGGER IF NOT EXISTS change_journal_no_delete
+        BEFORE DELETE ON change_journal
+        BEGIN
+            SELECT RAISE(ABORT, 'change_journal is append-only');
+        END;",
+    )
+}
+
+/// Version 8
+/// Per-universe log of structural changes for undo and redo. Each entry
+/// holds the steps of one change as JSON, with the operation reversing each.
+fn migrate_v8(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS undo_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            universe_id TEXT NOT NULL,
+            label TEXT NOT NULL,
+            steps TEXT NOT NULL,
+            undone INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (universe_id) REFERENCES universes(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_undo_log_universe ON undo_log(universe_id, undone, id);",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Build a database at `version` by applying only the first migrations
+    fn fixture_at_version(version: i32) -> (Connection, PathBuf, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("bright.db");
+        let conn = Connection::open(&db_path).unwrap();
+        let steps = MIGRATIONS
+            .iter()
+            .take_while(|m| m.version <= version)
+            .count();
+        apply_migrations(&conn, &MIGRATIONS[..steps]).unwrap();
+        (conn, db_path, temp_dir)
+    }
+
+    fn count(conn: &Connection, table: &str) -> i32 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    fn failing_migration(_conn: &Connection) -> rusqlite::Result<()> {
+        Err(rusqlite::Error::InvalidQuery)
+    }
+
+    #[test]
+    fn test_registry_versions_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i32 + 1);
+        }
+        assert_eq!(SCHEMA_VERSION, MIGRATIONS.len() as i32);
+    }
+
+    #[test]
+    fn test_run_migrations_on_new_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        // Running again is a no-op
+        run_migrations(&conn).unwrap();
+        assert_eq!(count(&conn, "schema_version"), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_v4_counts_existing_story_content() {
+        let (conn, _db_path, _temp_dir) = fixture_at_version(3);
+        conn.execute_batch(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+                 VALUES ('u1', 'World', 'Desc', '2024-01-01', '2024-01-01', 'active');
+             INSERT INTO stories (id, universe_id, title, content, word_count, last_edited_at,
+                                  variation_group_id, created_at, updated_at)
+                 VALUES ('s1', 'u1', 'Chapter', '# Title
+
+Once upon a time.', 0,
+                         '2024-01-01', 'g1', '2024-01-01', '2024-01-01');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let (words, paragraphs): (u32, u32) = conn
+            .query_row(
+                "SELECT word_count, paragraph_count FROM stories WHERE id = 's1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((words, paragraphs), (5, 2));
+    }
+
+    #[test]
+    fn test_v1_database_upgrades_without_data_loss() {
+        let (conn, db_path, _temp_dir) = fixture_at_version(1);
+        conn.execute_batch(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+                 VALUES ('u1', 'World', 'Desc', '2024-01-01', '2024-01-01', 'active');
+             INSERT INTO containers (id, universe_id, container_type, title, \"order\", created_at, updated_at)
+                 VALUES ('c1', 'u1', 'novel', 'Book', NULL, '2024-01-01', '2024-01-01');
+             INSERT INTO stories (id, universe_id, container_id, title, content, last_edited_at,
+                                  variation_group_id, created_at, updated_at)
+                 VALUES ('s1', 'u1', 'c1', 'Chapter', 'Once upon a time', '2024-01-01', 'g1',
+                         '2024-01-01', '2024-01-01');
+             INSERT INTO elements (id, universe_id, name, description, created_at, updated_at, element_type)
+                 VALUES ('e1', 'u1', 'Sarah', 'Hero', '2024-01-01', '2024-01-01', 'character');",
+        )
+        .unwrap();
+
+        let backup = upgrade_database(&conn, &db_path).unwrap();
+
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert_eq!(count(&conn, "universes"), 1);
+        assert_eq!(count(&conn, "containers"), 1);
+        assert_eq!(count(&conn, "stories"), 1);
+        assert_eq!(count(&conn, "elements"), 1);
+        let content: String = conn
+            .query_row("SELECT content FROM stories WHERE id = 's1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(content, "Once upon a time");
+        let order: i32 = conn
+            .query_row(
+                "SELECT \"order\" FROM containers WHERE id = 'c1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(order, 0);
+
+        // The backup holds the pre-upgrade database
+        let backup = backup.expect("an upgrade should take a backup");
+        assert!(backup.exists());
+        let backup_conn = Connection::open(&backup).unwrap();
+        assert_eq!(current_version(&backup_conn).unwrap(), 1);
+        assert_eq!(count(&backup_conn, "stories"), 1);
+    }
+
+    #[test]
+    fn test_v3_indexes_existing_rows() {
+        let (conn, _db_path, _temp_dir) = fixture_at_version(2);
+        conn.execute_batch(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status, worldbuilding_notes)
+                 VALUES ('u1', 'World', 'Desc', '2024-01-01', '2024-01-01', 'active', 'Volcanic islands');
+             INSERT INTO stories (id, universe_id, title, content, last_edited_at,
+                                  variation_group_id, created_at, updated_at)
+                 VALUES ('s1', 'u1', 'Landfall', 'The ship ran aground', '2024-01-01', 'g1',
+                         '2024-01-01', '2024-01-01');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let matches = |term: &str| -> Vec<String> {
+            let mut stmt = conn
+                .prepare("SELECT entity_id FROM search_index WHERE search_index MATCH ?1")
+                .unwrap();
+            stmt.query_map(params![term], |row| row.get(0))
+                .unwrap()
+                .collect::<rusqlite::Result<Vec<String>>>()
+                .unwrap()
+        };
+        assert_eq!(matches("aground"), vec!["s1"]);
+        assert_eq!(matches("volcanic"), vec!["u1"]);
+    }
+
+    #[test]
+    fn test_upgrade_database_skips_backup_when_current_or_new() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("bright.db");
+        let conn = Connection::open(&db_path).unwrap();
+
+        assert_eq!(upgrade_database(&conn, &db_path).unwrap(), None);
+        assert_eq!(upgrade_database(&conn, &db_path).unwrap(), None);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_refuses_newer_database() {
+        let (conn, db_path, _temp_dir) = fixture_at_version(SCHEMA_VERSION);
+        conn.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            params![SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+
+        let err = upgrade_database(&conn, &db_path).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::NewerSchema { found, supported }
+                if found == SCHEMA_VERSION + 1 && supported == SCHEMA_VERSION
+        ));
+        assert!(run_migrations(&conn).is_err());
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let (conn, _db_path, _temp_dir) = fixture_at_version(1);
+        let migrations = [
+            Migration {
+                version: 1,
+                description: "Initial schema",
+                up: migrate_v1,
+            },
+            Migration {
+                version: 2,
+                description: "Half-applied step",
+                up: |conn| {
+                    conn.execute("ALTER TABLE stories ADD COLUMN scratch TEXT", [])?;
+                    failing_migration(conn)
+                },
+            },
+        ];
+
+        assert!(apply_migrations(&conn, &migrations).is_err());
+
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        let has_scratch: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM pragma_table_info('stories') WHERE name = 'scratch')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!has_scratch);
+    }
+}
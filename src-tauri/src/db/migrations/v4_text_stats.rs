@@ -0,0 +1,205 @@
+//! Story statistics as computed when schema version 4 shipped
+//!
+//! `migrate_v4` backfills the new count columns with these rules. This is a
+//! frozen copy of `text_stats`: the migration must produce the same result
+//! whenever it runs, so don't change it when the live counting rules change.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Average silent reading speed used for reading time estimates
+const WORDS_PER_MINUTE: u32 = 230;
+
+/// Counts for a piece of text
+#[derive(Default)]
+pub(super) struct TextStats {
+    pub word_count: u32,
+    pub character_count: u32,
+    pub character_count_no_spaces: u32,
+    pub paragraph_count: u32,
+    pub sentence_count: u32,
+    pub reading_time_seconds: u32,
+}
+
+// Escaped ASCII punctuation is parked in a private use plane while stripping
+const ESCAPE_OFFSET: u32 = 0xF0000;
+
+static IMAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+static REFERENCE_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\[[^\]]*\]").unwrap());
+static FOOTNOTE_REF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\^[^\]]*\]").unwrap());
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"</?[A-Za-z!][^>]*>").unwrap());
+static EMPHASIS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[*`~]+").unwrap());
+// Underscores only mark emphasis at word boundaries; snake_case stays one word
+static UNDERSCORE_EMPHASIS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[^\p{L}\p{N}])_+|_+([^\p{L}\p{N}]|$)").unwrap());
+static ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\\([\\`*_{}\[\]()#+\-.!>~|])").unwrap());
+static BLOCK_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:>\s*)*(?:(?:[-*+]|\d+[.)])\s+(?:\[[ xX]\]\s+)?)?").unwrap()
+});
+static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*#{1,6}(?:\s+|$)").unwrap());
+static RULE_OR_TABLE_DIVIDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:(?:[-*_]\s*){3,}|\|?(?:\s*:?-+:?\s*\|)+\s*:?-*:?\s*)$").unwrap()
+});
+static FENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?:```|~~~)").unwrap());
+static LINK_DEFINITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\[[^\]]+\]:\s+\S").unwrap());
+
+/// Compute statistics for markdown text
+pub(super) fn compute(markdown: &str) -> TextStats {
+    let mut stats = TextStats::default();
+
+    for paragraph in plain_paragraphs(markdown) {
+        let words = count_words(&paragraph);
+        if words == 0 {
+            continue;
+        }
+        stats.word_count += words;
+        stats.paragraph_count += 1;
+        stats.sentence_count += count_sentences(&paragraph);
+        for c in paragraph
+            .chars()
+            .filter(|c| *c != '\n' && !is_zero_width(*c))
+        {
+            stats.character_count += 1;
+            if !c.is_whitespace() {
+                stats.character_count_no_spaces += 1;
+            }
+        }
+    }
+
+    stats.reading_time_seconds = (stats.word_count * 60).div_ceil(WORDS_PER_MINUTE);
+    stats
+}
+
+/// Count the words in plain text (markdown already stripped)
+fn count_words(text: &str) -> u32 {
+    let mut words = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if is_unspaced_script(c) {
+            words += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() && !in_word {
+            words += 1;
+            in_word = true;
+        }
+    }
+    words
+}
+
+/// Count sentences in one paragraph of plain text
+///
+/// A sentence ends at a run of terminal punctuation; trailing text without
+/// one (a heading, an unfinished line) still counts as a sentence.
+fn count_sentences(paragraph: &str) -> u32 {
+    let mut sentences = 0;
+    let mut has_words = false;
+    for c in paragraph.chars() {
+        if c.is_alphanumeric() {
+            has_words = true;
+        } else if matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？') && has_words {
+            sentences += 1;
+            has_words = false;
+        }
+    }
+    if has_words {
+        sentences += 1;
+    }
+    sentences
+}
+
+/// Split markdown into paragraphs of plain text
+///
+/// Paragraphs are separated by blank lines; headings, rules and fences also
+/// end a paragraph. Lines within a paragraph are joined with a space.
+fn plain_paragraphs(markdown: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut flush = |current: &mut Vec<String>| {
+        if !current.is_empty() {
+            paragraphs.push(current.join(" "));
+            current.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        if line.trim().is_empty()
+            || FENCE.is_match(line)
+            || RULE_OR_TABLE_DIVIDER.is_match(line)
+            || LINK_DEFINITION.is_match(line)
+        {
+            flush(&mut current);
+            continue;
+        }
+
+        if HEADING.is_match(line) {
+            flush(&mut current);
+            let text = strip_inline(HEADING.replace(line, "").trim_end_matches([' ', '#']));
+            current.push(text);
+            flush(&mut current);
+            continue;
+        }
+
+        let text = strip_inline(&BLOCK_PREFIX.replace(line, ""));
+        let text = text.trim();
+        if !text.is_empty() {
+            current.push(text.to_string());
+        }
+    }
+    flush(&mut current);
+
+    paragraphs
+}
+
+/// Remove inline markdown syntax from a line, keeping the visible text
+fn strip_inline(line: &str) -> String {
+    let text = IMAGE.replace_all(line, "$1");
+    let text = LINK.replace_all(&text, "$1");
+    let text = REFERENCE_LINK.replace_all(&text, "$1");
+    let text = FOOTNOTE_REF.replace_all(&text, "");
+    let text = HTML_TAG.replace_all(&text, "");
+
+    // Escaped characters are literal text; hide them from the emphasis rules
+    let text = ESCAPE.replace_all(&text, |caps: &regex::Captures| {
+        let c = caps[1].chars().next().unwrap();
+        char::from_u32(ESCAPE_OFFSET + c as u32)
+            .unwrap()
+            .to_string()
+    });
+    let text = EMPHASIS.replace_all(&text, "");
+    let text = UNDERSCORE_EMPHASIS.replace_all(&text, "${1}${2}");
+
+    text.chars()
+        .map(|c| match c {
+            '|' => ' ',
+            '\u{F0000}'..='\u{F007F}' => char::from_u32(c as u32 - ESCAPE_OFFSET).unwrap(),
+            c => c,
+        })
+        .collect()
+}
+
+/// Characters of scripts that don't separate words with spaces
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B-F
+    )
+}
+
+/// Characters that combine with the previous one rather than standing alone
+fn is_zero_width(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'   // Combining diacritical marks
+        | '\u{200B}'..='\u{200D}' // Zero-width space and joiners
+        | '\u{FE00}'..='\u{FE0F}' // Variation selectors
+        | '\u{1F3FB}'..='\u{1F3FF}' // Skin tone modifiers
+    )
+}
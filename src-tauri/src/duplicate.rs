@@ -0,0 +1,1196 @@
+//! Duplicating universes, containers and stories
+//!
+//! A duplicate is a deep copy with fresh IDs. Copying a universe brings its
+//! elements with their relationships, its containers and its stories;
+//! copying a container brings everything below it. References inside the
+//! copy (parent containers, relationship targets, related elements,
+//! variations) point at the copied entities, never back at the originals.
+//! A copy within the same universe keeps referring to the same elements.
+//!
+//! Repositories come along as `HistoryMode` says: `Keep` copies the original
+//! repository with all its branches and commits (leaving out histories
+//! imported for stories that aren't part of the copy), `Squash` starts a
+//! fresh one holding only the current text. Either way the copy's
+//! metadata.json is rewritten and its story files are brought in line with
+//! the database. A story copied within its container shares the container's
+//! repository, so it's added there as a single commit whatever the mode.
+//!
+//! The copy's rows are inserted in one transaction, so a copy that fails
+//! leaves none behind; the repositories created for it are removed.
+//!
+//! Templates (`UniverseStatus::Template`) are instantiated by copying them
+//! into an active universe with squashed history.
+
+use crate::db::Database;
+use crate::file_management::{
+    drop_imported_mappings, story_filename, sync_story_file, write_container_metadata_file,
+    write_metadata_file, FileManagementError,
+};
+use crate::git::{GitService, GitServiceError};
+use crate::models::{Container, Element, ElementRelationship, Story, Universe, UniverseStatus};
+use crate::repositories::{
+    ContainerRepository, ElementRepository, StoryRepository, UniverseRepository,
+};
+use chrono::Utc;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Commit message for the text of a copy with squashed history
+const SQUASHED_COMMIT_MESSAGE: &str = "Add stories copied from the original";
+
+/// Error type for duplicate operations
+#[derive(Debug)]
+pub enum DuplicateError {
+    /// Reading or writing the database failed
+    Database(rusqlite::Error),
+    /// Creating a repository or committing to it failed
+    Git(GitServiceError),
+    /// Copying a repository or writing story files failed
+    Files(FileManagementError),
+    /// The entity can't be duplicated as asked
+    Invalid(String),
+}
+
+impl fmt::Display for DuplicateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateError::Database(e) => write!(f, "Database error: {e}"),
+            DuplicateError::Git(e) => write!(f, "Git error: {e}"),
+            DuplicateError::Files(e) => write!(f, "{e}"),
+            DuplicateError::Invalid(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for DuplicateError {}
+
+impl From<rusqlite::Error> for DuplicateError {
+    fn from(err: rusqlite::Error) -> Self {
+        DuplicateError::Database(err)
+    }
+}
+
+impl From<GitServiceError> for DuplicateError {
+    fn from(err: GitServiceError) -> Self {
+        DuplicateError::Git(err)
+    }
+}
+
+impl From<FileManagementError> for DuplicateError {
+    fn from(err: FileManagementError) -> Self {
+        DuplicateError::Files(err)
+    }
+}
+
+/// Result type alias for duplicate operations
+pub type DuplicateResult<T> = Result<T, DuplicateError>;
+
+/// What a copy's repositories keep of the original's history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryMode {
+    /// Copy the repository with all its branches and commits
+    Keep,
+    /// Start a fresh repository with the current text as its only change
+    Squash,
+}
+
+/// Duplicate a universe with its elements, containers and stories
+///
+/// If any part fails, the partial copy is removed again.
+///
+/// # Arguments
+/// * `app_data_dir` - Directory holding `git-repos`
+/// * `name` - Name of the copy ("<name> (copy)" if None)
+/// * `status` - Status of the copy (the original's, if None)
+/// * `history` - Whether the copied repositories keep their history
+pub fn duplicate_universe(
+    db: &Database,
+    app_data_dir: &Path,
+    id: &str,
+    name: Option<&str>,
+    status: Option<UniverseStatus>,
+    history: HistoryMode,
+) -> DuplicateResult<Universe> {
+    let source = UniverseRepository::find_by_id(db, id)?;
+    let elements = ElementRepository::list_by_universe(db, id)?;
+    let roots = ContainerRepository::list_by_universe(db, id)?
+        .into_iter()
+        .filter(|container| container.parent_container_id.is_none())
+        .collect();
+    let containers = top_down(db, roots)?;
+    let container_ids: HashSet<&str> = containers.iter().map(|c| c.id.as_str()).collect();
+    // Stories of trashed containers stay behind with them
+    let stories: Vec<Story> = StoryRepository::list_by_universe(db, id)?
+        .into_iter()
+        .filter(|story| match story.container_id {
+            Some(ref container_id) => container_ids.contains(container_id.as_str()),
+            None => true,
+        })
+        .collect();
+
+    let mut copier = Copier::new(db, app_data_dir, history, &containers, &stories);
+    copier.element_ids = Some(elements.iter().map(|e| (e.id.clone(), new_id())).collect());
+
+    let universe = Universe {
+        id: new_id(),
+        name: name.map_or_else(|| copy_title(&source.name), str::to_string),
+        status: status.unwrap_or_else(|| source.status.clone()),
+        created_at: copier.now.clone(),
+        updated_at: copier.now.clone(),
+        ..source
+    };
+
+    let copied: DuplicateResult<String> = db.transaction(|| {
+        let universe = UniverseRepository::insert(db, &universe)?;
+        copier.copy_elements(&universe.id, &elements)?;
+        copier.copy_containers(&universe.id, &containers, &stories)?;
+        for story in stories.iter().filter(|s| s.container_id.is_none()) {
+            copier.copy_standalone_story(story, &universe.id)?;
+        }
+        Ok(universe.id)
+    });
+    let copy_id = copied.inspect_err(|_| copier.discard())?;
+
+    UniverseRepository::find_by_id(db, &copy_id).map_err(DuplicateError::from)
+}
+
+/// Create a universe from a template
+///
+/// The template is copied into an active universe with squashed history,
+/// so the new universe doesn't start with the template's commits.
+pub fn create_universe_from_template(
+    db: &Database,
+    app_data_dir: &Path,
+    template_id: &str,
+    name: &str,
+) -> DuplicateResult<Universe> {
+    let template = UniverseRepository::find_by_id(db, template_id)?;
+    if template.status != UniverseStatus::Template {
+        return Err(DuplicateError::Invalid(format!(
+            "Universe '{}' is not a template",
+            template.name
+        )));
+    }
+    if name.trim().is_empty() {
+        return Err(DuplicateError::Invalid(
+            "Universe name cannot be empty".to_string(),
+        ));
+    }
+
+    duplicate_universe(
+        db,
+        app_data_dir,
+        template_id,
+        Some(name),
+        Some(UniverseStatus::Active),
+        HistoryMode::Squash,
+    )
+}
+
+/// Duplicate a container with everything below it
+///
+/// The copy is titled "<title> (copy)" and placed after the container's
+/// last sibling.
+///
+/// # Arguments
+/// * `app_data_dir` - Directory holding `git-repos`
+/// * `history` - Whether the copied repositories keep their history
+pub fn duplicate_container(
+    db: &Database,
+    app_data_dir: &Path,
+    id: &str,
+    history: HistoryMode,
+) -> DuplicateResult<Container> {
+    if ContainerRepository::is_trashed(db, id)? {
+        return Err(DuplicateError::Invalid(
+            "Cannot duplicate a container in the trash".to_string(),
+        ));
+    }
+    let mut root = ContainerRepository::find_by_id(db, id)?;
+    let siblings = match root.parent_container_id {
+        Some(ref parent_id) => ContainerRepository::list_children(db, parent_id)?,
+        None => ContainerRepository::list_by_universe(db, &root.universe_id)?
+            .into_iter()
+            .filter(|container| container.parent_container_id.is_none())
+            .collect(),
+    };
+    root.order = siblings
+        .iter()
+        .map(|c| c.order)
+        .max()
+        .map_or(0, |order| order + 1);
+    root.title = copy_title(&root.title);
+
+    let containers = top_down(db, vec![root])?;
+    let mut stories = Vec::new();
+    for container in &containers {
+        stories.extend(StoryRepository::list_by_container(db, &container.id)?);
+    }
+
+    let mut copier = Copier::new(db, app_data_dir, history, &containers, &stories);
+    let copy_id = copier.container_ids[id].clone();
+    let universe_id = containers[0].universe_id.clone();
+    db.transaction(|| copier.copy_containers(&universe_id, &containers, &stories))
+        .inspect_err(|_| copier.discard())?;
+
+    ContainerRepository::find_by_id(db, &copy_id).map_err(DuplicateError::from)
+}
+
+/// Duplicate a single story
+///
+/// The copy is titled "<title> (copy)". In a container it's placed after
+/// the last story and committed to the container's repository, so
+/// `history` only applies to standalone stories, which get a repository of
+/// their own. The copy starts a variation group of its own.
+///
+/// # Arguments
+/// * `app_data_dir` - Directory holding `git-repos`
+/// * `history` - Whether a standalone copy's repository keeps its history
+pub fn duplicate_story(
+    db: &Database,
+    app_data_dir: &Path,
+    id: &str,
+    history: HistoryMode,
+) -> DuplicateResult<Story> {
+    if StoryRepository::is_trashed(db, id)? {
+        return Err(DuplicateError::Invalid(
+            "Cannot duplicate a story in the trash".to_string(),
+        ));
+    }
+    let mut source = StoryRepository::find_by_id(db, id)?;
+    source.title = copy_title(&source.title);
+
+    let mut copier = Copier::new(
+        db,
+        app_data_dir,
+        history,
+        &[],
+        std::slice::from_ref(&source),
+    );
+    let universe_id = source.universe_id.clone();
+    if let Some(ref container_id) = source.container_id {
+        source.order = StoryRepository::list_by_container(db, container_id)?
+            .iter()
+            .filter_map(|story| story.order)
+            .max()
+            .map(|order| order + 1);
+    }
+    db.transaction(|| match source.container_id.clone() {
+        Some(container_id) => copier.copy_contained_story(&source, &container_id),
+        None => copier.copy_standalone_story(&source, &universe_id),
+    })
+    .inspect_err(|_| copier.discard())
+}
+
+/// Copies entities under fresh IDs, remembering which new ID stands for
+/// which original so references within the copy can be rewritten
+struct Copier<'a> {
+    db: &'a Database,
+    app_data_dir: &'a Path,
+    history: HistoryMode,
+    now: String,
+    /// New element IDs, or None if the copy stays in the same universe and
+    /// keeps referring to the same elements
+    element_ids: Option<HashMap<String, String>>,
+    container_ids: HashMap<String, String>,
+    story_ids: HashMap<String, String>,
+    variation_groups: HashMap<String, String>,
+    /// Repositories created so far, removed again if the copy fails
+    repos: Vec<PathBuf>,
+}
+
+impl<'a> Copier<'a> {
+    /// Prepare a copy of `containers` and `stories`, assigning their new IDs
+    fn new(
+        db: &'a Database,
+        app_data_dir: &'a Path,
+        history: HistoryMode,
+        containers: &[Container],
+        stories: &[Story],
+    ) -> Self {
+        Copier {
+            db,
+            app_data_dir,
+            history,
+            now: Utc::now().to_rfc3339(),
+            element_ids: None,
+            container_ids: containers
+                .iter()
+                .map(|c| (c.id.clone(), new_id()))
+                .collect(),
+            story_ids: stories.iter().map(|s| (s.id.clone(), new_id())).collect(),
+            variation_groups: HashMap::new(),
+            repos: Vec::new(),
+        }
+    }
+
+    /// Copy elements into a universe, with their relationships between them
+    fn copy_elements(&self, universe_id: &str, elements: &[Element]) -> DuplicateResult<()> {
+        let Some(ref ids) = self.element_ids else {
+            return Ok(());
+        };
+        let copies: Vec<Element> = elements
+            .iter()
+            .map(|element| Element {
+                id: ids[&element.id].clone(),
+                universe_id: universe_id.to_string(),
+                relationships: element.relationships.as_ref().map(|relationships| {
+                    relationships
+                        .iter()
+                        .filter_map(|rel| {
+                            Some(ElementRelationship {
+                                target_element_id: ids.get(&rel.target_element_id)?.clone(),
+                                ..rel.clone()
+                            })
+                        })
+                        .collect()
+                }),
+                created_at: self.now.clone(),
+                updated_at: self.now.clone(),
+                ..element.clone()
+            })
+            .collect();
+
+        // Inserting skips relationships to elements that don't exist yet,
+        // so each element also brings those of the elements before it
+        for (i, element) in copies.iter().enumerate() {
+            let incoming: Vec<(String, ElementRelationship)> = copies[..i]
+                .iter()
+                .flat_map(|source| {
+                    source
+                        .relationships
+                        .iter()
+                        .flatten()
+                        .filter(|rel| rel.target_element_id == element.id)
+                        .map(|rel| (source.id.clone(), rel.clone()))
+                })
+                .collect();
+            ElementRepository::insert(self.db, element, &incoming)?;
+        }
+        Ok(())
+    }
+
+    /// Copy containers, parents before children, with their stories
+    ///
+    /// Parents outside the copy are kept, so the root of a copied subtree
+    /// stays where the original is.
+    fn copy_containers(
+        &mut self,
+        universe_id: &str,
+        containers: &[Container],
+        stories: &[Story],
+    ) -> DuplicateResult<()> {
+        for source in containers {
+            let mut container = Container {
+                id: self.container_ids[&source.id].clone(),
+                universe_id: universe_id.to_string(),
+                parent_container_id: source
+                    .parent_container_id
+                    .as_ref()
+                    .map(|id| self.container_ids.get(id).unwrap_or(id).clone()),
+                git_repo_path: None,
+                current_branch: None,
+                staged_changes: false,
+                created_at: self.now.clone(),
+                updated_at: self.now.clone(),
+                ..source.clone()
+            };
+            let copies: Vec<Story> = stories
+                .iter()
+                .filter(|story| story.container_id.as_deref() == Some(source.id.as_str()))
+                .map(|story| self.copy_story(story, universe_id, Some(container.id.clone())))
+                .collect();
+
+            if let Some(source_repo) = existing_repo(source.git_repo_path.as_deref()) {
+                let stories: Vec<&str> = stories
+                    .iter()
+                    .filter(|story| story.container_id.as_deref() == Some(source.id.as_str()))
+                    .map(|story| story.id.as_str())
+                    .collect();
+                let repo_path = self.create_repo(&source_repo, &container.id, &stories)?;
+                container.git_repo_path = Some(repo_path.to_string_lossy().into_owned());
+                container.current_branch = Some(GitService::get_current_branch(&repo_path)?);
+                write_container_metadata_file(&repo_path, &container)?;
+                self.write_story_files(&repo_path, &copies)?;
+            }
+
+            ContainerRepository::insert(self.db, &container)?;
+            for story in &copies {
+                StoryRepository::insert(self.db, story)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy a standalone story, with its own repository
+    fn copy_standalone_story(
+        &mut self,
+        source: &Story,
+        universe_id: &str,
+    ) -> DuplicateResult<Story> {
+        let mut story = self.copy_story(source, universe_id, None);
+        let source_repo = StoryRepository::find_git_repo_path(self.db, source)?;
+        if let Some(source_repo) = existing_repo(source_repo.as_deref()) {
+            let repo_path = self.create_repo(&source_repo, &story.id, &[&source.id])?;
+            story.git_repo_path = repo_path.to_string_lossy().into_owned();
+            story.current_branch = GitService::get_current_branch(&repo_path)?;
+            write_metadata_file(&repo_path, &story)?;
+            self.write_story_files(&repo_path, std::slice::from_ref(&story))?;
+        }
+        StoryRepository::insert(self.db, &story).map_err(DuplicateError::from)
+    }
+
+    /// Copy a story into its own container, committing its file to the
+    /// container's repository
+    fn copy_contained_story(
+        &mut self,
+        source: &Story,
+        container_id: &str,
+    ) -> DuplicateResult<Story> {
+        let story = self.copy_story(source, &source.universe_id, Some(container_id.to_string()));
+        let story = StoryRepository::insert(self.db, &story)?;
+
+        let repo_path = StoryRepository::find_git_repo_path(self.db, &story)?;
+        if let Some(repo_path) = existing_repo(repo_path.as_deref()) {
+            let message = format!("Copy in {}: {}", story_filename(&story), story.title);
+            sync_story_file(&repo_path, None, &story, Some(&message))?;
+        }
+        Ok(story)
+    }
+
+    /// Build the copy of a story, with references rewritten to the copy
+    ///
+    /// Related elements that weren't copied are dropped, as is a parent
+    /// variation outside the copy.
+    fn copy_story(
+        &mut self,
+        source: &Story,
+        universe_id: &str,
+        container_id: Option<String>,
+    ) -> Story {
+        let related_element_ids = match self.element_ids {
+            Some(ref ids) => source.related_element_ids.as_ref().map(|related| {
+                related
+                    .iter()
+                    .filter_map(|id| ids.get(id).cloned())
+                    .collect()
+            }),
+            None => source.related_element_ids.clone(),
+        };
+        let variation_group_id = self
+            .variation_groups
+            .entry(source.variation_group_id.clone())
+            .or_insert_with(new_id)
+            .clone();
+
+        Story {
+            id: self.story_ids[&source.id].clone(),
+            universe_id: universe_id.to_string(),
+            container_id,
+            related_element_ids,
+            variation_group_id,
+            parent_variation_id: source
+                .parent_variation_id
+                .as_ref()
+                .and_then(|id| self.story_ids.get(id).cloned()),
+            git_repo_path: String::new(),
+            current_branch: "main".to_string(),
+            staged_changes: false,
+            created_at: self.now.clone(),
+            updated_at: self.now.clone(),
+            last_edited_at: self.now.clone(),
+            version: 1,
+            ..source.clone()
+        }
+    }
+
+    /// Create the repository of a copy, from the original's as `history` says
+    ///
+    /// A kept history only brings along the histories imported for `stories`
+    /// (original IDs), since those of stories that were moved in and left
+    /// again belong to someone else.
+    fn create_repo(
+        &mut self,
+        source_repo: &Path,
+        id: &str,
+        stories: &[&str],
+    ) -> DuplicateResult<PathBuf> {
+        let repo_path = match self.history {
+            HistoryMode::Keep => {
+                let repo_path = self.app_data_dir.join("git-repos").join(id);
+                // Recorded first, so a partial copy is cleaned up too
+                self.repos.push(repo_path.clone());
+                copy_dir(source_repo, &repo_path).map_err(FileManagementError::from)?;
+                GitService::prune_imported_history(&repo_path, stories)?;
+                drop_imported_mappings(&repo_path, stories)?;
+                return Ok(repo_path);
+            }
+            HistoryMode::Squash => GitService::init_repo(self.app_data_dir, id)?,
+        };
+        self.repos.push(repo_path.clone());
+        Ok(repo_path)
+    }
+
+    /// Bring a new repository's story files in line with the copied stories
+    ///
+    /// A kept history only gets commits for files that differ from the
+    /// database; a squashed one gets every file in a single commit.
+    fn write_story_files(&self, repo_path: &Path, stories: &[Story]) -> DuplicateResult<()> {
+        match self.history {
+            HistoryMode::Keep => {
+                for story in stories {
+                    sync_story_file(repo_path, None, story, None)?;
+                }
+            }
+            HistoryMode::Squash if !stories.is_empty() => {
+                for story in stories {
+                    fs::write(repo_path.join(story_filename(story)), &story.content)
+                        .map_err(FileManagementError::from)?;
+                }
+                GitService::commit_all(repo_path, SQUASHED_COMMIT_MESSAGE)?;
+            }
+            HistoryMode::Squash => {}
+        }
+        Ok(())
+    }
+
+    /// Remove the repositories created for a copy that failed
+    fn discard(&self) {
+        for repo_path in self.repos.iter().filter(|path| path.exists()) {
+            if let Err(e) = fs::remove_dir_all(repo_path) {
+                warn!(
+                    "Failed to remove repository {} of a failed copy: {e}",
+                    repo_path.display()
+                );
+            }
+        }
+    }
+}
+
+/// The containers under the roots and the roots themselves, parents before
+/// children
+fn top_down(db: &Database, roots: Vec<Container>) -> rusqlite::Result<Vec<Container>> {
+    let mut containers = Vec::new();
+    let mut queue = VecDeque::from(roots);
+    while let Some(container) = queue.pop_front() {
+        queue.extend(ContainerRepository::list_children(db, &container.id)?);
+        containers.push(container);
+    }
+    Ok(containers)
+}
+
+/// A repository path, if it's set and exists on disk
+fn existing_repo(path: Option<&str>) -> Option<PathBuf> {
+    path.filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+}
+
+/// Copy a directory and everything in it
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_title(title: &str) -> String {
+    format!("{title} (copy)")
+}
+
+fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_management::{read_metadata_file, StoryMetadata};
+    use crate::models::{
+        CreateElementInput, CreateStoryInput, StoryType, UpdateElementInput, UpdateStoryInput,
+    };
+    use tempfile::TempDir;
+
+    struct Fixture {
+        db: Database,
+        temp_dir: TempDir,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+             VALUES ('universe-1', 'Test Universe', 'A test universe', datetime('now'), datetime('now'), 'template')",
+            &[],
+        )
+        .unwrap();
+
+        Fixture { db, temp_dir }
+    }
+
+    fn create_container(
+        fixture: &Fixture,
+        title: &str,
+        parent: Option<&str>,
+        order: i32,
+    ) -> Container {
+        ContainerRepository::create(
+            &fixture.db,
+            "universe-1".to_string(),
+            parent.map(str::to_string),
+            "book".to_string(),
+            title.to_string(),
+            None,
+            order,
+        )
+        .unwrap()
+    }
+
+    /// Create a story with its file committed where the app would put it
+    fn create_story(fixture: &Fixture, title: &str, container_id: Option<&str>) -> Story {
+        let story = StoryRepository::create(
+            &fixture.db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: title.to_string(),
+                description: Some(String::new()),
+                story_type: Some(StoryType::Chapter),
+                content: Some(format!("{title} begins.\n")),
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: container_id.map(str::to_string),
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap();
+
+        // Place it after the container's other stories
+        if let Some(container_id) = container_id {
+            let mut ids: Vec<String> =
+                StoryRepository::list_by_container(&fixture.db, container_id)
+                    .unwrap()
+                    .into_iter()
+                    .map(|sibling| sibling.id)
+                    .filter(|id| *id != story.id)
+                    .collect();
+            ids.push(story.id.clone());
+            StoryRepository::reorder_by_container(&fixture.db, container_id, ids).unwrap();
+        }
+        let story = StoryRepository::find_by_id(&fixture.db, &story.id).unwrap();
+
+        let repo_path = match container_id {
+            Some(container_id) => {
+                let container = ContainerRepository::find_by_id(&fixture.db, container_id).unwrap();
+                match existing_repo(container.git_repo_path.as_deref()) {
+                    Some(repo_path) => repo_path,
+                    None => {
+                        let repo_path =
+                            GitService::init_repo(fixture.temp_dir.path(), container_id).unwrap();
+                        write_container_metadata_file(&repo_path, &container).unwrap();
+                        ContainerRepository::set_git_repo_path(
+                            &fixture.db,
+                            container_id,
+                            &repo_path.to_string_lossy(),
+                        )
+                        .unwrap();
+                        repo_path
+                    }
+                }
+            }
+            None => {
+                let repo_path = GitService::init_repo(fixture.temp_dir.path(), &story.id).unwrap();
+                StoryRepository::set_git_repo_path(
+                    &fixture.db,
+                    &story.id,
+                    &repo_path.to_string_lossy(),
+                )
+                .unwrap();
+                repo_path
+            }
+        };
+        sync_story_file(&repo_path, None, &story, None).unwrap();
+        StoryRepository::find_by_id(&fixture.db, &story.id).unwrap()
+    }
+
+    fn create_element(
+        fixture: &Fixture,
+        name: &str,
+        relationships: Vec<ElementRelationship>,
+    ) -> Element {
+        ElementRepository::create(
+            &fixture.db,
+            CreateElementInput {
+                universe_id: "universe-1".to_string(),
+                name: name.to_string(),
+                description: Some(String::new()),
+                element_type: None,
+                custom_type_name: None,
+                details: None,
+                attributes: None,
+                image_url: None,
+                tags: None,
+                relationships: Some(relationships),
+                color: None,
+                icon: None,
+            },
+        )
+        .unwrap()
+    }
+
+    fn relationship(target: &Element, label: &str) -> ElementRelationship {
+        ElementRelationship {
+            target_element_id: target.id.clone(),
+            label: label.to_string(),
+            inverse_label: None,
+            description: None,
+        }
+    }
+
+    fn repo_of(fixture: &Fixture, story: &Story) -> PathBuf {
+        PathBuf::from(
+            StoryRepository::find_git_repo_path(&fixture.db, story)
+                .unwrap()
+                .unwrap(),
+        )
+    }
+
+    fn commit_messages(repo_path: &Path) -> Vec<String> {
+        let branch = GitService::get_current_branch(repo_path).unwrap();
+        GitService::get_history(repo_path, &branch)
+            .unwrap()
+            .into_iter()
+            .map(|commit| commit.message)
+            .collect()
+    }
+
+    fn read_metadata(repo_path: &Path) -> StoryMetadata {
+        read_metadata_file(repo_path).unwrap()
+    }
+
+    fn set_status(fixture: &Fixture, status: &str) {
+        fixture
+            .db
+            .execute(
+                "UPDATE universes SET status = ?1 WHERE id = 'universe-1'",
+                &[&status],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_universe_copy_remaps_references() {
+        let fixture = setup();
+        let ship = create_element(&fixture, "Ship", vec![]);
+        let captain = create_element(&fixture, "Captain", vec![relationship(&ship, "commands")]);
+        // Relationship to an element inserted after it
+        let ship = ElementRepository::update(
+            &fixture.db,
+            &ship.id,
+            UpdateElementInput {
+                name: None,
+                description: None,
+                element_type: None,
+                custom_type_name: None,
+                details: None,
+                attributes: None,
+                image_url: None,
+                tags: None,
+                relationships: Some(vec![relationship(&captain, "carries")]),
+                related_story_ids: None,
+                color: None,
+                icon: None,
+                favorite: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let series = create_container(&fixture, "Series", None, 0);
+        let book = create_container(&fixture, "Book", Some(&series.id), 0);
+        let chapter = create_story(&fixture, "Chapter", Some(&book.id));
+        StoryRepository::update(
+            &fixture.db,
+            &chapter.id,
+            UpdateStoryInput {
+                related_element_ids: Some(vec![captain.id.clone()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let prologue = create_story(&fixture, "Prologue", None);
+
+        let copy = duplicate_universe(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            None,
+            Some(UniverseStatus::Active),
+            HistoryMode::Keep,
+        )
+        .unwrap();
+
+        assert_ne!(copy.id, "universe-1");
+        assert_eq!(copy.name, "Test Universe (copy)");
+        assert_eq!(copy.status, UniverseStatus::Active);
+
+        let elements = ElementRepository::list_by_universe(&fixture.db, &copy.id).unwrap();
+        assert_eq!(elements.len(), 2);
+        let copied_ship = elements.iter().find(|e| e.name == "Ship").unwrap();
+        let copied_captain = elements.iter().find(|e| e.name == "Captain").unwrap();
+        assert_ne!(copied_ship.id, ship.id);
+        let captain_rels = copied_captain.relationships.as_ref().unwrap();
+        assert_eq!(captain_rels.len(), 1);
+        assert_eq!(captain_rels[0].target_element_id, copied_ship.id);
+        let ship_rels = copied_ship.relationships.as_ref().unwrap();
+        assert_eq!(ship_rels.len(), 1);
+        assert_eq!(ship_rels[0].target_element_id, copied_captain.id);
+
+        let containers = ContainerRepository::list_by_universe(&fixture.db, &copy.id).unwrap();
+        assert_eq!(containers.len(), 2);
+        let copied_series = containers.iter().find(|c| c.title == "Series").unwrap();
+        let copied_book = containers.iter().find(|c| c.title == "Book").unwrap();
+        assert_eq!(
+            copied_book.parent_container_id.as_deref(),
+            Some(copied_series.id.as_str())
+        );
+
+        let stories = StoryRepository::list_by_universe(&fixture.db, &copy.id).unwrap();
+        assert_eq!(stories.len(), 2);
+        let copied_chapter = stories.iter().find(|s| s.title == "Chapter").unwrap();
+        assert_eq!(
+            copied_chapter.container_id.as_deref(),
+            Some(copied_book.id.as_str())
+        );
+        assert_eq!(
+            copied_chapter.related_element_ids,
+            Some(vec![copied_captain.id.clone()])
+        );
+
+        // Repositories were copied with their history
+        let book_repo = repo_of(&fixture, copied_chapter);
+        assert_ne!(book_repo, repo_of(&fixture, &chapter));
+        assert_eq!(
+            fs::read_to_string(book_repo.join("001-chapter.md")).unwrap(),
+            "Chapter begins.\n"
+        );
+        assert!(commit_messages(&book_repo)
+            .iter()
+            .any(|message| message.contains("001-chapter.md")));
+        let copied_prologue = stories.iter().find(|s| s.title == "Prologue").unwrap();
+        let prologue_repo = PathBuf::from(&copied_prologue.git_repo_path);
+        assert_ne!(prologue_repo, PathBuf::from(&prologue.git_repo_path));
+        assert_eq!(read_metadata(&prologue_repo).id, copied_prologue.id);
+
+        // The original is untouched
+        assert_eq!(
+            ContainerRepository::list_by_universe(&fixture.db, "universe-1")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            StoryRepository::find_by_id(&fixture.db, &chapter.id)
+                .unwrap()
+                .related_element_ids,
+            Some(vec![captain.id.clone()])
+        );
+    }
+
+    #[test]
+    fn test_squashed_copy_starts_fresh_history() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None, 0);
+        let chapter = create_story(&fixture, "Chapter", Some(&book.id));
+        let epilogue = create_story(&fixture, "Epilogue", Some(&book.id));
+        StoryRepository::update(
+            &fixture.db,
+            &epilogue.id,
+            UpdateStoryInput {
+                content: Some("Unsaved ending.\n".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let copy = duplicate_container(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &book.id,
+            HistoryMode::Squash,
+        )
+        .unwrap();
+
+        let repo_path = PathBuf::from(copy.git_repo_path.as_deref().unwrap());
+        assert_ne!(repo_path, repo_of(&fixture, &chapter));
+        assert_eq!(
+            fs::read_to_string(repo_path.join("002-epilogue.md")).unwrap(),
+            "Unsaved ending.\n"
+        );
+        let messages = commit_messages(&repo_path);
+        assert!(!messages
+            .iter()
+            .any(|message| message.contains("001-chapter.md")));
+        assert_eq!(
+            messages
+                .iter()
+                .filter(|message| message.as_str() == SQUASHED_COMMIT_MESSAGE)
+                .count(),
+            1
+        );
+        assert_eq!(read_metadata(&repo_path).id, copy.id);
+    }
+
+    #[test]
+    fn test_container_copy_goes_after_its_siblings() {
+        let fixture = setup();
+        let series = create_container(&fixture, "Series", None, 0);
+        let first = create_container(&fixture, "First", Some(&series.id), 0);
+        create_container(&fixture, "Second", Some(&series.id), 1);
+        let part = create_container(&fixture, "Part", Some(&first.id), 0);
+        let chapter = create_story(&fixture, "Chapter", Some(&part.id));
+
+        let copy = duplicate_container(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &first.id,
+            HistoryMode::Keep,
+        )
+        .unwrap();
+
+        assert_eq!(copy.title, "First (copy)");
+        assert_eq!(copy.order, 2);
+        assert_eq!(
+            copy.parent_container_id.as_deref(),
+            Some(series.id.as_str())
+        );
+        let children = ContainerRepository::list_children(&fixture.db, &copy.id).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].title, "Part");
+        assert_ne!(children[0].id, part.id);
+        let stories = StoryRepository::list_by_container(&fixture.db, &children[0].id).unwrap();
+        assert_eq!(stories.len(), 1);
+        assert_ne!(stories[0].id, chapter.id);
+        assert_eq!(stories[0].title, "Chapter");
+        assert_eq!(
+            StoryRepository::list_by_container(&fixture.db, &part.id)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_story_copy_in_container_is_committed_after_the_last_story() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None, 0);
+        let chapter = create_story(&fixture, "Chapter", Some(&book.id));
+        create_story(&fixture, "Interlude", Some(&book.id));
+
+        let copy = duplicate_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &chapter.id,
+            HistoryMode::Keep,
+        )
+        .unwrap();
+
+        assert_ne!(copy.id, chapter.id);
+        assert_ne!(copy.variation_group_id, chapter.variation_group_id);
+        assert_eq!(copy.title, "Chapter (copy)");
+        assert_eq!(copy.order, Some(2));
+        let repo_path = repo_of(&fixture, &copy);
+        assert_eq!(repo_path, repo_of(&fixture, &chapter));
+        assert_eq!(
+            fs::read_to_string(repo_path.join("003-chapter-copy.md")).unwrap(),
+            "Chapter begins.\n"
+        );
+        assert!(commit_messages(&repo_path)[0].starts_with("Copy in 003-chapter-copy.md"));
+    }
+
+    #[test]
+    fn test_standalone_story_copy_keeps_history() {
+        let fixture = setup();
+        let story = create_story(&fixture, "Novella", None);
+
+        let copy = duplicate_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            HistoryMode::Keep,
+        )
+        .unwrap();
+
+        let repo_path = PathBuf::from(&copy.git_repo_path);
+        assert_ne!(repo_path, PathBuf::from(&story.git_repo_path));
+        assert_eq!(copy.current_branch, "original");
+        assert_eq!(
+            fs::read_to_string(repo_path.join("content.md")).unwrap(),
+            "Novella begins.\n"
+        );
+        assert!(commit_messages(&repo_path)
+            .iter()
+            .any(|message| message.contains("Add content.md")));
+        assert_eq!(read_metadata(&repo_path).title, "Novella (copy)");
+    }
+
+    #[test]
+    fn test_kept_history_leaves_other_stories_histories_behind() {
+        let fixture = setup();
+        let story = create_story(&fixture, "Novella", None);
+        let repo_path = PathBuf::from(&story.git_repo_path);
+        {
+            let repo = git2::Repository::open(&repo_path).unwrap();
+            let head = repo.head().unwrap().target().unwrap();
+            for name in [
+                format!("{}/original", story.id),
+                "other/original".to_string(),
+            ] {
+                repo.reference(&format!("refs/history/{name}"), head, false, "test")
+                    .unwrap();
+            }
+        }
+        let mut metadata = read_metadata(&repo_path);
+        metadata.variations.extend([
+            (
+                format!("{}/original", story.id),
+                "Before the move".to_string(),
+            ),
+            ("other/original".to_string(), "Someone else's".to_string()),
+        ]);
+        fs::write(
+            repo_path.join("metadata.json"),
+            serde_json::to_string_pretty(&metadata).unwrap(),
+        )
+        .unwrap();
+        GitService::commit_all(&repo_path, "Import histories").unwrap();
+
+        let copy = duplicate_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            HistoryMode::Keep,
+        )
+        .unwrap();
+
+        let copy_repo = PathBuf::from(&copy.git_repo_path);
+        assert_eq!(
+            GitService::list_imported_history(&copy_repo).unwrap(),
+            [format!("{}/original", story.id)]
+        );
+        let variations = read_metadata(&copy_repo).variations;
+        assert!(variations.contains_key(&format!("{}/original", story.id)));
+        assert!(!variations.contains_key("other/original"));
+        // The original keeps everything
+        assert_eq!(
+            GitService::list_imported_history(&repo_path).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_failed_universe_copy_leaves_nothing_behind() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None, 0);
+        create_story(&fixture, "Chapter", Some(&book.id));
+        create_element(&fixture, "Ship", vec![]);
+        // Copied after the containers, and not a repository
+        let broken = create_story(&fixture, "Broken", None);
+        let broken_repo = fixture.temp_dir.path().join("not-a-repo");
+        fs::create_dir(&broken_repo).unwrap();
+        StoryRepository::set_git_repo_path(&fixture.db, &broken.id, &broken_repo.to_string_lossy())
+            .unwrap();
+        let count = |table: &str| -> i64 {
+            let conn = fixture.db.connection();
+            let conn = conn.lock().unwrap();
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        let counts: Vec<i64> = ["universes", "elements", "containers", "stories"]
+            .into_iter()
+            .map(count)
+            .collect();
+
+        let result = duplicate_universe(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            None,
+            None,
+            HistoryMode::Keep,
+        );
+
+        assert!(result.is_err());
+        assert_eq!(
+            ["universes", "elements", "containers", "stories"]
+                .into_iter()
+                .map(count)
+                .collect::<Vec<_>>(),
+            counts
+        );
+        let repos = fs::read_dir(fixture.temp_dir.path().join("git-repos"))
+            .unwrap()
+            .count();
+        // Only the originals' repositories are left
+        assert_eq!(repos, 2);
+    }
+
+    #[test]
+    fn test_universe_from_template() {
+        let fixture = setup();
+        create_story(&fixture, "Prologue", None);
+        set_status(&fixture, "active");
+        let result = create_universe_from_template(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Mine",
+        );
+        assert!(matches!(result, Err(DuplicateError::Invalid(_))));
+
+        set_status(&fixture, "template");
+        let universe = create_universe_from_template(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Mine",
+        )
+        .unwrap();
+
+        assert_eq!(universe.name, "Mine");
+        assert_eq!(universe.status, UniverseStatus::Active);
+        let stories = StoryRepository::list_by_universe(&fixture.db, &universe.id).unwrap();
+        assert_eq!(stories.len(), 1);
+        let messages = commit_messages(Path::new(&stories[0].git_repo_path));
+        assert!(messages.contains(&SQUASHED_COMMIT_MESSAGE.to_string()));
+        assert!(!messages
+            .iter()
+            .any(|message| message.contains("Add content.md")));
+    }
+}
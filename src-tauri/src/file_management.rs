@@ -4,8 +4,8 @@
 /// within Git repositories. Each story can have a Git repository with its chapters
 /// and scenes stored as markdown files.
 use crate::file_naming;
-use crate::git::GitService;
-use crate::models::Story;
+use crate::git::{GitService, HistoryMatch};
+use crate::models::{Container, Story};
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -159,7 +159,6 @@ pub fn create_story_file(
 }
 
 /// Represents a story file to be reordered
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct StoryFileToReorder {
     /// Current filename (e.g., "001-chapter.md")
@@ -196,10 +195,39 @@ pub struct FileRename {
 /// - A file doesn't exist
 /// - Git operations fail
 /// - IO operations fail
-#[allow(dead_code)]
 pub fn reorder_story_files(
     repo_path: &Path,
     stories: &[StoryFileToReorder],
+) -> FileManagementResult<Vec<FileRename>> {
+    let renames = rename_story_files(repo_path, stories)?;
+
+    // If no renames needed, return empty list
+    if renames.is_empty() {
+        return Ok(renames);
+    }
+
+    // Build commit message
+    let mut commit_message_parts = vec!["Reorder story files\n".to_string()];
+    commit_message_parts.extend(describe_renames(&renames));
+    let commit_message = commit_message_parts.join("\n");
+
+    // Commit all changes
+    GitService::commit_all(repo_path, &commit_message)?;
+
+    Ok(renames)
+}
+
+/// Rename story files to their new order prefixes without committing
+///
+/// The working-directory half of `reorder_story_files`, for callers that
+/// commit the renames together with other changes. The stories' records in
+/// metadata.json follow their files.
+///
+/// # Returns
+/// The renames performed, in input order
+pub fn rename_story_files(
+    repo_path: &Path,
+    stories: &[StoryFileToReorder],
 ) -> FileManagementResult<Vec<FileRename>> {
     use git2::Repository;
 
@@ -208,7 +236,6 @@ pub fn reorder_story_files(
         .map_err(|e| FileManagementError::Git(crate::git::GitServiceError::Git(e)))?;
 
     let mut renames = Vec::new();
-    let mut commit_message_parts = Vec::new();
 
     // First pass: determine all renames
     for story in stories {
@@ -223,11 +250,6 @@ pub fn reorder_story_files(
         }
     }
 
-    // If no renames needed, return empty list
-    if renames.is_empty() {
-        return Ok(renames);
-    }
-
     // Second pass: perform renames
     // We need to be careful about the order to avoid conflicts
     // Strategy: rename to temporary names first, then to final names
@@ -258,21 +280,212 @@ pub fn reorder_story_files(
         fs::rename(&temp_path, &final_path)?;
     }
 
-    // Build commit message
-    commit_message_parts.push("Reorder story files\n".to_string());
-    for rename in &renames {
-        commit_message_parts.push(format!(
-            "- {} → {}",
-            rename.old_filename, rename.new_filename
-        ));
+    if !renames.is_empty() {
+        rename_chapter_records(repo_path, stories)?;
     }
 
-    let commit_message = commit_message_parts.join("\n");
+    Ok(renames)
+}
 
-    // Commit all changes
-    GitService::commit_all(repo_path, &commit_message)?;
+/// One commit message line per rename ("- old → new")
+pub fn describe_renames(renames: &[FileRename]) -> Vec<String> {
+    renames
+        .iter()
+        .map(|rename| format!("- {} → {}", rename.old_filename, rename.new_filename))
+        .collect()
+}
 
-    Ok(renames)
+/// Content file of a standalone story, which has a repository of its own
+pub const STANDALONE_STORY_FILE: &str = "content.md";
+
+/// Get the filename of a story's content within its repository
+///
+/// Standalone stories always use `content.md`. Stories inside a container
+/// share the container's repository, so they are named after their order
+/// and title (e.g., "003-the-final-battle.md").
+///
+/// # Arguments
+/// * `story` - The story
+///
+/// # Returns
+/// The relative file path within the repository
+pub fn story_filename(story: &Story) -> String {
+    if story.should_have_git_repo() {
+        STANDALONE_STORY_FILE.to_string()
+    } else {
+        // Orders are 0-based in the database, filenames are 1-based
+        let order = story.order.unwrap_or(0) as usize + 1;
+        file_naming::generate_filename(order, &story.title)
+    }
+}
+
+/// Write a story's content into its repository and commit it
+///
+/// Writes the story's markdown file (see `story_filename`) and commits it,
+/// together with its record in the container's metadata.json for a story in
+/// a container (see `record_chapter`). If the title or order changed, the
+/// previous file is renamed so its history follows the story. Nothing is
+/// committed if the file and record are already up to date.
+///
+/// # Arguments
+/// * `repo_path` - Path to the story's Git repository (or its container's)
+/// * `previous_filename` - Filename before this update, if known
+/// * `story` - The story after the update
+/// * `message` - Commit message, or None to describe the change from the file
+///
+/// # Returns
+/// The commit hash, or None if nothing changed
+///
+/// # Errors
+/// Returns an error if:
+/// - IO operations fail
+/// - Git commit fails
+pub fn sync_story_file(
+    repo_path: &Path,
+    previous_filename: Option<&str>,
+    story: &Story,
+    message: Option<&str>,
+) -> FileManagementResult<Option<String>> {
+    let filename = story_filename(story);
+    let file_path = repo_path.join(&filename);
+
+    // Move the old file out of the way so Git records a rename
+    let renamed_from = previous_filename
+        .filter(|previous| *previous != filename && repo_path.join(previous).exists());
+    if let Some(previous) = renamed_from {
+        fs::rename(repo_path.join(previous), &file_path)?;
+    }
+
+    let existing = fs::read_to_string(&file_path).ok();
+    let recorded = record_chapter(repo_path, story)?;
+    if renamed_from.is_none() && !recorded && existing.as_deref() == Some(story.content.as_str()) {
+        return Ok(None);
+    }
+
+    fs::write(&file_path, &story.content)?;
+
+    let commit_message = match (message, renamed_from, existing) {
+        (Some(message), _, _) => message.to_string(),
+        (None, Some(previous), _) => format!("Rename {previous} → {filename}: {}", story.title),
+        (None, None, Some(_)) => format!("Update {filename}: {}", story.title),
+        (None, None, None) => format!("Add {filename}: {}", story.title),
+    };
+
+    // commit_all also stages the removal side of a rename
+    let commit_hash = GitService::commit_all(repo_path, &commit_message)?;
+
+    Ok(Some(commit_hash))
+}
+
+/// Record a story of a container in the container's metadata.json
+///
+/// The entry is stored under the story's current filename, replacing any it
+/// had under another one. The file is written but not committed. Standalone
+/// stories and repositories without a metadata.json are left alone.
+///
+/// # Returns
+/// True if the record changed
+pub fn record_chapter(repo_path: &Path, story: &Story) -> FileManagementResult<bool> {
+    if story.container_id.is_none() || !repo_path.join("metadata.json").exists() {
+        return Ok(false);
+    }
+    let _lock = MetadataLock::acquire(repo_path)?;
+
+    let mut metadata = read_metadata_file(repo_path)?;
+    let filename = story_filename(story);
+    let chapter = ChapterMetadata::from_story(story);
+    let count = metadata.chapters.len();
+    metadata
+        .chapters
+        .retain(|name, recorded| *name == filename || recorded.id != story.id);
+    if metadata.chapters.len() == count && metadata.chapters.get(&filename) == Some(&chapter) {
+        return Ok(false);
+    }
+    metadata.chapters.insert(filename, chapter);
+
+    write_metadata_json(repo_path, &metadata)?;
+    Ok(true)
+}
+
+/// Remove a story's record from its container's metadata.json
+///
+/// The file is written but not committed.
+pub fn forget_chapter(repo_path: &Path, story_id: &str) -> FileManagementResult<()> {
+    if !repo_path.join("metadata.json").exists() {
+        return Ok(());
+    }
+    let _lock = MetadataLock::acquire(repo_path)?;
+
+    let mut metadata = read_metadata_file(repo_path)?;
+    let count = metadata.chapters.len();
+    metadata
+        .chapters
+        .retain(|_, recorded| recorded.id != story_id);
+    if metadata.chapters.len() == count {
+        return Ok(());
+    }
+
+    write_metadata_json(repo_path, &metadata)
+}
+
+/// Record a story of a container in the container's metadata.json and
+/// commit it
+///
+/// For changes that don't touch the story file, like its status; see
+/// `sync_story_file` for the rest.
+pub fn update_chapter_record(repo_path: &Path, story: &Story) -> FileManagementResult<()> {
+    if record_chapter(repo_path, story)? {
+        let json = fs::read_to_string(repo_path.join("metadata.json"))?;
+        let commit_message = format!(
+            "Update record of {}: {}",
+            story_filename(story),
+            story.title
+        );
+        GitService::commit_file(repo_path, "metadata.json", &json, &commit_message)?;
+    }
+    Ok(())
+}
+
+/// Move the records of renamed story files in metadata.json along with them
+///
+/// The file is written but not committed; see `rename_story_files`.
+fn rename_chapter_records(
+    repo_path: &Path,
+    stories: &[StoryFileToReorder],
+) -> FileManagementResult<()> {
+    if !repo_path.join("metadata.json").exists() {
+        return Ok(());
+    }
+    let _lock = MetadataLock::acquire(repo_path)?;
+
+    let mut metadata = read_metadata_file(repo_path)?;
+    let before = metadata.chapters.clone();
+    let moved: Vec<(String, ChapterMetadata)> = stories
+        .iter()
+        .filter_map(|story| {
+            let mut chapter = metadata.chapters.remove(&story.current_filename)?;
+            chapter.order = Some(story.new_order.saturating_sub(1) as u32);
+            let filename = file_naming::generate_filename(story.new_order, &story.title);
+            Some((filename, chapter))
+        })
+        .collect();
+    metadata.chapters.extend(moved);
+    if metadata.chapters == before {
+        return Ok(());
+    }
+
+    write_metadata_json(repo_path, &metadata)
+}
+
+/// Write metadata.json without committing it
+fn write_metadata_json(repo_path: &Path, metadata: &StoryMetadata) -> FileManagementResult<()> {
+    let json = serde_json::to_string_pretty(metadata).map_err(|e| {
+        FileManagementError::Io(std::io::Error::other(format!(
+            "Failed to serialize metadata: {e}"
+        )))
+    })?;
+    fs::write(repo_path.join("metadata.json"), json)?;
+    Ok(())
 }
 
 /// Story metadata for storage in Git repository
@@ -312,11 +525,71 @@ pub struct StoryMetadata {
     /// This allows the UI to show friendly names while Git uses branch-safe slugs
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub variations: std::collections::HashMap<String, String>,
+    /// Maps milestone tag names (slugs) to their display name and note
+    /// e.g., "draft-1-sent-to-editor" -> "Draft 1 sent to editor"
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub milestones: std::collections::HashMap<String, MilestoneMetadata>,
+    /// What the repository holds: "story" or "container" (empty for legacy files)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub kind: String,
+    /// Container type, when `kind` is "container"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_type: Option<String>,
+    /// Parent container ID, when `kind` is "container"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_container_id: Option<String>,
+    /// Sort order within the parent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<i32>,
+    /// The container's stories by filename, when `kind` is "container"
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub chapters: std::collections::BTreeMap<String, ChapterMetadata>,
+}
+
+/// A story of a container, as recorded in the container's metadata.json
+///
+/// Filenames only keep a story's order and a slug of its title, so this is
+/// what lets `rebuild_from_repos` bring a chapter back under its own ID and
+/// exact title.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChapterMetadata {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub status: String,
+    /// Position among the container's stories (0-based)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+}
+
+impl ChapterMetadata {
+    /// Create the record of a story in a container
+    pub fn from_story(story: &Story) -> Self {
+        ChapterMetadata {
+            id: story.id.clone(),
+            title: story.title.clone(),
+            status: format!("{:?}", story.status),
+            order: story.order,
+        }
+    }
+}
+
+/// `StoryMetadata::kind` value for a standalone story repository
+pub const METADATA_KIND_STORY: &str = "story";
+
+/// `StoryMetadata::kind` value for a leaf container repository
+pub const METADATA_KIND_CONTAINER: &str = "container";
+
+/// Display name and note for a milestone, as stored in metadata.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MilestoneMetadata {
+    pub display_name: String,
+    #[serde(default)]
+    pub note: String,
 }
 
 impl StoryMetadata {
     /// Create metadata from a Story
-    #[allow(dead_code)]
     pub fn from_story(story: &Story) -> Self {
         StoryMetadata {
             id: story.id.clone(),
@@ -330,6 +603,35 @@ impl StoryMetadata {
             word_count: story.word_count,
             target_word_count: story.target_word_count,
             variations: std::collections::HashMap::new(),
+            milestones: std::collections::HashMap::new(),
+            kind: METADATA_KIND_STORY.to_string(),
+            container_type: None,
+            parent_container_id: None,
+            order: story.order.map(|o| o as i32),
+            chapters: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// Create metadata from a leaf Container
+    pub fn from_container(container: &Container) -> Self {
+        StoryMetadata {
+            id: container.id.clone(),
+            universe_id: container.universe_id.clone(),
+            title: container.title.clone(),
+            description: container.description.clone().unwrap_or_default(),
+            story_type: String::new(),
+            status: String::new(),
+            created_at: container.created_at.clone(),
+            updated_at: container.updated_at.clone(),
+            word_count: 0,
+            target_word_count: None,
+            variations: std::collections::HashMap::new(),
+            milestones: std::collections::HashMap::new(),
+            kind: METADATA_KIND_CONTAINER.to_string(),
+            container_type: Some(container.container_type.clone()),
+            parent_container_id: container.parent_container_id.clone(),
+            order: Some(container.order),
+            chapters: std::collections::BTreeMap::new(),
         }
     }
 }
@@ -342,6 +644,7 @@ impl StoryMetadata {
 ///
 /// On first creation, initializes the variations mapping with "original" -> "Original"
 /// to establish the default branch name mapping for the Version System UX Abstraction.
+/// Variation and milestone mappings already in the file are preserved.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
@@ -352,21 +655,59 @@ impl StoryMetadata {
 ///
 /// # Errors
 /// Returns an error if:
+/// - An existing metadata.json cannot be read or parsed
 /// - JSON serialization fails
 /// - File write fails
 /// - Git commit fails
-#[allow(dead_code)]
 pub fn write_metadata_file(repo_path: &Path, story: &Story) -> FileManagementResult<String> {
+    let commit_message = format!("Update metadata for story: {}", story.title);
+    write_metadata(repo_path, StoryMetadata::from_story(story), &commit_message)
+}
+
+/// Write metadata.json for a leaf container's Git repository
+///
+/// Records the container's identity and place in the hierarchy so the
+/// repository can be matched back to the database (or re-imported) later.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `container` - The container to generate metadata from
+///
+/// # Returns
+/// The relative path to the metadata file ("metadata.json")
+pub fn write_container_metadata_file(
+    repo_path: &Path,
+    container: &Container,
+) -> FileManagementResult<String> {
+    let commit_message = format!("Update metadata for container: {}", container.title);
+    write_metadata(
+        repo_path,
+        StoryMetadata::from_container(container),
+        &commit_message,
+    )
+}
+
+/// Write and commit metadata.json, keeping the variation and milestone
+/// mappings already recorded in the file
+fn write_metadata(
+    repo_path: &Path,
+    mut metadata: StoryMetadata,
+    commit_message: &str,
+) -> FileManagementResult<String> {
     // Acquire lock before any operations
     let _lock = MetadataLock::acquire(repo_path)?;
 
     let metadata_path = repo_path.join("metadata.json");
-    let is_new_metadata = !metadata_path.exists();
-
-    let mut metadata = StoryMetadata::from_story(story);
 
-    // Initialize variations mapping on first creation
-    if is_new_metadata && metadata.variations.is_empty() {
+    if metadata_path.exists() {
+        // An unreadable file is reported rather than overwritten, so its
+        // mappings aren't silently lost
+        let existing = read_metadata_file(repo_path)?;
+        metadata.variations = existing.variations;
+        metadata.milestones = existing.milestones;
+        metadata.chapters = existing.chapters;
+    } else {
+        // Initialize variations mapping on first creation
         metadata
             .variations
             .insert("original".to_string(), "Original".to_string());
@@ -382,13 +723,135 @@ pub fn write_metadata_file(repo_path: &Path, story: &Story) -> FileManagementRes
     fs::write(&metadata_path, &json)?;
 
     // Commit to Git
-    let commit_message = format!("Update metadata for story: {}", story.title);
-    GitService::commit_file(repo_path, "metadata.json", &json, &commit_message)?;
+    GitService::commit_file(repo_path, "metadata.json", &json, commit_message)?;
 
     // Lock is automatically released when _lock goes out of scope
     Ok("metadata.json".to_string())
 }
 
+/// Edit the variation and milestone mappings in metadata.json and commit them
+///
+/// `edit` returns whether it changed anything; nothing is written otherwise.
+fn update_mappings(
+    repo_path: &Path,
+    commit_message: &str,
+    edit: impl FnOnce(&mut StoryMetadata) -> bool,
+) -> FileManagementResult<()> {
+    // Acquire lock before any operations
+    let _lock = MetadataLock::acquire(repo_path)?;
+
+    let mut metadata = read_metadata_file(repo_path)?;
+    if !edit(&mut metadata) {
+        return Ok(());
+    }
+
+    // Serialize to pretty JSON
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| {
+        FileManagementError::Io(std::io::Error::other(format!(
+            "Failed to serialize metadata: {e}"
+        )))
+    })?;
+
+    fs::write(repo_path.join("metadata.json"), &json)?;
+    GitService::commit_file(repo_path, "metadata.json", &json, commit_message)?;
+
+    // Lock is automatically released when _lock goes out of scope
+    Ok(())
+}
+
+/// Carry another repository's variation and milestone names over with its
+/// history
+///
+/// Companion to `GitService::import_history`. Each name is keyed by where
+/// its ref was imported to, relative to `refs/history/`: `<name>/<branch>`,
+/// `<name>/archive/<branch>` or `<name>/tags/<tag>`. A source without a
+/// metadata.json has nothing to carry over.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository receiving the names
+/// * `source_path` - Path to the repository the history comes from
+/// * `name` - Name the history was imported under (e.g., the story ID)
+///
+/// # Errors
+/// Returns an error if the source's metadata.json cannot be parsed, or if
+/// reading its refs or committing the target's metadata fails
+pub fn import_mappings(
+    repo_path: &Path,
+    source_path: &Path,
+    name: &str,
+) -> FileManagementResult<()> {
+    if !source_path.join("metadata.json").exists() {
+        return Ok(());
+    }
+    let source = read_metadata_file(source_path)?;
+    let archived = GitService::list_archived_branches(source_path)?;
+    let tags: Vec<String> = GitService::list_tags(source_path)?
+        .into_iter()
+        .map(|tag| tag.name)
+        .collect();
+
+    let variations: Vec<(String, String)> = source
+        .variations
+        .into_iter()
+        .map(|(slug, display_name)| {
+            let key = if archived.contains(&slug) {
+                format!("{name}/archive/{slug}")
+            } else {
+                format!("{name}/{slug}")
+            };
+            (key, display_name)
+        })
+        .collect();
+    let milestones: Vec<(String, MilestoneMetadata)> = source
+        .milestones
+        .into_iter()
+        .map(|(slug, milestone)| {
+            // Names the source imported itself aren't tags there
+            let key = if tags.contains(&slug) {
+                format!("{name}/tags/{slug}")
+            } else {
+                format!("{name}/{slug}")
+            };
+            (key, milestone)
+        })
+        .collect();
+    if variations.is_empty() && milestones.is_empty() {
+        return Ok(());
+    }
+
+    let commit_message = format!("Import variation and milestone names of {name}");
+    update_mappings(repo_path, &commit_message, |metadata| {
+        metadata.variations.extend(variations);
+        metadata.milestones.extend(milestones);
+        true
+    })
+}
+
+/// Drop the names `import_mappings` carried over, except for some histories
+///
+/// Companion to `GitService::prune_imported_history`. A repository without a
+/// metadata.json has no names to drop.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `keep` - Names of the histories whose names are kept
+pub fn drop_imported_mappings(repo_path: &Path, keep: &[&str]) -> FileManagementResult<()> {
+    if !repo_path.join("metadata.json").exists() {
+        return Ok(());
+    }
+    // Imported names are the only keys with a history name in front
+    let kept = |key: &String| {
+        key.split_once('/')
+            .is_none_or(|(name, _)| keep.contains(&name))
+    };
+    update_mappings(repo_path, "Drop names of histories not kept", |metadata| {
+        let count = metadata.variations.len() + metadata.milestones.len();
+        metadata.variations.retain(|key, _| kept(key));
+        metadata.milestones.retain(|key, _| kept(key));
+        metadata.variations.len() + metadata.milestones.len() != count
+    })
+}
+
 /// Update metadata.json file in a Git repository
 ///
 /// This is an alias for write_metadata_file, as the operation is the same
@@ -471,34 +934,13 @@ pub fn save_variation_mapping(
     slug: &str,
     display_name: &str,
 ) -> FileManagementResult<()> {
-    // Acquire lock before any operations
-    let _lock = MetadataLock::acquire(repo_path)?;
-
-    // Read existing metadata
-    let mut metadata = read_metadata_file(repo_path)?;
-
-    // Update variations mapping
-    metadata
-        .variations
-        .insert(slug.to_string(), display_name.to_string());
-
-    // Serialize to pretty JSON
-    let json = serde_json::to_string_pretty(&metadata).map_err(|e| {
-        FileManagementError::Io(std::io::Error::other(format!(
-            "Failed to serialize metadata: {e}"
-        )))
-    })?;
-
-    // Write to file
-    let metadata_path = repo_path.join("metadata.json");
-    fs::write(&metadata_path, &json)?;
-
-    // Commit to Git
     let commit_message = format!("Save variation mapping: {slug} -> {display_name}");
-    GitService::commit_file(repo_path, "metadata.json", &json, &commit_message)?;
-
-    // Lock is automatically released when _lock goes out of scope
-    Ok(())
+    update_mappings(repo_path, &commit_message, |metadata| {
+        metadata
+            .variations
+            .insert(slug.to_string(), display_name.to_string());
+        true
+    })
 }
 
 /// Get the display name for a variation
@@ -555,106 +997,326 @@ pub fn list_variations(repo_path: &Path) -> FileManagementResult<Vec<VariationIn
     let current_branch = GitService::get_current_branch(repo_path)?;
 
     // Read metadata (handle missing file gracefully)
-    let metadata = read_metadata_file(repo_path).unwrap_or_else(|_| StoryMetadata {
-        id: String::new(),
-        universe_id: String::new(),
-        title: String::new(),
-        description: String::new(),
-        story_type: String::new(),
-        status: String::new(),
-        created_at: String::new(),
-        updated_at: String::new(),
-        word_count: 0,
-        target_word_count: None,
-        variations: HashMap::new(),
-    });
+    let metadata = read_metadata_file(repo_path).unwrap_or_else(|_| StoryMetadata {
+        id: String::new(),
+        universe_id: String::new(),
+        title: String::new(),
+        description: String::new(),
+        story_type: String::new(),
+        status: String::new(),
+        created_at: String::new(),
+        updated_at: String::new(),
+        word_count: 0,
+        target_word_count: None,
+        variations: HashMap::new(),
+        milestones: HashMap::new(),
+        kind: String::new(),
+        container_type: None,
+        parent_container_id: None,
+        order: None,
+        chapters: std::collections::BTreeMap::new(),
+    });
+
+    // Build VariationInfo for each branch
+    let variations = branches
+        .into_iter()
+        .map(|slug| {
+            let display_name = metadata
+                .variations
+                .get(&slug)
+                .cloned()
+                .unwrap_or_else(|| slug.clone());
+
+            let is_current = slug == current_branch;
+            let is_original = slug == "original" || slug == "main";
+
+            VariationInfo {
+                slug,
+                display_name,
+                is_current,
+                is_original,
+            }
+        })
+        .collect();
+
+    Ok(variations)
+}
+
+/// Remove a variation mapping from metadata.json
+///
+/// Removes the variation mapping when a branch is deleted. This should be called
+/// after the Git branch is deleted to keep metadata in sync.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - The Git branch name to remove
+///
+/// # Returns
+/// Success or error
+///
+/// # Errors
+/// Returns an error if:
+/// - Metadata file doesn't exist
+/// - File cannot be read or written
+/// - Git commit fails
+#[allow(dead_code)]
+pub fn remove_variation_mapping(repo_path: &Path, slug: &str) -> FileManagementResult<()> {
+    // Check if the branch has uncommitted changes
+    match GitService::has_uncommitted_changes(repo_path, slug) {
+        Ok(true) => {
+            return Err(FileManagementError::UncommittedChanges(format!(
+                "Cannot remove variation mapping for '{slug}': branch has uncommitted changes"
+            )));
+        }
+        Ok(false) => {
+            // No uncommitted changes, proceed
+        }
+        Err(_) => {
+            // Branch doesn't exist or other error - proceed anyway
+            // The variation mapping can be removed even if the branch doesn't exist
+        }
+    }
+
+    let commit_message = format!("Remove variation mapping: {slug}");
+    update_mappings(repo_path, &commit_message, |metadata| {
+        metadata.variations.remove(slug);
+        true
+    })
+}
+
+/// Rename a variation mapping in metadata.json
+///
+/// Moves the display name mapping from the old slug to the new slug. This
+/// should be called after the Git branch is renamed to keep metadata in sync.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `old_slug` - The previous Git branch name
+/// * `new_slug` - The new Git branch name
+/// * `display_name` - The new user-friendly name
+///
+/// # Returns
+/// Success or error
+///
+/// # Errors
+/// Returns an error if:
+/// - Metadata file doesn't exist
+/// - File cannot be read or written
+/// - Git commit fails
+pub fn rename_variation_mapping(
+    repo_path: &Path,
+    old_slug: &str,
+    new_slug: &str,
+    display_name: &str,
+) -> FileManagementResult<()> {
+    let commit_message = format!("Rename variation: {old_slug} -> {new_slug} ({display_name})");
+    update_mappings(repo_path, &commit_message, |metadata| {
+        metadata.variations.remove(old_slug);
+        metadata
+            .variations
+            .insert(new_slug.to_string(), display_name.to_string());
+        true
+    })
+}
+
+/// List archived variations in the repository
+///
+/// Archived variations are hidden from `list_variations` but keep their
+/// display name mapping so they can be shown and restored later.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// Vector of VariationInfo structs (never current, never original)
+///
+/// # Errors
+/// Returns an error if:
+/// - Repository doesn't exist
+/// - Git operations fail
+pub fn list_archived_variations(repo_path: &Path) -> FileManagementResult<Vec<VariationInfo>> {
+    let branches = GitService::list_archived_branches(repo_path)?;
+
+    // Missing metadata falls back to slugs as display names
+    let names = read_metadata_file(repo_path)
+        .map(|metadata| metadata.variations)
+        .unwrap_or_default();
+
+    Ok(branches
+        .into_iter()
+        .map(|slug| VariationInfo {
+            display_name: names.get(&slug).cloned().unwrap_or_else(|| slug.clone()),
+            slug,
+            is_current: false,
+            is_original: false,
+        })
+        .collect())
+}
+
+/// Information about a milestone (annotated Git tag with display name)
+///
+/// Milestones mark points in a story's history such as "Draft 1 sent to editor"
+/// or "Beta-reader version".
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct MilestoneInfo {
+    /// Git tag name (slug), e.g., "draft-1-sent-to-editor"
+    pub slug: String,
+    /// User-facing display name, e.g., "Draft 1 sent to editor"
+    pub display_name: String,
+    /// Free-form note attached to the milestone
+    pub note: String,
+    /// Hash of the commit the milestone points to
+    pub commit_hash: String,
+    /// Unix timestamp of when the milestone was created
+    pub timestamp: String,
+}
+
+/// Save or update a milestone mapping in metadata.json
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - The Git tag name (e.g., "draft-1-sent-to-editor")
+/// * `display_name` - The user-friendly name (e.g., "Draft 1 sent to editor")
+/// * `note` - Free-form note for the milestone
+///
+/// # Errors
+/// Returns an error if:
+/// - Metadata file doesn't exist
+/// - File cannot be read or written
+/// - Git commit fails
+pub fn save_milestone_mapping(
+    repo_path: &Path,
+    slug: &str,
+    display_name: &str,
+    note: &str,
+) -> FileManagementResult<()> {
+    let commit_message = format!("Save milestone mapping: {slug} -> {display_name}");
+    update_mappings(repo_path, &commit_message, |metadata| {
+        metadata.milestones.insert(
+            slug.to_string(),
+            MilestoneMetadata {
+                display_name: display_name.to_string(),
+                note: note.to_string(),
+            },
+        );
+        true
+    })
+}
+
+/// Remove a milestone mapping from metadata.json
+///
+/// Should be called after the Git tag is deleted to keep metadata in sync.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `slug` - The Git tag name to remove
+///
+/// # Errors
+/// Returns an error if:
+/// - Metadata file doesn't exist
+/// - File cannot be read or written
+/// - Git commit fails
+pub fn remove_milestone_mapping(repo_path: &Path, slug: &str) -> FileManagementResult<()> {
+    let commit_message = format!("Remove milestone mapping: {slug}");
+    // Nothing to commit if the mapping is already gone
+    update_mappings(repo_path, &commit_message, |metadata| {
+        metadata.milestones.remove(slug).is_some()
+    })
+}
+
+/// List all milestones in the repository
+///
+/// Tags without a mapping in metadata.json use the tag name as display name
+/// and the tag message as note.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// Vector of MilestoneInfo ordered from newest to oldest
+pub fn list_milestones(repo_path: &Path) -> FileManagementResult<Vec<MilestoneInfo>> {
+    let tags = GitService::list_tags(repo_path)?;
+
+    // Read metadata (handle missing file gracefully)
+    let mappings = read_metadata_file(repo_path)
+        .map(|metadata| metadata.milestones)
+        .unwrap_or_default();
 
-    // Build VariationInfo for each branch
-    let variations = branches
+    let milestones = tags
         .into_iter()
-        .map(|slug| {
-            let display_name = metadata
-                .variations
-                .get(&slug)
-                .cloned()
-                .unwrap_or_else(|| slug.clone());
-
-            let is_current = slug == current_branch;
-            let is_original = slug == "original" || slug == "main";
-
-            VariationInfo {
-                slug,
+        .map(|tag| {
+            let (display_name, note) = match mappings.get(&tag.name) {
+                Some(mapping) => (mapping.display_name.clone(), mapping.note.clone()),
+                None => (tag.name.clone(), tag.message.trim().to_string()),
+            };
+
+            MilestoneInfo {
+                slug: tag.name,
                 display_name,
-                is_current,
-                is_original,
+                note,
+                commit_hash: tag.commit_hash,
+                timestamp: tag.timestamp,
             }
         })
         .collect();
 
-    Ok(variations)
+    Ok(milestones)
 }
 
-/// Remove a variation mapping from metadata.json
-///
-/// Removes the variation mapping when a branch is deleted. This should be called
-/// after the Git branch is deleted to keep metadata in sync.
+/// Find the commit a milestone points to
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `slug` - The Git branch name to remove
+/// * `name` - The milestone slug or display name
 ///
 /// # Returns
-/// Success or error
+/// The hash of the milestone's commit
 ///
 /// # Errors
-/// Returns an error if:
-/// - Metadata file doesn't exist
-/// - File cannot be read or written
-/// - Git commit fails
-#[allow(dead_code)]
-pub fn remove_variation_mapping(repo_path: &Path, slug: &str) -> FileManagementResult<()> {
-    // Check if the branch has uncommitted changes
-    match GitService::has_uncommitted_changes(repo_path, slug) {
-        Ok(true) => {
-            return Err(FileManagementError::UncommittedChanges(format!(
-                "Cannot remove variation mapping for '{slug}': branch has uncommitted changes"
-            )));
-        }
-        Ok(false) => {
-            // No uncommitted changes, proceed
-        }
-        Err(_) => {
-            // Branch doesn't exist or other error - proceed anyway
-            // The variation mapping can be removed even if the branch doesn't exist
+/// Returns an error if no milestone matches the name
+pub fn resolve_milestone(repo_path: &Path, name: &str) -> FileManagementResult<String> {
+    let milestones = list_milestones(repo_path)?;
+
+    milestones
+        .iter()
+        .find(|m| m.slug == name)
+        .or_else(|| milestones.iter().find(|m| m.display_name == name))
+        .map(|m| m.commit_hash.clone())
+        .ok_or_else(|| {
+            FileManagementError::Git(crate::git::GitServiceError::NotFound(format!(
+                "Milestone '{name}' not found"
+            )))
+        })
+}
+
+/// Search a story's history, labelling matches with variation display names
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `pattern` - Phrase or regular expression to look for
+/// * `is_regex` - Treat `pattern` as a regular expression
+///
+/// # Returns
+/// Matches from `GitService::search_history`, newest first
+pub fn search_history(
+    repo_path: &Path,
+    pattern: &str,
+    is_regex: bool,
+) -> FileManagementResult<Vec<HistoryMatch>> {
+    let mut matches = GitService::search_history(repo_path, pattern, is_regex)?;
+
+    // Missing metadata falls back to slugs as display names
+    let names = read_metadata_file(repo_path)
+        .map(|metadata| metadata.variations)
+        .unwrap_or_default();
+    for found in &mut matches {
+        if let Some(name) = names.get(&found.branch) {
+            found.branch_display_name = name.clone();
         }
     }
 
-    // Acquire lock before any operations
-    let _lock = MetadataLock::acquire(repo_path)?;
-
-    // Read existing metadata
-    let mut metadata = read_metadata_file(repo_path)?;
-
-    // Remove the variation mapping
-    metadata.variations.remove(slug);
-
-    // Serialize to pretty JSON
-    let json = serde_json::to_string_pretty(&metadata).map_err(|e| {
-        FileManagementError::Io(std::io::Error::other(format!(
-            "Failed to serialize metadata: {e}"
-        )))
-    })?;
-
-    // Write to file
-    let metadata_path = repo_path.join("metadata.json");
-    fs::write(&metadata_path, &json)?;
-
-    // Commit to Git
-    let commit_message = format!("Remove variation mapping: {slug}");
-    GitService::commit_file(repo_path, "metadata.json", &json, &commit_message)?;
-
-    // Lock is automatically released when _lock goes out of scope
-    Ok(())
+    Ok(matches)
 }
 
 #[cfg(test)]
@@ -983,6 +1645,11 @@ mod tests {
             status: StoryStatus::InProgress,
             word_count: 1000,
             target_word_count: Some(50000),
+            character_count: 0,
+            character_count_no_spaces: 0,
+            paragraph_count: 0,
+            sentence_count: 0,
+            reading_time_seconds: 0,
             content: "Story content".to_string(),
             notes: Some("Story notes".to_string()),
             outline: None,
@@ -1004,6 +1671,125 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_story_filename_standalone_and_child() {
+        let mut story = create_test_story();
+        assert_eq!(story_filename(&story), "content.md");
+
+        story.container_id = Some("container-1".to_string());
+        story.order = Some(2);
+        assert_eq!(story_filename(&story), "003-test-story.md");
+    }
+
+    #[test]
+    fn test_sync_story_file_commits_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let mut story = create_test_story();
+        let first = sync_story_file(&repo_path, None, &story, None).unwrap();
+        assert!(first.is_some());
+        assert_eq!(
+            fs::read_to_string(repo_path.join("content.md")).unwrap(),
+            "Story content"
+        );
+
+        story.content = "Story content, revised".to_string();
+        sync_story_file(&repo_path, Some("content.md"), &story, None).unwrap();
+
+        let history = GitService::get_history(&repo_path, "original").unwrap();
+        assert!(history[0].message.contains("Update content.md"));
+        assert!(history[1].message.contains("Add content.md"));
+    }
+
+    #[test]
+    fn test_sync_story_file_skips_unchanged_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let story = create_test_story();
+        sync_story_file(&repo_path, None, &story, None).unwrap();
+        let before = GitService::get_history(&repo_path, "original")
+            .unwrap()
+            .len();
+
+        let result = sync_story_file(&repo_path, Some("content.md"), &story, None).unwrap();
+
+        assert!(result.is_none());
+        let after = GitService::get_history(&repo_path, "original")
+            .unwrap()
+            .len();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_sync_story_file_renames_child_story() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-container").unwrap();
+
+        let mut story = create_test_story();
+        story.container_id = Some("container-1".to_string());
+        story.order = Some(0);
+        sync_story_file(&repo_path, None, &story, None).unwrap();
+        let old_filename = story_filename(&story);
+        assert_eq!(old_filename, "001-test-story.md");
+
+        story.title = "New Title".to_string();
+        sync_story_file(&repo_path, Some(&old_filename), &story, None).unwrap();
+
+        assert!(!repo_path.join("001-test-story.md").exists());
+        assert!(repo_path.join("001-new-title.md").exists());
+
+        // History follows the rename
+        let history =
+            GitService::get_file_history(&repo_path, "original", "001-new-title.md").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].path, "001-test-story.md");
+    }
+
+    #[test]
+    fn test_chapter_records_follow_saves_and_reorders() {
+        use crate::models::StoryStatus;
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-container").unwrap();
+
+        let mut story = create_test_story();
+        story.title = "Into the Woods!".to_string();
+        story.container_id = Some("container-1".to_string());
+        story.order = Some(0);
+        sync_story_file(&repo_path, None, &story, None).unwrap();
+
+        let chapters = read_metadata_file(&repo_path).unwrap().chapters;
+        assert_eq!(
+            chapters.get("001-into-the-woods.md"),
+            Some(&ChapterMetadata::from_story(&story))
+        );
+
+        // A status change only touches the record, and is committed
+        story.status = StoryStatus::Completed;
+        update_chapter_record(&repo_path, &story).unwrap();
+        let chapters = read_metadata_file(&repo_path).unwrap().chapters;
+        assert_eq!(chapters["001-into-the-woods.md"].status, "Completed");
+        assert!(!GitService::has_uncommitted_changes(&repo_path, "original").unwrap());
+
+        reorder_story_files(
+            &repo_path,
+            &[StoryFileToReorder {
+                current_filename: "001-into-the-woods.md".to_string(),
+                new_order: 3,
+                title: story.title.clone(),
+            }],
+        )
+        .unwrap();
+
+        let chapters = read_metadata_file(&repo_path).unwrap().chapters;
+        assert_eq!(chapters.len(), 1);
+        let chapter = &chapters["003-into-the-woods.md"];
+        assert_eq!(chapter.id, story.id);
+        assert_eq!(chapter.title, "Into the Woods!");
+        assert_eq!(chapter.order, Some(2));
+    }
+
     #[test]
     fn test_write_metadata_file_creates_json() {
         let temp_dir = TempDir::new().unwrap();
@@ -1092,6 +1878,42 @@ mod tests {
         assert_eq!(metadata.word_count, 2000);
     }
 
+    #[test]
+    fn test_write_metadata_file_preserves_variations() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let mut story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+        save_variation_mapping(&repo_path, "alt-ending", "Alt Ending").unwrap();
+
+        story.title = "Renamed".to_string();
+        write_metadata_file(&repo_path, &story).unwrap();
+
+        let metadata = read_metadata_file(&repo_path).unwrap();
+        assert_eq!(metadata.title, "Renamed");
+        assert_eq!(metadata.kind, METADATA_KIND_STORY);
+        assert_eq!(
+            metadata.variations.get("alt-ending"),
+            Some(&"Alt Ending".to_string())
+        );
+    }
+
+    #[test]
+    fn test_write_metadata_file_rejects_unparseable_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+        fs::write(repo_path.join("metadata.json"), "{ not json").unwrap();
+
+        let result = write_metadata_file(&repo_path, &create_test_story());
+
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(repo_path.join("metadata.json")).unwrap(),
+            "{ not json"
+        );
+    }
+
     #[test]
     fn test_story_metadata_from_story() {
         let story = create_test_story();
@@ -1121,6 +1943,12 @@ mod tests {
             word_count: 1000,
             target_word_count: Some(50000),
             variations: std::collections::HashMap::new(),
+            milestones: std::collections::HashMap::new(),
+            kind: String::new(),
+            container_type: None,
+            parent_container_id: None,
+            order: None,
+            chapters: std::collections::BTreeMap::new(),
         };
 
         // Serialize and deserialize
@@ -1154,6 +1982,12 @@ mod tests {
             word_count: 1000,
             target_word_count: Some(50000),
             variations,
+            milestones: std::collections::HashMap::new(),
+            kind: String::new(),
+            container_type: None,
+            parent_container_id: None,
+            order: None,
+            chapters: std::collections::BTreeMap::new(),
         };
 
         // Serialize and deserialize
@@ -1183,6 +2017,12 @@ mod tests {
             word_count: 1000,
             target_word_count: Some(50000),
             variations: std::collections::HashMap::new(),
+            milestones: std::collections::HashMap::new(),
+            kind: String::new(),
+            container_type: None,
+            parent_container_id: None,
+            order: None,
+            chapters: std::collections::BTreeMap::new(),
         };
 
         let json = serde_json::to_string_pretty(&metadata).unwrap();
@@ -1285,6 +2125,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_restore_snapshot_keeps_later_variations() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+        let story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+        let snapshot =
+            GitService::commit_file(&repo_path, "content.md", "First draft.\n", "Draft").unwrap();
+        GitService::commit_file(&repo_path, "content.md", "Second draft.\n", "Revise").unwrap();
+
+        // Variation created after the snapshot
+        GitService::create_branch(&repo_path, "original", "what-if").unwrap();
+        save_variation_mapping(&repo_path, "what-if", "What if?").unwrap();
+
+        GitService::restore_snapshot(&repo_path, &snapshot).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(repo_path.join("content.md")).unwrap(),
+            "First draft.\n"
+        );
+        let variations = list_variations(&repo_path).unwrap();
+        let variation = variations.iter().find(|v| v.slug == "what-if").unwrap();
+        assert_eq!(variation.display_name, "What if?");
+    }
+
     #[test]
     fn test_save_variation_mapping_updates_existing_variation() {
         let temp_dir = TempDir::new().unwrap();
@@ -1674,6 +2539,131 @@ mod tests {
         assert!(metadata.variations.get("other-branch").is_none());
     }
 
+    #[test]
+    fn test_rename_variation_mapping_moves_display_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+
+        save_variation_mapping(&repo_path, "draft", "Draft").unwrap();
+        rename_variation_mapping(&repo_path, "draft", "second-draft", "Second Draft").unwrap();
+
+        let metadata = read_metadata_file(&repo_path).unwrap();
+        assert!(!metadata.variations.contains_key("draft"));
+        assert_eq!(
+            metadata.variations.get("second-draft"),
+            Some(&"Second Draft".to_string())
+        );
+    }
+
+    #[test]
+    fn test_list_archived_variations_uses_display_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+        let current_branch = GitService::get_current_branch(&repo_path).unwrap();
+
+        let story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+
+        GitService::create_branch(&repo_path, &current_branch, "old-idea").unwrap();
+        save_variation_mapping(&repo_path, "old-idea", "Old Idea").unwrap();
+        GitService::archive_branch(&repo_path, "old-idea").unwrap();
+
+        let variations = list_variations(&repo_path).unwrap();
+        assert!(!variations.iter().any(|v| v.slug == "old-idea"));
+
+        let archived = list_archived_variations(&repo_path).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].slug, "old-idea");
+        assert_eq!(archived[0].display_name, "Old Idea");
+        assert!(!archived[0].is_current);
+    }
+
+    // Tests for milestone functions
+
+    #[test]
+    fn test_save_milestone_mapping_and_list_milestones() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+
+        let commit_hash =
+            GitService::create_tag(&repo_path, "draft-1", None, "Draft 1 sent to editor").unwrap();
+        save_milestone_mapping(
+            &repo_path,
+            "draft-1",
+            "Draft 1 sent to editor",
+            "Waiting on notes",
+        )
+        .unwrap();
+
+        let milestones = list_milestones(&repo_path).unwrap();
+
+        assert_eq!(milestones.len(), 1);
+        assert_eq!(milestones[0].slug, "draft-1");
+        assert_eq!(milestones[0].display_name, "Draft 1 sent to editor");
+        assert_eq!(milestones[0].note, "Waiting on notes");
+        assert_eq!(milestones[0].commit_hash, commit_hash);
+    }
+
+    #[test]
+    fn test_list_milestones_falls_back_to_tag_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        GitService::create_tag(&repo_path, "beta", None, "Beta readers").unwrap();
+
+        let milestones = list_milestones(&repo_path).unwrap();
+
+        assert_eq!(milestones.len(), 1);
+        assert_eq!(milestones[0].display_name, "beta");
+        assert_eq!(milestones[0].note, "Beta readers");
+    }
+
+    #[test]
+    fn test_remove_milestone_mapping_removes_milestone() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+        save_milestone_mapping(&repo_path, "draft-1", "Draft 1", "").unwrap();
+
+        remove_milestone_mapping(&repo_path, "draft-1").unwrap();
+
+        let metadata = read_metadata_file(&repo_path).unwrap();
+        assert!(metadata.milestones.is_empty());
+
+        // Removing again is a no-op
+        assert!(remove_milestone_mapping(&repo_path, "draft-1").is_ok());
+    }
+
+    #[test]
+    fn test_resolve_milestone_by_slug_or_display_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-story").unwrap();
+
+        let story = create_test_story();
+        write_metadata_file(&repo_path, &story).unwrap();
+
+        let commit_hash = GitService::create_tag(&repo_path, "draft-1", None, "Draft").unwrap();
+        save_milestone_mapping(&repo_path, "draft-1", "Draft 1 sent to editor", "").unwrap();
+
+        assert_eq!(
+            resolve_milestone(&repo_path, "draft-1").unwrap(),
+            commit_hash
+        );
+        assert_eq!(
+            resolve_milestone(&repo_path, "Draft 1 sent to editor").unwrap(),
+            commit_hash
+        );
+        assert!(resolve_milestone(&repo_path, "Final").is_err());
+    }
+
     #[test]
     fn test_variation_info_struct() {
         let info = VariationInfo {
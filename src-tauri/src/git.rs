@@ -1,6 +1,11 @@
+use crate::prose_merge::{self, MergeRegion, ProseMergeResult};
+use crate::word_diff::{self, WordSegment};
 use git2::{Error as GitError, Oid, Repository, Signature};
 use log::warn;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -17,6 +22,16 @@ pub enum GitServiceError {
     RepositoryNotFound(PathBuf),
     /// Invalid operation
     InvalidOperation(String),
+    /// A branch, tag, commit or file does not exist
+    NotFound(String),
+    /// A branch, tag, file or repository with the same name already exists
+    AlreadyExists(String),
+    /// The operation is blocked by uncommitted changes in the working directory
+    UncommittedChanges(String),
+    /// The input (commit hash, search pattern) failed validation
+    InvalidInput(String),
+    /// A conflict is still unresolved
+    Conflict(String),
     /// Repository corruption detected
     RepositoryCorrupted(String),
 }
@@ -30,6 +45,11 @@ impl fmt::Display for GitServiceError {
                 write!(f, "Repository not found at: {}", path.display())
             }
             GitServiceError::InvalidOperation(msg) => write!(f, "Invalid operation: {msg}"),
+            GitServiceError::NotFound(msg)
+            | GitServiceError::AlreadyExists(msg)
+            | GitServiceError::UncommittedChanges(msg)
+            | GitServiceError::InvalidInput(msg)
+            | GitServiceError::Conflict(msg) => write!(f, "{msg}"),
             GitServiceError::RepositoryCorrupted(msg) => write!(f, "Repository corrupted: {msg}"),
         }
     }
@@ -57,8 +77,17 @@ pub type GitResult<T> = Result<T, GitServiceError>;
 #[ts(export, export_to = "../../src/types/")]
 pub struct FileChange {
     pub path: String,
+    /// Previous path of the file, set only for renamed files
+    pub old_path: Option<String>,
     pub status: ChangeStatus,
+    /// Unified patch text for the file (None for binary files)
     pub diff: Option<String>,
+    /// Changed regions of the file with line- and word-level detail
+    pub hunks: Vec<DiffHunk>,
+    /// Number of words added to the file
+    pub words_added: u32,
+    /// Number of words removed from the file
+    pub words_removed: u32,
 }
 
 /// Status of a file change
@@ -68,6 +97,43 @@ pub enum ChangeStatus {
     Added,
     Modified,
     Deleted,
+    Renamed,
+}
+
+/// A contiguous region of changes within a file
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct DiffHunk {
+    /// Hunk header, e.g. "@@ -1,3 +1,4 @@"
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// A single line within a diff hunk
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line content without the trailing newline
+    pub content: String,
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+    /// Word-level breakdown of a changed line, paired against the line it replaced.
+    /// Empty for context lines and for lines that were purely added or removed.
+    pub segments: Vec<WordSegment>,
+}
+
+/// Kind of a line within a diff hunk
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
 }
 
 /// Result of a diff operation between two branches
@@ -86,6 +152,67 @@ pub struct MergeResult {
     pub message: String,
 }
 
+/// Outcome a merge would have
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub enum MergeOutcome {
+    UpToDate,
+    FastForward,
+    Clean,
+    Conflicted,
+}
+
+/// Preview of a merge, computed without touching the repository
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct MergePreview {
+    pub outcome: MergeOutcome,
+    /// Files that would conflict, with their conflict hunks
+    pub conflicts: Vec<ConflictFile>,
+    /// Files changed on the source branch since the branches diverged
+    pub files_changed: u32,
+    pub words_added: u32,
+    pub words_removed: u32,
+}
+
+/// A conflicting passage within a conflicted file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct ConflictHunk {
+    pub id: u32,
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// A conflicted file with its conflict hunks in document order
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct ConflictFile {
+    pub path: String,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+/// How to resolve a single conflict hunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HunkResolution {
+    Ours,
+    Theirs,
+    OursThenTheirs,
+    TheirsThenOurs,
+    Custom { text: String },
+}
+
+/// The resolution chosen for a conflict hunk
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct HunkChoice {
+    pub hunk_id: u32,
+    pub resolution: HunkResolution,
+}
+
 /// Information about a commit in the history
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/")]
@@ -94,8 +221,90 @@ pub struct CommitInfo {
     pub message: String,
     pub author: String,
     pub timestamp: String,
+    /// Names of the tags (milestones) pointing at this commit
+    pub tags: Vec<String>,
+}
+
+/// A commit that changed a file, with the file's path at that commit
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct FileHistoryEntry {
+    pub path: String,
+    pub commit: CommitInfo,
+}
+
+/// The commit that last changed a paragraph
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct ParagraphBlame {
+    /// Position of the paragraph in the file (0-based)
+    pub index: u32,
+    /// First line of the paragraph (1-based)
+    pub start_line: u32,
+    /// Last line of the paragraph (1-based, inclusive)
+    pub end_line: u32,
+    pub text: String,
+    pub commit: CommitInfo,
+}
+
+/// A passage found by searching a repository's history
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct HistoryMatch {
+    /// Newest commit in which the passage had this text
+    pub commit: CommitInfo,
+    /// Branch (variation slug) the commit belongs to
+    pub branch: String,
+    /// User-facing name of the branch; same as `branch` unless filled in
+    /// from the variation mapping
+    pub branch_display_name: String,
+    /// Path of the file at that commit
+    pub file_path: String,
+    /// Position of the paragraph in the file at that commit (0-based)
+    pub paragraph_index: u32,
+    /// The whole paragraph containing the match
+    pub passage: String,
+    /// Text around the match, with "…" where the paragraph was cut
+    pub snippet: String,
+    /// Start of the match within `snippet`, in UTF-16 code units
+    pub match_start: u32,
+    /// End of the match within `snippet`, in UTF-16 code units
+    pub match_end: u32,
+}
+
+/// A file as it was in a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct SnapshotFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// Information about an annotated tag
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagInfo {
+    pub name: String,
+    /// Hash of the tagged commit
+    pub commit_hash: String,
+    pub message: String,
+    /// Unix timestamp of when the tag was created
+    pub timestamp: String,
+}
+
+/// The three versions of a conflicted file
+struct ConflictSides {
+    path: String,
+    base: String,
+    ours: String,
+    theirs: String,
 }
 
+/// Upper bound on the results of `GitService::search_history`
+const MAX_HISTORY_MATCHES: usize = 200;
+
+/// Characters of context kept on each side of a history search match
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
 /// Service for managing Git operations on story repositories
 pub struct GitService;
 
@@ -221,7 +430,7 @@ impl GitService {
 
         // Check if repository already exists
         if repo_path.exists() {
-            return Err(GitServiceError::InvalidOperation(format!(
+            return Err(GitServiceError::AlreadyExists(format!(
                 "Repository already exists at: {}",
                 repo_path.display()
             )));
@@ -360,6 +569,7 @@ impl GitService {
         // Stage all changes (modified, new, deleted files)
         let mut index = repo.index()?;
         index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"].iter(), None)?;
         index.write()?;
 
         // Create commit
@@ -402,7 +612,7 @@ impl GitService {
             .find_branch(new_branch, git2::BranchType::Local)
             .is_ok()
         {
-            return Err(GitServiceError::InvalidOperation(format!(
+            return Err(GitServiceError::AlreadyExists(format!(
                 "Branch '{new_branch}' already exists"
             )));
         }
@@ -411,9 +621,7 @@ impl GitService {
         let parent_ref = repo
             .find_branch(parent_branch, git2::BranchType::Local)
             .map_err(|_| {
-                GitServiceError::InvalidOperation(format!(
-                    "Parent branch '{parent_branch}' not found"
-                ))
+                GitServiceError::NotFound(format!("Parent branch '{parent_branch}' not found"))
             })?;
 
         let parent_commit = parent_ref.get().peel_to_commit()?;
@@ -440,7 +648,7 @@ impl GitService {
         // Check for uncommitted changes
         let statuses = repo.statuses(None)?;
         if !statuses.is_empty() {
-            return Err(GitServiceError::InvalidOperation(
+            return Err(GitServiceError::UncommittedChanges(
                 "Cannot checkout branch: uncommitted changes exist".to_string(),
             ));
         }
@@ -448,9 +656,7 @@ impl GitService {
         // Find the branch
         let branch_ref = repo
             .find_branch(branch, git2::BranchType::Local)
-            .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{branch}' not found"))
-            })?;
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
 
         let reference = branch_ref.get();
         let commit = reference.peel_to_commit()?;
@@ -472,7 +678,8 @@ impl GitService {
     /// * `branch_b` - Second branch to compare
     ///
     /// # Returns
-    /// DiffResult with file changes between branches
+    /// DiffResult with file changes between branches, including patch text,
+    /// hunks with word-level changes, and word counts for each file
     pub fn diff_branches(
         repo_path: &Path,
         branch_a: &str,
@@ -485,31 +692,145 @@ impl GitService {
         // Get branch references
         let branch_a_ref = repo
             .find_branch(branch_a, git2::BranchType::Local)
-            .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{branch_a}' not found"))
-            })?;
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch_a}' not found")))?;
 
         let branch_b_ref = repo
             .find_branch(branch_b, git2::BranchType::Local)
-            .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{branch_b}' not found"))
-            })?;
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch_b}' not found")))?;
 
         // Get trees from branches
         let tree_a = branch_a_ref.get().peel_to_tree()?;
         let tree_b = branch_b_ref.get().peel_to_tree()?;
 
         // Create diff
-        let diff = repo.diff_tree_to_tree(Some(&tree_a), Some(&tree_b), None)?;
+        let mut diff = repo.diff_tree_to_tree(Some(&tree_a), Some(&tree_b), None)?;
+
+        Self::collect_changes(&mut diff)
+    }
+
+    /// Get diff between two commits
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `from_commit` - Hash of the older commit (e.g., from get_history)
+    /// * `to_commit` - Hash of the newer commit
+    ///
+    /// # Returns
+    /// DiffResult with file changes between the two commits
+    pub fn diff_commits(
+        repo_path: &Path,
+        from_commit: &str,
+        to_commit: &str,
+    ) -> GitResult<DiffResult> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let tree_from = Self::find_commit(&repo, from_commit)?.tree()?;
+        let tree_to = Self::find_commit(&repo, to_commit)?.tree()?;
+
+        // Create diff
+        let mut diff = repo.diff_tree_to_tree(Some(&tree_from), Some(&tree_to), None)?;
+
+        Self::collect_changes(&mut diff)
+    }
+
+    /// Get diff between a commit and the working tree
+    ///
+    /// Includes both staged and unstaged changes, as well as new files that
+    /// have not been committed yet.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `commit_hash` - Hash of the commit to compare against
+    ///
+    /// # Returns
+    /// DiffResult with file changes from the commit to the current working tree
+    pub fn diff_commit_to_workdir(repo_path: &Path, commit_hash: &str) -> GitResult<DiffResult> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let tree = Self::find_commit(&repo, commit_hash)?.tree()?;
+
+        // Create diff
+        let mut opts = Self::workdir_diff_options();
+        let mut diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut opts))?;
+
+        Self::collect_changes(&mut diff)
+    }
+
+    /// Get diff between the index and the working tree
+    ///
+    /// Shows changes that have not been staged or committed yet, including
+    /// new files.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// DiffResult with unstaged file changes
+    pub fn diff_index_to_workdir(repo_path: &Path) -> GitResult<DiffResult> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Create diff
+        let mut opts = Self::workdir_diff_options();
+        let mut diff = repo.diff_index_to_workdir(None, Some(&mut opts))?;
+
+        Self::collect_changes(&mut diff)
+    }
+
+    /// Find a commit by its hash
+    ///
+    /// # Errors
+    /// Returns InvalidOperation if the hash is malformed or the commit doesn't exist
+    fn find_commit<'r>(repo: &'r Repository, commit_hash: &str) -> GitResult<git2::Commit<'r>> {
+        // Parse commit hash
+        let oid = Oid::from_str(commit_hash).map_err(|_| {
+            GitServiceError::InvalidInput(format!("Invalid commit hash: {commit_hash}"))
+        })?;
+
+        // Find the commit
+        repo.find_commit(oid)
+            .map_err(|_| GitServiceError::NotFound(format!("Commit not found: {commit_hash}")))
+    }
+
+    /// Diff options for comparisons against the working tree
+    ///
+    /// New story files are untracked until their first commit, so untracked
+    /// files are included along with their content.
+    fn workdir_diff_options() -> git2::DiffOptions {
+        let mut opts = git2::DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        opts
+    }
+
+    /// Build a DiffResult from a git2 diff
+    ///
+    /// Detects renames, then collects each file's patch text, hunks with
+    /// line- and word-level changes, and added/removed word counts.
+    fn collect_changes(diff: &mut git2::Diff) -> GitResult<DiffResult> {
+        // Pair up deleted/added files that are actually renames
+        let mut find_opts = git2::DiffFindOptions::new();
+        find_opts.renames(true).for_untracked(true);
+        diff.find_similar(Some(&mut find_opts))?;
 
         let mut changes = Vec::new();
 
-        // Iterate over deltas to collect changes
-        for delta in diff.deltas() {
+        for idx in 0..diff.deltas().len() {
+            let Some(delta) = diff.get_delta(idx) else {
+                continue;
+            };
+
             let status = match delta.status() {
-                git2::Delta::Added => ChangeStatus::Added,
+                git2::Delta::Added | git2::Delta::Untracked => ChangeStatus::Added,
                 git2::Delta::Modified => ChangeStatus::Modified,
                 git2::Delta::Deleted => ChangeStatus::Deleted,
+                git2::Delta::Renamed => ChangeStatus::Renamed,
                 _ => continue, // Skip other statuses
             };
 
@@ -520,16 +841,141 @@ impl GitService {
                 .map(|p| p.to_string_lossy().to_string())
                 .unwrap_or_else(|| "unknown".to_string());
 
-            changes.push(FileChange {
+            let old_path = if status == ChangeStatus::Renamed {
+                delta
+                    .old_file()
+                    .path()
+                    .map(|p| p.to_string_lossy().to_string())
+            } else {
+                None
+            };
+
+            let mut change = FileChange {
                 path,
+                old_path,
                 status,
-                diff: None, // Simplified - not including full diff content
-            });
+                diff: None,
+                hunks: Vec::new(),
+                words_added: 0,
+                words_removed: 0,
+            };
+
+            // Binary files have no textual patch
+            if !delta.flags().is_binary() {
+                if let Some(mut patch) = git2::Patch::from_diff(diff, idx)? {
+                    let buf = patch.to_buf()?;
+                    change.diff = buf.as_str().map(|s| s.to_string());
+                    Self::collect_hunks(&patch, &mut change)?;
+                }
+            }
+
+            changes.push(change);
         }
 
         Ok(DiffResult { changes })
     }
 
+    /// Collect hunks from a patch and attach word-level changes
+    ///
+    /// Within each hunk, a run of removed lines followed by a run of added lines
+    /// is treated as an edit: lines are paired in order and compared word by word.
+    /// Unpaired lines count all of their words as added or removed.
+    fn collect_hunks(patch: &git2::Patch, change: &mut FileChange) -> GitResult<()> {
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_idx)?;
+
+            let mut lines = Vec::with_capacity(line_count);
+            for line_idx in 0..line_count {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                let kind = match line.origin() {
+                    '+' => DiffLineKind::Added,
+                    '-' => DiffLineKind::Removed,
+                    ' ' => DiffLineKind::Context,
+                    _ => continue, // Skip "no newline at end of file" markers
+                };
+
+                let content = String::from_utf8_lossy(line.content())
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string();
+
+                lines.push(DiffLine {
+                    kind,
+                    content,
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                    segments: Vec::new(),
+                });
+            }
+
+            let (added, removed) = Self::pair_word_changes(&mut lines);
+            change.words_added += added;
+            change.words_removed += removed;
+
+            change.hunks.push(DiffHunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Pair removed/added lines within a hunk and fill in their word segments
+    ///
+    /// # Returns
+    /// (words_added, words_removed) for the hunk
+    fn pair_word_changes(lines: &mut [DiffLine]) -> (u32, u32) {
+        let mut words_added = 0;
+        let mut words_removed = 0;
+        let mut i = 0;
+
+        while i < lines.len() {
+            if lines[i].kind == DiffLineKind::Context {
+                i += 1;
+                continue;
+            }
+
+            // Find the run of removed lines followed by the run of added lines
+            let removed_start = i;
+            while i < lines.len() && lines[i].kind == DiffLineKind::Removed {
+                i += 1;
+            }
+            let added_start = i;
+            while i < lines.len() && lines[i].kind == DiffLineKind::Added {
+                i += 1;
+            }
+
+            let removed_count = added_start - removed_start;
+            let added_count = i - added_start;
+            let paired = removed_count.min(added_count);
+
+            for offset in 0..paired {
+                let old_idx = removed_start + offset;
+                let new_idx = added_start + offset;
+                let words = word_diff::diff_words(&lines[old_idx].content, &lines[new_idx].content);
+                words_added += words.words_added;
+                words_removed += words.words_removed;
+                lines[old_idx].segments = words.old_segments;
+                lines[new_idx].segments = words.new_segments;
+            }
+
+            for line in &lines[removed_start + paired..added_start] {
+                words_removed += word_diff::count_words(&line.content);
+            }
+            for line in &lines[added_start + paired..i] {
+                words_added += word_diff::count_words(&line.content);
+            }
+        }
+
+        (words_added, words_removed)
+    }
+
     /// Merge one branch into another
     ///
     /// # Arguments
@@ -551,15 +997,11 @@ impl GitService {
         // Get branch references
         let from_ref = repo
             .find_branch(from_branch, git2::BranchType::Local)
-            .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{from_branch}' not found"))
-            })?;
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{from_branch}' not found")))?;
 
         let into_ref = repo
             .find_branch(into_branch, git2::BranchType::Local)
-            .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{into_branch}' not found"))
-            })?;
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{into_branch}' not found")))?;
 
         // Checkout the into_branch first
         Self::checkout_branch(repo_path, into_branch)?;
@@ -597,7 +1039,12 @@ impl GitService {
         repo.merge(&[&annotated_commit], None, None)?;
 
         // Check for conflicts
-        let index = repo.index()?;
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            // Story files get a second chance with the sentence-level merge
+            Self::resolve_prose_conflicts(&repo, repo_path, &mut index)?;
+        }
+
         if index.has_conflicts() {
             let mut conflicts = Vec::new();
             for conflict in (index.conflicts()?).flatten() {
@@ -641,11 +1088,105 @@ impl GitService {
         })
     }
 
+    /// Preview merging one branch into another without changing anything
+    ///
+    /// The two branch tips are merged in memory, so HEAD, the index and the
+    /// working tree are left untouched. Story files that only conflict at
+    /// paragraph level are re-merged the same way `merge_branches` would.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `from_branch` - Branch to merge from
+    /// * `into_branch` - Branch to merge into
+    ///
+    /// # Returns
+    /// MergePreview with the expected outcome, conflicts and change stats
+    pub fn preview_merge(
+        repo_path: &Path,
+        from_branch: &str,
+        into_branch: &str,
+    ) -> GitResult<MergePreview> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Resolve both branch tips
+        let from_commit = repo
+            .find_branch(from_branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{from_branch}' not found")))?
+            .get()
+            .peel_to_commit()?;
+        let into_commit = repo
+            .find_branch(into_branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{into_branch}' not found")))?
+            .get()
+            .peel_to_commit()?;
+
+        let base_id = repo.merge_base(from_commit.id(), into_commit.id())?;
+
+        // Stats cover what the source branch changed since the branches diverged
+        let base_tree = repo.find_commit(base_id)?.tree()?;
+        let mut diff =
+            repo.diff_tree_to_tree(Some(&base_tree), Some(&from_commit.tree()?), None)?;
+        let changes = Self::collect_changes(&mut diff)?.changes;
+
+        let mut preview = MergePreview {
+            outcome: MergeOutcome::Clean,
+            conflicts: vec![],
+            files_changed: changes.len() as u32,
+            words_added: changes.iter().map(|c| c.words_added).sum(),
+            words_removed: changes.iter().map(|c| c.words_removed).sum(),
+        };
+
+        if base_id == from_commit.id() {
+            preview.outcome = MergeOutcome::UpToDate;
+            return Ok(preview);
+        }
+        if base_id == into_commit.id() {
+            preview.outcome = MergeOutcome::FastForward;
+            return Ok(preview);
+        }
+
+        // Merge the tips in memory and collect what still conflicts
+        let index = repo.merge_commits(&into_commit, &from_commit, None)?;
+        for conflict in index.conflicts()?.flatten() {
+            match Self::read_conflict_sides(&repo, &conflict)? {
+                Some(sides) => {
+                    let result = prose_merge::merge_prose(&sides.base, &sides.ours, &sides.theirs);
+                    if result.clean && prose_merge::is_prose_file(&sides.path) {
+                        continue;
+                    }
+                    preview.conflicts.push(ConflictFile {
+                        path: sides.path,
+                        hunks: Self::conflict_hunks(result),
+                    });
+                }
+                None => {
+                    // Binary or deleted on one side: the whole file conflicts
+                    let entry = conflict.our.as_ref().or(conflict.their.as_ref());
+                    if let Some(entry) = entry {
+                        preview.conflicts.push(ConflictFile {
+                            path: String::from_utf8_lossy(&entry.path).to_string(),
+                            hunks: vec![],
+                        });
+                    }
+                }
+            }
+        }
+
+        if !preview.conflicts.is_empty() {
+            preview.outcome = MergeOutcome::Conflicted;
+        }
+
+        Ok(preview)
+    }
+
     /// Get commit history for a branch
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
-    /// * `branch` - Branch to get history from
+    /// * `branch` - Branch to get history from, or a history imported from
+    ///   another repository (see `list_imported_history`)
     ///
     /// # Returns
     /// Vec of CommitInfo ordered from newest to oldest
@@ -654,15 +1195,17 @@ impl GitService {
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        // Get branch reference
-        let branch_ref = repo
-            .find_branch(branch, git2::BranchType::Local)
-            .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{branch}' not found"))
-            })?;
+        // Get the commit the branch (or imported history) points to
+        let commit = match repo.find_branch(branch, git2::BranchType::Local) {
+            Ok(branch_ref) => branch_ref.get().peel_to_commit()?,
+            Err(_) => repo
+                .find_reference(&format!("refs/history/{branch}"))
+                .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?
+                .peel_to_commit()?,
+        };
 
-        // Get the commit the branch points to
-        let commit = branch_ref.get().peel_to_commit()?;
+        // Collect tags so they can be attached to the commits they point at
+        let tags = Self::tags_by_commit(repo_path)?;
 
         let mut commits = Vec::new();
         let mut revwalk = repo.revwalk()?;
@@ -673,305 +1216,1771 @@ impl GitService {
             let oid = oid?;
             let commit = repo.find_commit(oid)?;
 
-            commits.push(CommitInfo {
-                hash: oid.to_string(),
-                message: commit.message().unwrap_or("").to_string(),
-                author: commit.author().name().unwrap_or("Unknown").to_string(),
-                timestamp: commit.time().seconds().to_string(),
-            });
+            commits.push(Self::commit_info(&commit, &tags));
         }
 
         Ok(commits)
     }
 
-    /// Restore repository to a specific commit
+    /// Get the commits that changed a single file, following renames
+    ///
+    /// Walks the first-parent history of the branch, so renames made by
+    /// reordering story files are followed back to the file's creation.
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
-    /// * `commit_hash` - Hash of the commit to restore
+    /// * `branch` - Branch to get history from
+    /// * `file_path` - Relative path of the file at the tip of the branch
     ///
     /// # Returns
-    /// Success or error
-    pub fn restore_commit(repo_path: &Path, commit_hash: &str) -> GitResult<()> {
+    /// Vec of FileHistoryEntry ordered from newest to oldest, each with the
+    /// file's path as of that commit
+    pub fn get_file_history(
+        repo_path: &Path,
+        branch: &str,
+        file_path: &str,
+    ) -> GitResult<Vec<FileHistoryEntry>> {
         // Open repository
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        // Check for uncommitted changes
-        let statuses = repo.statuses(None)?;
-        if !statuses.is_empty() {
-            return Err(GitServiceError::InvalidOperation(
-                "Cannot restore commit: uncommitted changes exist".to_string(),
-            ));
-        }
-
-        // Parse commit hash
-        let oid = Oid::from_str(commit_hash).map_err(|_| {
-            GitServiceError::InvalidOperation(format!("Invalid commit hash: {commit_hash}"))
-        })?;
-
-        // Find the commit
-        let commit = repo.find_commit(oid).map_err(|_| {
-            GitServiceError::InvalidOperation(format!("Commit not found: {commit_hash}"))
-        })?;
-
-        // Checkout the commit
-        repo.checkout_tree(commit.as_object(), None)?;
-
-        // Update HEAD to point to the commit (detached HEAD state)
-        repo.set_head_detached(oid)?;
-
-        Ok(())
+        let tags = Self::tags_by_commit(repo_path)?;
+
+        Self::file_versions(&repo, branch, file_path)?
+            .into_iter()
+            .map(|(oid, path)| {
+                let commit = repo.find_commit(oid)?;
+                Ok(FileHistoryEntry {
+                    path,
+                    commit: Self::commit_info(&commit, &tags),
+                })
+            })
+            .collect()
     }
 
-    /// List all local branches in the repository
+    /// Map each paragraph of a file to the commit that last changed it
+    ///
+    /// Paragraphs are runs of non-blank lines. A paragraph is attributed to the
+    /// newest commit that changed any of its lines. Uses the committed content
+    /// at the tip of the branch and follows renames like `get_file_history`.
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Branch to blame
+    /// * `file_path` - Relative path of the file at the tip of the branch
     ///
     /// # Returns
-    /// Vector of branch names
-    pub fn list_branches(repo_path: &Path) -> GitResult<Vec<String>> {
+    /// Vec of ParagraphBlame in document order
+    pub fn blame_paragraphs(
+        repo_path: &Path,
+        branch: &str,
+        file_path: &str,
+    ) -> GitResult<Vec<ParagraphBlame>> {
         // Open repository
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        let mut branches = Vec::new();
+        let mut versions = Self::file_versions(&repo, branch, file_path)?;
+        if versions.is_empty() {
+            return Err(GitServiceError::NotFound(format!(
+                "File '{file_path}' not found on branch '{branch}'"
+            )));
+        }
+        versions.reverse();
+
+        // Replay the file's history oldest first, tracking which version
+        // introduced each line
+        let mut lines: Vec<String> = Vec::new();
+        let mut origins: Vec<usize> = Vec::new();
+        for (version, (oid, path)) in versions.iter().enumerate() {
+            let tree = repo.find_commit(*oid)?.tree()?;
+            let blob = repo.find_blob(tree.get_path(Path::new(path))?.id())?;
+            let text = String::from_utf8_lossy(blob.content());
+            let new_lines: Vec<String> = text.lines().map(str::to_string).collect();
+
+            let mut new_origins = vec![version; new_lines.len()];
+            for op in capture_diff_slices(Algorithm::Myers, &lines, &new_lines) {
+                if let DiffOp::Equal {
+                    old_index,
+                    new_index,
+                    len,
+                } = op
+                {
+                    new_origins[new_index..new_index + len]
+                        .copy_from_slice(&origins[old_index..old_index + len]);
+                }
+            }
 
-        // Iterate over local branches
-        for branch_result in repo.branches(Some(git2::BranchType::Local))? {
-            let (branch, _) = branch_result?;
-            if let Some(name) = branch.name()? {
-                branches.push(name.to_string());
+            lines = new_lines;
+            origins = new_origins;
+        }
+
+        // Group lines into paragraphs
+        let tags = Self::tags_by_commit(repo_path)?;
+        let mut paragraphs = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            if lines[i].trim().is_empty() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                i += 1;
             }
+
+            let newest = origins[start..i].iter().copied().max().unwrap_or(0);
+            let commit = repo.find_commit(versions[newest].0)?;
+            paragraphs.push(ParagraphBlame {
+                index: paragraphs.len() as u32,
+                start_line: start as u32 + 1,
+                end_line: i as u32,
+                text: lines[start..i].join("\n"),
+                commit: Self::commit_info(&commit, &tags),
+            });
         }
 
-        Ok(branches)
+        Ok(paragraphs)
     }
 
-    /// Get the canonical "original" branch name, with backward compatibility for "main"
+    /// Search the markdown files of every commit on every branch
+    ///
+    /// Scans each version of each story file (skipping versions already
+    /// scanned) for `pattern` and reports the paragraphs that match. A
+    /// paragraph that survived several commits unchanged is reported once, at
+    /// the newest commit that contains it, so deleted passages show up at the
+    /// last version before they were removed. Archived variations are
+    /// searched too.
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
+    /// * `pattern` - Phrase or regular expression to look for
+    /// * `is_regex` - Treat `pattern` as a regular expression; otherwise it is
+    ///   matched literally, ignoring case
     ///
     /// # Returns
-    /// "original" if it exists, otherwise "main" if it exists, otherwise error
+    /// Matches ordered from newest to oldest commit, at most
+    /// `MAX_HISTORY_MATCHES` of them
     ///
-    /// # Backward Compatibility
-    /// For existing repositories created before the "original" branch naming,
-    /// this function will return "main" if "original" doesn't exist.
-    pub fn get_original_branch(repo_path: &Path) -> GitResult<String> {
+    /// # Errors
+    /// Returns an error if the pattern is empty or not a valid regular expression
+    pub fn search_history(
+        repo_path: &Path,
+        pattern: &str,
+        is_regex: bool,
+    ) -> GitResult<Vec<HistoryMatch>> {
         // Open repository
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        // Try to find "original" branch first
-        if repo
-            .find_branch("original", git2::BranchType::Local)
-            .is_ok()
-        {
-            return Ok("original".to_string());
-        }
+        let matcher = Self::history_matcher(pattern, is_regex)?;
+        let tags = Self::tags_by_commit(repo_path)?;
 
-        // Fall back to "main" for backward compatibility
-        if repo.find_branch("main", git2::BranchType::Local).is_ok() {
-            return Ok("main".to_string());
-        }
+        let mut commits = Self::commits_by_branch(&repo, repo_path)?;
+        commits.sort_by_key(|(commit_id, _)| {
+            std::cmp::Reverse(
+                repo.find_commit(*commit_id)
+                    .map(|c| c.time().seconds())
+                    .unwrap_or(0),
+            )
+        });
 
-        Err(GitServiceError::InvalidOperation(
-            "Neither 'original' nor 'main' branch found".to_string(),
-        ))
-    }
+        let mut scanned_blobs = HashSet::new();
+        let mut seen_passages = HashSet::new();
+        let mut matches = Vec::new();
 
-    /// Get the name of the current branch
-    ///
-    /// # Arguments
-    /// * `repo_path` - Path to the Git repository
-    ///
-    /// # Returns
-    /// Current branch name, or "HEAD" if in detached HEAD state
-    pub fn get_current_branch(repo_path: &Path) -> GitResult<String> {
-        // Open repository
-        let repo = Repository::open(repo_path)
-            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+        'commits: for (commit_id, branch) in commits {
+            let commit = repo.find_commit(commit_id)?;
+            let tree = commit.tree()?;
 
-        let head = repo.head()?;
-        Ok(head.shorthand().unwrap_or("HEAD").to_string())
+            let mut files = Vec::new();
+            tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+                let name = entry.name().unwrap_or("");
+                if entry.kind() == Some(git2::ObjectType::Blob) && name.ends_with(".md") {
+                    files.push((format!("{dir}{name}"), entry.id()));
+                }
+                git2::TreeWalkResult::Ok
+            })?;
+
+            for (file_path, blob_id) in files {
+                if !scanned_blobs.insert(blob_id) {
+                    continue;
+                }
+                let blob = repo.find_blob(blob_id)?;
+                let text = String::from_utf8_lossy(blob.content());
+
+                for (index, passage) in split_paragraphs(&text).into_iter().enumerate() {
+                    let Some(found) = matcher.find(&passage) else {
+                        continue;
+                    };
+                    if !seen_passages.insert((file_path.clone(), passage.clone())) {
+                        continue;
+                    }
+
+                    let (snippet, match_start, match_end) =
+                        history_snippet(&passage, found.start(), found.end());
+                    matches.push(HistoryMatch {
+                        commit: Self::commit_info(&commit, &tags),
+                        branch: branch.clone(),
+                        branch_display_name: branch.clone(),
+                        file_path: file_path.clone(),
+                        paragraph_index: index as u32,
+                        passage,
+                        snippet,
+                        match_start,
+                        match_end,
+                    });
+                    if matches.len() >= MAX_HISTORY_MATCHES {
+                        break 'commits;
+                    }
+                }
+            }
+        }
+
+        Ok(matches)
     }
 
-    /// Check if a branch has uncommitted changes
+    /// Copy a paragraph from an old commit back into a file
+    ///
+    /// The passage is placed after the paragraph that preceded it in the old
+    /// version (or before the one that followed it) when that neighbour still
+    /// exists; otherwise at the same position, or at the end of the file.
+    /// The change is committed to the current branch.
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
-    /// * `branch` - Name of the branch to check
+    /// * `commit_hash` - Commit containing the passage
+    /// * `source_path` - Path of the file at that commit
+    /// * `paragraph_index` - Position of the paragraph in that file (0-based)
+    /// * `target_path` - File in the working directory to restore it into
     ///
     /// # Returns
-    /// True if the branch has uncommitted changes, false otherwise
+    /// Hash of the new commit
     ///
     /// # Errors
-    /// Returns error if branch doesn't exist or repository cannot be opened
-    pub fn has_uncommitted_changes(repo_path: &Path, branch: &str) -> GitResult<bool> {
+    /// Returns an error if the passage doesn't exist, the target file doesn't
+    /// exist, or the target already contains the passage
+    pub fn restore_passage(
+        repo_path: &Path,
+        commit_hash: &str,
+        source_path: &str,
+        paragraph_index: u32,
+        target_path: &str,
+    ) -> GitResult<String> {
         // Open repository
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        // Check if branch exists
-        repo.find_branch(branch, git2::BranchType::Local)
+        let oid = Oid::from_str(commit_hash).map_err(|_| {
+            GitServiceError::InvalidInput(format!("Invalid commit hash: {commit_hash}"))
+        })?;
+        let commit = repo.find_commit(oid)?;
+        let entry = commit
+            .tree()?
+            .get_path(Path::new(source_path))
             .map_err(|_| {
-                GitServiceError::InvalidOperation(format!("Branch '{branch}' not found"))
+                GitServiceError::NotFound(format!(
+                    "File '{source_path}' not found in commit {commit_hash}"
+                ))
             })?;
+        let blob = repo.find_blob(entry.id())?;
+        let old_paragraphs = split_paragraphs(&String::from_utf8_lossy(blob.content()));
+        let index = paragraph_index as usize;
+        let passage = old_paragraphs.get(index).cloned().ok_or_else(|| {
+            GitServiceError::NotFound(format!(
+                "Paragraph {paragraph_index} not found in '{source_path}' at commit {commit_hash}"
+            ))
+        })?;
 
-        // Get current branch to restore later
-        let current_branch = Self::get_current_branch(repo_path)?;
-
-        // If we're checking the current branch, just check status
-        if current_branch == branch {
-            let statuses = repo.statuses(None)?;
-            return Ok(!statuses.is_empty());
+        let target_file = repo_path.join(target_path);
+        if !target_file.exists() {
+            return Err(GitServiceError::NotFound(format!(
+                "File '{target_path}' not found"
+            )));
+        }
+        let current = fs::read_to_string(&target_file)?;
+        let mut lines: Vec<String> = current.lines().map(str::to_string).collect();
+        let ranges = paragraph_ranges(&lines);
+        let paragraph_at =
+            |(start, end): (usize, usize), lines: &[String]| lines[start..end].join("\n");
+
+        if ranges.iter().any(|r| paragraph_at(*r, &lines) == passage) {
+            return Err(GitServiceError::AlreadyExists(format!(
+                "Passage already exists in '{target_path}'"
+            )));
         }
 
-        // For other branches, we need to temporarily switch to check their status
-        // However, switching branches requires no uncommitted changes in current branch
-        // So we'll use a different approach: check if the branch tip matches the working tree
+        let find = |text: &str| {
+            ranges
+                .iter()
+                .copied()
+                .find(|r| paragraph_at(*r, &lines) == text)
+        };
+        let previous = index
+            .checked_sub(1)
+            .and_then(|i| old_paragraphs.get(i))
+            .and_then(|p| find(p));
+        let next = old_paragraphs.get(index + 1).and_then(|p| find(p));
+        let passage_lines: Vec<String> = passage.lines().map(str::to_string).collect();
+
+        let (insert_at, block) = match (previous, next) {
+            (Some((_, end)), _) => (end, [vec![String::new()], passage_lines].concat()),
+            (None, Some((start, _))) => (start, [passage_lines, vec![String::new()]].concat()),
+            (None, None) => match ranges.get(index) {
+                Some((start, _)) => (*start, [passage_lines, vec![String::new()]].concat()),
+                None if lines.iter().all(|l| l.trim().is_empty()) => {
+                    lines.clear();
+                    (0, passage_lines)
+                }
+                None => {
+                    let end = ranges.last().map_or(lines.len(), |(_, end)| *end);
+                    (end, [vec![String::new()], passage_lines].concat())
+                }
+            },
+        };
+        lines.splice(insert_at..insert_at, block);
 
-        // Actually, we can't reliably check uncommitted changes on a non-current branch
-        // without checking it out. The safest approach is to check the current branch only
-        // and assume other branches are clean (or require checkout first).
+        let mut content = lines.join("\n");
+        if current.ends_with('\n') || current.is_empty() {
+            content.push('\n');
+        }
 
-        // For the use case of removing a variation mapping, we should check if the
-        // branch being removed is the current branch and if so, check for uncommitted changes.
+        let short_hash = &commit_hash[..commit_hash.len().min(7)];
+        Self::commit_file(
+            repo_path,
+            target_path,
+            &content,
+            &format!("Restore passage from {short_hash} in {target_path}"),
+        )
+    }
 
-        Ok(false) // Non-current branches are assumed to have no uncommitted changes
+    /// Build the matcher for `search_history`
+    fn history_matcher(pattern: &str, is_regex: bool) -> GitResult<Regex> {
+        if pattern.trim().is_empty() {
+            return Err(GitServiceError::InvalidInput(
+                "Search pattern cannot be empty".to_string(),
+            ));
+        }
+        let source = if is_regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern.trim())
+        };
+        RegexBuilder::new(&source)
+            .case_insensitive(!is_regex)
+            .build()
+            .map_err(|e| GitServiceError::InvalidInput(format!("Invalid search pattern: {e}")))
     }
 
-    /// Resolve a file conflict by choosing ours or theirs version
+    /// Every commit reachable from a branch, labelled with one branch
     ///
-    /// # Arguments
-    /// * `repo_path` - Path to the Git repository
-    /// * `file_path` - Relative path of the conflicted file
-    /// * `take_theirs` - If true, take theirs version; if false, take ours
+    /// Commits shared by several branches are attributed to the original
+    /// branch first, then to other branches in name order, then to archived
+    /// variations.
+    fn commits_by_branch(repo: &Repository, repo_path: &Path) -> GitResult<Vec<(Oid, String)>> {
+        let original = Self::get_original_branch(repo_path).ok();
+        let mut branches = Self::list_branches(repo_path)?;
+        branches.sort_by_key(|b| (Some(b) != original.as_ref(), b.clone()));
+
+        let mut tips: Vec<(String, Oid)> = Vec::new();
+        for branch in branches {
+            let reference = repo.find_branch(&branch, git2::BranchType::Local)?;
+            tips.push((branch, reference.get().peel_to_commit()?.id()));
+        }
+        for branch in Self::list_archived_branches(repo_path)? {
+            let reference = repo.find_reference(&format!("refs/archive/{branch}"))?;
+            tips.push((branch, reference.peel_to_commit()?.id()));
+        }
+
+        let mut seen = HashSet::new();
+        let mut commits = Vec::new();
+        for (branch, tip) in tips {
+            let mut revwalk = repo.revwalk()?;
+            revwalk.push(tip)?;
+            for oid in revwalk {
+                let oid = oid?;
+                if seen.insert(oid) {
+                    commits.push((oid, branch.clone()));
+                }
+            }
+        }
+
+        Ok(commits)
+    }
+
+    /// Find the first-parent commits that changed a file, following renames
     ///
     /// # Returns
-    /// Success or error
-    pub fn resolve_conflict(repo_path: &Path, file_path: &str, take_theirs: bool) -> GitResult<()> {
-        // Open repository
-        let repo = Repository::open(repo_path)
-            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+    /// (commit id, path in that commit) pairs ordered from newest to oldest
+    fn file_versions(
+        repo: &Repository,
+        branch: &str,
+        file_path: &str,
+    ) -> GitResult<Vec<(Oid, String)>> {
+        // Get branch reference
+        let branch_ref = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
+        let tip = branch_ref.get().peel_to_commit()?;
 
-        // Get the index with conflicts
-        let mut index = repo.index()?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(tip.id())?;
+        revwalk.simplify_first_parent()?;
 
-        // Check if file has conflict
-        let has_conflict = index.conflicts()?.flatten().any(|conflict| {
-            conflict
-                .our
-                .as_ref()
-                .and_then(|our| std::str::from_utf8(&our.path).ok().map(|p| p == file_path))
-                .unwrap_or(false)
-        });
+        let mut versions = Vec::new();
+        let mut path = file_path.to_string();
 
-        if !has_conflict {
-            return Err(GitServiceError::InvalidOperation(format!(
-                "File '{file_path}' does not have a conflict"
-            )));
-        }
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let tree = commit.tree()?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
+            };
 
-        // Checkout the chosen version using git2 checkout
-        let mut checkout_builder = git2::build::CheckoutBuilder::new();
-        checkout_builder.path(file_path);
+            let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            let mut find_opts = git2::DiffFindOptions::new();
+            find_opts.renames(true);
+            diff.find_similar(Some(&mut find_opts))?;
 
-        if take_theirs {
-            checkout_builder.use_theirs(true);
-        } else {
-            checkout_builder.use_ours(true);
+            // Look for the delta that produced the file at its current path
+            let delta = diff.deltas().find(|delta| {
+                delta.status() != git2::Delta::Deleted
+                    && delta.new_file().path().and_then(|p| p.to_str()) == Some(path.as_str())
+            });
+            let Some(delta) = delta else {
+                continue;
+            };
+
+            versions.push((commit.id(), path.clone()));
+
+            match delta.status() {
+                git2::Delta::Added => break,
+                git2::Delta::Renamed => {
+                    if let Some(old_path) = delta.old_file().path().and_then(|p| p.to_str()) {
+                        path = old_path.to_string();
+                    }
+                }
+                _ => {}
+            }
         }
 
-        repo.checkout_index(Some(&mut index), Some(&mut checkout_builder))?;
+        Ok(versions)
+    }
 
-        // Add the resolved file to staging area
-        index.add_path(Path::new(file_path))?;
-        index.write()?;
+    /// Map commit ids to the names of the tags pointing at them
+    fn tags_by_commit(repo_path: &Path) -> GitResult<HashMap<Oid, Vec<String>>> {
+        let mut tags: HashMap<Oid, Vec<String>> = HashMap::new();
+        for tag in Self::list_tags(repo_path)? {
+            let oid = Oid::from_str(&tag.commit_hash)?;
+            tags.entry(oid).or_default().push(tag.name);
+        }
+        Ok(tags)
+    }
 
-        Ok(())
+    /// Build a CommitInfo for a commit
+    fn commit_info(commit: &git2::Commit, tags: &HashMap<Oid, Vec<String>>) -> CommitInfo {
+        CommitInfo {
+            hash: commit.id().to_string(),
+            message: commit.message().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("Unknown").to_string(),
+            timestamp: commit.time().seconds().to_string(),
+            tags: tags.get(&commit.id()).cloned().unwrap_or_default(),
+        }
     }
 
-    /// Abort a merge in progress
+    /// Restore repository to a specific commit
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
+    /// * `commit_hash` - Hash of the commit to restore
     ///
     /// # Returns
     /// Success or error
-    pub fn abort_merge(repo_path: &Path) -> GitResult<()> {
+    pub fn restore_commit(repo_path: &Path, commit_hash: &str) -> GitResult<()> {
         // Open repository
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        // Check if merge is in progress
-        let state = repo.state();
-        if state != git2::RepositoryState::Merge {
-            return Err(GitServiceError::InvalidOperation(
-                "No merge in progress".to_string(),
+        // Check for uncommitted changes
+        let statuses = repo.statuses(None)?;
+        if !statuses.is_empty() {
+            return Err(GitServiceError::UncommittedChanges(
+                "Cannot restore commit: uncommitted changes exist".to_string(),
             ));
         }
 
-        // Clean up merge state
-        repo.cleanup_state()?;
+        // Parse commit hash
+        let oid = Oid::from_str(commit_hash).map_err(|_| {
+            GitServiceError::InvalidInput(format!("Invalid commit hash: {commit_hash}"))
+        })?;
 
-        // Reset to HEAD
-        let head = repo.head()?;
-        let commit = head.peel_to_commit()?;
-        repo.reset(commit.as_object(), git2::ResetType::Hard, None)?;
+        // Find the commit
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|_| GitServiceError::NotFound(format!("Commit not found: {commit_hash}")))?;
+
+        // Checkout the commit
+        repo.checkout_tree(commit.as_object(), None)?;
+
+        // Update HEAD to point to the commit (detached HEAD state)
+        repo.set_head_detached(oid)?;
 
         Ok(())
     }
 
-    /// Get the content of a conflicted file with conflict markers
+    /// Restore a snapshot as a new commit on the current branch
+    ///
+    /// Unlike `restore_commit`, HEAD stays on the current branch: the snapshot's
+    /// files are written to the working tree and committed on top of the branch
+    /// as "Restored from <snapshot>", so no history is lost.
+    ///
+    /// metadata.json is left as it is on the branch. Its variation and
+    /// milestone mappings describe branches and tags, which a restore doesn't
+    /// touch, so the snapshot's older mappings would orphan them.
     ///
     /// # Arguments
     /// * `repo_path` - Path to the Git repository
-    /// * `file_path` - Relative path of the conflicted file
+    /// * `commit_hash` - Hash of the snapshot to restore
     ///
     /// # Returns
-    /// File content with conflict markers as a string
-    pub fn get_conflict_content(repo_path: &Path, file_path: &str) -> GitResult<String> {
+    /// The hash of the new commit, or of the current commit if it already
+    /// matches the snapshot
+    ///
+    /// # Errors
+    /// Returns an error if there are uncommitted changes or HEAD is detached
+    pub fn restore_snapshot(repo_path: &Path, commit_hash: &str) -> GitResult<String> {
         // Open repository
         let repo = Repository::open(repo_path)
             .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
 
-        // Get the index
-        let index = repo.index()?;
-
-        // Check if file has conflict
-        let has_conflict = index.conflicts()?.flatten().any(|conflict| {
-            conflict
-                .our
-                .as_ref()
-                .and_then(|our| std::str::from_utf8(&our.path).ok().map(|p| p == file_path))
-                .unwrap_or(false)
-        });
+        if repo.head_detached()? {
+            return Err(GitServiceError::InvalidOperation(
+                "Cannot restore snapshot: HEAD is detached".to_string(),
+            ));
+        }
 
-        if !has_conflict {
-            return Err(GitServiceError::InvalidOperation(format!(
-                "File '{file_path}' does not have a conflict"
-            )));
+        // Check for uncommitted changes
+        let statuses = repo.statuses(None)?;
+        if !statuses.is_empty() {
+            return Err(GitServiceError::UncommittedChanges(
+                "Cannot restore snapshot: uncommitted changes exist".to_string(),
+            ));
         }
 
-        // Read the file from disk (it will have conflict markers)
-        let full_path = repo_path.join(file_path);
-        let content = fs::read_to_string(&full_path)?;
+        let snapshot = Self::find_commit(&repo, commit_hash)?;
+        let head_commit = repo.head()?.peel_to_commit()?;
 
-        Ok(content)
-    }
-}
+        // The snapshot's files, with the branch's current metadata.json
+        let mut builder = repo.treebuilder(Some(&snapshot.tree()?))?;
+        match head_commit.tree()?.get_name("metadata.json") {
+            Some(metadata) => {
+                builder.insert("metadata.json", metadata.id(), metadata.filemode())?;
+            }
+            None => {
+                if builder.get("metadata.json")?.is_some() {
+                    builder.remove("metadata.json")?;
+                }
+            }
+        }
+        let tree = repo.find_tree(builder.write()?)?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
+        // Nothing to do if the branch already has the snapshot's content
+        if tree.id() == head_commit.tree_id() {
+            return Ok(head_commit.id().to_string());
+        }
 
-    #[test]
+        // Write the snapshot's files to the working tree and index
+        let mut checkout_builder = git2::build::CheckoutBuilder::new();
+        checkout_builder.force();
+        repo.checkout_tree(tree.as_object(), Some(&mut checkout_builder))?;
+
+        let mut index = repo.index()?;
+        index.read_tree(&tree)?;
+        index.write()?;
+
+        // Commit on top of the current branch
+        let short_hash = &snapshot.id().to_string()[..7];
+        let message = match snapshot.summary() {
+            Some(summary) if !summary.is_empty() => {
+                format!("Restored from {short_hash} ({summary})")
+            }
+            _ => format!("Restored from {short_hash}"),
+        };
+        let signature = Self::create_signature(&repo)?;
+        let oid = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[&head_commit],
+        )?;
+
+        Ok(oid.to_string())
+    }
+
+    /// Read the files of a snapshot without checking anything out
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `commit_hash` - Hash of the snapshot to read
+    ///
+    /// # Returns
+    /// Vec of SnapshotFile with the path and text content of every file.
+    /// Binary files are skipped.
+    pub fn peek_snapshot(repo_path: &Path, commit_hash: &str) -> GitResult<Vec<SnapshotFile>> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let commit = Self::find_commit(&repo, commit_hash)?;
+        let tree = commit.tree()?;
+
+        let mut files = Vec::new();
+        let mut error = None;
+
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() != Some(git2::ObjectType::Blob) {
+                return git2::TreeWalkResult::Ok;
+            }
+
+            let blob = match repo.find_blob(entry.id()) {
+                Ok(blob) => blob,
+                Err(e) => {
+                    error = Some(e);
+                    return git2::TreeWalkResult::Abort;
+                }
+            };
+
+            if let (Some(name), Ok(content)) = (entry.name(), std::str::from_utf8(blob.content())) {
+                if !blob.is_binary() {
+                    files.push(SnapshotFile {
+                        path: format!("{dir}{name}"),
+                        content: content.to_string(),
+                    });
+                }
+            }
+
+            git2::TreeWalkResult::Ok
+        })?;
+
+        if let Some(e) = error {
+            return Err(e.into());
+        }
+
+        Ok(files)
+    }
+
+    /// Check whether HEAD is detached (e.g. after `restore_commit`)
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// True if HEAD points at a commit instead of a branch
+    pub fn is_head_detached(repo_path: &Path) -> GitResult<bool> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        Ok(repo.head_detached()?)
+    }
+
+    /// Reattach a detached HEAD to a branch without losing work
+    ///
+    /// If the detached commit's content differs from the branch tip (because a
+    /// snapshot was restored, or commits were made while detached), that
+    /// content is committed on top of the branch.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Branch to reattach to, or None to pick the branch the
+    ///   detached commit came from (preferring the original branch)
+    ///
+    /// # Returns
+    /// The name of the branch HEAD now points to
+    ///
+    /// # Errors
+    /// Returns an error if HEAD is not detached or there are uncommitted changes
+    pub fn recover_detached_head(repo_path: &Path, branch: Option<&str>) -> GitResult<String> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        if !repo.head_detached()? {
+            return Err(GitServiceError::InvalidOperation(
+                "HEAD is not detached".to_string(),
+            ));
+        }
+
+        // Check for uncommitted changes
+        let statuses = repo.statuses(None)?;
+        if !statuses.is_empty() {
+            return Err(GitServiceError::UncommittedChanges(
+                "Cannot recover HEAD: uncommitted changes exist".to_string(),
+            ));
+        }
+
+        let head_commit = repo.head()?.peel_to_commit()?;
+
+        // Pick the branch to reattach to
+        let branch = match branch {
+            Some(branch) => branch.to_string(),
+            None => Self::closest_branch(&repo, repo_path, head_commit.id())?,
+        };
+        let branch_ref = repo
+            .find_branch(&branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
+        let branch_commit = branch_ref.get().peel_to_commit()?;
+        let ref_name = format!("refs/heads/{branch}");
+
+        // Keep the detached content as a new commit on the branch
+        if head_commit.tree_id() != branch_commit.tree_id() {
+            let tree = head_commit.tree()?;
+            let signature = Self::create_signature(&repo)?;
+            let short_hash = &head_commit.id().to_string()[..7];
+            repo.commit(
+                Some(&ref_name),
+                &signature,
+                &signature,
+                &format!("Restored from {short_hash}"),
+                &tree,
+                &[&branch_commit],
+            )?;
+        }
+
+        // The working tree already matches the new branch tip
+        repo.set_head(&ref_name)?;
+
+        Ok(branch)
+    }
+
+    /// Find the branch sharing the most history with a detached commit
+    ///
+    /// Ties go to the original branch, then to the first branch by name.
+    fn closest_branch(repo: &Repository, repo_path: &Path, commit_id: Oid) -> GitResult<String> {
+        let original = Self::get_original_branch(repo_path).ok();
+        let mut best: Option<(usize, bool, String)> = None;
+
+        for branch_result in repo.branches(Some(git2::BranchType::Local))? {
+            let (branch, _) = branch_result?;
+            let Some(name) = branch.name()?.map(str::to_string) else {
+                continue;
+            };
+            let tip = branch.get().peel_to_commit()?.id();
+
+            // Commits on the detached HEAD that the branch does not have
+            let Ok(base) = repo.merge_base(commit_id, tip) else {
+                continue;
+            };
+            let (unshared, _) = repo.graph_ahead_behind(commit_id, base)?;
+            let is_original = original.as_deref() == Some(name.as_str());
+
+            let better = match &best {
+                None => true,
+                Some((best_unshared, best_is_original, best_name)) => {
+                    (unshared, !is_original, &name) < (*best_unshared, !best_is_original, best_name)
+                }
+            };
+            if better {
+                best = Some((unshared, is_original, name));
+            }
+        }
+
+        best.map(|(_, _, name)| name).ok_or_else(|| {
+            GitServiceError::NotFound("No branch found to reattach HEAD".to_string())
+        })
+    }
+
+    /// List all local branches in the repository
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// Vector of branch names
+    pub fn list_branches(repo_path: &Path) -> GitResult<Vec<String>> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let mut branches = Vec::new();
+
+        // Iterate over local branches
+        for branch_result in repo.branches(Some(git2::BranchType::Local))? {
+            let (branch, _) = branch_result?;
+            if let Some(name) = branch.name()? {
+                branches.push(name.to_string());
+            }
+        }
+
+        Ok(branches)
+    }
+
+    /// Create an annotated tag
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `tag_name` - Name of the tag (must be a valid ref name)
+    /// * `commit_hash` - Commit to tag, or None to tag HEAD
+    /// * `message` - Tag message
+    ///
+    /// # Returns
+    /// The hash of the tagged commit
+    ///
+    /// # Errors
+    /// Returns an error if the tag already exists or the commit is not found
+    pub fn create_tag(
+        repo_path: &Path,
+        tag_name: &str,
+        commit_hash: Option<&str>,
+        message: &str,
+    ) -> GitResult<String> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Check if tag already exists
+        if repo
+            .find_reference(&format!("refs/tags/{tag_name}"))
+            .is_ok()
+        {
+            return Err(GitServiceError::AlreadyExists(format!(
+                "Tag '{tag_name}' already exists"
+            )));
+        }
+
+        // Find the commit to tag
+        let commit = match commit_hash {
+            Some(hash) => Self::find_commit(&repo, hash)?,
+            None => repo.head()?.peel_to_commit()?,
+        };
+
+        let signature = Self::create_signature(&repo)?;
+        repo.tag(tag_name, commit.as_object(), &signature, message, false)?;
+
+        Ok(commit.id().to_string())
+    }
+
+    /// List all tags in the repository
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// Vec of TagInfo ordered from newest to oldest
+    pub fn list_tags(repo_path: &Path) -> GitResult<Vec<TagInfo>> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let mut tags = Vec::new();
+
+        for name in repo.tag_names(None)?.iter().flatten() {
+            let reference = repo.find_reference(&format!("refs/tags/{name}"))?;
+            let commit = reference.peel_to_commit()?;
+
+            // Lightweight tags have no message or tagger of their own
+            let (message, timestamp) = match reference.peel_to_tag() {
+                Ok(tag) => (
+                    tag.message().unwrap_or("").to_string(),
+                    tag.tagger()
+                        .map(|tagger| tagger.when().seconds())
+                        .unwrap_or_else(|| commit.time().seconds()),
+                ),
+                Err(_) => (String::new(), commit.time().seconds()),
+            };
+
+            tags.push(TagInfo {
+                name: name.to_string(),
+                commit_hash: commit.id().to_string(),
+                message,
+                timestamp: timestamp.to_string(),
+            });
+        }
+
+        tags.sort_by_key(|tag| std::cmp::Reverse(tag.timestamp.parse::<i64>().unwrap_or(0)));
+
+        Ok(tags)
+    }
+
+    /// Delete a tag
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `tag_name` - Name of the tag to delete
+    ///
+    /// # Errors
+    /// Returns an error if the tag does not exist
+    pub fn delete_tag(repo_path: &Path, tag_name: &str) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        repo.tag_delete(tag_name)
+            .map_err(|_| GitServiceError::NotFound(format!("Tag '{tag_name}' not found")))
+    }
+
+    /// Get the canonical "original" branch name, with backward compatibility for "main"
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// "original" if it exists, otherwise "main" if it exists, otherwise error
+    ///
+    /// # Backward Compatibility
+    /// For existing repositories created before the "original" branch naming,
+    /// this function will return "main" if "original" doesn't exist.
+    pub fn get_original_branch(repo_path: &Path) -> GitResult<String> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Try to find "original" branch first
+        if repo
+            .find_branch("original", git2::BranchType::Local)
+            .is_ok()
+        {
+            return Ok("original".to_string());
+        }
+
+        // Fall back to "main" for backward compatibility
+        if repo.find_branch("main", git2::BranchType::Local).is_ok() {
+            return Ok("main".to_string());
+        }
+
+        Err(GitServiceError::NotFound(
+            "Neither 'original' nor 'main' branch found".to_string(),
+        ))
+    }
+
+    /// Get the name of the current branch
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// Current branch name, or "HEAD" if in detached HEAD state
+    pub fn get_current_branch(repo_path: &Path) -> GitResult<String> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let head = repo.head()?;
+        Ok(head.shorthand().unwrap_or("HEAD").to_string())
+    }
+
+    /// Rename a branch
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Current name of the branch
+    /// * `new_name` - New name for the branch
+    ///
+    /// # Errors
+    /// Returns an error if the branch is the original branch, does not exist,
+    /// or a branch named `new_name` already exists
+    pub fn rename_branch(repo_path: &Path, branch: &str, new_name: &str) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        if Self::is_original_branch(repo_path, branch) {
+            return Err(GitServiceError::InvalidOperation(
+                "Cannot rename the original branch".to_string(),
+            ));
+        }
+
+        // Check if target name is taken
+        if repo.find_branch(new_name, git2::BranchType::Local).is_ok() {
+            return Err(GitServiceError::AlreadyExists(format!(
+                "Branch '{new_name}' already exists"
+            )));
+        }
+
+        let mut branch_ref = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
+
+        // HEAD follows the rename if the branch is checked out
+        branch_ref.rename(new_name, false)?;
+
+        Ok(())
+    }
+
+    /// Count commits that exist only on a branch
+    ///
+    /// These are the commits that would be lost if the branch were deleted.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Name of the branch
+    ///
+    /// # Returns
+    /// Number of commits not reachable from any other local branch
+    pub fn count_unmerged_commits(repo_path: &Path, branch: &str) -> GitResult<usize> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let tip = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?
+            .get()
+            .peel_to_commit()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(tip.id())?;
+
+        // Hide everything reachable from the other branches
+        for branch_result in repo.branches(Some(git2::BranchType::Local))? {
+            let (other, _) = branch_result?;
+            if other.name()? != Some(branch) {
+                revwalk.hide(other.get().peel_to_commit()?.id())?;
+            }
+        }
+
+        Ok(revwalk.count())
+    }
+
+    /// Delete a branch
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Name of the branch to delete
+    /// * `force` - Delete even if the branch has commits no other branch has
+    ///
+    /// # Errors
+    /// Returns an error if the branch is the current or original branch, or
+    /// has unmerged commits and `force` is false
+    pub fn delete_branch(repo_path: &Path, branch: &str, force: bool) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        Self::ensure_removable_branch(repo_path, branch)?;
+
+        let mut branch_ref = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
+
+        if !force {
+            let unmerged = Self::count_unmerged_commits(repo_path, branch)?;
+            if unmerged > 0 {
+                return Err(GitServiceError::InvalidOperation(format!(
+                    "Branch '{branch}' has {unmerged} unmerged commit(s) that would be lost"
+                )));
+            }
+        }
+
+        branch_ref.delete()?;
+
+        Ok(())
+    }
+
+    /// Archive a branch
+    ///
+    /// Moves the branch out of `refs/heads` into `refs/archive`, so it no longer
+    /// shows up in `list_branches` but its commits are kept and it can be
+    /// brought back with `restore_archived_branch`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Name of the branch to archive
+    ///
+    /// # Errors
+    /// Returns an error if the branch is the current or original branch, or an
+    /// archived branch with the same name already exists
+    pub fn archive_branch(repo_path: &Path, branch: &str) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        Self::ensure_removable_branch(repo_path, branch)?;
+
+        let mut branch_ref = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
+        let tip = branch_ref.get().peel_to_commit()?.id();
+
+        // Keep the commits reachable from the archive ref
+        let archive_ref = format!("refs/archive/{branch}");
+        if repo.find_reference(&archive_ref).is_ok() {
+            return Err(GitServiceError::AlreadyExists(format!(
+                "An archived branch named '{branch}' already exists"
+            )));
+        }
+        repo.reference(
+            &archive_ref,
+            tip,
+            false,
+            &format!("Archive branch {branch}"),
+        )?;
+
+        branch_ref.delete()?;
+
+        Ok(())
+    }
+
+    /// List archived branches
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// Vector of archived branch names
+    pub fn list_archived_branches(repo_path: &Path) -> GitResult<Vec<String>> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let mut branches = Vec::new();
+        for reference in repo.references_glob("refs/archive/*")? {
+            if let Some(name) = reference?.name() {
+                branches.push(name.trim_start_matches("refs/archive/").to_string());
+            }
+        }
+
+        Ok(branches)
+    }
+
+    /// Restore an archived branch
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Name of the archived branch
+    ///
+    /// # Errors
+    /// Returns an error if no archived branch has this name or a branch with
+    /// the same name already exists
+    pub fn restore_archived_branch(repo_path: &Path, branch: &str) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let mut archive_ref = repo
+            .find_reference(&format!("refs/archive/{branch}"))
+            .map_err(|_| {
+                GitServiceError::NotFound(format!("Archived branch '{branch}' not found"))
+            })?;
+
+        if repo.find_branch(branch, git2::BranchType::Local).is_ok() {
+            return Err(GitServiceError::AlreadyExists(format!(
+                "Branch '{branch}' already exists"
+            )));
+        }
+
+        let commit = archive_ref.peel_to_commit()?;
+        repo.branch(branch, &commit, false)?;
+        archive_ref.delete()?;
+
+        Ok(())
+    }
+
+    /// Make another repository's history reachable from this one
+    ///
+    /// Fetches every branch of `source_path` into `refs/history/<name>/`, so
+    /// the commits of a story that moved here from another repository can
+    /// still be browsed once the source is gone. Archived branches and tags
+    /// come along under `refs/history/<name>/archive/` and
+    /// `refs/history/<name>/tags/`; they don't become branches or milestones
+    /// of this repository. Histories the source had imported itself come
+    /// along under the same name, so a story that moved several times keeps
+    /// all of them. An existing history with the same name is replaced.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository receiving the history
+    /// * `source_path` - Path to the repository the history comes from
+    /// * `name` - Name to keep the history under (e.g., the story ID)
+    pub fn import_history(repo_path: &Path, source_path: &Path, name: &str) -> GitResult<()> {
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+        if Repository::open(source_path).is_err() {
+            return Err(GitServiceError::RepositoryNotFound(
+                source_path.to_path_buf(),
+            ));
+        }
+
+        let source = source_path.to_string_lossy();
+        let mut remote = repo.remote_anonymous(&source)?;
+        let refspecs = [
+            format!("+refs/heads/*:refs/history/{name}/*"),
+            format!("+refs/archive/*:refs/history/{name}/archive/*"),
+            format!("+refs/tags/*:refs/history/{name}/tags/*"),
+            format!("+refs/history/*:refs/history/{name}/*"),
+        ];
+        // Tags are only wanted under the history name, not in refs/tags
+        let mut options = git2::FetchOptions::new();
+        options.download_tags(git2::AutotagOption::None);
+        remote.fetch(&refspecs, Some(&mut options), None)?;
+
+        Ok(())
+    }
+
+    /// List histories imported with `import_history`
+    ///
+    /// # Returns
+    /// Names of the form `<name>/<branch>`, usable as the branch argument of
+    /// `get_history`
+    pub fn list_imported_history(repo_path: &Path) -> GitResult<Vec<String>> {
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let mut names = Vec::new();
+        for reference in repo.references_glob("refs/history/*")? {
+            if let Some(name) = reference?.name() {
+                names.push(name.trim_start_matches("refs/history/").to_string());
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Delete histories imported with `import_history`, except some
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `keep` - Names of the histories to keep
+    pub fn prune_imported_history(repo_path: &Path, keep: &[&str]) -> GitResult<()> {
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let mut pruned = Vec::new();
+        for reference in repo.references_glob("refs/history/*")? {
+            let reference = reference?;
+            let Some(name) = reference.name() else {
+                continue;
+            };
+            let history = name.trim_start_matches("refs/history/");
+            let history = history.split('/').next().unwrap_or(history);
+            if !keep.contains(&history) {
+                pruned.push(name.to_string());
+            }
+        }
+        for name in pruned {
+            repo.find_reference(&name)?.delete()?;
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a branch is the original branch ("original" or legacy "main")
+    fn is_original_branch(repo_path: &Path, branch: &str) -> bool {
+        Self::get_original_branch(repo_path)
+            .map(|original| original == branch)
+            .unwrap_or(false)
+    }
+
+    /// Refuse to delete or archive the current or original branch
+    fn ensure_removable_branch(repo_path: &Path, branch: &str) -> GitResult<()> {
+        if Self::is_original_branch(repo_path, branch) {
+            return Err(GitServiceError::InvalidOperation(
+                "Cannot remove the original branch".to_string(),
+            ));
+        }
+
+        if Self::get_current_branch(repo_path)? == branch {
+            return Err(GitServiceError::InvalidOperation(format!(
+                "Cannot remove '{branch}' while it is checked out"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check if a branch has uncommitted changes
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `branch` - Name of the branch to check
+    ///
+    /// # Returns
+    /// True if the branch has uncommitted changes, false otherwise
+    ///
+    /// # Errors
+    /// Returns error if branch doesn't exist or repository cannot be opened
+    pub fn has_uncommitted_changes(repo_path: &Path, branch: &str) -> GitResult<bool> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Check if branch exists
+        repo.find_branch(branch, git2::BranchType::Local)
+            .map_err(|_| GitServiceError::NotFound(format!("Branch '{branch}' not found")))?;
+
+        // Get current branch to restore later
+        let current_branch = Self::get_current_branch(repo_path)?;
+
+        // If we're checking the current branch, just check status
+        if current_branch == branch {
+            let statuses = repo.statuses(None)?;
+            return Ok(!statuses.is_empty());
+        }
+
+        // For other branches, we need to temporarily switch to check their status
+        // However, switching branches requires no uncommitted changes in current branch
+        // So we'll use a different approach: check if the branch tip matches the working tree
+
+        // Actually, we can't reliably check uncommitted changes on a non-current branch
+        // without checking it out. The safest approach is to check the current branch only
+        // and assume other branches are clean (or require checkout first).
+
+        // For the use case of removing a variation mapping, we should check if the
+        // branch being removed is the current branch and if so, check for uncommitted changes.
+
+        Ok(false) // Non-current branches are assumed to have no uncommitted changes
+    }
+
+    /// Resolve a file conflict by choosing ours or theirs version
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `file_path` - Relative path of the conflicted file
+    /// * `take_theirs` - If true, take theirs version; if false, take ours
+    ///
+    /// # Returns
+    /// Success or error
+    pub fn resolve_conflict(repo_path: &Path, file_path: &str, take_theirs: bool) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Get the index with conflicts
+        let mut index = repo.index()?;
+
+        // Check if file has conflict
+        let has_conflict = index.conflicts()?.flatten().any(|conflict| {
+            conflict
+                .our
+                .as_ref()
+                .and_then(|our| std::str::from_utf8(&our.path).ok().map(|p| p == file_path))
+                .unwrap_or(false)
+        });
+
+        if !has_conflict {
+            return Err(GitServiceError::InvalidOperation(format!(
+                "File '{file_path}' does not have a conflict"
+            )));
+        }
+
+        // Checkout the chosen version using git2 checkout
+        let mut checkout_builder = git2::build::CheckoutBuilder::new();
+        checkout_builder.path(file_path);
+
+        if take_theirs {
+            checkout_builder.use_theirs(true);
+        } else {
+            checkout_builder.use_ours(true);
+        }
+
+        repo.checkout_index(Some(&mut index), Some(&mut checkout_builder))?;
+
+        // Add the resolved file to staging area
+        index.add_path(Path::new(file_path))?;
+        index.write()?;
+
+        Ok(())
+    }
+
+    /// Abort a merge in progress
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// Success or error
+    pub fn abort_merge(repo_path: &Path) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Check if merge is in progress
+        let state = repo.state();
+        if state != git2::RepositoryState::Merge {
+            return Err(GitServiceError::InvalidOperation(
+                "No merge in progress".to_string(),
+            ));
+        }
+
+        // Clean up merge state
+        repo.cleanup_state()?;
+
+        // Reset to HEAD
+        let head = repo.head()?;
+        let commit = head.peel_to_commit()?;
+        repo.reset(commit.as_object(), git2::ResetType::Hard, None)?;
+
+        Ok(())
+    }
+
+    /// Get the content of a conflicted file with conflict markers
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `file_path` - Relative path of the conflicted file
+    ///
+    /// # Returns
+    /// File content with conflict markers as a string
+    pub fn get_conflict_content(repo_path: &Path, file_path: &str) -> GitResult<String> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        // Get the index
+        let index = repo.index()?;
+
+        // Check if file has conflict
+        let has_conflict = index.conflicts()?.flatten().any(|conflict| {
+            conflict
+                .our
+                .as_ref()
+                .and_then(|our| std::str::from_utf8(&our.path).ok().map(|p| p == file_path))
+                .unwrap_or(false)
+        });
+
+        if !has_conflict {
+            return Err(GitServiceError::InvalidOperation(format!(
+                "File '{file_path}' does not have a conflict"
+            )));
+        }
+
+        // Read the file from disk (it will have conflict markers)
+        let full_path = repo_path.join(file_path);
+        let content = fs::read_to_string(&full_path)?;
+
+        Ok(content)
+    }
+
+    /// Get the conflict regions of a conflicted file
+    ///
+    /// Re-merges the base, ours and theirs versions of the file with the
+    /// prose-aware merge, so the result lists only the sentences that really
+    /// conflict instead of whole paragraphs wrapped in conflict markers.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `file_path` - Relative path of the conflicted file
+    ///
+    /// # Returns
+    /// ProseMergeResult with resolved and conflicting regions in document order
+    pub fn get_conflict_regions(repo_path: &Path, file_path: &str) -> GitResult<ProseMergeResult> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        Self::merge_conflicted_file(&repo, file_path)
+    }
+
+    /// List every conflicted text file as an ordered list of conflict hunks
+    ///
+    /// Hunk ids are the position of each hunk within its file and are what
+    /// `resolve_conflict_hunks` expects. Files that cannot be merged as text
+    /// (binary, or deleted on one side) are left out and must be resolved with
+    /// `resolve_conflict`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    ///
+    /// # Returns
+    /// Vec of ConflictFile, one per conflicted text file
+    pub fn get_conflict_files(repo_path: &Path) -> GitResult<Vec<ConflictFile>> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let index = repo.index()?;
+        let mut files = Vec::new();
+
+        for conflict in index.conflicts()?.flatten() {
+            let Some(sides) = Self::read_conflict_sides(&repo, &conflict)? else {
+                continue;
+            };
+
+            let result = prose_merge::merge_prose(&sides.base, &sides.ours, &sides.theirs);
+            files.push(ConflictFile {
+                path: sides.path,
+                hunks: Self::conflict_hunks(result),
+            });
+        }
+
+        Ok(files)
+    }
+
+    /// Resolve a conflicted file hunk by hunk, then write and stage it
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the Git repository
+    /// * `file_path` - Relative path of the conflicted file
+    /// * `resolutions` - One resolution per conflict hunk, by hunk id
+    ///
+    /// # Errors
+    /// Returns an error if a hunk has no resolution, more than one, or a
+    /// resolution refers to a hunk that does not exist
+    pub fn resolve_conflict_hunks(
+        repo_path: &Path,
+        file_path: &str,
+        resolutions: &[HunkChoice],
+    ) -> GitResult<()> {
+        // Open repository
+        let repo = Repository::open(repo_path)
+            .map_err(|_| GitServiceError::RepositoryNotFound(repo_path.to_path_buf()))?;
+
+        let result = Self::merge_conflicted_file(&repo, file_path)?;
+        let hunk_count = result
+            .regions
+            .iter()
+            .filter(|region| matches!(region, MergeRegion::Conflict { .. }))
+            .count() as u32;
+
+        if let Some(choice) = resolutions.iter().find(|c| c.hunk_id >= hunk_count) {
+            return Err(GitServiceError::NotFound(format!(
+                "Conflict hunk {} not found in '{file_path}'",
+                choice.hunk_id
+            )));
+        }
+        let mut seen = HashSet::new();
+        if let Some(choice) = resolutions.iter().find(|c| !seen.insert(c.hunk_id)) {
+            return Err(GitServiceError::InvalidInput(format!(
+                "Conflict hunk {} in '{file_path}' has more than one resolution",
+                choice.hunk_id
+            )));
+        }
+
+        // Build the merged file, applying the chosen resolution to each hunk
+        let mut content = String::new();
+        let mut hunk_id = 0;
+        for region in &result.regions {
+            match region {
+                MergeRegion::Resolved { text } => content.push_str(text),
+                MergeRegion::Conflict { ours, theirs, .. } => {
+                    let choice = resolutions
+                        .iter()
+                        .find(|c| c.hunk_id == hunk_id)
+                        .ok_or_else(|| {
+                            GitServiceError::Conflict(format!(
+                                "Conflict hunk {hunk_id} in '{file_path}' has no resolution"
+                            ))
+                        })?;
+
+                    match &choice.resolution {
+                        HunkResolution::Ours => content.push_str(ours),
+                        HunkResolution::Theirs => content.push_str(theirs),
+                        HunkResolution::OursThenTheirs => {
+                            content.push_str(ours);
+                            content.push_str(theirs);
+                        }
+                        HunkResolution::TheirsThenOurs => {
+                            content.push_str(theirs);
+                            content.push_str(ours);
+                        }
+                        HunkResolution::Custom { text } => content.push_str(text),
+                    }
+                    hunk_id += 1;
+                }
+            }
+        }
+
+        // Write the merged file and mark it resolved
+        fs::write(repo_path.join(file_path), content)?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new(file_path))?;
+        index.write()?;
+
+        Ok(())
+    }
+
+    /// Number the conflict regions of a prose merge as conflict hunks
+    fn conflict_hunks(result: ProseMergeResult) -> Vec<ConflictHunk> {
+        result
+            .regions
+            .into_iter()
+            .filter_map(|region| match region {
+                MergeRegion::Conflict { base, ours, theirs } => Some((base, ours, theirs)),
+                MergeRegion::Resolved { .. } => None,
+            })
+            .enumerate()
+            .map(|(id, (base, ours, theirs))| ConflictHunk {
+                id: id as u32,
+                base,
+                ours,
+                theirs,
+            })
+            .collect()
+    }
+
+    /// Re-merge the three versions of a conflicted file with the prose merge
+    fn merge_conflicted_file(repo: &Repository, file_path: &str) -> GitResult<ProseMergeResult> {
+        // Find the conflict entry for the file
+        let index = repo.index()?;
+        let conflict = index
+            .conflicts()?
+            .flatten()
+            .find(|conflict| {
+                conflict
+                    .our
+                    .as_ref()
+                    .and_then(|our| std::str::from_utf8(&our.path).ok().map(|p| p == file_path))
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| {
+                GitServiceError::InvalidOperation(format!(
+                    "File '{file_path}' does not have a conflict"
+                ))
+            })?;
+
+        let sides = Self::read_conflict_sides(repo, &conflict)?.ok_or_else(|| {
+            GitServiceError::InvalidOperation(format!(
+                "File '{file_path}' cannot be merged as text"
+            ))
+        })?;
+
+        Ok(prose_merge::merge_prose(
+            &sides.base,
+            &sides.ours,
+            &sides.theirs,
+        ))
+    }
+
+    /// Resolve conflicted story files with the prose-aware merge
+    ///
+    /// Files that merge cleanly at sentence level are written to the working
+    /// tree and staged; the rest are left conflicted.
+    fn resolve_prose_conflicts(
+        repo: &Repository,
+        repo_path: &Path,
+        index: &mut git2::Index,
+    ) -> GitResult<()> {
+        let conflicts: Vec<git2::IndexConflict> = index.conflicts()?.flatten().collect();
+        let mut resolved_any = false;
+
+        for conflict in &conflicts {
+            let Some(sides) = Self::read_conflict_sides(repo, conflict)? else {
+                continue;
+            };
+            if !prose_merge::is_prose_file(&sides.path) {
+                continue;
+            }
+
+            let result = prose_merge::merge_prose(&sides.base, &sides.ours, &sides.theirs);
+            if let Some(merged) = result.merged_text() {
+                fs::write(repo_path.join(&sides.path), merged)?;
+                index.add_path(Path::new(&sides.path))?;
+                resolved_any = true;
+            }
+        }
+
+        if resolved_any {
+            index.write()?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the base, ours and theirs text of a conflict entry
+    ///
+    /// # Returns
+    /// None if either side was deleted or a version is not valid UTF-8
+    fn read_conflict_sides(
+        repo: &Repository,
+        conflict: &git2::IndexConflict,
+    ) -> GitResult<Option<ConflictSides>> {
+        let (Some(our), Some(their)) = (&conflict.our, &conflict.their) else {
+            return Ok(None);
+        };
+        let Ok(path) = std::str::from_utf8(&our.path) else {
+            return Ok(None);
+        };
+
+        let read_text = |id: Oid| -> GitResult<Option<String>> {
+            let blob = repo.find_blob(id)?;
+            Ok(String::from_utf8(blob.content().to_vec()).ok())
+        };
+
+        // Both sides added the file when there is no common ancestor
+        let base = match &conflict.ancestor {
+            Some(ancestor) => read_text(ancestor.id)?,
+            None => Some(String::new()),
+        };
+
+        match (base, read_text(our.id)?, read_text(their.id)?) {
+            (Some(base), Some(ours), Some(theirs)) => Ok(Some(ConflictSides {
+                path: path.to_string(),
+                base,
+                ours,
+                theirs,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Line ranges (start inclusive, end exclusive) of each run of non-blank lines
+fn paragraph_ranges(lines: &[String]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].trim().is_empty() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < lines.len() && !lines[i].trim().is_empty() {
+            i += 1;
+        }
+        ranges.push((start, i));
+    }
+    ranges
+}
+
+/// Split text into paragraphs the same way `blame_paragraphs` groups lines
+fn split_paragraphs(text: &str) -> Vec<String> {
+    let lines: Vec<String> = text.lines().map(str::to_string).collect();
+    paragraph_ranges(&lines)
+        .into_iter()
+        .map(|(start, end)| lines[start..end].join("\n"))
+        .collect()
+}
+
+/// Cut a snippet around a match in a passage
+///
+/// Takes byte offsets of the match and returns the snippet with the match
+/// position converted to UTF-16 code units, as the frontend indexes strings.
+fn history_snippet(passage: &str, start: usize, end: usize) -> (String, u32, u32) {
+    let from = passage[..start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map_or(0, |(i, _)| i);
+    let to = passage[end..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map_or(passage.len(), |(i, _)| end + i);
+
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    let utf16_len = |s: &str| s.encode_utf16().count() as u32;
+    let match_start = utf16_len(&snippet) + utf16_len(&passage[from..start]);
+    let match_end = match_start + utf16_len(&passage[start..end]);
+    snippet.push_str(&passage[from..to]);
+    if to < passage.len() {
+        snippet.push('…');
+    }
+
+    (snippet, match_start, match_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
     fn test_git_service_creation() {
         let service = GitService::new();
         // Basic smoke test to ensure the module compiles
@@ -979,489 +2988,1274 @@ mod tests {
     }
 
     #[test]
-    fn test_init_repo_creates_repository() {
+    fn test_init_repo_creates_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-story-123";
+
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Verify repository was created
+        assert!(repo_path.exists());
+        assert!(repo_path.join(".git").exists());
+        assert!(repo_path.join("metadata.json").exists());
+
+        // Verify we can open the repository
+        let repo = Repository::open(&repo_path).unwrap();
+        assert!(!repo.is_bare());
+    }
+
+    #[test]
+    fn test_init_repo_creates_initial_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-story-456";
+
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let repo = Repository::open(&repo_path).unwrap();
+
+        // Verify HEAD exists and points to a commit
+        let head = repo.head().unwrap();
+        assert!(head.is_branch());
+
+        let commit = head.peel_to_commit().unwrap();
+        assert_eq!(commit.message().unwrap(), "Initial commit");
+    }
+
+    #[test]
+    fn test_init_repo_metadata_contains_story_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-story-789";
+
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let metadata_content = fs::read_to_string(repo_path.join("metadata.json")).unwrap();
+
+        assert!(metadata_content.contains(story_id));
+        assert!(metadata_content.contains("created_at"));
+    }
+
+    #[test]
+    fn test_init_repo_fails_if_already_exists() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-story-duplicate";
+
+        // First init should succeed
+        GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Second init should fail
+        let result = GitService::init_repo(temp_dir.path(), story_id);
+        assert!(result.is_err());
+
+        match result {
+            Err(GitServiceError::AlreadyExists(msg)) => {
+                assert!(msg.contains("already exists"));
+            }
+            _ => panic!("Expected AlreadyExists error"),
+        }
+    }
+
+    #[test]
+    fn test_init_repo_path_structure() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-story-path";
+
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Verify path structure: {base}/git-repos/{story_id}
+        assert!(repo_path.ends_with(format!("git-repos/{story_id}")));
+    }
+
+    #[test]
+    fn test_commit_file_creates_file_and_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-commit-file";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Commit a file
+        let file_content = "# Chapter 1\n\nThis is the first chapter.";
+        let commit_hash =
+            GitService::commit_file(&repo_path, "chapter1.md", file_content, "Add chapter 1")
+                .unwrap();
+
+        // Verify file was created
+        let file_path = repo_path.join("chapter1.md");
+        assert!(file_path.exists());
+
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert_eq!(content, file_content);
+
+        // Verify commit was created
+        assert!(!commit_hash.is_empty());
+
+        let repo = Repository::open(&repo_path).unwrap();
+        let commit = repo
+            .find_commit(Oid::from_str(&commit_hash).unwrap())
+            .unwrap();
+        assert_eq!(commit.message().unwrap(), "Add chapter 1");
+    }
+
+    #[test]
+    fn test_commit_file_creates_subdirectories() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-commit-nested";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Commit a file in a nested directory
+        let commit_hash = GitService::commit_file(
+            &repo_path,
+            "chapters/part1/chapter1.md",
+            "Content",
+            "Add nested file",
+        )
+        .unwrap();
+
+        // Verify nested directory and file were created
+        assert!(repo_path.join("chapters/part1/chapter1.md").exists());
+        assert!(!commit_hash.is_empty());
+    }
+
+    #[test]
+    fn test_commit_all_stages_multiple_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-commit-all";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Create multiple files
+        fs::write(repo_path.join("file1.txt"), "Content 1").unwrap();
+        fs::write(repo_path.join("file2.txt"), "Content 2").unwrap();
+        fs::write(repo_path.join("file3.txt"), "Content 3").unwrap();
+
+        // Commit all files
+        let commit_hash = GitService::commit_all(&repo_path, "Add three files").unwrap();
+
+        // Verify commit was created
+        assert!(!commit_hash.is_empty());
+
+        let repo = Repository::open(&repo_path).unwrap();
+        let commit = repo
+            .find_commit(Oid::from_str(&commit_hash).unwrap())
+            .unwrap();
+        assert_eq!(commit.message().unwrap(), "Add three files");
+
+        // Verify all files are in the commit
+        let tree = commit.tree().unwrap();
+        assert!(tree.get_name("file1.txt").is_some());
+        assert!(tree.get_name("file2.txt").is_some());
+        assert!(tree.get_name("file3.txt").is_some());
+    }
+
+    #[test]
+    fn test_commit_file_returns_error_for_invalid_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let invalid_path = temp_dir.path().join("nonexistent");
+
+        let result = GitService::commit_file(&invalid_path, "file.txt", "content", "message");
+
+        assert!(result.is_err());
+        match result {
+            Err(GitServiceError::RepositoryNotFound(_)) => {}
+            _ => panic!("Expected RepositoryNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_commit_all_returns_error_for_invalid_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let invalid_path = temp_dir.path().join("nonexistent");
+
+        let result = GitService::commit_all(&invalid_path, "message");
+
+        assert!(result.is_err());
+        match result {
+            Err(GitServiceError::RepositoryNotFound(_)) => {}
+            _ => panic!("Expected RepositoryNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_create_branch_from_main() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-branch-create";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Get the current branch name (might be "master" or "main")
+        let repo = Repository::open(&repo_path).unwrap();
+        let head = repo.head().unwrap();
+        let current_branch = head.shorthand().unwrap();
+
+        // Create a new branch from current branch
+        GitService::create_branch(&repo_path, current_branch, "feature-branch").unwrap();
+
+        // Verify branch was created
+        let branch = repo
+            .find_branch("feature-branch", git2::BranchType::Local)
+            .unwrap();
+        assert!(!branch.is_head()); // Not checked out yet
+    }
+
+    #[test]
+    fn test_create_branch_fails_if_already_exists() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-branch-duplicate";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Get current branch name
+        let repo = Repository::open(&repo_path).unwrap();
+        let head = repo.head().unwrap();
+        let current_branch = head.shorthand().unwrap();
+
+        // Create branch
+        GitService::create_branch(&repo_path, current_branch, "feature").unwrap();
+
+        // Try to create again
+        let result = GitService::create_branch(&repo_path, current_branch, "feature");
+
+        assert!(result.is_err());
+        match result {
+            Err(GitServiceError::AlreadyExists(msg)) => {
+                assert!(msg.contains("already exists"));
+            }
+            _ => panic!("Expected AlreadyExists error"),
+        }
+    }
+
+    #[test]
+    fn test_create_branch_fails_if_parent_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-branch-invalid-parent";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Try to create branch from nonexistent parent
+        let result = GitService::create_branch(&repo_path, "nonexistent", "feature");
+
+        assert!(result.is_err());
+        match result {
+            Err(GitServiceError::NotFound(msg)) => {
+                assert!(msg.contains("not found"));
+            }
+            _ => panic!("Expected NotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_checkout_branch_switches_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-branch-checkout";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Get current branch name
+        let current_branch = {
+            let repo = Repository::open(&repo_path).unwrap();
+            let head = repo.head().unwrap();
+            head.shorthand().unwrap().to_string()
+        };
+
+        // Create and commit a file on current branch
+        GitService::commit_file(&repo_path, "main.txt", "Main branch", "Add main file").unwrap();
+
+        // Create a new branch
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+
+        // Checkout the new branch
+        GitService::checkout_branch(&repo_path, "feature").unwrap();
+
+        // Verify we're on the feature branch
+        let repo = Repository::open(&repo_path).unwrap();
+        let head = repo.head().unwrap();
+        assert!(head.shorthand().unwrap() == "feature");
+    }
+
+    #[test]
+    fn test_checkout_branch_fails_with_uncommitted_changes() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-story-123";
+        let story_id = "test-checkout-dirty";
 
+        // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
 
-        // Verify repository was created
-        assert!(repo_path.exists());
-        assert!(repo_path.join(".git").exists());
-        assert!(repo_path.join("metadata.json").exists());
-
-        // Verify we can open the repository
+        // Get current branch name
         let repo = Repository::open(&repo_path).unwrap();
-        assert!(!repo.is_bare());
+        let head = repo.head().unwrap();
+        let current_branch = head.shorthand().unwrap();
+
+        // Create a branch
+        GitService::create_branch(&repo_path, current_branch, "feature").unwrap();
+
+        // Create an uncommitted file
+        fs::write(repo_path.join("uncommitted.txt"), "Uncommitted").unwrap();
+
+        // Try to checkout - should fail
+        let result = GitService::checkout_branch(&repo_path, "feature");
+
+        assert!(result.is_err());
+        match result {
+            Err(GitServiceError::UncommittedChanges(msg)) => {
+                assert!(msg.contains("uncommitted changes"));
+            }
+            _ => panic!("Expected UncommittedChanges error"),
+        }
     }
 
     #[test]
-    fn test_init_repo_creates_initial_commit() {
+    fn test_checkout_branch_fails_if_branch_not_found() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-story-456";
+        let story_id = "test-checkout-invalid";
 
+        // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
-        let repo = Repository::open(&repo_path).unwrap();
 
-        // Verify HEAD exists and points to a commit
-        let head = repo.head().unwrap();
-        assert!(head.is_branch());
+        // Try to checkout nonexistent branch
+        let result = GitService::checkout_branch(&repo_path, "nonexistent");
 
-        let commit = head.peel_to_commit().unwrap();
-        assert_eq!(commit.message().unwrap(), "Initial commit");
+        assert!(result.is_err());
+        match result {
+            Err(GitServiceError::NotFound(msg)) => {
+                assert!(msg.contains("not found"));
+            }
+            _ => panic!("Expected NotFound error"),
+        }
     }
 
     #[test]
-    fn test_init_repo_metadata_contains_story_id() {
+    fn test_diff_branches_shows_changes() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-story-789";
+        let story_id = "test-diff";
 
+        // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
-        let metadata_content = fs::read_to_string(repo_path.join("metadata.json")).unwrap();
 
-        assert!(metadata_content.contains(story_id));
-        assert!(metadata_content.contains("created_at"));
+        // Get current branch
+        let current_branch = {
+            let repo = Repository::open(&repo_path).unwrap();
+            let x = repo.head().unwrap().shorthand().unwrap().to_string();
+            x
+        };
+
+        // Commit a file on current branch
+        GitService::commit_file(&repo_path, "file1.txt", "Content A", "Add file1").unwrap();
+
+        // Create feature branch
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        GitService::checkout_branch(&repo_path, "feature").unwrap();
+
+        // Make changes on feature branch
+        GitService::commit_file(&repo_path, "file2.txt", "Content B", "Add file2").unwrap();
+        GitService::commit_file(
+            &repo_path,
+            "file1.txt",
+            "Content A Modified",
+            "Modify file1",
+        )
+        .unwrap();
+
+        // Get diff
+        let diff = GitService::diff_branches(&repo_path, &current_branch, "feature").unwrap();
+
+        // Verify changes
+        assert_eq!(diff.changes.len(), 2);
+        let has_added = diff
+            .changes
+            .iter()
+            .any(|c| c.path == "file2.txt" && c.status == ChangeStatus::Added);
+        let has_modified = diff
+            .changes
+            .iter()
+            .any(|c| c.path == "file1.txt" && c.status == ChangeStatus::Modified);
+        assert!(has_added);
+        assert!(has_modified);
     }
 
     #[test]
-    fn test_init_repo_fails_if_already_exists() {
+    fn test_diff_branches_includes_word_level_changes() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-story-duplicate";
+        let story_id = "test-diff-words";
 
-        // First init should succeed
-        GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let current_branch = GitService::get_current_branch(&repo_path).unwrap();
 
-        // Second init should fail
-        let result = GitService::init_repo(temp_dir.path(), story_id);
-        assert!(result.is_err());
+        // One paragraph per line, as story files are stored
+        GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "# Chapter 1\n\nThe gate was open and the night was quiet.\n",
+            "Add chapter",
+        )
+        .unwrap();
+
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        GitService::checkout_branch(&repo_path, "feature").unwrap();
+        GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "# Chapter 1\n\nThe gate was shut and the night was very quiet.\n",
+            "Edit paragraph",
+        )
+        .unwrap();
+
+        let diff = GitService::diff_branches(&repo_path, &current_branch, "feature").unwrap();
+
+        assert_eq!(diff.changes.len(), 1);
+        let change = &diff.changes[0];
+        assert_eq!(change.status, ChangeStatus::Modified);
+        assert!(change.diff.as_ref().unwrap().contains("+The gate was shut"));
+        assert_eq!(change.hunks.len(), 1);
+
+        // "open" -> "shut" and "very" inserted
+        assert_eq!(change.words_added, 2);
+        assert_eq!(change.words_removed, 1);
+
+        let hunk = &change.hunks[0];
+        let removed = hunk
+            .lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        let added = hunk
+            .lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Added)
+            .unwrap();
+
+        let removed_words: Vec<_> = removed
+            .segments
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.trim())
+            .collect();
+        let added_words: Vec<_> = added
+            .segments
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.trim())
+            .collect();
+        assert_eq!(removed_words, vec!["open"]);
+        assert_eq!(added_words, vec!["shut", "very"]);
+    }
+
+    #[test]
+    fn test_diff_branches_counts_words_for_added_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-diff-added-words";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let current_branch = GitService::get_current_branch(&repo_path).unwrap();
+
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        GitService::checkout_branch(&repo_path, "feature").unwrap();
+        GitService::commit_file(&repo_path, "new.md", "Four words right here\n", "Add file")
+            .unwrap();
+
+        let diff = GitService::diff_branches(&repo_path, &current_branch, "feature").unwrap();
+
+        let change = diff.changes.iter().find(|c| c.path == "new.md").unwrap();
+        assert_eq!(change.status, ChangeStatus::Added);
+        assert_eq!(change.words_added, 4);
+        assert_eq!(change.words_removed, 0);
+    }
+
+    #[test]
+    fn test_diff_branches_detects_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-diff-rename";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let current_branch = GitService::get_current_branch(&repo_path).unwrap();
+
+        let content =
+            "A long enough paragraph so that rename detection has something to compare.\n";
+        GitService::commit_file(&repo_path, "001-first.md", content, "Add chapter").unwrap();
+
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        GitService::checkout_branch(&repo_path, "feature").unwrap();
+
+        // Rename the file, as reorder_story_files does
+        fs::rename(
+            repo_path.join("001-first.md"),
+            repo_path.join("002-first.md"),
+        )
+        .unwrap();
+        GitService::commit_all(&repo_path, "Reorder").unwrap();
+
+        let diff = GitService::diff_branches(&repo_path, &current_branch, "feature").unwrap();
+
+        assert_eq!(diff.changes.len(), 1);
+        let change = &diff.changes[0];
+        assert_eq!(change.status, ChangeStatus::Renamed);
+        assert_eq!(change.path, "002-first.md");
+        assert_eq!(change.old_path.as_deref(), Some("001-first.md"));
+        assert_eq!(change.words_added, 0);
+        assert_eq!(change.words_removed, 0);
+    }
+
+    #[test]
+    fn test_diff_commits_between_snapshots() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-diff-commits";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        let first =
+            GitService::commit_file(&repo_path, "chapter.md", "Version one\n", "First").unwrap();
+        GitService::commit_file(&repo_path, "notes.md", "Notes\n", "Second").unwrap();
+        let third =
+            GitService::commit_file(&repo_path, "chapter.md", "Version two\n", "Third").unwrap();
+
+        let diff = GitService::diff_commits(&repo_path, &first, &third).unwrap();
+
+        assert_eq!(diff.changes.len(), 2);
+        let chapter = diff
+            .changes
+            .iter()
+            .find(|c| c.path == "chapter.md")
+            .unwrap();
+        assert_eq!(chapter.status, ChangeStatus::Modified);
+        assert_eq!(chapter.words_added, 1);
+        assert_eq!(chapter.words_removed, 1);
+        assert!(diff
+            .changes
+            .iter()
+            .any(|c| c.path == "notes.md" && c.status == ChangeStatus::Added));
+    }
+
+    #[test]
+    fn test_diff_commits_fails_with_invalid_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-diff-commits-invalid";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let head = GitService::commit_file(&repo_path, "file.txt", "Content", "Add").unwrap();
+
+        let result = GitService::diff_commits(&repo_path, "invalid_hash", &head);
 
         match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
-                assert!(msg.contains("already exists"));
+            Err(GitServiceError::InvalidInput(msg)) => {
+                assert!(msg.contains("Invalid commit hash"));
             }
-            _ => panic!("Expected InvalidOperation error"),
+            _ => panic!("Expected InvalidInput error"),
         }
     }
 
     #[test]
-    fn test_init_repo_path_structure() {
+    fn test_diff_commit_to_workdir_shows_uncommitted_changes() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-story-path";
+        let story_id = "test-diff-workdir";
 
+        // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
 
-        // Verify path structure: {base}/git-repos/{story_id}
-        assert!(repo_path.ends_with(format!("git-repos/{story_id}")));
+        let snapshot =
+            GitService::commit_file(&repo_path, "chapter.md", "The night was quiet.\n", "Add")
+                .unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "The night was loud.\n", "Edit").unwrap();
+
+        // Unsaved edit plus a brand new file
+        fs::write(repo_path.join("chapter.md"), "The night was very loud.\n").unwrap();
+        fs::write(repo_path.join("new.md"), "Fresh scene\n").unwrap();
+
+        let diff = GitService::diff_commit_to_workdir(&repo_path, &snapshot).unwrap();
+
+        let chapter = diff
+            .changes
+            .iter()
+            .find(|c| c.path == "chapter.md")
+            .unwrap();
+        assert_eq!(chapter.status, ChangeStatus::Modified);
+        assert_eq!(chapter.words_added, 2);
+        assert_eq!(chapter.words_removed, 1);
+
+        let new_file = diff.changes.iter().find(|c| c.path == "new.md").unwrap();
+        assert_eq!(new_file.status, ChangeStatus::Added);
+        assert_eq!(new_file.words_added, 2);
     }
 
     #[test]
-    fn test_commit_file_creates_file_and_commits() {
+    fn test_diff_index_to_workdir_shows_only_unstaged_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-diff-index";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Saved text\n", "Add").unwrap();
+
+        // Clean working tree has no changes
+        let diff = GitService::diff_index_to_workdir(&repo_path).unwrap();
+        assert!(diff.changes.is_empty());
+
+        fs::write(repo_path.join("chapter.md"), "Unsaved text\n").unwrap();
+
+        let diff = GitService::diff_index_to_workdir(&repo_path).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].path, "chapter.md");
+        assert_eq!(diff.changes[0].status, ChangeStatus::Modified);
+        assert!(diff.changes[0]
+            .diff
+            .as_ref()
+            .unwrap()
+            .contains("+Unsaved text"));
+    }
+
+    #[test]
+    fn test_merge_branches_fast_forward() {
+        let temp_dir = TempDir::new().unwrap();
+        let story_id = "test-merge-ff";
+
+        // Initialize repository
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+
+        // Get current branch
+        let current_branch = {
+            let repo = Repository::open(&repo_path).unwrap();
+            let x = repo.head().unwrap().shorthand().unwrap().to_string();
+            x
+        };
+
+        // Create feature branch and add commits
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        GitService::checkout_branch(&repo_path, "feature").unwrap();
+        GitService::commit_file(&repo_path, "feature.txt", "Feature content", "Add feature")
+            .unwrap();
+
+        // Merge feature into main (fast-forward)
+        let result = GitService::merge_branches(&repo_path, "feature", &current_branch).unwrap();
+
+        assert!(result.success);
+        assert!(result.conflicts.is_empty());
+        assert!(result.message.contains("Fast-forward"));
+    }
+
+    #[test]
+    fn test_merge_branches_already_up_to_date() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-commit-file";
+        let story_id = "test-merge-uptodate";
 
         // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
 
-        // Commit a file
-        let file_content = "# Chapter 1\n\nThis is the first chapter.";
-        let commit_hash =
-            GitService::commit_file(&repo_path, "chapter1.md", file_content, "Add chapter 1")
-                .unwrap();
-
-        // Verify file was created
-        let file_path = repo_path.join("chapter1.md");
-        assert!(file_path.exists());
+        // Get current branch
+        let current_branch = {
+            let repo = Repository::open(&repo_path).unwrap();
+            let x = repo.head().unwrap().shorthand().unwrap().to_string();
+            x
+        };
 
-        let content = fs::read_to_string(&file_path).unwrap();
-        assert_eq!(content, file_content);
+        // Create feature branch (no new commits)
+        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
 
-        // Verify commit was created
-        assert!(!commit_hash.is_empty());
+        // Try to merge feature into main (already up-to-date)
+        let result = GitService::merge_branches(&repo_path, "feature", &current_branch).unwrap();
 
-        let repo = Repository::open(&repo_path).unwrap();
-        let commit = repo
-            .find_commit(Oid::from_str(&commit_hash).unwrap())
-            .unwrap();
-        assert_eq!(commit.message().unwrap(), "Add chapter 1");
+        assert!(result.success);
+        assert!(result.message.contains("up-to-date"));
     }
 
     #[test]
-    fn test_commit_file_creates_subdirectories() {
+    fn test_diff_branches_fails_if_branch_not_found() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-commit-nested";
+        let story_id = "test-diff-invalid";
 
         // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
 
-        // Commit a file in a nested directory
-        let commit_hash = GitService::commit_file(
-            &repo_path,
-            "chapters/part1/chapter1.md",
-            "Content",
-            "Add nested file",
-        )
-        .unwrap();
+        // Get current branch
+        let current_branch = {
+            let repo = Repository::open(&repo_path).unwrap();
+            let x = repo.head().unwrap().shorthand().unwrap().to_string();
+            x
+        };
 
-        // Verify nested directory and file were created
-        assert!(repo_path.join("chapters/part1/chapter1.md").exists());
-        assert!(!commit_hash.is_empty());
+        // Try to diff with nonexistent branch
+        let result = GitService::diff_branches(&repo_path, &current_branch, "nonexistent");
+
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_commit_all_stages_multiple_files() {
+    fn test_merge_branches_fails_if_branch_not_found() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-commit-all";
+        let story_id = "test-merge-invalid";
 
         // Initialize repository
         let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
 
-        // Create multiple files
-        fs::write(repo_path.join("file1.txt"), "Content 1").unwrap();
-        fs::write(repo_path.join("file2.txt"), "Content 2").unwrap();
-        fs::write(repo_path.join("file3.txt"), "Content 3").unwrap();
+        // Get current branch
+        let current_branch = {
+            let repo = Repository::open(&repo_path).unwrap();
+            let x = repo.head().unwrap().shorthand().unwrap().to_string();
+            x
+        };
 
-        // Commit all files
-        let commit_hash = GitService::commit_all(&repo_path, "Add three files").unwrap();
+        // Try to merge from nonexistent branch
+        let result = GitService::merge_branches(&repo_path, "nonexistent", &current_branch);
 
-        // Verify commit was created
-        assert!(!commit_hash.is_empty());
+        assert!(result.is_err());
+    }
 
-        let repo = Repository::open(&repo_path).unwrap();
-        let commit = repo
-            .find_commit(Oid::from_str(&commit_hash).unwrap())
-            .unwrap();
-        assert_eq!(commit.message().unwrap(), "Add three files");
+    /// Create "original" and "alt" branches that each edit the chapter differently
+    fn setup_divergent_chapter(
+        temp_dir: &TempDir,
+        story_id: &str,
+        base: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> PathBuf {
+        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", base, "Add chapter").unwrap();
 
-        // Verify all files are in the commit
-        let tree = commit.tree().unwrap();
-        assert!(tree.get_name("file1.txt").is_some());
-        assert!(tree.get_name("file2.txt").is_some());
-        assert!(tree.get_name("file3.txt").is_some());
+        GitService::create_branch(&repo_path, "original", "alt").unwrap();
+        GitService::checkout_branch(&repo_path, "alt").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", theirs, "Edit on alt").unwrap();
+
+        GitService::checkout_branch(&repo_path, "original").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", ours, "Edit on original").unwrap();
+
+        repo_path
     }
 
     #[test]
-    fn test_commit_file_returns_error_for_invalid_repo() {
+    fn test_merge_branches_merges_sentence_edits_in_same_paragraph() {
         let temp_dir = TempDir::new().unwrap();
-        let invalid_path = temp_dir.path().join("nonexistent");
+        let repo_path = setup_divergent_chapter(
+            &temp_dir,
+            "test-merge-prose",
+            "The night was quiet. The gate stood open.\n",
+            "The night was very quiet. The gate stood open.\n",
+            "The night was quiet. The gate stood shut.\n",
+        );
 
-        let result = GitService::commit_file(&invalid_path, "file.txt", "content", "message");
+        let result = GitService::merge_branches(&repo_path, "alt", "original").unwrap();
 
-        assert!(result.is_err());
-        match result {
-            Err(GitServiceError::RepositoryNotFound(_)) => {}
-            _ => panic!("Expected RepositoryNotFound error"),
-        }
+        assert!(result.success);
+        assert!(result.conflicts.is_empty());
+
+        let content = fs::read_to_string(repo_path.join("chapter.md")).unwrap();
+        assert_eq!(content, "The night was very quiet. The gate stood shut.\n");
+
+        // The merge was committed and the repository is clean again
+        let repo = Repository::open(&repo_path).unwrap();
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 2);
     }
 
     #[test]
-    fn test_commit_all_returns_error_for_invalid_repo() {
+    fn test_get_conflict_regions_returns_conflicting_sentences() {
         let temp_dir = TempDir::new().unwrap();
-        let invalid_path = temp_dir.path().join("nonexistent");
-
-        let result = GitService::commit_all(&invalid_path, "message");
+        let repo_path = setup_divergent_chapter(
+            &temp_dir,
+            "test-conflict-regions",
+            "The night was quiet. The gate stood open.\nMara slept.\n",
+            "The night was still. The gate stood open.\nMara slept.\n",
+            "The night was cold. The gate stood open.\nMara woke.\n",
+        );
 
-        assert!(result.is_err());
-        match result {
-            Err(GitServiceError::RepositoryNotFound(_)) => {}
-            _ => panic!("Expected RepositoryNotFound error"),
-        }
+        let result = GitService::merge_branches(&repo_path, "alt", "original").unwrap();
+        assert!(!result.success);
+        assert_eq!(result.conflicts, vec!["chapter.md".to_string()]);
+
+        let regions = GitService::get_conflict_regions(&repo_path, "chapter.md").unwrap();
+
+        assert!(!regions.clean);
+        assert_eq!(
+            regions.regions,
+            vec![
+                MergeRegion::Conflict {
+                    base: "The night was quiet. ".to_string(),
+                    ours: "The night was still. ".to_string(),
+                    theirs: "The night was cold. ".to_string(),
+                },
+                MergeRegion::Resolved {
+                    text: "The gate stood open.\nMara woke.\n".to_string()
+                },
+            ]
+        );
     }
 
     #[test]
-    fn test_create_branch_from_main() {
+    fn test_get_conflict_regions_fails_without_conflict() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-branch-create";
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-regions-none").unwrap();
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let result = GitService::get_conflict_regions(&repo_path, "chapter.md");
 
-        // Get the current branch name (might be "master" or "main")
-        let repo = Repository::open(&repo_path).unwrap();
-        let head = repo.head().unwrap();
-        let current_branch = head.shorthand().unwrap();
+        assert!(result.is_err());
+    }
 
-        // Create a new branch from current branch
-        GitService::create_branch(&repo_path, current_branch, "feature-branch").unwrap();
+    /// Set up a merge of "alt" into "original" that conflicts in two paragraphs
+    fn setup_two_hunk_conflict(temp_dir: &TempDir, story_id: &str) -> PathBuf {
+        let repo_path = setup_divergent_chapter(
+            temp_dir,
+            story_id,
+            "The night was quiet.\nMara waited.\nThe gate stood open.\n",
+            "The night was still.\nMara waited.\nThe gate was locked.\n",
+            "The night was cold.\nMara waited.\nThe gate had vanished.\n",
+        );
 
-        // Verify branch was created
-        let branch = repo
-            .find_branch("feature-branch", git2::BranchType::Local)
-            .unwrap();
-        assert!(!branch.is_head()); // Not checked out yet
+        let result = GitService::merge_branches(&repo_path, "alt", "original").unwrap();
+        assert!(!result.success);
+
+        repo_path
     }
 
     #[test]
-    fn test_create_branch_fails_if_already_exists() {
+    fn test_get_conflict_files_lists_hunks_in_order() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-branch-duplicate";
+        let repo_path = setup_two_hunk_conflict(&temp_dir, "test-conflict-files");
+
+        let files = GitService::get_conflict_files(&repo_path).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "chapter.md");
+        assert_eq!(
+            files[0].hunks,
+            vec![
+                ConflictHunk {
+                    id: 0,
+                    base: "The night was quiet.\n".to_string(),
+                    ours: "The night was still.\n".to_string(),
+                    theirs: "The night was cold.\n".to_string(),
+                },
+                ConflictHunk {
+                    id: 1,
+                    base: "The gate stood open.\n".to_string(),
+                    ours: "The gate was locked.\n".to_string(),
+                    theirs: "The gate had vanished.\n".to_string(),
+                },
+            ]
+        );
+    }
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+    #[test]
+    fn test_resolve_conflict_hunks_writes_and_stages_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = setup_two_hunk_conflict(&temp_dir, "test-resolve-hunks");
+
+        let resolutions = vec![
+            HunkChoice {
+                hunk_id: 0,
+                resolution: HunkResolution::TheirsThenOurs,
+            },
+            HunkChoice {
+                hunk_id: 1,
+                resolution: HunkResolution::Custom {
+                    text: "The gate was gone.\n".to_string(),
+                },
+            },
+        ];
+        GitService::resolve_conflict_hunks(&repo_path, "chapter.md", &resolutions).unwrap();
+
+        let content = fs::read_to_string(repo_path.join("chapter.md")).unwrap();
+        assert_eq!(
+            content,
+            "The night was cold.\nThe night was still.\nMara waited.\nThe gate was gone.\n"
+        );
 
-        // Get current branch name
+        // The file is staged and no longer conflicted
         let repo = Repository::open(&repo_path).unwrap();
-        let head = repo.head().unwrap();
-        let current_branch = head.shorthand().unwrap();
+        assert!(!repo.index().unwrap().has_conflicts());
+        assert!(GitService::get_conflict_files(&repo_path)
+            .unwrap()
+            .is_empty());
+    }
 
-        // Create branch
-        GitService::create_branch(&repo_path, current_branch, "feature").unwrap();
+    #[test]
+    fn test_resolve_conflict_hunks_requires_every_hunk() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = setup_two_hunk_conflict(&temp_dir, "test-resolve-hunks-missing");
 
-        // Try to create again
-        let result = GitService::create_branch(&repo_path, current_branch, "feature");
+        let resolutions = vec![HunkChoice {
+            hunk_id: 0,
+            resolution: HunkResolution::Ours,
+        }];
+        let result = GitService::resolve_conflict_hunks(&repo_path, "chapter.md", &resolutions);
 
         assert!(result.is_err());
-        match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
-                assert!(msg.contains("already exists"));
-            }
-            _ => panic!("Expected InvalidOperation error"),
-        }
+        let repo = Repository::open(&repo_path).unwrap();
+        assert!(repo.index().unwrap().has_conflicts());
     }
 
     #[test]
-    fn test_create_branch_fails_if_parent_not_found() {
+    fn test_resolve_conflict_hunks_rejects_unknown_hunk() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-branch-invalid-parent";
-
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
-
-        // Try to create branch from nonexistent parent
-        let result = GitService::create_branch(&repo_path, "nonexistent", "feature");
+        let repo_path = setup_two_hunk_conflict(&temp_dir, "test-resolve-hunks-unknown");
+
+        let resolutions = vec![
+            HunkChoice {
+                hunk_id: 0,
+                resolution: HunkResolution::Ours,
+            },
+            HunkChoice {
+                hunk_id: 1,
+                resolution: HunkResolution::Theirs,
+            },
+            HunkChoice {
+                hunk_id: 2,
+                resolution: HunkResolution::Ours,
+            },
+        ];
+        let result = GitService::resolve_conflict_hunks(&repo_path, "chapter.md", &resolutions);
 
         assert!(result.is_err());
-        match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
-                assert!(msg.contains("not found"));
-            }
-            _ => panic!("Expected InvalidOperation error"),
-        }
     }
 
     #[test]
-    fn test_checkout_branch_switches_branch() {
+    fn test_resolve_conflict_hunks_rejects_duplicate_hunk() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-branch-checkout";
-
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let repo_path = setup_two_hunk_conflict(&temp_dir, "test-resolve-hunks-duplicate");
+
+        let resolutions = vec![
+            HunkChoice {
+                hunk_id: 0,
+                resolution: HunkResolution::Ours,
+            },
+            HunkChoice {
+                hunk_id: 1,
+                resolution: HunkResolution::Theirs,
+            },
+            HunkChoice {
+                hunk_id: 0,
+                resolution: HunkResolution::Theirs,
+            },
+        ];
+        let result = GitService::resolve_conflict_hunks(&repo_path, "chapter.md", &resolutions);
+
+        assert!(matches!(result, Err(GitServiceError::InvalidInput(_))));
+        let repo = Repository::open(&repo_path).unwrap();
+        assert!(repo.index().unwrap().has_conflicts());
+    }
 
-        // Get current branch name
-        let current_branch = {
-            let repo = Repository::open(&repo_path).unwrap();
-            let head = repo.head().unwrap();
-            head.shorthand().unwrap().to_string()
-        };
+    /// Snapshot of HEAD, index and working tree used to check a preview changed nothing
+    fn repo_state(repo_path: &Path) -> (String, Vec<String>, String) {
+        let repo = Repository::open(repo_path).unwrap();
+        let head = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string();
+        let index: Vec<String> = repo
+            .index()
+            .unwrap()
+            .iter()
+            .map(|entry| format!("{}:{}", String::from_utf8_lossy(&entry.path), entry.id))
+            .collect();
+        let content = fs::read_to_string(repo_path.join("chapter.md")).unwrap();
+        (head, index, content)
+    }
 
-        // Create and commit a file on current branch
-        GitService::commit_file(&repo_path, "main.txt", "Main branch", "Add main file").unwrap();
+    #[test]
+    fn test_preview_merge_reports_conflicts_without_touching_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = setup_divergent_chapter(
+            &temp_dir,
+            "test-preview-conflict",
+            "The night was quiet.\nMara waited.\nThe fire burned.\n",
+            "The night was still.\nMara waited.\nThe fire burned.\n",
+            "The night was cold.\nMara waited.\nThe fire died.\n",
+        );
+        let before = repo_state(&repo_path);
 
-        // Create a new branch
-        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        let preview = GitService::preview_merge(&repo_path, "alt", "original").unwrap();
 
-        // Checkout the new branch
-        GitService::checkout_branch(&repo_path, "feature").unwrap();
+        assert_eq!(preview.outcome, MergeOutcome::Conflicted);
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(preview.conflicts[0].path, "chapter.md");
+        assert_eq!(preview.conflicts[0].hunks.len(), 1);
+        assert_eq!(
+            preview.conflicts[0].hunks[0].theirs,
+            "The night was cold.\n"
+        );
+        assert_eq!(preview.files_changed, 1);
+        assert_eq!(preview.words_added, 2);
+        assert_eq!(preview.words_removed, 2);
 
-        // Verify we're on the feature branch
+        // Nothing changed on disk or in the repository
+        assert_eq!(repo_state(&repo_path), before);
         let repo = Repository::open(&repo_path).unwrap();
-        let head = repo.head().unwrap();
-        assert!(head.shorthand().unwrap() == "feature");
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().shorthand(), Some("original"));
     }
 
     #[test]
-    fn test_checkout_branch_fails_with_uncommitted_changes() {
+    fn test_preview_merge_clean_when_prose_merge_resolves() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-checkout-dirty";
-
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
-
-        // Get current branch name
-        let repo = Repository::open(&repo_path).unwrap();
-        let head = repo.head().unwrap();
-        let current_branch = head.shorthand().unwrap();
-
-        // Create a branch
-        GitService::create_branch(&repo_path, current_branch, "feature").unwrap();
-
-        // Create an uncommitted file
-        fs::write(repo_path.join("uncommitted.txt"), "Uncommitted").unwrap();
+        let repo_path = setup_divergent_chapter(
+            &temp_dir,
+            "test-preview-clean",
+            "The night was quiet. The gate stood open.\n",
+            "The night was very quiet. The gate stood open.\n",
+            "The night was quiet. The gate stood shut.\n",
+        );
+        let before = repo_state(&repo_path);
 
-        // Try to checkout - should fail
-        let result = GitService::checkout_branch(&repo_path, "feature");
+        let preview = GitService::preview_merge(&repo_path, "alt", "original").unwrap();
 
-        assert!(result.is_err());
-        match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
-                assert!(msg.contains("uncommitted changes"));
-            }
-            _ => panic!("Expected InvalidOperation error"),
-        }
+        assert_eq!(preview.outcome, MergeOutcome::Clean);
+        assert!(preview.conflicts.is_empty());
+        assert_eq!(repo_state(&repo_path), before);
     }
 
     #[test]
-    fn test_checkout_branch_fails_if_branch_not_found() {
+    fn test_preview_merge_fast_forward_and_up_to_date() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-checkout-invalid";
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-preview-ff").unwrap();
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        GitService::create_branch(&repo_path, "original", "alt").unwrap();
+        GitService::checkout_branch(&repo_path, "alt").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "New chapter.\n", "Add chapter").unwrap();
+        GitService::checkout_branch(&repo_path, "original").unwrap();
 
-        // Try to checkout nonexistent branch
-        let result = GitService::checkout_branch(&repo_path, "nonexistent");
+        let preview = GitService::preview_merge(&repo_path, "alt", "original").unwrap();
+        assert_eq!(preview.outcome, MergeOutcome::FastForward);
+        assert_eq!(preview.files_changed, 1);
+        assert_eq!(preview.words_added, 2);
 
-        assert!(result.is_err());
-        match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
-                assert!(msg.contains("not found"));
-            }
-            _ => panic!("Expected InvalidOperation error"),
-        }
+        let preview = GitService::preview_merge(&repo_path, "original", "alt").unwrap();
+        assert_eq!(preview.outcome, MergeOutcome::UpToDate);
+
+        // HEAD still points at the original branch
+        let repo = Repository::open(&repo_path).unwrap();
+        assert_eq!(repo.head().unwrap().shorthand(), Some("original"));
     }
 
     #[test]
-    fn test_diff_branches_shows_changes() {
+    fn test_preview_merge_fails_if_branch_not_found() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-diff";
-
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-preview-invalid").unwrap();
 
-        // Get current branch
-        let current_branch = {
-            let repo = Repository::open(&repo_path).unwrap();
-            let x = repo.head().unwrap().shorthand().unwrap().to_string();
-            x
-        };
+        let result = GitService::preview_merge(&repo_path, "nonexistent", "original");
 
-        // Commit a file on current branch
-        GitService::commit_file(&repo_path, "file1.txt", "Content A", "Add file1").unwrap();
+        assert!(result.is_err());
+    }
 
-        // Create feature branch
-        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
-        GitService::checkout_branch(&repo_path, "feature").unwrap();
+    #[test]
+    fn test_create_tag_and_list_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-tags").unwrap();
+        let first =
+            GitService::commit_file(&repo_path, "chapter.md", "Draft one.\n", "Draft").unwrap();
+        GitService::create_tag(&repo_path, "draft-1", None, "Sent to editor").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Draft two.\n", "Revise").unwrap();
+
+        let tags = GitService::list_tags(&repo_path).unwrap();
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "draft-1");
+        assert_eq!(tags[0].commit_hash, first);
+        assert!(tags[0].message.contains("Sent to editor"));
+    }
 
-        // Make changes on feature branch
-        GitService::commit_file(&repo_path, "file2.txt", "Content B", "Add file2").unwrap();
-        GitService::commit_file(
-            &repo_path,
-            "file1.txt",
-            "Content A Modified",
-            "Modify file1",
-        )
-        .unwrap();
+    #[test]
+    fn test_create_tag_on_specific_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-tags-commit").unwrap();
+        let first = GitService::commit_file(&repo_path, "chapter.md", "One.\n", "One").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Two.\n", "Two").unwrap();
 
-        // Get diff
-        let diff = GitService::diff_branches(&repo_path, &current_branch, "feature").unwrap();
+        let tagged = GitService::create_tag(&repo_path, "beta", Some(&first), "Beta").unwrap();
 
-        // Verify changes
-        assert_eq!(diff.changes.len(), 2);
-        let has_added = diff
-            .changes
-            .iter()
-            .any(|c| c.path == "file2.txt" && c.status == ChangeStatus::Added);
-        let has_modified = diff
-            .changes
-            .iter()
-            .any(|c| c.path == "file1.txt" && c.status == ChangeStatus::Modified);
-        assert!(has_added);
-        assert!(has_modified);
+        assert_eq!(tagged, first);
     }
 
     #[test]
-    fn test_merge_branches_fast_forward() {
+    fn test_create_tag_fails_if_already_exists() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-merge-ff";
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-tags-dup").unwrap();
+        GitService::create_tag(&repo_path, "draft-1", None, "First").unwrap();
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        let result = GitService::create_tag(&repo_path, "draft-1", None, "Again");
 
-        // Get current branch
-        let current_branch = {
-            let repo = Repository::open(&repo_path).unwrap();
-            let x = repo.head().unwrap().shorthand().unwrap().to_string();
-            x
-        };
+        assert!(result.is_err());
+    }
 
-        // Create feature branch and add commits
-        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
-        GitService::checkout_branch(&repo_path, "feature").unwrap();
-        GitService::commit_file(&repo_path, "feature.txt", "Feature content", "Add feature")
-            .unwrap();
+    #[test]
+    fn test_delete_tag_removes_tag() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-tags-delete").unwrap();
+        GitService::create_tag(&repo_path, "draft-1", None, "First").unwrap();
 
-        // Merge feature into main (fast-forward)
-        let result = GitService::merge_branches(&repo_path, "feature", &current_branch).unwrap();
+        GitService::delete_tag(&repo_path, "draft-1").unwrap();
 
-        assert!(result.success);
-        assert!(result.conflicts.is_empty());
-        assert!(result.message.contains("Fast-forward"));
+        assert!(GitService::list_tags(&repo_path).unwrap().is_empty());
+        assert!(GitService::delete_tag(&repo_path, "draft-1").is_err());
     }
 
     #[test]
-    fn test_merge_branches_already_up_to_date() {
+    fn test_get_history_includes_tags() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-merge-uptodate";
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-history-tags").unwrap();
+        let tagged = GitService::commit_file(&repo_path, "chapter.md", "One.\n", "One").unwrap();
+        GitService::create_tag(&repo_path, "draft-1", None, "Draft 1").unwrap();
+        GitService::create_tag(&repo_path, "beta", None, "Beta").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Two.\n", "Two").unwrap();
+
+        let history = GitService::get_history(&repo_path, "original").unwrap();
+
+        let tagged_commit = history.iter().find(|c| c.hash == tagged).unwrap();
+        let mut tags = tagged_commit.tags.clone();
+        tags.sort();
+        assert_eq!(tags, vec!["beta".to_string(), "draft-1".to_string()]);
+        assert!(history[0].tags.is_empty());
+    }
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+    #[test]
+    fn test_rename_branch_renames_and_keeps_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-rename-branch").unwrap();
+        GitService::create_branch(&repo_path, "original", "draft").unwrap();
+        GitService::checkout_branch(&repo_path, "draft").unwrap();
+
+        GitService::rename_branch(&repo_path, "draft", "second-draft").unwrap();
+
+        let branches = GitService::list_branches(&repo_path).unwrap();
+        assert!(branches.contains(&"second-draft".to_string()));
+        assert!(!branches.contains(&"draft".to_string()));
+        assert_eq!(
+            GitService::get_current_branch(&repo_path).unwrap(),
+            "second-draft"
+        );
+    }
 
-        // Get current branch
-        let current_branch = {
-            let repo = Repository::open(&repo_path).unwrap();
-            let x = repo.head().unwrap().shorthand().unwrap().to_string();
-            x
-        };
+    #[test]
+    fn test_rename_branch_refuses_original_and_existing_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-rename-refuse").unwrap();
+        GitService::create_branch(&repo_path, "original", "a").unwrap();
+        GitService::create_branch(&repo_path, "original", "b").unwrap();
 
-        // Create feature branch (no new commits)
-        GitService::create_branch(&repo_path, &current_branch, "feature").unwrap();
+        assert!(GitService::rename_branch(&repo_path, "original", "renamed").is_err());
+        assert!(GitService::rename_branch(&repo_path, "a", "b").is_err());
+        assert!(GitService::rename_branch(&repo_path, "missing", "c").is_err());
+    }
 
-        // Try to merge feature into main (already up-to-date)
-        let result = GitService::merge_branches(&repo_path, "feature", &current_branch).unwrap();
+    #[test]
+    fn test_delete_branch_refuses_current_and_original() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-delete-refuse").unwrap();
+        GitService::create_branch(&repo_path, "original", "alt").unwrap();
+        GitService::checkout_branch(&repo_path, "alt").unwrap();
 
-        assert!(result.success);
-        assert!(result.message.contains("up-to-date"));
+        assert!(GitService::delete_branch(&repo_path, "alt", true).is_err());
+        assert!(GitService::delete_branch(&repo_path, "original", true).is_err());
     }
 
     #[test]
-    fn test_diff_branches_fails_if_branch_not_found() {
+    fn test_delete_branch_warns_about_unmerged_commits() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-diff-invalid";
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-delete-unmerged").unwrap();
+        GitService::create_branch(&repo_path, "original", "alt").unwrap();
+        GitService::checkout_branch(&repo_path, "alt").unwrap();
+        GitService::commit_file(&repo_path, "alt.md", "Alt.\n", "Alt work").unwrap();
+        GitService::checkout_branch(&repo_path, "original").unwrap();
+
+        assert_eq!(
+            GitService::count_unmerged_commits(&repo_path, "alt").unwrap(),
+            1
+        );
+        let result = GitService::delete_branch(&repo_path, "alt", false);
+        match result {
+            Err(GitServiceError::InvalidOperation(msg)) => assert!(msg.contains("unmerged")),
+            _ => panic!("Expected InvalidOperation error"),
+        }
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        GitService::delete_branch(&repo_path, "alt", true).unwrap();
+        let branches = GitService::list_branches(&repo_path).unwrap();
+        assert!(!branches.contains(&"alt".to_string()));
+    }
 
-        // Get current branch
-        let current_branch = {
-            let repo = Repository::open(&repo_path).unwrap();
-            let x = repo.head().unwrap().shorthand().unwrap().to_string();
-            x
-        };
+    #[test]
+    fn test_delete_branch_without_unmerged_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-delete-merged").unwrap();
+        GitService::create_branch(&repo_path, "original", "alt").unwrap();
 
-        // Try to diff with nonexistent branch
-        let result = GitService::diff_branches(&repo_path, &current_branch, "nonexistent");
+        GitService::delete_branch(&repo_path, "alt", false).unwrap();
 
-        assert!(result.is_err());
+        let branches = GitService::list_branches(&repo_path).unwrap();
+        assert_eq!(branches, vec!["original".to_string()]);
     }
 
     #[test]
-    fn test_merge_branches_fails_if_branch_not_found() {
+    fn test_archive_and_restore_branch() {
         let temp_dir = TempDir::new().unwrap();
-        let story_id = "test-merge-invalid";
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-archive-branch").unwrap();
+        GitService::create_branch(&repo_path, "original", "alt").unwrap();
+        GitService::checkout_branch(&repo_path, "alt").unwrap();
+        let tip = GitService::commit_file(&repo_path, "alt.md", "Alt.\n", "Alt work").unwrap();
+        GitService::checkout_branch(&repo_path, "original").unwrap();
+
+        GitService::archive_branch(&repo_path, "alt").unwrap();
+
+        assert!(!GitService::list_branches(&repo_path)
+            .unwrap()
+            .contains(&"alt".to_string()));
+        assert_eq!(
+            GitService::list_archived_branches(&repo_path).unwrap(),
+            vec!["alt".to_string()]
+        );
 
-        // Initialize repository
-        let repo_path = GitService::init_repo(temp_dir.path(), story_id).unwrap();
+        GitService::restore_archived_branch(&repo_path, "alt").unwrap();
 
-        // Get current branch
-        let current_branch = {
-            let repo = Repository::open(&repo_path).unwrap();
-            let x = repo.head().unwrap().shorthand().unwrap().to_string();
-            x
-        };
+        assert!(GitService::list_archived_branches(&repo_path)
+            .unwrap()
+            .is_empty());
+        let history = GitService::get_history(&repo_path, "alt").unwrap();
+        assert_eq!(history[0].hash, tip);
+    }
 
-        // Try to merge from nonexistent branch
-        let result = GitService::merge_branches(&repo_path, "nonexistent", &current_branch);
+    #[test]
+    fn test_archive_branch_refuses_current_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-archive-current").unwrap();
 
-        assert!(result.is_err());
+        assert!(GitService::archive_branch(&repo_path, "original").is_err());
+        assert!(GitService::restore_archived_branch(&repo_path, "missing").is_err());
+    }
+
+    #[test]
+    fn test_import_history_keeps_source_commits_reachable() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = GitService::init_repo(temp_dir.path(), "test-import-source").unwrap();
+        let tip = GitService::commit_file(&source, "content.md", "Draft.\n", "Draft").unwrap();
+        GitService::create_branch(&source, "original", "alt").unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-import-target").unwrap();
+
+        GitService::import_history(&repo_path, &source, "story-1").unwrap();
+        fs::remove_dir_all(&source).unwrap();
+
+        let mut imported = GitService::list_imported_history(&repo_path).unwrap();
+        imported.sort();
+        assert_eq!(imported, vec!["story-1/alt", "story-1/original"]);
+        let history = GitService::get_history(&repo_path, "story-1/original").unwrap();
+        assert_eq!(history[0].hash, tip);
+        assert_eq!(history[0].message, "Draft");
+        assert!(GitService::list_branches(&repo_path)
+            .unwrap()
+            .iter()
+            .all(|branch| !branch.starts_with("story-1")));
+
+        // Moving on again carries the earlier history along
+        let next = GitService::init_repo(temp_dir.path(), "test-import-next").unwrap();
+        GitService::import_history(&next, &repo_path, "story-1").unwrap();
+        let history = GitService::get_history(&next, "story-1/story-1/original").unwrap();
+        assert_eq!(history[0].hash, tip);
     }
 
     #[test]
@@ -1502,6 +4296,212 @@ mod tests {
         assert!(!history[0].timestamp.is_empty());
     }
 
+    #[test]
+    fn test_get_file_history_only_lists_commits_touching_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-file-history").unwrap();
+        GitService::commit_file(&repo_path, "001-one.md", "One.\n", "Add one").unwrap();
+        GitService::commit_file(&repo_path, "002-two.md", "Two.\n", "Add two").unwrap();
+        GitService::commit_file(&repo_path, "001-one.md", "One, revised.\n", "Revise one").unwrap();
+
+        let history = GitService::get_file_history(&repo_path, "original", "001-one.md").unwrap();
+
+        let messages: Vec<_> = history.iter().map(|e| e.commit.message.as_str()).collect();
+        assert_eq!(messages, vec!["Revise one", "Add one"]);
+        assert!(history.iter().all(|e| e.path == "001-one.md"));
+    }
+
+    #[test]
+    fn test_get_file_history_follows_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-file-history-mv").unwrap();
+        let text = "A paragraph long enough to be recognised as the same file.\n";
+        GitService::commit_file(&repo_path, "001-intro.md", text, "Add intro").unwrap();
+
+        fs::rename(
+            repo_path.join("001-intro.md"),
+            repo_path.join("002-intro.md"),
+        )
+        .unwrap();
+        GitService::commit_all(&repo_path, "Reorder story files").unwrap();
+        GitService::commit_file(&repo_path, "002-intro.md", "Rewritten.\n", "Rewrite").unwrap();
+
+        let history = GitService::get_file_history(&repo_path, "original", "002-intro.md").unwrap();
+
+        let entries: Vec<_> = history
+            .iter()
+            .map(|e| (e.commit.message.as_str(), e.path.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("Rewrite", "002-intro.md"),
+                ("Reorder story files", "002-intro.md"),
+                ("Add intro", "001-intro.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blame_paragraphs_maps_paragraphs_to_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-blame").unwrap();
+        let first = GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "First paragraph.\n\nSecond paragraph.\n\nThird paragraph.\n",
+            "Draft",
+        )
+        .unwrap();
+        let second = GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "First paragraph.\n\nSecond paragraph, revised.\n\nThird paragraph.\n",
+            "Revise",
+        )
+        .unwrap();
+
+        let blame = GitService::blame_paragraphs(&repo_path, "original", "chapter.md").unwrap();
+
+        assert_eq!(blame.len(), 3);
+        assert_eq!(blame[0].commit.hash, first);
+        assert_eq!(blame[1].commit.hash, second);
+        assert_eq!(blame[1].text, "Second paragraph, revised.");
+        assert_eq!((blame[1].start_line, blame[1].end_line), (3, 3));
+        assert_eq!(blame[2].commit.hash, first);
+        assert_eq!(blame[2].index, 2);
+    }
+
+    #[test]
+    fn test_blame_paragraphs_follows_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-blame-mv").unwrap();
+        let text = "An opening paragraph that stays the same.\n\nA closing paragraph.\n";
+        let first = GitService::commit_file(&repo_path, "001-intro.md", text, "Draft").unwrap();
+
+        fs::rename(
+            repo_path.join("001-intro.md"),
+            repo_path.join("002-intro.md"),
+        )
+        .unwrap();
+        GitService::commit_all(&repo_path, "Reorder story files").unwrap();
+
+        let blame = GitService::blame_paragraphs(&repo_path, "original", "002-intro.md").unwrap();
+
+        assert_eq!(blame.len(), 2);
+        assert!(blame.iter().all(|p| p.commit.hash == first));
+    }
+
+    #[test]
+    fn test_blame_paragraphs_fails_for_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-blame-missing").unwrap();
+
+        let result = GitService::blame_paragraphs(&repo_path, "original", "missing.md");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_search_history_finds_deleted_passage() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-search").unwrap();
+        let with_passage = GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "Opening.\n\nThe Lighthouse keeper waved.\n\nClosing.\n",
+            "Draft",
+        )
+        .unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Opening.\n\nClosing.\n", "Cut").unwrap();
+
+        let matches = GitService::search_history(&repo_path, "lighthouse keeper", false).unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].commit.hash, with_passage);
+        assert_eq!(matches[0].branch, "original");
+        assert_eq!(matches[0].file_path, "chapter.md");
+        assert_eq!(matches[0].paragraph_index, 1);
+        assert_eq!(matches[0].passage, "The Lighthouse keeper waved.");
+        assert_eq!((matches[0].match_start, matches[0].match_end), (4, 21));
+    }
+
+    #[test]
+    fn test_search_history_covers_variations_and_regex() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-search-branches").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Sarah dies.\n", "Draft").unwrap();
+        GitService::create_branch(&repo_path, "original", "sarah-lives").unwrap();
+        GitService::checkout_branch(&repo_path, "sarah-lives").unwrap();
+        GitService::commit_file(&repo_path, "chapter.md", "Sarah lives.\n", "Rewrite").unwrap();
+        GitService::checkout_branch(&repo_path, "original").unwrap();
+
+        let matches = GitService::search_history(&repo_path, r"Sarah (lives|dies)", true).unwrap();
+
+        let found: Vec<_> = matches
+            .iter()
+            .map(|m| (m.branch.as_str(), m.passage.as_str()))
+            .collect();
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&("original", "Sarah dies.")));
+        assert!(found.contains(&("sarah-lives", "Sarah lives.")));
+    }
+
+    #[test]
+    fn test_search_history_rejects_invalid_regex() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-search-invalid").unwrap();
+
+        let result = GitService::search_history(&repo_path, "(unclosed", true);
+
+        assert!(matches!(result, Err(GitServiceError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_restore_passage_inserts_after_old_neighbour() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-restore-passage").unwrap();
+        let old = GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "Opening.\n\nLost line.\n\nClosing.\n",
+            "Draft",
+        )
+        .unwrap();
+        GitService::commit_file(
+            &repo_path,
+            "chapter.md",
+            "New start.\n\nOpening.\n\nClosing.\n",
+            "Revise",
+        )
+        .unwrap();
+
+        GitService::restore_passage(&repo_path, &old, "chapter.md", 1, "chapter.md").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(repo_path.join("chapter.md")).unwrap(),
+            "New start.\n\nOpening.\n\nLost line.\n\nClosing.\n"
+        );
+        let again = GitService::restore_passage(&repo_path, &old, "chapter.md", 1, "chapter.md");
+        assert!(again.is_err());
+    }
+
+    #[test]
+    fn test_history_snippet_trims_context_and_counts_utf16() {
+        let passage = format!("{}🙂 needle rest", "a".repeat(80));
+        let start = passage.find("needle").unwrap();
+
+        let (snippet, match_start, match_end) = history_snippet(&passage, start, start + 6);
+
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with("needle rest"));
+        let utf16: Vec<u16> = snippet.encode_utf16().collect();
+        assert_eq!(
+            String::from_utf16(&utf16[match_start as usize..match_end as usize]).unwrap(),
+            "needle"
+        );
+    }
+
     #[test]
     fn test_restore_commit_restores_to_previous_state() {
         let temp_dir = TempDir::new().unwrap();
@@ -1536,6 +4536,114 @@ mod tests {
         assert_eq!(content, "Version 1");
     }
 
+    #[test]
+    fn test_restore_snapshot_commits_on_current_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-restore-snapshot").unwrap();
+
+        let first =
+            GitService::commit_file(&repo_path, "file.txt", "Version 1", "Add file").unwrap();
+        GitService::commit_file(&repo_path, "file.txt", "Version 2", "Update file").unwrap();
+        GitService::commit_file(&repo_path, "extra.txt", "Extra", "Add extra").unwrap();
+
+        let restored = GitService::restore_snapshot(&repo_path, &first).unwrap();
+
+        // Content matches the snapshot, including removed files
+        let content = fs::read_to_string(repo_path.join("file.txt")).unwrap();
+        assert_eq!(content, "Version 1");
+        assert!(!repo_path.join("extra.txt").exists());
+
+        // HEAD is still on the branch, with the restore as the newest commit
+        assert!(!GitService::is_head_detached(&repo_path).unwrap());
+        assert_eq!(
+            GitService::get_current_branch(&repo_path).unwrap(),
+            "original"
+        );
+        let history = GitService::get_history(&repo_path, "original").unwrap();
+        assert_eq!(history[0].hash, restored);
+        assert!(history[0]
+            .message
+            .starts_with(&format!("Restored from {}", &first[..7])));
+        assert_eq!(history.len(), 5);
+
+        // Working tree is clean
+        let repo = Repository::open(&repo_path).unwrap();
+        assert!(repo.statuses(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_snapshot_fails_with_uncommitted_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-restore-snap-dirty").unwrap();
+        let first = GitService::commit_file(&repo_path, "file.txt", "One", "One").unwrap();
+        GitService::commit_file(&repo_path, "file.txt", "Two", "Two").unwrap();
+        fs::write(repo_path.join("file.txt"), "Unsaved").unwrap();
+
+        let result = GitService::restore_snapshot(&repo_path, &first);
+
+        assert!(result.is_err());
+        let content = fs::read_to_string(repo_path.join("file.txt")).unwrap();
+        assert_eq!(content, "Unsaved");
+    }
+
+    #[test]
+    fn test_peek_snapshot_reads_files_without_checkout() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-peek").unwrap();
+        let first =
+            GitService::commit_file(&repo_path, "notes/chapter.md", "Old text", "One").unwrap();
+        GitService::commit_file(&repo_path, "notes/chapter.md", "New text", "Two").unwrap();
+
+        let files = GitService::peek_snapshot(&repo_path, &first).unwrap();
+
+        let chapter = files.iter().find(|f| f.path == "notes/chapter.md").unwrap();
+        assert_eq!(chapter.content, "Old text");
+        assert!(files.iter().any(|f| f.path == "metadata.json"));
+
+        // Working tree is untouched
+        let content = fs::read_to_string(repo_path.join("notes/chapter.md")).unwrap();
+        assert_eq!(content, "New text");
+    }
+
+    #[test]
+    fn test_recover_detached_head_keeps_orphaned_work() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-recover-head").unwrap();
+        let first = GitService::commit_file(&repo_path, "file.txt", "Version 1", "One").unwrap();
+        GitService::commit_file(&repo_path, "file.txt", "Version 2", "Two").unwrap();
+
+        // Old-style restore leaves HEAD detached; commits made now are orphaned
+        GitService::restore_commit(&repo_path, &first).unwrap();
+        assert!(GitService::is_head_detached(&repo_path).unwrap());
+        GitService::commit_file(&repo_path, "file.txt", "Version 1b", "Orphan").unwrap();
+
+        let branch = GitService::recover_detached_head(&repo_path, None).unwrap();
+
+        assert_eq!(branch, "original");
+        assert!(!GitService::is_head_detached(&repo_path).unwrap());
+        assert_eq!(
+            GitService::get_current_branch(&repo_path).unwrap(),
+            "original"
+        );
+        let content = fs::read_to_string(repo_path.join("file.txt")).unwrap();
+        assert_eq!(content, "Version 1b");
+
+        // The branch keeps its history plus a commit with the recovered content
+        let history = GitService::get_history(&repo_path, "original").unwrap();
+        assert!(history[0].message.starts_with("Restored from"));
+        assert!(history.iter().any(|c| c.message == "Two"));
+    }
+
+    #[test]
+    fn test_recover_detached_head_fails_when_attached() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = GitService::init_repo(temp_dir.path(), "test-recover-attached").unwrap();
+
+        let result = GitService::recover_detached_head(&repo_path, None);
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_restore_commit_fails_with_invalid_hash() {
         let temp_dir = TempDir::new().unwrap();
@@ -1549,10 +4657,10 @@ mod tests {
 
         assert!(result.is_err());
         match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
+            Err(GitServiceError::InvalidInput(msg)) => {
                 assert!(msg.contains("Invalid commit hash"));
             }
-            _ => panic!("Expected InvalidOperation error"),
+            _ => panic!("Expected InvalidInput error"),
         }
     }
 
@@ -1585,10 +4693,10 @@ mod tests {
 
         assert!(result.is_err());
         match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
+            Err(GitServiceError::UncommittedChanges(msg)) => {
                 assert!(msg.contains("uncommitted changes"));
             }
-            _ => panic!("Expected InvalidOperation error"),
+            _ => panic!("Expected UncommittedChanges error"),
         }
     }
 
@@ -1772,10 +4880,10 @@ mod tests {
 
         assert!(result.is_err());
         match result {
-            Err(GitServiceError::InvalidOperation(msg)) => {
+            Err(GitServiceError::NotFound(msg)) => {
                 assert!(msg.contains("not found"));
             }
-            _ => panic!("Expected InvalidOperation error"),
+            _ => panic!("Expected NotFound error"),
         }
     }
 
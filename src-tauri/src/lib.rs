@@ -1,10 +1,21 @@
+mod autosave;
 mod commands;
+mod consistency;
 mod db;
+mod duplicate;
 mod file_management;
 mod file_naming;
 mod git;
 mod models;
+mod moves;
+mod prose_merge;
+mod recovery;
 mod repositories;
+mod restructure;
+mod text_stats;
+mod trash;
+mod undo;
+mod word_diff;
 
 use tauri::Manager;
 
@@ -32,18 +43,28 @@ pub fn run() {
             let db_path = app_data_dir.join("bright.db");
 
             // Initialize database
-            let database =
-                db::Database::new(db_path).expect("Failed to create database connection");
+            let database = db::Database::new(db_path.clone())
+                .expect("Failed to create database connection");
 
-            // Run migrations
+            // Back up and migrate the database if it's behind the current schema
             let conn = database.connection();
             let conn = conn.lock().unwrap();
-            db::migrations::run_migrations(&conn).expect("Failed to run migrations");
+            match db::migrations::upgrade_database(&conn, &db_path) {
+                Ok(Some(backup)) => {
+                    log::info!("Backed up database to {} before upgrading", backup.display())
+                }
+                Ok(None) => {}
+                Err(e) => panic!("Failed to run migrations: {e}"),
+            }
             drop(conn);
 
             // Store database in app state
             app.manage(database);
 
+            // Commit story edits in the background once the writer goes idle
+            app.manage(autosave::AutosaveService::default());
+            autosave::spawn_worker(app.handle().clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -54,14 +75,23 @@ pub fn run() {
             commands::list_universes,
             commands::update_universe,
             commands::delete_universe,
+            commands::duplicate_universe,
+            commands::create_universe_from_template,
+            // Autosave commands
+            commands::get_autosave_config,
+            commands::set_autosave_config,
+            commands::flush_autosave,
             // Container commands
             commands::create_container,
             commands::get_container,
             commands::list_containers,
             commands::list_container_children,
+            commands::get_container_stats,
             commands::update_container,
             commands::delete_container,
             commands::reorder_container_children,
+            commands::move_container,
+            commands::duplicate_container,
             commands::ensure_container_git_repo,
             commands::check_empty_non_leaf_container,
             commands::convert_to_leaf_container,
@@ -72,6 +102,11 @@ pub fn run() {
             commands::list_story_variations,
             commands::update_story,
             commands::delete_story,
+            commands::move_story,
+            commands::split_story,
+            commands::merge_stories,
+            commands::duplicate_story,
+            commands::restore_story_passage,
             commands::ensure_story_git_repo,
             // Element commands
             commands::create_element,
@@ -88,15 +123,79 @@ pub fn run() {
             commands::git_create_branch,
             commands::git_checkout_branch,
             commands::git_diff_branches,
+            commands::git_diff_commits,
+            commands::git_diff_commit_to_workdir,
+            commands::git_diff_index_to_workdir,
             commands::git_merge_branches,
+            commands::git_preview_merge,
             commands::git_get_history,
+            commands::git_get_file_history,
+            commands::git_blame_paragraphs,
+            commands::git_search_history,
             commands::git_restore_commit,
+            commands::git_restore_snapshot,
+            commands::git_peek_snapshot,
+            commands::git_is_head_detached,
+            commands::git_recover_detached_head,
             commands::git_list_branches,
             commands::git_get_current_branch,
+            commands::git_rename_branch,
+            commands::git_delete_branch,
+            commands::git_count_unmerged_commits,
+            commands::git_archive_branch,
+            commands::git_list_archived_branches,
+            commands::git_list_imported_history,
+            commands::git_restore_archived_branch,
             commands::git_resolve_conflict,
             commands::git_abort_merge,
             commands::git_get_conflict_content,
+            commands::git_get_conflict_regions,
+            commands::git_get_conflict_files,
+            commands::git_resolve_conflict_hunks,
+            commands::git_create_milestone,
+            commands::git_list_milestones,
+            commands::git_delete_milestone,
+            commands::git_diff_milestone,
+            commands::git_restore_milestone,
+            // Search commands
+            commands::search,
+            // Writing session commands
+            commands::get_writing_history,
+            commands::get_writing_streak,
+            commands::create_writing_goal,
+            commands::list_writing_goals,
+            commands::delete_writing_goal,
+            commands::get_goal_progress,
+            commands::list_goal_progress,
+            // Trash commands
+            commands::list_trash,
+            commands::restore_from_trash,
+            commands::purge_from_trash,
+            commands::purge_expired_trash,
+            // Journal commands
+            commands::get_change_timeline,
+            commands::revert_field_change,
+            // Undo commands
+            commands::undo,
+            commands::redo,
+            commands::get_undo_state,
+            // Maintenance commands
+            commands::rebuild_database_from_repos,
+            commands::check_consistency,
+            commands::fix_consistency_issue,
+            commands::fix_all_consistency_issues,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Don't lose pending autosave edits when the app quits, unless
+            // the user commits by hand
+            if let tauri::RunEvent::Exit = event {
+                let db = app.state::<db::Database>();
+                let autosave = app.state::<autosave::AutosaveService>();
+                if autosave.config().enabled {
+                    autosave.flush_all(&db);
+                }
+            }
+        });
 }
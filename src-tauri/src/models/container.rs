@@ -129,6 +129,17 @@ pub struct ContainerChildren {
     pub stories: Vec<crate::models::Story>,
 }
 
+/// Content totals for a container and everything nested under it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerStats {
+    pub container_id: String,
+    /// Number of stories counted (variations are not included)
+    pub story_count: u32,
+    pub stats: crate::text_stats::TextStats,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
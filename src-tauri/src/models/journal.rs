@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// Where a change came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOrigin {
+    /// An edit made by the writer
+    #[default]
+    Ui,
+    /// Rows recreated from git repositories
+    Import,
+    /// A background save from the editor
+    Autosave,
+}
+
+/// The kind of entity a journal entry is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum JournalEntityType {
+    Universe,
+    Container,
+    Element,
+    Story,
+}
+
+/// What happened to the entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum JournalAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// The value of one field before and after a change
+///
+/// Values are the field's JSON form as the frontend sees it; `null` stands
+/// for a field that wasn't set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct FieldChange {
+    #[ts(type = "unknown")]
+    pub before: serde_json::Value,
+    #[ts(type = "unknown")]
+    pub after: serde_json::Value,
+}
+
+/// One change to a universe, container, element or story's metadata
+///
+/// Story content is versioned in git and isn't journaled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub entity_type: JournalEntityType,
+    pub entity_id: String,
+    pub universe_id: String,
+    pub action: JournalAction,
+    /// Changed fields by their camelCase name
+    pub changes: BTreeMap<String, FieldChange>,
+    pub origin: ChangeOrigin,
+    pub created_at: String,
+}
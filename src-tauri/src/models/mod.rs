@@ -1,13 +1,23 @@
 pub mod container;
 pub mod element;
+pub mod journal;
+pub mod search;
+pub mod session;
 pub mod story;
 pub mod universe;
 
 pub use container::{
-    Container, ContainerChildren, CreateContainerInput, UpdateContainerInput, MAX_NESTING_DEPTH,
+    Container, ContainerChildren, ContainerStats, CreateContainerInput, UpdateContainerInput,
+    MAX_NESTING_DEPTH,
 };
 pub use element::{
     CreateElementInput, Element, ElementRelationship, ElementType, UpdateElementInput,
 };
+pub use journal::{ChangeOrigin, FieldChange, JournalAction, JournalEntityType, JournalEntry};
+pub use search::{HighlightRange, SearchEntityType, SearchFilters, SearchHit};
+pub use session::{
+    CreateWritingGoalInput, GoalProgress, GoalType, WritingDay, WritingGoal, WritingScope,
+    WritingStreak,
+};
 pub use story::{CreateStoryInput, Story, StoryStatus, StoryType, UpdateStoryInput, VariationType};
 pub use universe::{CreateUniverseInput, Universe, UniverseStatus, UpdateUniverseInput};
@@ -0,0 +1,76 @@
+use crate::models::StoryStatus;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Kind of entity a search hit refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEntityType {
+    Story,
+    Element,
+    Universe,
+}
+
+impl SearchEntityType {
+    /// Value stored in the `entity_type` column of the search index
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchEntityType::Story => "story",
+            SearchEntityType::Element => "element",
+            SearchEntityType::Universe => "universe",
+        }
+    }
+}
+
+/// Optional filters for a search
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilters {
+    /// Only return hits from this universe
+    #[serde(default)]
+    pub universe_id: Option<String>,
+    /// Only return these kinds of entity (all kinds if empty or absent)
+    #[serde(default)]
+    pub entity_types: Option<Vec<SearchEntityType>>,
+    /// Only return stories, elements and universes carrying this tag
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Only return stories with this status
+    #[serde(default)]
+    pub status: Option<StoryStatus>,
+    /// Maximum number of hits (default 50)
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// A highlighted range within a search hit's text
+///
+/// Offsets are in UTF-16 code units so they can be used directly with
+/// JavaScript string methods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct HighlightRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A single ranked search result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub entity_type: SearchEntityType,
+    pub entity_id: String,
+    pub universe_id: String,
+    pub title: String,
+    /// Matched terms within `title`
+    pub title_highlights: Vec<HighlightRange>,
+    /// Excerpt around the best match, with "…" where text was cut
+    pub snippet: String,
+    /// Matched terms within `snippet`
+    pub snippet_highlights: Vec<HighlightRange>,
+    /// Relevance score; higher is better
+    pub score: f64,
+}
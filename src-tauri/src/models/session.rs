@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Words written on one day
+///
+/// Recorded from content saves: each save compares the new content with the
+/// previous one word by word. Days are local calendar days (`YYYY-MM-DD`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct WritingDay {
+    pub day: String,
+    pub words_added: u32,
+    pub words_removed: u32,
+    /// `words_added - words_removed`
+    pub net_words: i32,
+}
+
+/// Which writing a history or streak query covers
+///
+/// The most specific id wins: a story, else a container (including everything
+/// nested under it), else a universe. With no ids set, all writing counts.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct WritingScope {
+    #[serde(default)]
+    pub universe_id: Option<String>,
+    #[serde(default)]
+    pub container_id: Option<String>,
+    #[serde(default)]
+    pub story_id: Option<String>,
+}
+
+/// What a writing goal measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum GoalType {
+    /// Write `target_words` new words every day
+    Daily,
+    /// Bring a story or container to `target_words` in total by `deadline`
+    Deadline,
+}
+
+/// A writing goal
+///
+/// Daily goals apply to all writing, or to one universe when `universe_id` is
+/// set. Deadline goals apply to exactly one container or story.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct WritingGoal {
+    pub id: String,
+    pub goal_type: GoalType,
+    pub target_words: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub universe_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub story_id: Option<String>,
+    /// Local date (`YYYY-MM-DD`) the target should be reached by
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input for creating a writing goal
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWritingGoalInput {
+    pub goal_type: GoalType,
+    pub target_words: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub universe_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub story_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+}
+
+/// How far along a goal is
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    pub goal: WritingGoal,
+    /// Words added today (daily goals) or total words (deadline goals)
+    pub current_words: u32,
+    pub remaining_words: u32,
+    pub completed: bool,
+    /// Average net words per day over the recent window
+    pub average_daily_words: i32,
+    /// Words per day needed to meet the deadline (deadline goals only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words_per_day_needed: Option<u32>,
+    /// Day the target will be reached at the current pace, if it is being
+    /// approached at all (deadline goals only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projected_completion: Option<String>,
+    /// Whether the projected completion is on or before the deadline
+    pub on_track: bool,
+}
+
+/// Consecutive days of writing
+///
+/// A day counts when at least one word was added, or at least the daily
+/// goal's target when the streak is measured against one. Today doesn't
+/// break the current streak until it's over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct WritingStreak {
+    pub current: u32,
+    pub longest: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_writing_day: Option<String>,
+}
@@ -24,6 +24,13 @@ pub struct Story {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target_word_count: Option<u32>,
 
+    // Content Statistics (recomputed whenever content is saved)
+    pub character_count: u32,
+    pub character_count_no_spaces: u32,
+    pub paragraph_count: u32,
+    pub sentence_count: u32,
+    pub reading_time_seconds: u32,
+
     // Content
     pub content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -148,7 +155,7 @@ pub struct CreateStoryInput {
 }
 
 /// Input for updating an existing Story
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Default, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/")]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateStoryInput {
@@ -199,6 +206,11 @@ mod tests {
             status: StoryStatus::InProgress,
             word_count: 5000,
             target_word_count: Some(100000),
+            character_count: 0,
+            character_count_no_spaces: 0,
+            paragraph_count: 0,
+            sentence_count: 0,
+            reading_time_seconds: 0,
             content: "Once upon a time...".to_string(),
             notes: Some("Character notes".to_string()),
             outline: Some("Chapter 1: Beginning".to_string()),
@@ -268,6 +280,11 @@ mod tests {
             status: StoryStatus::Draft,
             word_count: 100,
             target_word_count: None,
+            character_count: 0,
+            character_count_no_spaces: 0,
+            paragraph_count: 0,
+            sentence_count: 0,
+            reading_time_seconds: 0,
             content: "test".to_string(),
             notes: None,
             outline: None,
@@ -417,6 +434,11 @@ mod tests {
             status: StoryStatus::Draft,
             word_count: 0,
             target_word_count: None,
+            character_count: 0,
+            character_count_no_spaces: 0,
+            paragraph_count: 0,
+            sentence_count: 0,
+            reading_time_seconds: 0,
             content: "".to_string(),
             notes: None,
             outline: None,
@@ -0,0 +1,641 @@
+//! Moving stories and containers between parents and universes
+//!
+//! The database rules for a move (leaf protection, nesting depth, universe
+//! consistency) are enforced by the repositories. This module brings the
+//! files along: a story's content lives in its own repository when it
+//! stands alone and in its container's repository otherwise, so a move
+//! between those writes the story's file into the destination repository
+//! and removes it from the source.
+//!
+//! Before the source is cleaned up, its branches, archived branches and
+//! tags are imported into the destination under `refs/history/<story id>/`
+//! (see `GitService::import_history`), together with their names from
+//! metadata.json, which keeps the commits made before the move reachable.
+//! Container repositories are keyed by container ID and never move on disk;
+//! only their metadata.json is refreshed.
+
+use crate::db::Database;
+use crate::file_management::{
+    import_mappings, story_filename, sync_story_file, write_container_metadata_file,
+    write_metadata_file, FileManagementError,
+};
+use crate::git::{GitService, GitServiceError};
+use crate::models::{Container, Story, UpdateStoryInput};
+use crate::repositories::{ContainerRepository, StoryRepository};
+use log::warn;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Error type for move operations
+#[derive(Debug)]
+pub enum MoveError {
+    /// Reading or writing the database failed, or the move breaks a
+    /// hierarchy rule
+    Database(rusqlite::Error),
+    /// Creating a repository or importing history failed
+    Git(GitServiceError),
+    /// Writing the story's file or metadata failed
+    Files(FileManagementError),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::Database(e) => write!(f, "Database error: {e}"),
+            MoveError::Git(e) => write!(f, "Git error: {e}"),
+            MoveError::Files(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+impl From<rusqlite::Error> for MoveError {
+    fn from(err: rusqlite::Error) -> Self {
+        MoveError::Database(err)
+    }
+}
+
+impl From<GitServiceError> for MoveError {
+    fn from(err: GitServiceError) -> Self {
+        MoveError::Git(err)
+    }
+}
+
+impl From<FileManagementError> for MoveError {
+    fn from(err: FileManagementError) -> Self {
+        MoveError::Files(err)
+    }
+}
+
+/// Result type alias for move operations
+pub type MoveResult<T> = Result<T, MoveError>;
+
+/// Move a story into a container, or out of one to stand alone
+///
+/// The story's file and history follow it to the destination repository,
+/// which is created if it doesn't exist yet. If that fails, the story is
+/// put back where it was. Moving to another universe drops its related
+/// elements.
+///
+/// # Arguments
+/// * `app_data_dir` - Directory holding `git-repos`
+/// * `universe_id` - Destination universe (the container's, or the story's
+///   current universe, if None)
+/// * `container_id` - Destination container, or None to stand alone
+/// * `order` - Position in the container (after the last story if None)
+pub fn move_story(
+    db: &Database,
+    app_data_dir: &Path,
+    id: &str,
+    universe_id: Option<&str>,
+    container_id: Option<&str>,
+    order: Option<u32>,
+) -> MoveResult<Story> {
+    let before = StoryRepository::find_by_id(db, id)?;
+    let universe_id = match (universe_id, container_id) {
+        (Some(universe_id), _) => universe_id.to_string(),
+        (None, Some(container_id)) => {
+            ContainerRepository::find_by_id(db, container_id)?.universe_id
+        }
+        (None, None) => before.universe_id.clone(),
+    };
+    if universe_id == before.universe_id && container_id == before.container_id.as_deref() {
+        return Ok(before);
+    }
+
+    let source_repo = StoryRepository::find_git_repo_path(db, &before)?
+        .map(PathBuf::from)
+        .filter(|path| path.exists());
+    let story = StoryRepository::move_to(db, id, &universe_id, container_id, order)?;
+
+    match move_story_files(db, app_data_dir, &before, &story, source_repo.as_deref()) {
+        Ok(story) => Ok(story),
+        Err(e) => {
+            restore_story_location(db, &before);
+            Err(e)
+        }
+    }
+}
+
+/// Move a container, with everything below it, to a new parent
+///
+/// # Arguments
+/// * `universe_id` - Destination universe (the parent's, or the container's
+///   current universe, if None)
+/// * `parent_container_id` - New parent, or None for a top-level container
+/// * `order` - Position among the new siblings (after the last one if None)
+pub fn move_container(
+    db: &Database,
+    id: &str,
+    universe_id: Option<&str>,
+    parent_container_id: Option<&str>,
+    order: Option<i32>,
+) -> MoveResult<Container> {
+    let before = ContainerRepository::find_by_id(db, id)?;
+    let universe_id = match (universe_id, parent_container_id) {
+        (Some(universe_id), _) => universe_id.to_string(),
+        (None, Some(parent_id)) => ContainerRepository::find_by_id(db, parent_id)?.universe_id,
+        (None, None) => before.universe_id.clone(),
+    };
+    if universe_id == before.universe_id
+        && parent_container_id == before.parent_container_id.as_deref()
+    {
+        return Ok(before);
+    }
+
+    let container = ContainerRepository::move_to(db, id, parent_container_id, &universe_id, order)?;
+
+    // The move is done; stale metadata only matters for recovery
+    for leaf in ContainerRepository::get_subtree(db, id, None)? {
+        let Some(repo_path) = leaf
+            .git_repo_path
+            .as_deref()
+            .filter(|path| !path.is_empty())
+        else {
+            continue;
+        };
+        let repo_path = Path::new(repo_path);
+        if repo_path.exists() {
+            if let Err(e) = write_container_metadata_file(repo_path, &leaf) {
+                warn!(
+                    "Failed to update metadata of moved container {}: {e}",
+                    leaf.id
+                );
+            }
+        }
+    }
+
+    Ok(container)
+}
+
+/// Write a moved story's file into its new repository and clean up the old one
+fn move_story_files(
+    db: &Database,
+    app_data_dir: &Path,
+    before: &Story,
+    story: &Story,
+    source_repo: Option<&Path>,
+) -> MoveResult<Story> {
+    if before.should_have_git_repo() && story.should_have_git_repo() {
+        // Same repository, only the universe changed
+        if let Some(repo_path) = source_repo {
+            write_metadata_file(repo_path, story)?;
+        }
+        return StoryRepository::find_by_id(db, &story.id).map_err(MoveError::from);
+    }
+
+    let target_repo = match story.container_id {
+        Some(ref container_id) => container_repo(db, app_data_dir, container_id)?,
+        None => {
+            let repo_path = GitService::init_repo(app_data_dir, &story.id)?;
+            if let Err(e) = init_story_repo(db, &repo_path, story) {
+                let _ = fs::remove_dir_all(&repo_path);
+                return Err(e);
+            }
+            repo_path
+        }
+    };
+
+    let filename = story_filename(story);
+    let imported = match source_repo {
+        Some(source_repo) => GitService::import_history(&target_repo, source_repo, &story.id)
+            .map_err(MoveError::from)
+            .and_then(|_| {
+                import_mappings(&target_repo, source_repo, &story.id).map_err(MoveError::from)
+            }),
+        None => Ok(()),
+    };
+    let synced = imported.and_then(|_| {
+        let message = format!("Move in {filename}: {}", story.title);
+        sync_story_file(&target_repo, None, story, Some(&message)).map_err(MoveError::from)
+    });
+    if let Err(e) = synced {
+        if story.should_have_git_repo() {
+            let _ = fs::remove_dir_all(&target_repo);
+        }
+        return Err(e);
+    }
+
+    if let Some(source_repo) = source_repo {
+        if let Err(e) = remove_from_source(source_repo, before) {
+            warn!(
+                "Moved story {} but failed to clean up {}: {e}",
+                story.id,
+                source_repo.display()
+            );
+        }
+    }
+
+    StoryRepository::find_by_id(db, &story.id).map_err(MoveError::from)
+}
+
+/// Get a container's repository, creating it if it doesn't exist yet
+fn container_repo(db: &Database, app_data_dir: &Path, container_id: &str) -> MoveResult<PathBuf> {
+    let container = ContainerRepository::find_by_id(db, container_id)?;
+    if let Some(repo_path) = container
+        .git_repo_path
+        .as_deref()
+        .filter(|path| !path.is_empty())
+    {
+        let repo_path = PathBuf::from(repo_path);
+        if repo_path.exists() {
+            return Ok(repo_path);
+        }
+    }
+
+    let repo_path = GitService::init_repo(app_data_dir, &container.id)?;
+    let initialized = write_container_metadata_file(&repo_path, &container)
+        .map_err(MoveError::from)
+        .and_then(|_| GitService::get_current_branch(&repo_path).map_err(MoveError::from))
+        .and_then(|branch| {
+            ContainerRepository::set_git_repo_path(
+                db,
+                &container.id,
+                &repo_path.to_string_lossy(),
+            )?;
+            ContainerRepository::set_current_branch(db, &container.id, &branch)?;
+            Ok(())
+        });
+    if let Err(e) = initialized {
+        let _ = fs::remove_dir_all(&repo_path);
+        let _ = ContainerRepository::clear_git_repo_path(db, &container.id);
+        return Err(e);
+    }
+
+    Ok(repo_path)
+}
+
+/// Write a new standalone repository's metadata and point the story at it
+fn init_story_repo(db: &Database, repo_path: &Path, story: &Story) -> MoveResult<()> {
+    write_metadata_file(repo_path, story)?;
+    let branch = GitService::get_current_branch(repo_path)?;
+    StoryRepository::set_git_repo_path(db, &story.id, &repo_path.to_string_lossy())?;
+    StoryRepository::set_current_branch(db, &story.id, &branch)?;
+    Ok(())
+}
+
+/// Remove a moved story from the repository it came from
+///
+/// A standalone story's repository goes away entirely; in a container's
+/// repository, the removal of the story's file is committed.
+fn remove_from_source(source_repo: &Path, before: &Story) -> MoveResult<()> {
+    if before.should_have_git_repo() {
+        fs::remove_dir_all(source_repo).map_err(FileManagementError::from)?;
+        return Ok(());
+    }
+
+    let filename = story_filename(before);
+    let file_path = source_repo.join(&filename);
+    if file_path.exists() {
+        fs::remove_file(&file_path).map_err(FileManagementError::from)?;
+        GitService::commit_all(
+            source_repo,
+            &format!("Move out {filename}: {}", before.title),
+        )?;
+    }
+    Ok(())
+}
+
+/// Put a story back where it was after its files failed to move
+fn restore_story_location(db: &Database, before: &Story) {
+    let restored = StoryRepository::move_to(
+        db,
+        &before.id,
+        &before.universe_id,
+        before.container_id.as_deref(),
+        before.order,
+    )
+    .and_then(|_| StoryRepository::set_git_repo_path(db, &before.id, &before.git_repo_path))
+    .and_then(|_| StoryRepository::set_current_branch(db, &before.id, &before.current_branch))
+    .and_then(|_| match before.related_element_ids {
+        Some(ref related_element_ids) => {
+            let input = UpdateStoryInput {
+                related_element_ids: Some(related_element_ids.clone()),
+                ..Default::default()
+            };
+            StoryRepository::update(db, &before.id, input).map(|_| ())
+        }
+        None => Ok(()),
+    });
+    if let Err(e) = restored {
+        warn!(
+            "Failed to put story {} back after a failed move: {e}",
+            before.id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_management::{
+        read_metadata_file, save_milestone_mapping, save_variation_mapping,
+    };
+    use crate::models::{CreateStoryInput, StoryType};
+    use tempfile::TempDir;
+
+    struct Fixture {
+        db: Database,
+        temp_dir: TempDir,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        for id in ["universe-1", "universe-2"] {
+            db.execute(
+                "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+                 VALUES (?1, 'Test Universe', 'A test universe', datetime('now'), datetime('now'), 'active')",
+                &[&id],
+            )
+            .unwrap();
+        }
+
+        Fixture { db, temp_dir }
+    }
+
+    fn create_container(fixture: &Fixture, title: &str, parent: Option<&str>) -> Container {
+        ContainerRepository::create(
+            &fixture.db,
+            "universe-1".to_string(),
+            parent.map(str::to_string),
+            "book".to_string(),
+            title.to_string(),
+            None,
+            0,
+        )
+        .unwrap()
+    }
+
+    /// Create a story with its file committed where the app would put it
+    fn create_story(fixture: &Fixture, title: &str, container_id: Option<&str>) -> Story {
+        let story = StoryRepository::create(
+            &fixture.db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: title.to_string(),
+                description: Some(String::new()),
+                story_type: Some(StoryType::Chapter),
+                content: Some(format!("{title} begins.\n")),
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: container_id.map(str::to_string),
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap();
+
+        let repo_path = match container_id {
+            Some(container_id) => {
+                container_repo(&fixture.db, fixture.temp_dir.path(), container_id).unwrap()
+            }
+            None => {
+                let repo_path = GitService::init_repo(fixture.temp_dir.path(), &story.id).unwrap();
+                init_story_repo(&fixture.db, &repo_path, &story).unwrap();
+                repo_path
+            }
+        };
+        sync_story_file(&repo_path, None, &story, None).unwrap();
+        StoryRepository::find_by_id(&fixture.db, &story.id).unwrap()
+    }
+
+    fn repo_of(fixture: &Fixture, story: &Story) -> PathBuf {
+        PathBuf::from(
+            StoryRepository::find_git_repo_path(&fixture.db, story)
+                .unwrap()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_standalone_story_moves_into_container_with_history() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let story = create_story(&fixture, "Prologue", None);
+        let source_repo = repo_of(&fixture, &story);
+
+        let moved = move_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            None,
+            Some(&book.id),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(moved.container_id.as_deref(), Some(book.id.as_str()));
+        assert_eq!(moved.git_repo_path, "");
+        assert!(!source_repo.exists());
+        let target_repo = repo_of(&fixture, &moved);
+        assert_eq!(
+            fs::read_to_string(target_repo.join("001-prologue.md")).unwrap(),
+            "Prologue begins.\n"
+        );
+        let history =
+            GitService::get_history(&target_repo, &format!("{}/original", story.id)).unwrap();
+        assert!(history
+            .iter()
+            .any(|commit| commit.message.contains("content.md")));
+    }
+
+    #[test]
+    fn test_moved_story_keeps_archived_variations_and_milestones() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let story = create_story(&fixture, "Prologue", None);
+        let source_repo = repo_of(&fixture, &story);
+        GitService::create_branch(&source_repo, "original", "dark-ending").unwrap();
+        GitService::checkout_branch(&source_repo, "original").unwrap();
+        save_variation_mapping(&source_repo, "dark-ending", "Dark ending").unwrap();
+        GitService::archive_branch(&source_repo, "dark-ending").unwrap();
+        let tagged = GitService::create_tag(&source_repo, "first-draft", None, "").unwrap();
+        save_milestone_mapping(&source_repo, "first-draft", "First draft", "Sent out").unwrap();
+
+        let moved = move_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            None,
+            Some(&book.id),
+            None,
+        )
+        .unwrap();
+
+        let target_repo = repo_of(&fixture, &moved);
+        let imported = GitService::list_imported_history(&target_repo).unwrap();
+        assert!(imported.contains(&format!("{}/archive/dark-ending", story.id)));
+        assert!(imported.contains(&format!("{}/tags/first-draft", story.id)));
+        let history =
+            GitService::get_history(&target_repo, &format!("{}/tags/first-draft", story.id))
+                .unwrap();
+        assert_eq!(history[0].hash, tagged);
+        // Tags don't become milestones of the container
+        assert!(GitService::list_tags(&target_repo).unwrap().is_empty());
+
+        let metadata = read_metadata_file(&target_repo).unwrap();
+        assert_eq!(
+            metadata
+                .variations
+                .get(&format!("{}/archive/dark-ending", story.id)),
+            Some(&"Dark ending".to_string())
+        );
+        let milestone = &metadata.milestones[&format!("{}/tags/first-draft", story.id)];
+        assert_eq!(milestone.display_name, "First draft");
+        assert_eq!(milestone.note, "Sent out");
+    }
+
+    #[test]
+    fn test_container_story_moves_out_to_its_own_repo() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let story = create_story(&fixture, "Chapter", Some(&book.id));
+        let book_repo = repo_of(&fixture, &story);
+
+        let moved = move_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(moved.container_id.is_none());
+        let own_repo = PathBuf::from(&moved.git_repo_path);
+        assert_ne!(own_repo, book_repo);
+        assert_eq!(
+            fs::read_to_string(own_repo.join("content.md")).unwrap(),
+            "Chapter begins.\n"
+        );
+        assert!(!book_repo.join("001-chapter.md").exists());
+        assert!(GitService::list_imported_history(&own_repo)
+            .unwrap()
+            .contains(&format!("{}/original", story.id)));
+    }
+
+    #[test]
+    fn test_story_moves_between_containers_after_existing_stories() {
+        let fixture = setup();
+        let first = create_container(&fixture, "First", None);
+        let second = create_container(&fixture, "Second", None);
+        let story = create_story(&fixture, "Wanderer", Some(&first.id));
+        create_story(&fixture, "Resident", Some(&second.id));
+
+        let moved = move_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            None,
+            Some(&second.id),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(moved.order, Some(1));
+        let second_repo = repo_of(&fixture, &moved);
+        assert!(second_repo.join("002-wanderer.md").exists());
+        assert!(second_repo.join("001-resident.md").exists());
+        assert!(!repo_of(&fixture, &story).join("001-wanderer.md").exists());
+    }
+
+    #[test]
+    fn test_story_moves_to_another_universe_without_related_elements() {
+        let fixture = setup();
+        let story = create_story(&fixture, "Exile", None);
+        let input = UpdateStoryInput {
+            related_element_ids: Some(vec!["element-1".to_string()]),
+            ..Default::default()
+        };
+        StoryRepository::update(&fixture.db, &story.id, input).unwrap();
+
+        let moved = move_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            Some("universe-2"),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(moved.universe_id, "universe-2");
+        assert!(moved.related_element_ids.is_none());
+        assert_eq!(moved.git_repo_path, story.git_repo_path);
+        let metadata =
+            fs::read_to_string(Path::new(&moved.git_repo_path).join("metadata.json")).unwrap();
+        assert!(metadata.contains("universe-2"));
+    }
+
+    #[test]
+    fn test_story_cannot_move_into_container_with_child_containers() {
+        let fixture = setup();
+        let series = create_container(&fixture, "Series", None);
+        create_container(&fixture, "Book", Some(&series.id));
+        let story = create_story(&fixture, "Stray", None);
+
+        let result = move_story(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &story.id,
+            None,
+            Some(&series.id),
+            None,
+        );
+
+        assert!(result.is_err());
+        let unchanged = StoryRepository::find_by_id(&fixture.db, &story.id).unwrap();
+        assert!(unchanged.container_id.is_none());
+        assert!(Path::new(&unchanged.git_repo_path).exists());
+    }
+
+    #[test]
+    fn test_container_moves_with_subtree_into_another_universe() {
+        let fixture = setup();
+        let series = create_container(&fixture, "Series", None);
+        let book = create_container(&fixture, "Book", Some(&series.id));
+        let story = create_story(&fixture, "Chapter", Some(&book.id));
+
+        move_container(&fixture.db, &series.id, Some("universe-2"), None, None).unwrap();
+
+        let book = ContainerRepository::find_by_id(&fixture.db, &book.id).unwrap();
+        assert_eq!(book.universe_id, "universe-2");
+        let story = StoryRepository::find_by_id(&fixture.db, &story.id).unwrap();
+        assert_eq!(story.universe_id, "universe-2");
+        let metadata = fs::read_to_string(
+            Path::new(book.git_repo_path.as_deref().unwrap()).join("metadata.json"),
+        )
+        .unwrap();
+        assert!(metadata.contains("universe-2"));
+    }
+
+    #[test]
+    fn test_container_move_keeps_hierarchy_rules() {
+        let fixture = setup();
+        let series = create_container(&fixture, "Series", None);
+        let book = create_container(&fixture, "Book", Some(&series.id));
+        let leaf = create_container(&fixture, "Leaf", None);
+        create_story(&fixture, "Chapter", Some(&leaf.id));
+
+        // Into its own descendant
+        assert!(move_container(&fixture.db, &series.id, None, Some(&book.id), None).is_err());
+        // Into a container holding stories
+        assert!(move_container(&fixture.db, &book.id, None, Some(&leaf.id), None).is_err());
+
+        let moved = move_container(&fixture.db, &leaf.id, None, Some(&book.id), None).unwrap();
+        assert_eq!(moved.parent_container_id.as_deref(), Some(book.id.as_str()));
+    }
+}
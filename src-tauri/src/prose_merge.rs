@@ -0,0 +1,411 @@
+//! Three-way merging for prose
+//!
+//! Git's line-based merge treats a whole paragraph as a single line, so two
+//! writers editing different sentences of the same paragraph always conflict.
+//! This module merges story files in two passes: first paragraph by paragraph,
+//! then sentence by sentence inside any paragraphs that both sides changed.
+//! Only edits that touch the same sentences are reported as conflicts.
+
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+use ts_rs::TS;
+
+/// A region of a three-way merge result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MergeRegion {
+    /// Text both sides agree on, or that only one side changed
+    Resolved { text: String },
+    /// Text both sides changed in different ways
+    Conflict {
+        base: String,
+        ours: String,
+        theirs: String,
+    },
+}
+
+/// Result of merging a prose file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct ProseMergeResult {
+    /// True when every region was resolved automatically
+    pub clean: bool,
+    /// Ordered regions that together make up the merged file
+    pub regions: Vec<MergeRegion>,
+}
+
+impl ProseMergeResult {
+    /// The merged text, if the merge had no conflicts
+    pub fn merged_text(&self) -> Option<String> {
+        if !self.clean {
+            return None;
+        }
+
+        Some(
+            self.regions
+                .iter()
+                .map(|region| match region {
+                    MergeRegion::Resolved { text } => text.as_str(),
+                    MergeRegion::Conflict { ours, .. } => ours.as_str(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Check whether a file should be merged with the prose strategy
+pub fn is_prose_file(path: &str) -> bool {
+    path.to_lowercase().ends_with(".md")
+}
+
+/// Merge two versions of a prose document against their common ancestor
+///
+/// # Arguments
+/// * `base` - The common ancestor text
+/// * `ours` - The text on the branch being merged into
+/// * `theirs` - The text on the branch being merged from
+///
+/// # Returns
+/// ProseMergeResult with resolved and conflicting regions in document order
+pub fn merge_prose(base: &str, ours: &str, theirs: &str) -> ProseMergeResult {
+    let mut regions = Vec::new();
+
+    // Pass 1: merge whole paragraphs
+    for chunk in merge_tokens(&paragraphs(base), &paragraphs(ours), &paragraphs(theirs)) {
+        match chunk {
+            MergeRegion::Resolved { text } => push_resolved(&mut regions, text),
+            MergeRegion::Conflict { base, ours, theirs } => {
+                // Pass 2: retry the overlapping paragraphs sentence by sentence
+                let sentence_chunks =
+                    merge_tokens(&sentences(&base), &sentences(&ours), &sentences(&theirs));
+                for sentence_chunk in sentence_chunks {
+                    match sentence_chunk {
+                        MergeRegion::Resolved { text } => push_resolved(&mut regions, text),
+                        conflict => regions.push(conflict),
+                    }
+                }
+            }
+        }
+    }
+
+    let clean = regions
+        .iter()
+        .all(|region| matches!(region, MergeRegion::Resolved { .. }));
+
+    ProseMergeResult { clean, regions }
+}
+
+/// Split text into paragraphs, keeping line endings so the pieces rejoin exactly
+fn paragraphs(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// Split text into sentences, keeping trailing whitespace with each sentence
+///
+/// A sentence ends after `.`, `!` or `?` (plus any closing quotes or brackets)
+/// when followed by whitespace, and always at a line break.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut after_terminator = false;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((idx, ch)) = chars.next() {
+        if ch == '\n' {
+            let end = idx + ch.len_utf8();
+            result.push(&text[start..end]);
+            start = end;
+            after_terminator = false;
+            continue;
+        }
+
+        if matches!(ch, '.' | '!' | '?' | '…') {
+            after_terminator = true;
+            continue;
+        }
+
+        if after_terminator && matches!(ch, '"' | '\'' | ')' | ']' | '”' | '’' | '»') {
+            continue;
+        }
+
+        if after_terminator && ch.is_whitespace() {
+            // Keep the run of spaces with the sentence, but leave line breaks
+            // to close the sentence on their own
+            let mut end = idx + ch.len_utf8();
+            while let Some(&(next_idx, next)) = chars.peek() {
+                if next == '\n' || !next.is_whitespace() {
+                    break;
+                }
+                end = next_idx + next.len_utf8();
+                chars.next();
+            }
+            if chars.peek().map(|&(_, next)| next != '\n').unwrap_or(false) {
+                result.push(&text[start..end]);
+                start = end;
+            }
+        }
+
+        after_terminator = false;
+    }
+
+    if start < text.len() {
+        result.push(&text[start..]);
+    }
+
+    result
+}
+
+/// Append resolved text, joining it to the previous region when possible
+fn push_resolved(regions: &mut Vec<MergeRegion>, text: String) {
+    if text.is_empty() {
+        return;
+    }
+
+    if let Some(MergeRegion::Resolved { text: last }) = regions.last_mut() {
+        last.push_str(&text);
+        return;
+    }
+
+    regions.push(MergeRegion::Resolved { text });
+}
+
+/// A change one side made to the base
+///
+/// Base tokens `start..end` were replaced by that side's tokens
+/// `new_start..new_end`.
+struct Edit {
+    start: usize,
+    end: usize,
+    new_start: usize,
+    new_end: usize,
+    ours: bool,
+}
+
+/// Three-way merge of token sequences
+///
+/// Collects the edits each side made to the base and groups edits whose base
+/// ranges overlap. A group touched by only one side takes that side's text; a
+/// group touched by both is resolved only if both produced the same text.
+/// Edits that merely touch at a boundary do not overlap, so changes to
+/// neighbouring sentences merge cleanly.
+fn merge_tokens(base: &[&str], ours: &[&str], theirs: &[&str]) -> Vec<MergeRegion> {
+    let mut edits = collect_edits(base, ours, true);
+    edits.extend(collect_edits(base, theirs, false));
+    // Insertions sort before replacements starting at the same token
+    edits.sort_by_key(|edit| (edit.start, edit.end));
+
+    let mut regions = Vec::new();
+    let mut pos = 0;
+    let mut i = 0;
+
+    while i < edits.len() {
+        // Grow a group of overlapping edits; two insertions at the same
+        // point also overlap
+        let (group_start, mut group_end) = (edits[i].start, edits[i].end);
+        let mut j = i + 1;
+        while j < edits.len() {
+            let edit = &edits[j];
+            let both_insert_here =
+                group_start == group_end && edit.start == group_start && edit.start == edit.end;
+            if edit.start >= group_end && !both_insert_here {
+                break;
+            }
+            group_end = group_end.max(edit.end);
+            j += 1;
+        }
+        let group = &edits[i..j];
+
+        // Unchanged text before the group
+        push_resolved(&mut regions, base[pos..group_start].concat());
+
+        let ours_text = apply_edits(base, ours, group_start, group_end, group, true);
+        let theirs_text = apply_edits(base, theirs, group_start, group_end, group, false);
+        let touched_by_ours = group.iter().any(|edit| edit.ours);
+        let touched_by_theirs = group.iter().any(|edit| !edit.ours);
+
+        if !touched_by_theirs || ours_text == theirs_text {
+            push_resolved(&mut regions, ours_text);
+        } else if !touched_by_ours {
+            push_resolved(&mut regions, theirs_text);
+        } else {
+            regions.push(MergeRegion::Conflict {
+                base: base[group_start..group_end].concat(),
+                ours: ours_text,
+                theirs: theirs_text,
+            });
+        }
+
+        pos = group_end;
+        i = j;
+    }
+
+    push_resolved(&mut regions, base[pos..].concat());
+
+    regions
+}
+
+/// Collect the edits that turn `base` into `other`
+fn collect_edits(base: &[&str], other: &[&str], ours: bool) -> Vec<Edit> {
+    let mut edits: Vec<Edit> = Vec::new();
+
+    for op in capture_diff_slices(Algorithm::Myers, base, other) {
+        if matches!(op, DiffOp::Equal { .. }) {
+            continue;
+        }
+
+        let old_range = op.old_range();
+        let new_range = op.new_range();
+
+        // Back-to-back deletes and inserts form a single replacement
+        if let Some(last) = edits.last_mut() {
+            if last.end == old_range.start && last.new_end == new_range.start {
+                last.end = old_range.end;
+                last.new_end = new_range.end;
+                continue;
+            }
+        }
+
+        edits.push(Edit {
+            start: old_range.start,
+            end: old_range.end,
+            new_start: new_range.start,
+            new_end: new_range.end,
+            ours,
+        });
+    }
+
+    edits
+}
+
+/// Rebuild one side's text for the base range `start..end` from its edits
+fn apply_edits(
+    base: &[&str],
+    side: &[&str],
+    start: usize,
+    end: usize,
+    group: &[Edit],
+    ours: bool,
+) -> String {
+    let mut text = String::new();
+    let mut pos = start;
+
+    for edit in group.iter().filter(|edit| edit.ours == ours) {
+        text.push_str(&base[pos..edit.start].concat());
+        text.push_str(&side[edit.new_start..edit.new_end].concat());
+        pos = edit.end;
+    }
+    text.push_str(&base[pos..end].concat());
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "The night was quiet. The gate stood open.\n\
+                        Mara waited by the fire. She did not sleep.\n";
+
+    #[test]
+    fn test_sentences_split_on_terminators_and_newlines() {
+        let parts = sentences("He left. \"Why?\" she asked.\nNext line");
+
+        assert_eq!(
+            parts,
+            vec!["He left. ", "\"Why?\" ", "she asked.\n", "Next line"]
+        );
+    }
+
+    #[test]
+    fn test_sentences_rejoin_to_original() {
+        let text = "One.  Two!\n\nThree… four? Five";
+        assert_eq!(sentences(text).concat(), text);
+    }
+
+    #[test]
+    fn test_merge_prose_takes_one_sided_changes() {
+        let theirs = BASE.replace("quiet", "cold");
+        let result = merge_prose(BASE, BASE, &theirs);
+
+        assert!(result.clean);
+        assert_eq!(result.merged_text().unwrap(), theirs);
+    }
+
+    #[test]
+    fn test_merge_prose_merges_edits_to_different_sentences_of_a_paragraph() {
+        let ours = BASE.replace("The night was quiet.", "The night was very quiet.");
+        let theirs = BASE.replace("The gate stood open.", "The gate stood shut.");
+        let result = merge_prose(BASE, &ours, &theirs);
+
+        assert!(result.clean);
+        assert_eq!(
+            result.merged_text().unwrap(),
+            "The night was very quiet. The gate stood shut.\n\
+             Mara waited by the fire. She did not sleep.\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_prose_merges_edits_to_different_paragraphs() {
+        let ours = BASE.replace("quiet", "still");
+        let theirs = BASE.replace("did not sleep", "slept");
+        let result = merge_prose(BASE, &ours, &theirs);
+
+        assert!(result.clean);
+        assert_eq!(
+            result.merged_text().unwrap(),
+            "The night was still. The gate stood open.\n\
+             Mara waited by the fire. She slept.\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_prose_reports_conflicting_sentence() {
+        let ours = BASE.replace("stood open", "was locked");
+        let theirs = BASE.replace("stood open", "had vanished");
+        let result = merge_prose(BASE, &ours, &theirs);
+
+        assert!(!result.clean);
+        assert!(result.merged_text().is_none());
+
+        let conflicts: Vec<_> = result
+            .regions
+            .iter()
+            .filter(|r| matches!(r, MergeRegion::Conflict { .. }))
+            .collect();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0],
+            &MergeRegion::Conflict {
+                base: "The gate stood open.\n".to_string(),
+                ours: "The gate was locked.\n".to_string(),
+                theirs: "The gate had vanished.\n".to_string(),
+            }
+        );
+
+        // The untouched sentence before the conflict is kept as resolved text
+        assert_eq!(
+            result.regions[0],
+            MergeRegion::Resolved {
+                text: "The night was quiet. ".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_prose_identical_changes_do_not_conflict() {
+        let changed = BASE.replace("fire", "hearth");
+        let result = merge_prose(BASE, &changed, &changed);
+
+        assert!(result.clean);
+        assert_eq!(result.merged_text().unwrap(), changed);
+    }
+
+    #[test]
+    fn test_is_prose_file() {
+        assert!(is_prose_file("001-chapter-one.md"));
+        assert!(is_prose_file("notes/Draft.MD"));
+        assert!(!is_prose_file(".bright/metadata.json"));
+    }
+}
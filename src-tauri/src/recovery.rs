@@ -0,0 +1,906 @@
+//! Disaster recovery: rebuild the database from git repositories
+//!
+//! Every standalone story and leaf container keeps its content in a git
+//! repository under `git-repos/<id>`, together with a metadata.json that
+//! describes the entity. If bright.db is lost or damaged, `rebuild_from_repos`
+//! scans that directory and recreates the universes, containers and stories
+//! it finds. A container's stories are restored from the records in its
+//! metadata.json; a story file without one gets a new ID and a title guessed
+//! from its filename, and a warning in the report. Repositories that can't be
+//! read come back as orphaned; entities that already exist in the database
+//! come back as conflicts and are left untouched.
+
+use crate::db::Database;
+use crate::file_management::{
+    read_metadata_file, StoryMetadata, METADATA_KIND_CONTAINER, METADATA_KIND_STORY,
+    STANDALONE_STORY_FILE,
+};
+use crate::git::GitService;
+use crate::models::{
+    ChangeOrigin, Container, Story, StoryStatus, StoryType, Universe, UniverseStatus, VariationType,
+};
+use crate::repositories::{
+    ContainerRepository, JournalRepository, StoryRepository, UniverseRepository,
+};
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Name given to the universe that collects repos without a universe_id
+const FALLBACK_UNIVERSE_NAME: &str = "Recovered Stories";
+
+/// Error type for recovery operations
+#[derive(Debug)]
+pub enum RecoveryError {
+    /// Reading or writing the database failed
+    Database(rusqlite::Error),
+    /// The git-repos directory couldn't be listed
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RecoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecoveryError::Database(e) => write!(f, "Database error: {e}"),
+            RecoveryError::Io(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RecoveryError {}
+
+impl From<rusqlite::Error> for RecoveryError {
+    fn from(err: rusqlite::Error) -> Self {
+        RecoveryError::Database(err)
+    }
+}
+
+impl From<std::io::Error> for RecoveryError {
+    fn from(err: std::io::Error) -> Self {
+        RecoveryError::Io(err)
+    }
+}
+
+/// Result type alias for recovery operations
+pub type RecoveryResult<T> = Result<T, RecoveryError>;
+
+/// A universe, container or story recreated by the import
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredEntity {
+    pub id: String,
+    pub title: String,
+    pub universe_id: String,
+    /// Repository the entity was read from, if it has one of its own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_repo_path: Option<String>,
+}
+
+/// A repository the import couldn't (or wouldn't) restore
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryIssue {
+    /// Path of the repository directory
+    pub path: String,
+    /// Entity ID, when it could be determined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub reason: String,
+}
+
+/// Outcome of rebuilding the database from git repositories
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    pub universes: Vec<RecoveredEntity>,
+    pub containers: Vec<RecoveredEntity>,
+    pub stories: Vec<RecoveredEntity>,
+    /// Repos whose entity already exists in the database, or whose metadata
+    /// disagrees with their location
+    pub conflicts: Vec<RecoveryIssue>,
+    /// Directories that aren't valid repos or have no readable metadata.json
+    pub orphaned_repos: Vec<RecoveryIssue>,
+    /// Entities that were restored with missing information filled in
+    pub warnings: Vec<RecoveryIssue>,
+}
+
+/// Recreate database rows for every repository under `git_repos_dir`
+///
+/// Each `git-repos/<id>` directory is read with `read_metadata_file`.
+/// Standalone story repos become stories with their content.md as content;
+/// leaf container repos become containers, and each numbered markdown file in
+/// them becomes a chapter. Universes and parent containers referenced by the
+/// metadata but absent from the database are recreated as placeholders, and
+/// repos whose metadata doesn't name a universe are collected into a
+/// "Recovered Stories" universe. Every restored row gets the repo's
+/// `git_repo_path` and its checked-out branch as `current_branch`.
+///
+/// # Arguments
+/// * `db` - The database to restore into (usually freshly migrated)
+/// * `git_repos_dir` - The app data `git-repos` directory
+///
+/// # Returns
+/// A report of what was restored, what conflicted and what was orphaned
+///
+/// # Errors
+/// Returns an error if the directory can't be listed or a database write fails.
+/// Problems with individual repositories are reported instead.
+pub fn rebuild_from_repos(db: &Database, git_repos_dir: &Path) -> RecoveryResult<RecoveryReport> {
+    let mut report = RecoveryReport::default();
+    if !git_repos_dir.exists() {
+        return Ok(report);
+    }
+
+    let mut repo_dirs = fs::read_dir(git_repos_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    repo_dirs.sort();
+
+    let mut importer = Importer::new(db, &mut report);
+    for repo_path in repo_dirs {
+        JournalRepository::with_origin(ChangeOrigin::Import, || importer.import_repo(&repo_path))?;
+    }
+
+    Ok(report)
+}
+
+/// Recreate database rows for a single repository
+///
+/// Same rules as `rebuild_from_repos`, applied to one `git-repos/<id>`
+/// directory. Used to adopt a repository that has no database row.
+///
+/// # Arguments
+/// * `db` - The database to restore into
+/// * `repo_path` - Path to the repository directory
+///
+/// # Returns
+/// A report of what was restored; a repo that couldn't be imported shows up
+/// under `conflicts` or `orphaned_repos`
+pub fn import_repo(db: &Database, repo_path: &Path) -> RecoveryResult<RecoveryReport> {
+    let mut report = RecoveryReport::default();
+    JournalRepository::with_origin(ChangeOrigin::Import, || {
+        Importer::new(db, &mut report).import_repo(repo_path)
+    })?;
+    Ok(report)
+}
+
+/// State shared while importing the repos of one directory
+struct Importer<'a> {
+    db: &'a Database,
+    report: &'a mut RecoveryReport,
+    known_universes: HashSet<String>,
+    fallback_universe_id: Option<String>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(db: &'a Database, report: &'a mut RecoveryReport) -> Self {
+        Importer {
+            db,
+            report,
+            known_universes: HashSet::new(),
+            fallback_universe_id: None,
+        }
+    }
+
+    fn import_repo(&mut self, repo_path: &Path) -> RecoveryResult<()> {
+        let path_str = repo_path.to_string_lossy().to_string();
+        let dir_id = repo_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if let Err(e) = GitService::validate_repo_integrity(repo_path) {
+            self.report.orphaned_repos.push(RecoveryIssue {
+                path: path_str,
+                id: Some(dir_id),
+                reason: format!("Not a usable git repository: {e}"),
+            });
+            return Ok(());
+        }
+
+        let metadata = match read_metadata_file(repo_path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                self.report.orphaned_repos.push(RecoveryIssue {
+                    path: path_str,
+                    id: Some(dir_id),
+                    reason: format!("metadata.json is missing or unreadable: {e}"),
+                });
+                return Ok(());
+            }
+        };
+
+        if !metadata.id.is_empty() && metadata.id != dir_id {
+            self.report.conflicts.push(RecoveryIssue {
+                path: path_str,
+                id: Some(dir_id),
+                reason: format!(
+                    "metadata.json belongs to '{}', not to this directory",
+                    metadata.id
+                ),
+            });
+            return Ok(());
+        }
+
+        if self.entity_exists(&dir_id)? {
+            self.report.conflicts.push(RecoveryIssue {
+                path: path_str,
+                id: Some(dir_id),
+                reason: "Already exists in the database".to_string(),
+            });
+            return Ok(());
+        }
+
+        let current_branch = match GitService::get_current_branch(repo_path) {
+            Ok(branch) => branch,
+            Err(e) => {
+                self.report.orphaned_repos.push(RecoveryIssue {
+                    path: path_str,
+                    id: Some(dir_id),
+                    reason: format!("Could not determine the current branch: {e}"),
+                });
+                return Ok(());
+            }
+        };
+
+        let universe_id = self.resolve_universe(&metadata)?;
+        if is_container_repo(repo_path, &metadata) {
+            self.import_container(repo_path, &dir_id, &metadata, universe_id, current_branch)
+        } else {
+            self.import_story(repo_path, &dir_id, &metadata, universe_id, current_branch)
+        }
+    }
+
+    fn import_story(
+        &mut self,
+        repo_path: &Path,
+        id: &str,
+        metadata: &StoryMetadata,
+        universe_id: String,
+        current_branch: String,
+    ) -> RecoveryResult<()> {
+        let path_str = repo_path.to_string_lossy().to_string();
+        let content = fs::read_to_string(repo_path.join(STANDALONE_STORY_FILE)).unwrap_or_default();
+        let created_at = non_empty_or(&metadata.created_at, Utc::now().to_rfc3339());
+        let updated_at = non_empty_or(&metadata.updated_at, created_at.clone());
+
+        let title = if metadata.title.is_empty() {
+            self.warn(
+                &path_str,
+                id,
+                "No title in metadata.json; a placeholder was used",
+            );
+            format!("Recovered story {}", short_id(id))
+        } else {
+            metadata.title.clone()
+        };
+
+        let story = Story {
+            id: id.to_string(),
+            universe_id,
+            title,
+            description: metadata.description.clone(),
+            created_at,
+            updated_at: updated_at.clone(),
+            story_type: parse_debug_name(&metadata.story_type, STORY_TYPES)
+                .unwrap_or(StoryType::ShortStory),
+            status: parse_debug_name(&metadata.status, STORY_STATUSES)
+                .unwrap_or(StoryStatus::Draft),
+            // Counts are computed from the content on insert
+            word_count: 0,
+            target_word_count: metadata.target_word_count,
+            character_count: 0,
+            character_count_no_spaces: 0,
+            paragraph_count: 0,
+            sentence_count: 0,
+            reading_time_seconds: 0,
+            content,
+            notes: None,
+            outline: None,
+            order: metadata.order.map(|o| o.max(0) as u32),
+            tags: None,
+            color: None,
+            favorite: None,
+            related_element_ids: None,
+            container_id: None,
+            series_name: None,
+            last_edited_at: updated_at,
+            version: 1,
+            variation_group_id: Uuid::new_v4().to_string(),
+            variation_type: VariationType::Original,
+            parent_variation_id: None,
+            git_repo_path: path_str.clone(),
+            current_branch,
+            staged_changes: false,
+        };
+
+        let story = StoryRepository::insert(self.db, &story)?;
+        self.report.stories.push(RecoveredEntity {
+            id: story.id,
+            title: story.title,
+            universe_id: story.universe_id,
+            git_repo_path: Some(path_str),
+        });
+        Ok(())
+    }
+
+    fn import_container(
+        &mut self,
+        repo_path: &Path,
+        id: &str,
+        metadata: &StoryMetadata,
+        universe_id: String,
+        current_branch: String,
+    ) -> RecoveryResult<()> {
+        let path_str = repo_path.to_string_lossy().to_string();
+        let created_at = non_empty_or(&metadata.created_at, Utc::now().to_rfc3339());
+        let updated_at = non_empty_or(&metadata.updated_at, created_at.clone());
+
+        let title = if metadata.title.is_empty() {
+            self.warn(
+                &path_str,
+                id,
+                "No title in metadata.json; a placeholder was used",
+            );
+            format!("Recovered container {}", short_id(id))
+        } else {
+            metadata.title.clone()
+        };
+
+        let parent_container_id = match metadata.parent_container_id {
+            Some(ref parent_id) => Some(self.resolve_parent(parent_id, &universe_id)?),
+            None => None,
+        };
+
+        let container = Container {
+            id: id.to_string(),
+            universe_id,
+            parent_container_id,
+            container_type: metadata
+                .container_type
+                .clone()
+                .unwrap_or_else(|| "novel".to_string()),
+            title,
+            description: Some(metadata.description.clone()).filter(|d| !d.is_empty()),
+            order: metadata.order.unwrap_or(0),
+            git_repo_path: Some(path_str.clone()),
+            current_branch: Some(current_branch.clone()),
+            staged_changes: false,
+            created_at,
+            updated_at,
+        };
+        let container = ContainerRepository::insert(self.db, &container)?;
+
+        // Each numbered markdown file is one of the container's stories,
+        // recorded in metadata.json under its filename
+        let mut chapter_files = fs::read_dir(repo_path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter_map(|name| parse_chapter_filename(&name).map(|parsed| (name, parsed)))
+            .collect::<Vec<_>>();
+        chapter_files.sort();
+
+        for (filename, (order, title)) in chapter_files {
+            let content = fs::read_to_string(repo_path.join(&filename))?;
+            let (story_id, title, status, order) = match metadata.chapters.get(&filename) {
+                Some(chapter) => {
+                    let story_id = if self.entity_exists(&chapter.id)? {
+                        self.warn(
+                            &path_str,
+                            id,
+                            &format!(
+                                "Story {} of {filename} already exists; a new ID was assigned",
+                                chapter.id
+                            ),
+                        );
+                        Uuid::new_v4().to_string()
+                    } else {
+                        chapter.id.clone()
+                    };
+                    (
+                        story_id,
+                        chapter.title.clone(),
+                        parse_debug_name(&chapter.status, STORY_STATUSES)
+                            .unwrap_or(StoryStatus::Draft),
+                        chapter.order.unwrap_or(order),
+                    )
+                }
+                None => {
+                    self.warn(
+                        &path_str,
+                        id,
+                        &format!(
+                            "No entry for {filename} in metadata.json; title guessed from the filename and a new ID assigned"
+                        ),
+                    );
+                    (Uuid::new_v4().to_string(), title, StoryStatus::Draft, order)
+                }
+            };
+            let story = Story {
+                id: story_id,
+                universe_id: container.universe_id.clone(),
+                title,
+                description: String::new(),
+                created_at: container.created_at.clone(),
+                updated_at: container.updated_at.clone(),
+                story_type: StoryType::Chapter,
+                status,
+                word_count: 0,
+                target_word_count: None,
+                character_count: 0,
+                character_count_no_spaces: 0,
+                paragraph_count: 0,
+                sentence_count: 0,
+                reading_time_seconds: 0,
+                content,
+                notes: None,
+                outline: None,
+                order: Some(order),
+                tags: None,
+                color: None,
+                favorite: None,
+                related_element_ids: None,
+                container_id: Some(container.id.clone()),
+                series_name: None,
+                last_edited_at: container.updated_at.clone(),
+                version: 1,
+                variation_group_id: Uuid::new_v4().to_string(),
+                variation_type: VariationType::Original,
+                parent_variation_id: None,
+                git_repo_path: String::new(),
+                current_branch: current_branch.clone(),
+                staged_changes: false,
+            };
+            let story = StoryRepository::insert(self.db, &story)?;
+            self.report.stories.push(RecoveredEntity {
+                id: story.id,
+                title: story.title,
+                universe_id: story.universe_id,
+                git_repo_path: None,
+            });
+        }
+
+        self.report.containers.push(RecoveredEntity {
+            id: container.id,
+            title: container.title,
+            universe_id: container.universe_id,
+            git_repo_path: Some(path_str),
+        });
+        Ok(())
+    }
+
+    /// Find or recreate the universe a repo belongs to
+    fn resolve_universe(&mut self, metadata: &StoryMetadata) -> RecoveryResult<String> {
+        if metadata.universe_id.is_empty() {
+            if let Some(ref id) = self.fallback_universe_id {
+                return Ok(id.clone());
+            }
+            // Reuse the fallback universe from an earlier import
+            let existing = UniverseRepository::list_all(self.db)?
+                .into_iter()
+                .find(|u| u.name == FALLBACK_UNIVERSE_NAME);
+            if let Some(universe) = existing {
+                self.fallback_universe_id = Some(universe.id.clone());
+                return Ok(universe.id);
+            }
+            let id = Uuid::new_v4().to_string();
+            self.create_universe(&id, FALLBACK_UNIVERSE_NAME)?;
+            self.fallback_universe_id = Some(id.clone());
+            return Ok(id);
+        }
+
+        let id = metadata.universe_id.clone();
+        if !self.known_universes.contains(&id) {
+            if !row_exists(self.db, "universes", &id)? {
+                self.create_universe(&id, &format!("Recovered Universe {}", short_id(&id)))?;
+            }
+            self.known_universes.insert(id.clone());
+        }
+        Ok(id)
+    }
+
+    fn create_universe(&mut self, id: &str, name: &str) -> RecoveryResult<()> {
+        let now = Utc::now().to_rfc3339();
+        let universe = UniverseRepository::insert(
+            self.db,
+            &Universe {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: "Recreated from git repositories".to_string(),
+                created_at: now.clone(),
+                updated_at: now,
+                genre: None,
+                tone: None,
+                worldbuilding_notes: None,
+                themes: None,
+                status: UniverseStatus::Active,
+                color: None,
+                icon: None,
+                tags: None,
+            },
+        )?;
+        self.known_universes.insert(universe.id.clone());
+        self.report.universes.push(RecoveredEntity {
+            id: universe.id.clone(),
+            title: universe.name,
+            universe_id: universe.id,
+            git_repo_path: None,
+        });
+        Ok(())
+    }
+
+    /// Find or recreate a leaf container's parent
+    ///
+    /// Non-leaf containers have no repository of their own, so a missing
+    /// parent is recreated as a placeholder that keeps its siblings together.
+    fn resolve_parent(&mut self, parent_id: &str, universe_id: &str) -> RecoveryResult<String> {
+        if row_exists(self.db, "containers", parent_id)? {
+            return Ok(parent_id.to_string());
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let placeholder = ContainerRepository::insert(
+            self.db,
+            &Container {
+                id: parent_id.to_string(),
+                universe_id: universe_id.to_string(),
+                parent_container_id: None,
+                container_type: "series".to_string(),
+                title: format!("Recovered container {}", short_id(parent_id)),
+                description: None,
+                order: 0,
+                git_repo_path: None,
+                current_branch: None,
+                staged_changes: false,
+                created_at: now.clone(),
+                updated_at: now,
+            },
+        )?;
+        self.report.containers.push(RecoveredEntity {
+            id: placeholder.id.clone(),
+            title: placeholder.title,
+            universe_id: placeholder.universe_id,
+            git_repo_path: None,
+        });
+        Ok(placeholder.id)
+    }
+
+    fn entity_exists(&self, id: &str) -> RecoveryResult<bool> {
+        Ok(row_exists(self.db, "stories", id)? || row_exists(self.db, "containers", id)?)
+    }
+
+    fn warn(&mut self, path: &str, id: &str, reason: &str) {
+        self.report.warnings.push(RecoveryIssue {
+            path: path.to_string(),
+            id: Some(id.to_string()),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// Check whether a row with the given ID exists in `table`
+fn row_exists(db: &Database, table: &str, id: &str) -> rusqlite::Result<bool> {
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1)"),
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+/// Decide whether a repo holds a leaf container rather than a standalone story
+///
+/// Older metadata.json files don't record a kind; for those, a repo with
+/// numbered chapter files and no content.md is treated as a container.
+fn is_container_repo(repo_path: &Path, metadata: &StoryMetadata) -> bool {
+    match metadata.kind.as_str() {
+        METADATA_KIND_CONTAINER => true,
+        METADATA_KIND_STORY => false,
+        _ => {
+            !repo_path.join(STANDALONE_STORY_FILE).exists()
+                && fs::read_dir(repo_path)
+                    .map(|entries| {
+                        entries.filter_map(|e| e.ok()).any(|e| {
+                            parse_chapter_filename(&e.file_name().to_string_lossy()).is_some()
+                        })
+                    })
+                    .unwrap_or(false)
+        }
+    }
+}
+
+/// Parse a `NNN-slug.md` filename into a 0-based order and a readable title
+fn parse_chapter_filename(filename: &str) -> Option<(u32, String)> {
+    let stem = filename.strip_suffix(".md")?;
+    let (number, slug) = stem.split_once('-')?;
+    if number.len() != 3 || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let order = number.parse::<u32>().ok()?.saturating_sub(1);
+
+    let mut title = slug.replace('-', " ");
+    if let Some(first) = title.get(..1) {
+        title = first.to_uppercase() + &title[1..];
+    }
+    if title.is_empty() {
+        title = format!("Chapter {}", order + 1);
+    }
+    Some((order, title))
+}
+
+const STORY_TYPES: &[StoryType] = &[
+    StoryType::Chapter,
+    StoryType::ShortStory,
+    StoryType::Scene,
+    StoryType::Episode,
+    StoryType::Poem,
+    StoryType::Outline,
+    StoryType::Treatment,
+    StoryType::Screenplay,
+];
+
+const STORY_STATUSES: &[StoryStatus] = &[
+    StoryStatus::Draft,
+    StoryStatus::InProgress,
+    StoryStatus::Completed,
+    StoryStatus::Published,
+    StoryStatus::Archived,
+];
+
+/// metadata.json stores enum values by their Debug name (e.g. "ShortStory")
+fn parse_debug_name<T: fmt::Debug + Clone>(name: &str, variants: &[T]) -> Option<T> {
+    variants.iter().find(|v| format!("{v:?}") == name).cloned()
+}
+
+fn non_empty_or(value: &str, fallback: String) -> String {
+    if value.is_empty() {
+        fallback
+    } else {
+        value.to_string()
+    }
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_management::{
+        sync_story_file, write_container_metadata_file, write_metadata_file,
+    };
+    use crate::models::JournalEntityType;
+    use tempfile::TempDir;
+
+    fn setup_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        (db, temp_dir)
+    }
+
+    fn test_story(id: &str, universe_id: &str) -> Story {
+        Story {
+            id: id.to_string(),
+            universe_id: universe_id.to_string(),
+            title: "The Lighthouse".to_string(),
+            description: "A short story".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-02T00:00:00Z".to_string(),
+            story_type: StoryType::Poem,
+            status: StoryStatus::InProgress,
+            word_count: 0,
+            target_word_count: Some(2000),
+            character_count: 0,
+            character_count_no_spaces: 0,
+            paragraph_count: 0,
+            sentence_count: 0,
+            reading_time_seconds: 0,
+            content: "The light turned all night long.".to_string(),
+            notes: None,
+            outline: None,
+            order: None,
+            tags: None,
+            color: None,
+            favorite: None,
+            related_element_ids: None,
+            container_id: None,
+            series_name: None,
+            last_edited_at: "2024-01-02T00:00:00Z".to_string(),
+            version: 1,
+            variation_group_id: "group-1".to_string(),
+            variation_type: VariationType::Original,
+            parent_variation_id: None,
+            git_repo_path: String::new(),
+            current_branch: "original".to_string(),
+            staged_changes: false,
+        }
+    }
+
+    fn test_container(id: &str, universe_id: &str) -> Container {
+        Container {
+            id: id.to_string(),
+            universe_id: universe_id.to_string(),
+            parent_container_id: Some("series-1".to_string()),
+            container_type: "novel".to_string(),
+            title: "The Long Road".to_string(),
+            description: None,
+            order: 2,
+            git_repo_path: None,
+            current_branch: None,
+            staged_changes: false,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-02T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rebuild_restores_standalone_story() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let repo_path = GitService::init_repo(&base, "story-1").unwrap();
+        let story = test_story("story-1", "universe-9");
+        sync_story_file(&repo_path, None, &story, None).unwrap();
+        write_metadata_file(&repo_path, &story).unwrap();
+        GitService::create_branch(&repo_path, "original", "draft-two").unwrap();
+        GitService::checkout_branch(&repo_path, "draft-two").unwrap();
+
+        let report = rebuild_from_repos(&db, &base.join("git-repos")).unwrap();
+
+        assert_eq!(report.stories.len(), 1);
+        assert_eq!(report.universes.len(), 1);
+        assert_eq!(report.universes[0].id, "universe-9");
+        assert!(report.conflicts.is_empty());
+        assert!(report.orphaned_repos.is_empty());
+
+        let restored = StoryRepository::find_by_id(&db, "story-1").unwrap();
+        assert_eq!(restored.title, "The Lighthouse");
+        assert_eq!(restored.universe_id, "universe-9");
+        assert_eq!(restored.story_type, StoryType::Poem);
+        assert_eq!(restored.status, StoryStatus::InProgress);
+        assert_eq!(restored.content, "The light turned all night long.");
+        assert_eq!(restored.word_count, 6);
+        assert_eq!(restored.git_repo_path, repo_path.to_string_lossy());
+        assert_eq!(restored.current_branch, "draft-two");
+        assert!(UniverseRepository::find_by_id(&db, "universe-9").is_ok());
+
+        let journal =
+            JournalRepository::timeline(&db, JournalEntityType::Story, "story-1", None).unwrap();
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal[0].origin, ChangeOrigin::Import);
+    }
+
+    #[test]
+    fn test_rebuild_restores_container_and_chapters() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let repo_path = GitService::init_repo(&base, "novel-1").unwrap();
+        write_container_metadata_file(&repo_path, &test_container("novel-1", "universe-1"))
+            .unwrap();
+        for (order, title) in [(0, "Into the Woods!"), (1, "Middle Ground")] {
+            let mut chapter = test_story(&format!("chapter-{order}"), "universe-1");
+            chapter.title = title.to_string();
+            chapter.container_id = Some("novel-1".to_string());
+            chapter.order = Some(order);
+            sync_story_file(&repo_path, None, &chapter, None).unwrap();
+        }
+
+        let report = rebuild_from_repos(&db, &base.join("git-repos")).unwrap();
+        assert!(report.warnings.is_empty());
+
+        // The missing parent series is recreated as a placeholder
+        assert_eq!(report.containers.len(), 2);
+        let series = ContainerRepository::find_by_id(&db, "series-1").unwrap();
+        assert!(series.git_repo_path.is_none());
+
+        let novel = ContainerRepository::find_by_id(&db, "novel-1").unwrap();
+        assert_eq!(novel.title, "The Long Road");
+        assert_eq!(novel.parent_container_id.as_deref(), Some("series-1"));
+        assert_eq!(novel.order, 2);
+        assert_eq!(novel.current_branch.as_deref(), Some("original"));
+
+        let chapters = StoryRepository::list_by_container(&db, "novel-1").unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].id, "chapter-0");
+        assert_eq!(chapters[0].title, "Into the Woods!");
+        assert_eq!(chapters[0].status, StoryStatus::InProgress);
+        assert_eq!(chapters[0].order, Some(0));
+        assert_eq!(chapters[1].id, "chapter-1");
+        assert_eq!(chapters[1].title, "Middle Ground");
+        assert_eq!(chapters[1].content, "The light turned all night long.");
+    }
+
+    #[test]
+    fn test_rebuild_guesses_chapters_missing_from_metadata() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let repo_path = GitService::init_repo(&base, "novel-1").unwrap();
+        write_container_metadata_file(&repo_path, &test_container("novel-1", "universe-1"))
+            .unwrap();
+        fs::write(repo_path.join("001-into-the-woods.md"), "Trees.").unwrap();
+
+        let report = rebuild_from_repos(&db, &base.join("git-repos")).unwrap();
+
+        let chapters = StoryRepository::list_by_container(&db, "novel-1").unwrap();
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].title, "Into the woods");
+        assert_eq!(chapters[0].status, StoryStatus::Draft);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].id.as_deref(), Some("novel-1"));
+        assert!(report.warnings[0].reason.contains("001-into-the-woods.md"));
+    }
+
+    #[test]
+    fn test_rebuild_reports_conflicts_and_orphans() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        let git_repos = base.join("git-repos");
+
+        let repo_path = GitService::init_repo(&base, "story-1").unwrap();
+        write_metadata_file(&repo_path, &test_story("story-1", "universe-1")).unwrap();
+        rebuild_from_repos(&db, &git_repos).unwrap();
+
+        // A plain directory and a repo that lost its metadata.json
+        fs::create_dir_all(git_repos.join("not-a-repo")).unwrap();
+        let bare_path = GitService::init_repo(&base, "story-2").unwrap();
+        fs::remove_file(bare_path.join("metadata.json")).unwrap();
+
+        let report = rebuild_from_repos(&db, &git_repos).unwrap();
+
+        assert!(report.stories.is_empty());
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].id.as_deref(), Some("story-1"));
+        let orphaned = report
+            .orphaned_repos
+            .iter()
+            .filter_map(|issue| issue.id.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(orphaned, vec!["not-a-repo", "story-2"]);
+    }
+
+    #[test]
+    fn test_rebuild_collects_legacy_repos_into_fallback_universe() {
+        let (db, temp_dir) = setup_test_db();
+        let base = temp_dir.path().join("app");
+        GitService::init_repo(&base, "legacy-a").unwrap();
+        GitService::init_repo(&base, "legacy-b").unwrap();
+
+        let report = rebuild_from_repos(&db, &base.join("git-repos")).unwrap();
+
+        assert_eq!(report.universes.len(), 1);
+        assert_eq!(report.universes[0].title, FALLBACK_UNIVERSE_NAME);
+        assert_eq!(report.stories.len(), 2);
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report
+            .stories
+            .iter()
+            .all(|s| s.universe_id == report.universes[0].id));
+    }
+
+    #[test]
+    fn test_parse_chapter_filename() {
+        assert_eq!(
+            parse_chapter_filename("003-into-the-woods.md"),
+            Some((2, "Into the woods".to_string()))
+        );
+        assert_eq!(parse_chapter_filename("content.md"), None);
+        assert_eq!(parse_chapter_filename("12-short.md"), None);
+        assert_eq!(parse_chapter_filename("001-notes.txt"), None);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::db::Database;
-use crate::models::{Container, MAX_NESTING_DEPTH};
+use crate::models::{Container, ContainerStats, JournalEntityType, MAX_NESTING_DEPTH};
+use crate::repositories::{JournalRepository, StoryRepository};
+use crate::text_stats::TextStats;
 use chrono::Utc;
 use log::warn;
 use rusqlite::{params, Result};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 pub struct ContainerRepository;
@@ -67,7 +70,53 @@ impl ContainerRepository {
             ],
         )?;
 
-        Self::find_by_id(db, &id)
+        let container = Self::find_by_id(db, &id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Container,
+            &container.id,
+            &container.universe_id,
+            None,
+            Some(&container),
+        )?;
+        Ok(container)
+    }
+
+    /// Insert a fully-formed Container, keeping its ID and timestamps
+    ///
+    /// Used when restoring containers whose identity comes from elsewhere
+    /// (e.g. a git repository's metadata.json). No hierarchy validation is applied.
+    pub fn insert(db: &Database, container: &Container) -> Result<Container> {
+        db.execute(
+            "INSERT INTO containers (
+                id, universe_id, parent_container_id, container_type, title,
+                description, \"order\", git_repo_path, current_branch, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                &container.id,
+                &container.universe_id,
+                &container.parent_container_id,
+                &container.container_type,
+                &container.title,
+                &container.description,
+                &container.order,
+                &container.git_repo_path,
+                &container.current_branch,
+                &container.created_at,
+                &container.updated_at,
+            ],
+        )?;
+
+        let inserted = Self::find_by_id(db, &container.id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Container,
+            &inserted.id,
+            &inserted.universe_id,
+            None,
+            Some(&inserted),
+        )?;
+        Ok(inserted)
     }
 
     /// Find a Container by ID
@@ -93,7 +142,7 @@ impl ContainerRepository {
             "SELECT id, universe_id, parent_container_id, container_type, title,
                     description, \"order\", git_repo_path, current_branch, created_at, updated_at
              FROM containers
-             WHERE universe_id = ?1
+             WHERE universe_id = ?1 AND deleted_at IS NULL
              ORDER BY \"order\" ASC, created_at ASC",
         )?;
 
@@ -113,7 +162,7 @@ impl ContainerRepository {
             "SELECT id, universe_id, parent_container_id, container_type, title,
                     description, \"order\", git_repo_path, current_branch, created_at, updated_at
              FROM containers
-             WHERE parent_container_id = ?1
+             WHERE parent_container_id = ?1 AND deleted_at IS NULL
              ORDER BY \"order\" ASC, created_at ASC",
         )?;
 
@@ -183,7 +232,7 @@ impl ContainerRepository {
                            c.created_at, c.updated_at, s.depth + 1
                     FROM containers c
                     INNER JOIN subtree s ON c.parent_container_id = s.id
-                    WHERE s.depth < {}
+                    WHERE s.depth < {} AND c.deleted_at IS NULL
                 )
                 SELECT id, universe_id, parent_container_id, container_type, title,
                        description, \"order\", git_repo_path, current_branch, created_at, updated_at
@@ -208,6 +257,7 @@ impl ContainerRepository {
                        c.created_at, c.updated_at
                 FROM containers c
                 INNER JOIN subtree s ON c.parent_container_id = s.id
+                WHERE c.deleted_at IS NULL
             )
             SELECT id, universe_id, parent_container_id, container_type, title,
                    description, \"order\", git_repo_path, current_branch, created_at, updated_at
@@ -225,6 +275,91 @@ impl ContainerRepository {
         Ok(containers)
     }
 
+    /// Roll story statistics up through a container subtree
+    ///
+    /// Each container's totals cover its own stories plus those of every
+    /// container below it, so a series reports the word count of all its
+    /// novels. Only the original of each variation group is counted.
+    ///
+    /// # Returns
+    ///
+    /// One entry per container from `get_subtree`, in the same order
+    pub fn get_subtree_stats(db: &Database, container_id: &str) -> Result<Vec<ContainerStats>> {
+        let subtree = Self::get_subtree(db, container_id, None)?;
+
+        let mut own: HashMap<&str, (u32, TextStats)> = HashMap::new();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*), COALESCE(SUM(word_count), 0), COALESCE(SUM(character_count), 0),
+                        COALESCE(SUM(character_count_no_spaces), 0),
+                        COALESCE(SUM(paragraph_count), 0), COALESCE(SUM(sentence_count), 0),
+                        COALESCE(SUM(reading_time_seconds), 0)
+                 FROM stories
+                 WHERE container_id = ?1 AND parent_variation_id IS NULL
+                   AND deleted_at IS NULL",
+            )?;
+            for container in &subtree {
+                let totals = stmt.query_row(params![container.id], |row| {
+                    Ok((
+                        row.get(0)?,
+                        TextStats {
+                            word_count: row.get(1)?,
+                            character_count: row.get(2)?,
+                            character_count_no_spaces: row.get(3)?,
+                            paragraph_count: row.get(4)?,
+                            sentence_count: row.get(5)?,
+                            reading_time_seconds: row.get(6)?,
+                        },
+                    ))
+                })?;
+                own.insert(container.id.as_str(), totals);
+            }
+        }
+
+        let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+        for container in subtree.iter().filter(|c| c.id != container_id) {
+            if let Some(parent) = container.parent_container_id.as_deref() {
+                children.entry(parent).or_default().push(&container.id);
+            }
+        }
+
+        fn total<'a>(
+            id: &'a str,
+            own: &HashMap<&'a str, (u32, TextStats)>,
+            children: &HashMap<&'a str, Vec<&'a str>>,
+            totals: &mut HashMap<&'a str, (u32, TextStats)>,
+        ) -> (u32, TextStats) {
+            let (mut story_count, mut stats) = own.get(id).copied().unwrap_or_default();
+            for child in children.get(id).into_iter().flatten() {
+                let (child_count, child_stats) = total(child, own, children, totals);
+                story_count += child_count;
+                stats.add(&child_stats);
+            }
+            totals.insert(id, (story_count, stats));
+            (story_count, stats)
+        }
+
+        let mut totals = HashMap::new();
+        total(container_id, &own, &children, &mut totals);
+
+        Ok(subtree
+            .iter()
+            .map(|container| {
+                let (story_count, stats) = totals
+                    .get(container.id.as_str())
+                    .copied()
+                    .unwrap_or_default();
+                ContainerStats {
+                    container_id: container.id.clone(),
+                    story_count,
+                    stats,
+                }
+            })
+            .collect())
+    }
+
     /// Reorder children by updating their order fields
     pub fn reorder_children(
         db: &Database,
@@ -234,8 +369,8 @@ impl ContainerRepository {
         let conn = db.connection();
         let mut conn = conn.lock().unwrap();
 
-        // Start transaction using Rust's transaction API
-        let tx = conn.transaction()?;
+        // A savepoint, so this also works inside `Database::transaction`
+        let tx = conn.savepoint()?;
 
         // Validate that all container_ids belong to the parent
         for container_id in &container_ids {
@@ -281,6 +416,7 @@ impl ContainerRepository {
         container_type: Option<String>,
         order: Option<i32>,
     ) -> Result<Container> {
+        let before = Self::find_by_id(db, id)?;
         let now = Utc::now().to_rfc3339();
 
         let mut updates = vec!["updated_at = ?1"];
@@ -312,6 +448,167 @@ impl ContainerRepository {
 
         db.execute(&query, &params_refs)?;
 
+        let container = Self::find_by_id(db, id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Container,
+            id,
+            &container.universe_id,
+            Some(&before),
+            Some(&container),
+        )?;
+        Ok(container)
+    }
+
+    /// Move a Container, with everything below it, to a new parent
+    ///
+    /// With no parent the container becomes a top-level container of
+    /// `universe_id`; with a parent it must be in the parent's universe. The
+    /// creation rules still apply: the parent can't hold stories, can't be
+    /// the container itself or one of its descendants, and the deepest
+    /// container of the moved subtree must stay within `MAX_NESTING_DEPTH`.
+    ///
+    /// When the universe changes, every container and story below moves
+    /// with it. Those stories lose their related elements, which belong to
+    /// the old universe.
+    ///
+    /// # Arguments
+    /// * `order` - Position among the new siblings (after the last one if None)
+    pub fn move_to(
+        db: &Database,
+        id: &str,
+        parent_container_id: Option<&str>,
+        universe_id: &str,
+        order: Option<i32>,
+    ) -> Result<Container> {
+        let before = Self::find_by_id(db, id)?;
+        if Self::is_trashed(db, id)? {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "Cannot move a container that is in the trash".to_string(),
+            ));
+        }
+        let subtree = Self::subtree_depths(db, id)?;
+
+        if let Some(parent_id) = parent_container_id {
+            let parent = Self::find_by_id(db, parent_id)?;
+            if Self::is_trashed(db, parent_id)? {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Parent container is in the trash".to_string(),
+                ));
+            }
+            if parent.universe_id != universe_id {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Parent container belongs to a different universe".to_string(),
+                ));
+            }
+            if subtree
+                .iter()
+                .any(|(container_id, _)| container_id == parent_id)
+            {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Cannot move a container into itself or one of its descendants".to_string(),
+                ));
+            }
+            if Self::get_story_count(db, parent_id)? > 0 {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Cannot add child container to a container that already has stories"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // Depth Limit: the deepest container below must still fit
+        let depth = Self::calculate_depth(db, parent_container_id)?;
+        let height = subtree.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+        if depth + height >= MAX_NESTING_DEPTH {
+            return Err(rusqlite::Error::InvalidParameterName(format!(
+                "Maximum container nesting depth of {} levels exceeded. Moved depth: {}",
+                MAX_NESTING_DEPTH,
+                depth + height
+            )));
+        }
+
+        let universe_changed = before.universe_id != universe_id;
+        let story_ids: Vec<String> = if universe_changed {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id FROM stories WHERE container_id = ?1")?;
+            let mut ids = Vec::new();
+            for (container_id, _) in &subtree {
+                ids.extend(
+                    stmt.query_map(params![container_id], |row| row.get(0))?
+                        .collect::<Result<Vec<String>>>()?,
+                );
+            }
+            ids
+        } else {
+            Vec::new()
+        };
+        let containers_before = subtree
+            .iter()
+            .map(|(container_id, _)| Self::find_by_id(db, container_id))
+            .collect::<Result<Vec<_>>>()?;
+        let stories_before = story_ids
+            .iter()
+            .map(|story_id| StoryRepository::find_by_id(db, story_id))
+            .collect::<Result<Vec<_>>>()?;
+
+        {
+            let conn = db.connection();
+            let mut conn = conn.lock().unwrap();
+            let tx = conn.savepoint()?;
+
+            let order = match order {
+                Some(order) => order,
+                None => tx.query_row(
+                    "SELECT COALESCE(MAX(\"order\") + 1, 0) FROM containers
+                     WHERE parent_container_id IS ?1 AND universe_id = ?2 AND id != ?3",
+                    params![parent_container_id, universe_id, id],
+                    |row| row.get(0),
+                )?,
+            };
+            tx.execute(
+                "UPDATE containers SET parent_container_id = ?1, \"order\" = ?2, updated_at = ?3
+                 WHERE id = ?4",
+                params![parent_container_id, order, Utc::now().to_rfc3339(), id],
+            )?;
+            for (container_id, _) in &subtree {
+                tx.execute(
+                    "UPDATE containers SET universe_id = ?1 WHERE id = ?2",
+                    params![universe_id, container_id],
+                )?;
+            }
+            for story_id in &story_ids {
+                tx.execute(
+                    "UPDATE stories SET universe_id = ?1, related_element_ids = NULL WHERE id = ?2",
+                    params![universe_id, story_id],
+                )?;
+            }
+
+            tx.commit()?;
+        }
+
+        for container in &containers_before {
+            JournalRepository::record(
+                db,
+                JournalEntityType::Container,
+                &container.id,
+                universe_id,
+                Some(container),
+                Some(&Self::find_by_id(db, &container.id)?),
+            )?;
+        }
+        for story in &stories_before {
+            JournalRepository::record(
+                db,
+                JournalEntityType::Story,
+                &story.id,
+                universe_id,
+                Some(story),
+                Some(&StoryRepository::find_by_id(db, &story.id)?),
+            )?;
+        }
+
         Self::find_by_id(db, id)
     }
 
@@ -328,17 +625,34 @@ impl ContainerRepository {
         // Get the container before deletion to check for git repo
         let container = Self::find_by_id(db, id)?;
 
-        // First, get all children of this container
-        let children = Self::list_children(db, id)?;
+        // First, get all children of this container, including trashed ones
+        let child_ids: Vec<String> = {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt =
+                conn.prepare("SELECT id FROM containers WHERE parent_container_id = ?1")?;
+            let ids = stmt
+                .query_map(params![id], |row| row.get(0))?
+                .collect::<Result<Vec<_>>>()?;
+            ids
+        };
 
         // Recursively delete each child
-        for child in children {
-            Self::delete_recursive(db, &child.id, deleted_ids)?;
+        for child_id in child_ids {
+            Self::delete_recursive(db, &child_id, deleted_ids)?;
         }
 
         // Delete the container itself from the database
         // CASCADE will handle deleting stories in this container
         db.execute("DELETE FROM containers WHERE id = ?1", params![id])?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Container,
+            id,
+            &container.universe_id,
+            Some(&container),
+            None,
+        )?;
 
         // If container has a git repo, remove the directory from filesystem
         if let Some(git_repo_path) = container.git_repo_path {
@@ -375,6 +689,42 @@ impl ContainerRepository {
         Ok(count)
     }
 
+    /// Check whether a container is in the trash
+    pub fn is_trashed(db: &Database, id: &str) -> Result<bool> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT deleted_at IS NOT NULL FROM containers WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Get a container and every container below it, including trashed ones,
+    /// with their depth relative to the container
+    fn subtree_depths(db: &Database, id: &str) -> Result<Vec<(String, u32)>> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE subtree(id, depth) AS (
+                SELECT id, 0 FROM containers WHERE id = ?1
+                UNION ALL
+                SELECT c.id, s.depth + 1
+                FROM containers c
+                INNER JOIN subtree s ON c.parent_container_id = s.id
+            )
+            SELECT id, depth FROM subtree",
+        )?;
+
+        let containers = stmt
+            .query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(containers)
+    }
+
     /// Calculate the depth of a container in the hierarchy by walking up the parent chain
     /// Returns 0 for root containers (no parent), 1 for direct children of root, etc.
     fn calculate_depth(db: &Database, parent_container_id: Option<&str>) -> Result<u32> {
@@ -399,6 +749,15 @@ impl ContainerRepository {
         Ok(())
     }
 
+    /// Forget a container's git repo so it is re-initialized on next use (internal use)
+    pub fn clear_git_repo_path(db: &Database, id: &str) -> Result<()> {
+        db.execute(
+            "UPDATE containers SET git_repo_path = NULL, current_branch = NULL WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
     /// Update the current branch for a container (internal use)
     pub fn set_current_branch(db: &Database, id: &str, branch: &str) -> Result<()> {
         db.execute(
@@ -1247,6 +1606,64 @@ mod tests {
         assert_eq!(last_depth, MAX_NESTING_DEPTH);
     }
 
+    #[test]
+    fn test_move_to_counts_depth_of_moved_subtree() {
+        let (db, _temp_dir) = setup_test_db();
+
+        // A chain of MAX_NESTING_DEPTH - 1 levels and a separate two-level tree
+        let mut current_parent: Option<String> = None;
+        for i in 0..MAX_NESTING_DEPTH - 1 {
+            let container = ContainerRepository::create(
+                &db,
+                "universe-1".to_string(),
+                current_parent,
+                "container".to_string(),
+                format!("Level {}", i),
+                None,
+                0,
+            )
+            .unwrap();
+            current_parent = Some(container.id);
+        }
+        let deepest = current_parent.unwrap();
+        let top = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            None,
+            "container".to_string(),
+            "Top".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+        let below = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            Some(top.id.clone()),
+            "container".to_string(),
+            "Below".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+
+        // The subtree's child would end up one level too deep
+        let result = ContainerRepository::move_to(&db, &top.id, Some(&deepest), "universe-1", None);
+        match result {
+            Err(rusqlite::Error::InvalidParameterName(msg)) => {
+                assert!(msg.contains("Maximum container nesting depth"));
+            }
+            _ => panic!("Expected InvalidParameterName error for max depth exceeded"),
+        }
+
+        // A single container still fits
+        let moved =
+            ContainerRepository::move_to(&db, &below.id, Some(&deepest), "universe-1", None)
+                .unwrap();
+        assert_eq!(moved.parent_container_id, Some(deepest));
+        assert_eq!(moved.order, 0);
+    }
+
     #[test]
     fn test_depth_limit_clear_error_message() {
         let (db, _temp_dir) = setup_test_db();
@@ -1679,6 +2096,56 @@ mod tests {
         assert_eq!(subtree[0].id, series.id);
     }
 
+    #[test]
+    fn test_get_subtree_stats_rolls_up_word_counts() {
+        let (db, _temp_dir) = setup_test_db();
+        let create = |parent: Option<String>, title: &str| {
+            ContainerRepository::create(
+                &db,
+                "universe-1".to_string(),
+                parent,
+                "novel".to_string(),
+                title.to_string(),
+                None,
+                1,
+            )
+            .unwrap()
+        };
+        let series = create(None, "Series");
+        let book1 = create(Some(series.id.clone()), "Book 1");
+        let book2 = create(Some(series.id.clone()), "Book 2");
+        for (container_id, content) in [
+            (&book1.id, "One two three."),
+            (&book1.id, "Four five."),
+            (&book2.id, "Six seven eight nine."),
+        ] {
+            db.execute(
+                "INSERT INTO stories (id, universe_id, container_id, title, content, word_count,
+                                      last_edited_at, variation_group_id, created_at, updated_at)
+                 VALUES (?1, 'universe-1', ?2, 'Chapter', ?3, ?4, '2024-01-01', ?1,
+                         '2024-01-01', '2024-01-01')",
+                params![
+                    Uuid::new_v4().to_string(),
+                    container_id,
+                    content,
+                    content.split_whitespace().count() as u32
+                ],
+            )
+            .unwrap();
+        }
+
+        let stats = ContainerRepository::get_subtree_stats(&db, &series.id).unwrap();
+
+        let words = |id: &str| {
+            let entry = stats.iter().find(|s| s.container_id == id).unwrap();
+            (entry.story_count, entry.stats.word_count)
+        };
+        assert_eq!(stats.len(), 3);
+        assert_eq!(words(&series.id), (3, 9));
+        assert_eq!(words(&book1.id), (2, 5));
+        assert_eq!(words(&book2.id), (1, 4));
+    }
+
     #[test]
     fn test_get_subtree_with_depth_limit() {
         let (db, _temp_dir) = setup_test_db();
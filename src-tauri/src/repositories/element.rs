@@ -1,9 +1,11 @@
 use crate::db::Database;
 use crate::models::{
-    CreateElementInput, Element, ElementRelationship, ElementType, UpdateElementInput,
+    CreateElementInput, Element, ElementRelationship, ElementType, JournalEntityType,
+    UpdateElementInput,
 };
+use crate::repositories::JournalRepository;
 use chrono::Utc;
-use rusqlite::{params, Result};
+use rusqlite::{params, OptionalExtension, Result};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -56,7 +58,86 @@ impl ElementRepository {
             }
         }
 
-        Self::find_by_id(db, &id)
+        let element = Self::find_by_id(db, &id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Element,
+            &element.id,
+            &element.universe_id,
+            None,
+            Some(&element),
+        )?;
+        Ok(element)
+    }
+
+    /// Insert a fully-formed Element, keeping its ID and timestamps
+    ///
+    /// Used to bring back a deleted element. Its own relationships are
+    /// recreated, as are the `incoming` relationships other elements had to
+    /// it (see `list_incoming_relationships`). Relationships to or from
+    /// elements that no longer exist are skipped.
+    pub fn insert(
+        db: &Database,
+        element: &Element,
+        incoming: &[(String, ElementRelationship)],
+    ) -> Result<Element> {
+        let element_type_str = format!("{:?}", element.element_type).to_lowercase();
+        let attributes_json = element
+            .attributes
+            .as_ref()
+            .map(|a| serde_json::to_string(a).unwrap());
+        let tags_json = element
+            .tags
+            .as_ref()
+            .map(|t| serde_json::to_string(t).unwrap());
+
+        db.execute(
+            "INSERT INTO elements (
+                id, universe_id, name, description, element_type, custom_type_name,
+                details, attributes, image_url, created_at, updated_at, tags,
+                color, icon, favorite, \"order\"
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                &element.id,
+                &element.universe_id,
+                &element.name,
+                &element.description,
+                &element_type_str,
+                &element.custom_type_name,
+                &element.details,
+                &attributes_json,
+                &element.image_url,
+                &element.created_at,
+                &element.updated_at,
+                &tags_json,
+                &element.color,
+                &element.icon,
+                element.favorite.unwrap_or(false),
+                element.order.unwrap_or(0),
+            ],
+        )?;
+
+        for rel in element.relationships.iter().flatten() {
+            if Self::exists(db, &rel.target_element_id)? {
+                Self::create_relationship(db, &element.id, rel)?;
+            }
+        }
+        for (source_id, rel) in incoming {
+            if Self::exists(db, source_id)? {
+                Self::create_relationship(db, source_id, rel)?;
+            }
+        }
+
+        let inserted = Self::find_by_id(db, &element.id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Element,
+            &inserted.id,
+            &inserted.universe_id,
+            None,
+            Some(&inserted),
+        )?;
+        Ok(inserted)
     }
 
     /// Find an Element by ID
@@ -155,6 +236,7 @@ impl ElementRepository {
 
     /// Update an Element
     pub fn update(db: &Database, id: &str, input: UpdateElementInput) -> Result<Element> {
+        let before = Self::find_by_id(db, id)?;
         let now = Utc::now().to_rfc3339();
 
         let element_type_str = input
@@ -233,13 +315,33 @@ impl ElementRepository {
             }
         }
 
-        Self::find_by_id(db, id)
+        let element = Self::find_by_id(db, id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Element,
+            id,
+            &element.universe_id,
+            Some(&before),
+            Some(&element),
+        )?;
+        Ok(element)
     }
 
     /// Delete an Element
     pub fn delete(db: &Database, id: &str) -> Result<()> {
+        let element = Self::find_by_id(db, id).optional()?;
         // Relationships will be cascade deleted by the database
         db.execute("DELETE FROM elements WHERE id = ?1", params![id])?;
+        if let Some(element) = element {
+            JournalRepository::record(
+                db,
+                JournalEntityType::Element,
+                id,
+                &element.universe_id,
+                Some(&element),
+                None,
+            )?;
+        }
         Ok(())
     }
 
@@ -249,14 +351,11 @@ impl ElementRepository {
         source_id: &str,
         rel: &ElementRelationship,
     ) -> Result<()> {
-        let rel_id = Uuid::new_v4().to_string();
-
         db.execute(
             "INSERT INTO element_relationships (
-                id, source_element_id, target_element_id, label, inverse_label, description
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                source_element_id, target_element_id, label, inverse_label, description
+            ) VALUES (?1, ?2, ?3, ?4, ?5)",
             params![
-                &rel_id,
                 source_id,
                 &rel.target_element_id,
                 &rel.label,
@@ -293,6 +392,52 @@ impl ElementRepository {
         Ok(relationships)
     }
 
+    /// Get the relationships other elements have to an element
+    ///
+    /// # Returns
+    /// Pairs of (source element ID, relationship)
+    pub fn list_incoming_relationships(
+        db: &Database,
+        element_id: &str,
+    ) -> Result<Vec<(String, ElementRelationship)>> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT source_element_id, target_element_id, label, inverse_label, description
+             FROM element_relationships
+             WHERE target_element_id = ?1 AND source_element_id != ?1",
+        )?;
+
+        let relationships = stmt
+            .query_map(params![element_id], |row| {
+                Ok((
+                    row.get(0)?,
+                    ElementRelationship {
+                        target_element_id: row.get(1)?,
+                        label: row.get(2)?,
+                        inverse_label: row.get(3)?,
+                        description: row.get(4)?,
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(relationships)
+    }
+
+    /// Check whether an element exists
+    fn exists(db: &Database, id: &str) -> Result<bool> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM elements WHERE id = ?1)",
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
     /// Delete all relationships for an element
     fn delete_relationships(db: &Database, element_id: &str) -> Result<()> {
         db.execute(
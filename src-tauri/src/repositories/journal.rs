@@ -0,0 +1,370 @@
+use crate::db::Database;
+use crate::models::{ChangeOrigin, FieldChange, JournalAction, JournalEntityType, JournalEntry};
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+/// Fields that aren't journaled: timestamps and counters that change on
+/// every save, story content (versioned in git) and repository bookkeeping
+const UNJOURNALED_FIELDS: &[&str] = &[
+    "id",
+    "createdAt",
+    "updatedAt",
+    "lastEditedAt",
+    "version",
+    "content",
+    "stagedChanges",
+    "wordCount",
+    "characterCount",
+    "characterCountNoSpaces",
+    "paragraphCount",
+    "sentenceCount",
+    "readingTimeSeconds",
+    "gitRepoPath",
+    "currentBranch",
+];
+
+thread_local! {
+    static ORIGIN: Cell<ChangeOrigin> = const { Cell::new(ChangeOrigin::Ui) };
+}
+
+pub struct JournalRepository;
+
+impl JournalRepository {
+    /// Run `f` with the changes it makes attributed to `origin`
+    ///
+    /// The origin applies to the current thread only; changes made outside
+    /// this scope are attributed to the UI.
+    pub fn with_origin<T>(origin: ChangeOrigin, f: impl FnOnce() -> T) -> T {
+        let previous = ORIGIN.replace(origin);
+        let result = f();
+        ORIGIN.set(previous);
+        result
+    }
+
+    /// Record a change to an entity
+    ///
+    /// `before` is None for a create and `after` is None for a delete. Only
+    /// fields whose values differ are recorded, and an update that changed
+    /// no journaled field isn't recorded at all.
+    pub fn record<T: Serialize>(
+        db: &Database,
+        entity_type: JournalEntityType,
+        entity_id: &str,
+        universe_id: &str,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<()> {
+        let action = match (before, after) {
+            (None, Some(_)) => JournalAction::Create,
+            (Some(_), None) => JournalAction::Delete,
+            _ => JournalAction::Update,
+        };
+        let changes = diff_fields(
+            before.map(journaled_fields).unwrap_or_default(),
+            after.map(journaled_fields).unwrap_or_default(),
+        );
+
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+        Self::append(&conn, entity_type, entity_id, universe_id, action, &changes)
+    }
+
+    /// Append an entry with the given field changes
+    ///
+    /// Takes a connection so it can be used inside a transaction. Entries
+    /// without changes are skipped.
+    pub fn append(
+        conn: &Connection,
+        entity_type: JournalEntityType,
+        entity_id: &str,
+        universe_id: &str,
+        action: JournalAction,
+        changes: &BTreeMap<String, FieldChange>,
+    ) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        conn.execute(
+            "INSERT INTO change_journal
+                (entity_type, entity_id, universe_id, action, changes, origin, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                enum_str(&entity_type),
+                entity_id,
+                universe_id,
+                enum_str(&action),
+                serde_json::to_string(changes).unwrap(),
+                enum_str(&ORIGIN.get()),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Find a journal entry by ID
+    pub fn find_by_id(db: &Database, id: i64) -> Result<JournalEntry> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT id, entity_type, entity_id, universe_id, action, changes, origin, created_at
+             FROM change_journal WHERE id = ?1",
+            params![id],
+            Self::map_row_to_entry,
+        )
+    }
+
+    /// Get the changes made to an entity, newest first
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of entries to return (all if None)
+    pub fn timeline(
+        db: &Database,
+        entity_type: JournalEntityType,
+        entity_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<JournalEntry>> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, entity_type, entity_id, universe_id, action, changes, origin, created_at
+             FROM change_journal
+             WHERE entity_type = ?1 AND entity_id = ?2
+             ORDER BY id DESC
+             LIMIT ?3",
+        )?;
+
+        let entries = stmt
+            .query_map(
+                params![
+                    enum_str(&entity_type),
+                    entity_id,
+                    limit.map_or(-1, i64::from)
+                ],
+                Self::map_row_to_entry,
+            )?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(entries)
+    }
+
+    /// Helper function to map a row to JournalEntry struct
+    fn map_row_to_entry(row: &rusqlite::Row) -> Result<JournalEntry> {
+        let entity_type_str: String = row.get(1)?;
+        let action_str: String = row.get(4)?;
+        let changes_json: String = row.get(5)?;
+        let origin_str: String = row.get(6)?;
+
+        Ok(JournalEntry {
+            id: row.get(0)?,
+            entity_type: serde_json::from_str(&format!("\"{entity_type_str}\"")).unwrap(),
+            entity_id: row.get(2)?,
+            universe_id: row.get(3)?,
+            action: serde_json::from_str(&format!("\"{action_str}\"")).unwrap(),
+            changes: serde_json::from_str(&changes_json).unwrap_or_default(),
+            origin: serde_json::from_str(&format!("\"{origin_str}\"")).unwrap(),
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+/// Serialize an enum to its stored lowercase name
+fn enum_str<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value)
+        .unwrap()
+        .trim_matches('"')
+        .to_string()
+}
+
+/// The journaled fields of an entity, as the frontend sees them
+fn journaled_fields<T: Serialize>(entity: &T) -> Map<String, Value> {
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(entity) else {
+        return Map::new();
+    };
+    fields.retain(|name, value| !value.is_null() && !UNJOURNALED_FIELDS.contains(&name.as_str()));
+    fields
+}
+
+/// The fields whose values differ between two versions of an entity
+fn diff_fields(
+    before: Map<String, Value>,
+    after: Map<String, Value>,
+) -> BTreeMap<String, FieldChange> {
+    let mut changes = BTreeMap::new();
+    for name in before.keys().chain(after.keys()) {
+        let old = before.get(name).cloned().unwrap_or(Value::Null);
+        let new = after.get(name).cloned().unwrap_or(Value::Null);
+        if old != new {
+            changes.insert(
+                name.clone(),
+                FieldChange {
+                    before: old,
+                    after: new,
+                },
+            );
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{UniverseStatus, UpdateUniverseInput};
+    use crate::repositories::{ElementRepository, UniverseRepository};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn setup_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        (db, temp_dir)
+    }
+
+    fn create_universe(db: &Database) -> String {
+        UniverseRepository::create(
+            db,
+            crate::models::CreateUniverseInput {
+                name: "Aster".to_string(),
+                description: Some("A test universe".to_string()),
+                genre: None,
+                tone: None,
+                worldbuilding_notes: None,
+                themes: None,
+                color: None,
+                icon: None,
+                tags: None,
+            },
+        )
+        .unwrap()
+        .id
+    }
+
+    fn rename_only(name: &str) -> UpdateUniverseInput {
+        UpdateUniverseInput {
+            name: Some(name.to_string()),
+            description: None,
+            genre: None,
+            tone: None,
+            worldbuilding_notes: None,
+            themes: None,
+            status: None,
+            color: None,
+            icon: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_fields_skips_unjournaled_and_unchanged() {
+        let before = json!({"id": "1", "title": "Old", "updatedAt": "a", "order": 1});
+        let after =
+            json!({"id": "1", "title": "New", "updatedAt": "b", "order": 1, "color": "red"});
+
+        let changes = diff_fields(journaled_fields(&before), journaled_fields(&after));
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes["title"].before, json!("Old"));
+        assert_eq!(changes["title"].after, json!("New"));
+        assert_eq!(changes["color"].before, Value::Null);
+    }
+
+    #[test]
+    fn test_repository_changes_are_journaled() {
+        let (db, _temp_dir) = setup_test_db();
+        let universe_id = create_universe(&db);
+        UniverseRepository::update(
+            &db,
+            &universe_id,
+            UpdateUniverseInput {
+                name: Some("Aster Reach".to_string()),
+                status: Some(UniverseStatus::Archived),
+                ..rename_only("Aster Reach")
+            },
+        )
+        .unwrap();
+        // Saving the same values again is not a change
+        UniverseRepository::update(&db, &universe_id, rename_only("Aster Reach")).unwrap();
+
+        let timeline =
+            JournalRepository::timeline(&db, JournalEntityType::Universe, &universe_id, None)
+                .unwrap();
+
+        assert_eq!(timeline.len(), 2);
+        let update = &timeline[0];
+        assert_eq!(update.action, JournalAction::Update);
+        assert_eq!(update.origin, ChangeOrigin::Ui);
+        assert_eq!(
+            update.changes.keys().collect::<Vec<_>>(),
+            vec!["name", "status"]
+        );
+        assert_eq!(update.changes["name"].before, json!("Aster"));
+        assert_eq!(update.changes["status"].after, json!("archived"));
+        let create = &timeline[1];
+        assert_eq!(create.action, JournalAction::Create);
+        assert_eq!(create.changes["name"].before, Value::Null);
+        assert_eq!(
+            JournalRepository::find_by_id(&db, create.id).unwrap(),
+            *create
+        );
+    }
+
+    #[test]
+    fn test_origin_and_delete_are_recorded() {
+        let (db, _temp_dir) = setup_test_db();
+        let universe_id = create_universe(&db);
+        let element = JournalRepository::with_origin(ChangeOrigin::Import, || {
+            ElementRepository::create(
+                &db,
+                crate::models::CreateElementInput {
+                    universe_id: universe_id.clone(),
+                    name: "Mira".to_string(),
+                    description: Some("A pilot".to_string()),
+                    element_type: None,
+                    custom_type_name: None,
+                    details: None,
+                    attributes: None,
+                    image_url: None,
+                    tags: None,
+                    relationships: None,
+                    color: None,
+                    icon: None,
+                },
+            )
+            .unwrap()
+        });
+        ElementRepository::delete(&db, &element.id).unwrap();
+
+        let timeline =
+            JournalRepository::timeline(&db, JournalEntityType::Element, &element.id, Some(5))
+                .unwrap();
+
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].action, JournalAction::Delete);
+        assert_eq!(timeline[0].origin, ChangeOrigin::Ui);
+        assert_eq!(timeline[0].changes["name"].after, Value::Null);
+        assert_eq!(timeline[1].origin, ChangeOrigin::Import);
+    }
+
+    #[test]
+    fn test_journal_is_append_only() {
+        let (db, _temp_dir) = setup_test_db();
+        create_universe(&db);
+
+        assert!(db.execute("DELETE FROM change_journal", &[]).is_err());
+        assert!(db
+            .execute("UPDATE change_journal SET origin = 'import'", &[])
+            .is_err());
+    }
+}
@@ -1,9 +1,15 @@
 pub mod container;
 pub mod element;
+pub mod journal;
+pub mod search;
+pub mod session;
 pub mod story;
 pub mod universe;
 
 pub use container::ContainerRepository;
 pub use element::ElementRepository;
+pub use journal::JournalRepository;
+pub use search::SearchRepository;
+pub use session::SessionRepository;
 pub use story::StoryRepository;
 pub use universe::UniverseRepository;
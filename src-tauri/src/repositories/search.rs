@@ -0,0 +1,488 @@
+use crate::db::Database;
+use crate::models::{HighlightRange, SearchEntityType, SearchFilters, SearchHit};
+use rusqlite::Result;
+
+/// Default number of hits returned by a search
+const DEFAULT_LIMIT: u32 = 50;
+
+/// Approximate number of tokens in a snippet
+const SNIPPET_TOKENS: u32 = 24;
+
+/// Markers wrapped around matched terms by FTS5's `highlight()`/`snippet()`
+const HIGHLIGHT_START: char = '\u{2}';
+const HIGHLIGHT_END: char = '\u{3}';
+
+pub struct SearchRepository;
+
+impl SearchRepository {
+    /// Full-text search across stories, elements and universes
+    ///
+    /// Words in `query` must all match (in any order); text in double quotes
+    /// must match as a phrase, and the last bare word also matches as a
+    /// prefix so results appear while typing. Title matches rank above body
+    /// matches. An empty query returns no hits.
+    pub fn search(db: &Database, query: &str, filters: &SearchFilters) -> Result<Vec<SearchHit>> {
+        let Some(match_expression) = build_match_expression(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut sql = String::from(
+            "SELECT search_index.entity_type, search_index.entity_id, search_index.universe_id,
+                    highlight(search_index, 3, char(2), char(3)),
+                    snippet(search_index, -1, char(2), char(3), '…', ?2),
+                    bm25(search_index, 0.0, 0.0, 0.0, 10.0, 1.0) AS rank
+             FROM search_index
+             LEFT JOIN stories st
+                ON search_index.entity_type = 'story' AND st.id = search_index.entity_id
+             LEFT JOIN elements el
+                ON search_index.entity_type = 'element' AND el.id = search_index.entity_id
+             LEFT JOIN universes un
+                ON search_index.entity_type = 'universe' AND un.id = search_index.entity_id
+             WHERE search_index MATCH ?1
+               AND st.deleted_at IS NULL
+               AND search_index.universe_id NOT IN
+                   (SELECT id FROM universes WHERE deleted_at IS NOT NULL)",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> =
+            vec![Box::new(match_expression), Box::new(SNIPPET_TOKENS)];
+
+        if let Some(ref universe_id) = filters.universe_id {
+            sql.push_str(" AND search_index.universe_id = ?");
+            params_vec.push(Box::new(universe_id.clone()));
+        }
+        if let Some(ref entity_types) = filters.entity_types {
+            if !entity_types.is_empty() {
+                let placeholders = vec!["?"; entity_types.len()].join(", ");
+                sql.push_str(&format!(
+                    " AND search_index.entity_type IN ({placeholders})"
+                ));
+                for entity_type in entity_types {
+                    params_vec.push(Box::new(entity_type.as_str()));
+                }
+            }
+        }
+        if let Some(ref status) = filters.status {
+            // Use serde serialization to get the stored lowercase format
+            let status_str = serde_json::to_string(status)
+                .unwrap()
+                .trim_matches('"')
+                .to_string();
+            sql.push_str(" AND st.status = ?");
+            params_vec.push(Box::new(status_str));
+        }
+        if let Some(ref tag) = filters.tag {
+            sql.push_str(
+                " AND EXISTS (SELECT 1 FROM json_each(COALESCE(st.tags, el.tags, un.tags))
+                              WHERE value = ?)",
+            );
+            params_vec.push(Box::new(tag.clone()));
+        }
+
+        sql.push_str(" ORDER BY rank LIMIT ?");
+        params_vec.push(Box::new(filters.limit.unwrap_or(DEFAULT_LIMIT)));
+
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|b| b.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let hits = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let entity_type_str: String = row.get(0)?;
+                let entity_type: SearchEntityType =
+                    serde_json::from_str(&format!("\"{entity_type_str}\"")).unwrap();
+                let (title, title_highlights) = split_highlights(&row.get::<_, String>(3)?);
+                let (snippet, snippet_highlights) = split_highlights(&row.get::<_, String>(4)?);
+                let rank: f64 = row.get(5)?;
+
+                Ok(SearchHit {
+                    entity_type,
+                    entity_id: row.get(1)?,
+                    universe_id: row.get(2)?,
+                    title,
+                    title_highlights,
+                    snippet,
+                    snippet_highlights,
+                    // bm25() is lower-is-better; flip it so higher is better
+                    score: -rank,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(hits)
+    }
+}
+
+/// Turn user input into an FTS5 query that can't fail to parse
+///
+/// Every word or quoted phrase becomes an FTS5 string, so punctuation and
+/// operator keywords (AND, NEAR, ...) are matched literally.
+fn build_match_expression(query: &str) -> Option<String> {
+    let mut terms: Vec<(String, bool)> = Vec::new();
+    let mut current = String::new();
+    let mut in_phrase = false;
+
+    for c in query.chars() {
+        if c == '"' || (c.is_whitespace() && !in_phrase) {
+            if !current.trim().is_empty() {
+                terms.push((current.trim().to_string(), in_phrase));
+            }
+            current.clear();
+            if c == '"' {
+                in_phrase = !in_phrase;
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.trim().is_empty() {
+        terms.push((current.trim().to_string(), in_phrase));
+    }
+
+    let last = terms.len().checked_sub(1)?;
+    let expression = terms
+        .iter()
+        .enumerate()
+        .map(|(i, (term, is_phrase))| {
+            let quoted = format!("\"{}\"", term.replace('"', "\"\""));
+            if i == last && !is_phrase {
+                quoted + "*"
+            } else {
+                quoted
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(expression)
+}
+
+/// Strip highlight markers, returning the plain text and the marked ranges
+/// in UTF-16 code units
+fn split_highlights(marked: &str) -> (String, Vec<HighlightRange>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut ranges = Vec::new();
+    let mut offset = 0u32;
+    let mut start = None;
+
+    for c in marked.chars() {
+        match c {
+            HIGHLIGHT_START => start = Some(offset),
+            HIGHLIGHT_END => {
+                if let Some(start) = start.take() {
+                    ranges.push(HighlightRange { start, end: offset });
+                }
+            }
+            _ => {
+                text.push(c);
+                offset += c.len_utf16() as u32;
+            }
+        }
+    }
+
+    (text, ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        CreateElementInput, CreateStoryInput, ElementType, StoryStatus, StoryType, UpdateStoryInput,
+    };
+    use crate::repositories::{ElementRepository, StoryRepository};
+    use rusqlite::params;
+    use tempfile::TempDir;
+
+    fn setup_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(db_path).unwrap();
+
+        // Run migrations
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        drop(conn);
+
+        // Create two test universes
+        for (id, notes) in [
+            ("universe-1", "The Obsidian Gate guards the northern pass."),
+            ("universe-2", "A quiet seaside town."),
+        ] {
+            db.execute(
+                "INSERT INTO universes (id, name, description, created_at, updated_at, status, worldbuilding_notes)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![id, "Test Universe", "Test", "2024-01-01T00:00:00Z", "2024-01-01T00:00:00Z", "active", notes],
+            )
+            .unwrap();
+        }
+
+        (db, temp_dir)
+    }
+
+    fn create_story(db: &Database, universe_id: &str, title: &str, content: &str) -> String {
+        let input = CreateStoryInput {
+            universe_id: universe_id.to_string(),
+            title: title.to_string(),
+            description: Some("Test".to_string()),
+            story_type: Some(StoryType::ShortStory),
+            content: Some(content.to_string()),
+            notes: None,
+            outline: None,
+            target_word_count: None,
+            tags: Some(vec!["draft-one".to_string()]),
+            color: None,
+            series_name: None,
+            container_id: None,
+            variation_type: None,
+            parent_variation_id: None,
+        };
+        StoryRepository::create(db, input).unwrap().id
+    }
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.entity_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_ranks_title_matches_first() {
+        let (db, _temp_dir) = setup_test_db();
+        let in_body = create_story(
+            &db,
+            "universe-1",
+            "Chapter One",
+            "They rode all night until the Obsidian Gate rose before them.",
+        );
+        let in_title = create_story(&db, "universe-1", "The Obsidian Gate", "It was closed.");
+
+        let hits =
+            SearchRepository::search(&db, "obsidian gate", &SearchFilters::default()).unwrap();
+
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].entity_id, in_title);
+        assert!(ids(&hits).contains(&in_body.as_str()));
+        assert!(ids(&hits).contains(&"universe-1"));
+        assert!(hits[0].score >= hits[1].score);
+    }
+
+    #[test]
+    fn test_search_returns_highlight_offsets() {
+        let (db, _temp_dir) = setup_test_db();
+        create_story(
+            &db,
+            "universe-2",
+            "Café",
+            "Élodie waited by the Obsidian Gate.",
+        );
+
+        let filters = SearchFilters {
+            entity_types: Some(vec![SearchEntityType::Story]),
+            ..Default::default()
+        };
+        let hits = SearchRepository::search(&db, "obsidian", &filters).unwrap();
+
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.snippet, "Élodie waited by the Obsidian Gate.");
+        assert_eq!(hit.snippet_highlights.len(), 1);
+        let range = &hit.snippet_highlights[0];
+        let utf16: Vec<u16> = hit.snippet.encode_utf16().collect();
+        assert_eq!(
+            String::from_utf16(&utf16[range.start as usize..range.end as usize]).unwrap(),
+            "Obsidian"
+        );
+        assert!(hit.title_highlights.is_empty());
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let (db, _temp_dir) = setup_test_db();
+        let story_1 = create_story(&db, "universe-1", "Gatekeeper", "The gate opens.");
+        let story_2 = create_story(&db, "universe-2", "Gate Two", "Another gate.");
+        StoryRepository::update(
+            &db,
+            &story_2,
+            UpdateStoryInput {
+                title: None,
+                description: None,
+                story_type: None,
+                status: Some(StoryStatus::Completed),
+                content: None,
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                order: None,
+                tags: Some(vec!["final".to_string()]),
+                color: None,
+                favorite: None,
+                related_element_ids: None,
+                series_name: None,
+            },
+        )
+        .unwrap();
+        let element = ElementRepository::create(
+            &db,
+            CreateElementInput {
+                universe_id: "universe-1".to_string(),
+                name: "Warden".to_string(),
+                description: Some("Keeps the gate".to_string()),
+                element_type: Some(ElementType::Character),
+                custom_type_name: None,
+                details: None,
+                attributes: None,
+                image_url: None,
+                tags: Some(vec!["final".to_string()]),
+                relationships: None,
+                color: None,
+                icon: None,
+            },
+        )
+        .unwrap();
+
+        let search = |filters: SearchFilters| SearchRepository::search(&db, "gate", &filters);
+
+        let by_universe = search(SearchFilters {
+            universe_id: Some("universe-1".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!ids(&by_universe).contains(&story_2.as_str()));
+        assert!(ids(&by_universe).contains(&element.id.as_str()));
+
+        let by_type = search(SearchFilters {
+            entity_types: Some(vec![SearchEntityType::Element]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(ids(&by_type), vec![element.id.as_str()]);
+
+        let by_tag = search(SearchFilters {
+            tag: Some("final".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut tagged = ids(&by_tag);
+        tagged.sort();
+        let mut expected = vec![story_2.as_str(), element.id.as_str()];
+        expected.sort();
+        assert_eq!(tagged, expected);
+
+        let by_status = search(SearchFilters {
+            status: Some(StoryStatus::Draft),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(ids(&by_status), vec![story_1.as_str()]);
+    }
+
+    #[test]
+    fn test_search_index_follows_updates_and_deletes() {
+        let (db, _temp_dir) = setup_test_db();
+        let story_id = create_story(&db, "universe-2", "Notes", "Mention of lanterns.");
+        db.execute(
+            "UPDATE stories SET content = 'Mention of candles.' WHERE id = ?1",
+            params![&story_id],
+        )
+        .unwrap();
+
+        let filters = SearchFilters::default();
+        assert!(SearchRepository::search(&db, "lanterns", &filters)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            ids(&SearchRepository::search(&db, "candles", &filters).unwrap()),
+            vec![story_id.as_str()]
+        );
+
+        StoryRepository::delete(&db, &story_id).unwrap();
+        assert!(SearchRepository::search(&db, "candles", &filters)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_skips_trashed_entities() {
+        let (db, _temp_dir) = setup_test_db();
+        let story_id = create_story(&db, "universe-1", "Lighthouse", "A lamp in the tower.");
+        let other_id = create_story(&db, "universe-2", "Keeper", "Another lamp.");
+        let filters = SearchFilters::default();
+
+        db.execute(
+            "UPDATE stories SET deleted_at = '2024-01-01T00:00:00+00:00' WHERE id = ?1",
+            params![&story_id],
+        )
+        .unwrap();
+        assert_eq!(
+            ids(&SearchRepository::search(&db, "lamp", &filters).unwrap()),
+            vec![other_id.as_str()]
+        );
+
+        db.execute(
+            "UPDATE universes SET deleted_at = '2024-01-01T00:00:00+00:00' WHERE id = 'universe-2'",
+            &[],
+        )
+        .unwrap();
+        assert!(SearchRepository::search(&db, "lamp", &filters)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_element_attributes() {
+        let (db, _temp_dir) = setup_test_db();
+        let mut attributes = std::collections::HashMap::new();
+        attributes.insert("eyes".to_string(), "violet".to_string());
+        let element = ElementRepository::create(
+            &db,
+            CreateElementInput {
+                universe_id: "universe-1".to_string(),
+                name: "Sarah".to_string(),
+                description: Some(String::new()),
+                element_type: Some(ElementType::Character),
+                custom_type_name: None,
+                details: None,
+                attributes: Some(attributes),
+                image_url: None,
+                tags: None,
+                relationships: None,
+                color: None,
+                icon: None,
+            },
+        )
+        .unwrap();
+
+        let hits = SearchRepository::search(&db, "violet", &SearchFilters::default()).unwrap();
+        assert_eq!(ids(&hits), vec![element.id.as_str()]);
+        assert_eq!(hits[0].snippet, "eyes: violet");
+    }
+
+    #[test]
+    fn test_build_match_expression() {
+        assert_eq!(build_match_expression("   "), None);
+        assert_eq!(
+            build_match_expression("obsidian ga"),
+            Some("\"obsidian\" \"ga\"*".to_string())
+        );
+        assert_eq!(
+            build_match_expression("\"the gate\" NEAR"),
+            Some("\"the gate\" \"NEAR\"*".to_string())
+        );
+        assert_eq!(
+            build_match_expression("\"the gate\""),
+            Some("\"the gate\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_split_highlights() {
+        let (text, ranges) = split_highlights("a \u{2}b\u{3} 😀 \u{2}c\u{3}");
+        assert_eq!(text, "a b 😀 c");
+        assert_eq!(
+            ranges,
+            vec![
+                HighlightRange { start: 2, end: 3 },
+                HighlightRange { start: 7, end: 8 }
+            ]
+        );
+    }
+}
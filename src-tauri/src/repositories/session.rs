@@ -0,0 +1,681 @@
+use crate::db::Database;
+use crate::models::{
+    CreateWritingGoalInput, GoalProgress, GoalType, WritingDay, WritingGoal, WritingScope,
+    WritingStreak,
+};
+use crate::repositories::{ContainerRepository, StoryRepository};
+use chrono::{Duration, NaiveDate, Utc};
+use rusqlite::{params, Result};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+/// Number of recent days the writing pace is averaged over
+pub const PACE_WINDOW_DAYS: i64 = 14;
+
+/// Date format of session days and goal deadlines
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+pub struct SessionRepository;
+
+impl SessionRepository {
+    /// Add words written to a story on a day
+    ///
+    /// Saves on the same day accumulate into one row per story. Saves that
+    /// changed no words are not recorded.
+    pub fn record(
+        db: &Database,
+        story_id: &str,
+        universe_id: &str,
+        day: NaiveDate,
+        words_added: u32,
+        words_removed: u32,
+    ) -> Result<()> {
+        if words_added == 0 && words_removed == 0 {
+            return Ok(());
+        }
+
+        db.execute(
+            "INSERT INTO writing_sessions
+                (story_id, universe_id, day, words_added, words_removed, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (story_id, day) DO UPDATE SET
+                words_added = words_added + excluded.words_added,
+                words_removed = words_removed + excluded.words_removed,
+                universe_id = excluded.universe_id,
+                updated_at = excluded.updated_at",
+            params![
+                story_id,
+                universe_id,
+                day.format(DAY_FORMAT).to_string(),
+                words_added,
+                words_removed,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Words written per day between two days (inclusive)
+    ///
+    /// Only days with activity are returned, oldest first.
+    pub fn daily_history(
+        db: &Database,
+        scope: &WritingScope,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<WritingDay>> {
+        let (scope_clause, scope_id) = Self::scope_clause(scope);
+        let query = format!(
+            "SELECT day, SUM(words_added), SUM(words_removed)
+             FROM writing_sessions
+             WHERE day >= ?1 AND day <= ?2 AND {scope_clause}
+             GROUP BY day
+             ORDER BY day ASC"
+        );
+
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(from.format(DAY_FORMAT).to_string()),
+            Box::new(to.format(DAY_FORMAT).to_string()),
+        ];
+        if let Some(id) = scope_id {
+            params_vec.push(Box::new(id));
+        }
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|b| b.as_ref()).collect();
+
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare(&query)?;
+        let days = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let words_added: u32 = row.get(1)?;
+                let words_removed: u32 = row.get(2)?;
+                Ok(WritingDay {
+                    day: row.get(0)?,
+                    words_added,
+                    words_removed,
+                    net_words: words_added as i32 - words_removed as i32,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(days)
+    }
+
+    /// Current and longest runs of consecutive writing days
+    ///
+    /// # Arguments
+    /// * `threshold` - Words that must be added for a day to count (at least 1)
+    /// * `today` - The local date the current streak is measured up to
+    pub fn streak(
+        db: &Database,
+        scope: &WritingScope,
+        threshold: u32,
+        today: NaiveDate,
+    ) -> Result<WritingStreak> {
+        let beginning = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let history = Self::daily_history(db, scope, beginning, today)?;
+        let threshold = threshold.max(1);
+        let days: BTreeSet<NaiveDate> = history
+            .iter()
+            .filter(|day| day.words_added >= threshold)
+            .filter_map(|day| NaiveDate::parse_from_str(&day.day, DAY_FORMAT).ok())
+            .collect();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in &days {
+            run = match previous {
+                Some(prev) if *day - prev == Duration::days(1) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            previous = Some(*day);
+        }
+
+        // An unfinished today doesn't break the streak
+        let mut day = if days.contains(&today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let mut current = 0;
+        while days.contains(&day) {
+            current += 1;
+            day -= Duration::days(1);
+        }
+
+        Ok(WritingStreak {
+            current,
+            longest,
+            last_writing_day: days.last().map(|d| d.format(DAY_FORMAT).to_string()),
+        })
+    }
+
+    /// Create a writing goal
+    ///
+    /// Daily goals may be limited to a universe. Deadline goals need a
+    /// deadline and exactly one of a container or story.
+    pub fn create_goal(db: &Database, input: CreateWritingGoalInput) -> Result<WritingGoal> {
+        if input.target_words == 0 {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "Goal target must be at least one word".to_string(),
+            ));
+        }
+        match input.goal_type {
+            GoalType::Daily => {
+                if input.container_id.is_some() || input.story_id.is_some() {
+                    return Err(rusqlite::Error::InvalidParameterName(
+                        "Daily goals can only be limited to a universe".to_string(),
+                    ));
+                }
+            }
+            GoalType::Deadline => {
+                if input.container_id.is_some() == input.story_id.is_some() {
+                    return Err(rusqlite::Error::InvalidParameterName(
+                        "Deadline goals need either a container or a story".to_string(),
+                    ));
+                }
+                let valid_deadline = input
+                    .deadline
+                    .as_deref()
+                    .is_some_and(|d| NaiveDate::parse_from_str(d, DAY_FORMAT).is_ok());
+                if !valid_deadline {
+                    return Err(rusqlite::Error::InvalidParameterName(
+                        "Deadline goals need a deadline date (YYYY-MM-DD)".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        let goal_type_str = serde_json::to_string(&input.goal_type)
+            .unwrap()
+            .trim_matches('"')
+            .to_string();
+        let deadline = match input.goal_type {
+            GoalType::Daily => None,
+            GoalType::Deadline => input.deadline,
+        };
+
+        db.execute(
+            "INSERT INTO writing_goals (
+                id, goal_type, target_words, universe_id, container_id, story_id, deadline,
+                created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                &id,
+                &goal_type_str,
+                &input.target_words,
+                &input.universe_id,
+                &input.container_id,
+                &input.story_id,
+                &deadline,
+                &now,
+                &now,
+            ],
+        )?;
+
+        Self::find_goal(db, &id)
+    }
+
+    /// Find a writing goal by ID
+    pub fn find_goal(db: &Database, id: &str) -> Result<WritingGoal> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT id, goal_type, target_words, universe_id, container_id, story_id, deadline,
+                    created_at, updated_at
+             FROM writing_goals WHERE id = ?1",
+            params![id],
+            Self::map_row_to_goal,
+        )
+    }
+
+    /// List all writing goals, oldest first
+    pub fn list_goals(db: &Database) -> Result<Vec<WritingGoal>> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, goal_type, target_words, universe_id, container_id, story_id, deadline,
+                    created_at, updated_at
+             FROM writing_goals
+             ORDER BY created_at ASC",
+        )?;
+
+        let goals = stmt
+            .query_map([], Self::map_row_to_goal)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(goals)
+    }
+
+    /// Delete a writing goal
+    pub fn delete_goal(db: &Database, id: &str) -> Result<()> {
+        db.execute("DELETE FROM writing_goals WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Target of the daily goal that applies to a universe
+    ///
+    /// A goal for the universe itself takes precedence over a global one.
+    pub fn daily_target(db: &Database, universe_id: Option<&str>) -> Result<Option<u32>> {
+        let goals = Self::list_goals(db)?;
+        let daily = |universe: Option<&str>| {
+            goals
+                .iter()
+                .find(|g| g.goal_type == GoalType::Daily && g.universe_id.as_deref() == universe)
+                .map(|g| g.target_words)
+        };
+
+        Ok(universe_id
+            .and_then(|id| daily(Some(id)))
+            .or_else(|| daily(None)))
+    }
+
+    /// Measure a goal as of `today`
+    ///
+    /// The pace is the average net words per day over the last
+    /// `PACE_WINDOW_DAYS` days (or since the goal was created, if later) and
+    /// drives the projected completion date of deadline goals.
+    pub fn goal_progress(
+        db: &Database,
+        goal: WritingGoal,
+        today: NaiveDate,
+    ) -> Result<GoalProgress> {
+        let scope = WritingScope {
+            universe_id: goal.universe_id.clone(),
+            container_id: goal.container_id.clone(),
+            story_id: goal.story_id.clone(),
+        };
+
+        let created = chrono::DateTime::parse_from_rfc3339(&goal.created_at)
+            .map(|dt| dt.date_naive())
+            .unwrap_or(today);
+        let window_start = (today - Duration::days(PACE_WINDOW_DAYS - 1)).max(created.min(today));
+        let window_days = (today - window_start).num_days() + 1;
+        let recent = Self::daily_history(db, &scope, window_start, today)?;
+        let net: i64 = recent.iter().map(|d| d.net_words as i64).sum();
+        let average_daily_words = (net / window_days) as i32;
+
+        let current_words = match goal.goal_type {
+            GoalType::Daily => {
+                let today_str = today.format(DAY_FORMAT).to_string();
+                recent
+                    .iter()
+                    .find(|d| d.day == today_str)
+                    .map_or(0, |d| d.words_added)
+            }
+            GoalType::Deadline => match (&goal.story_id, &goal.container_id) {
+                (Some(story_id), _) => StoryRepository::find_by_id(db, story_id)?.word_count,
+                (None, Some(container_id)) => {
+                    ContainerRepository::get_subtree_stats(db, container_id)?
+                        .into_iter()
+                        .find(|s| &s.container_id == container_id)
+                        .map_or(0, |s| s.stats.word_count)
+                }
+                (None, None) => 0,
+            },
+        };
+        let remaining_words = goal.target_words.saturating_sub(current_words);
+        let completed = remaining_words == 0;
+
+        let deadline = goal
+            .deadline
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, DAY_FORMAT).ok());
+        let (words_per_day_needed, projected_completion, on_track) = match deadline {
+            Some(_) if completed => (Some(0), None, true),
+            Some(deadline) => {
+                let days_left = (deadline - today).num_days() + 1;
+                let needed = (days_left > 0).then(|| remaining_words.div_ceil(days_left as u32));
+                let projected =
+                    (average_daily_words > 0).then(|| {
+                        today
+                            + Duration::days(
+                                remaining_words.div_ceil(average_daily_words as u32) as i64
+                            )
+                    });
+                let on_track = projected.is_some_and(|p| p <= deadline);
+                (
+                    needed,
+                    projected.map(|p| p.format(DAY_FORMAT).to_string()),
+                    on_track,
+                )
+            }
+            None => (None, None, completed),
+        };
+
+        Ok(GoalProgress {
+            goal,
+            current_words,
+            remaining_words,
+            completed,
+            average_daily_words,
+            words_per_day_needed,
+            projected_completion,
+            on_track,
+        })
+    }
+
+    /// SQL condition (and its parameter) selecting the sessions in a scope
+    fn scope_clause(scope: &WritingScope) -> (&'static str, Option<String>) {
+        if let Some(story_id) = &scope.story_id {
+            ("story_id = ?3", Some(story_id.clone()))
+        } else if let Some(container_id) = &scope.container_id {
+            (
+                "story_id IN (
+                    WITH RECURSIVE subtree(id) AS (
+                        SELECT ?3
+                        UNION ALL
+                        SELECT c.id FROM containers c
+                        INNER JOIN subtree s ON c.parent_container_id = s.id
+                    )
+                    SELECT id FROM stories WHERE container_id IN (SELECT id FROM subtree)
+                )",
+                Some(container_id.clone()),
+            )
+        } else if let Some(universe_id) = &scope.universe_id {
+            ("universe_id = ?3", Some(universe_id.clone()))
+        } else {
+            ("1 = 1", None)
+        }
+    }
+
+    /// Helper function to map a row to WritingGoal struct
+    fn map_row_to_goal(row: &rusqlite::Row) -> Result<WritingGoal> {
+        let goal_type_str: String = row.get(1)?;
+        let goal_type: GoalType = serde_json::from_str(&format!("\"{goal_type_str}\"")).unwrap();
+
+        Ok(WritingGoal {
+            id: row.get(0)?,
+            goal_type,
+            target_words: row.get(2)?,
+            universe_id: row.get(3)?,
+            container_id: row.get(4)?,
+            story_id: row.get(5)?,
+            deadline: row.get(6)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateStoryInput, StoryType, UpdateStoryInput};
+    use chrono::Local;
+    use tempfile::TempDir;
+
+    fn setup_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(db_path).unwrap();
+
+        // Run migrations
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        drop(conn);
+
+        // Create a test universe
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status) VALUES (?, ?, ?, ?, ?, ?)",
+            params!["universe-1", "Test Universe", "Test", "2024-01-01T00:00:00Z", "2024-01-01T00:00:00Z", "active"],
+        )
+        .unwrap();
+
+        (db, temp_dir)
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DAY_FORMAT).unwrap()
+    }
+
+    fn create_story(db: &Database, container_id: Option<String>) -> String {
+        let input = CreateStoryInput {
+            universe_id: "universe-1".to_string(),
+            title: "Chapter".to_string(),
+            description: Some(String::new()),
+            story_type: Some(StoryType::Chapter),
+            content: None,
+            notes: None,
+            outline: None,
+            target_word_count: None,
+            tags: None,
+            color: None,
+            series_name: None,
+            container_id,
+            variation_type: None,
+            parent_variation_id: None,
+        };
+        StoryRepository::create(db, input).unwrap().id
+    }
+
+    fn deadline_input(container_id: Option<String>, deadline: &str) -> CreateWritingGoalInput {
+        CreateWritingGoalInput {
+            goal_type: GoalType::Deadline,
+            target_words: 100,
+            universe_id: None,
+            container_id,
+            story_id: None,
+            deadline: Some(deadline.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_content_saves_accumulate_into_todays_session() {
+        let (db, _temp_dir) = setup_test_db();
+        let story_id = create_story(&db, None);
+        let save = |content: &str| {
+            let input = UpdateStoryInput {
+                content: Some(content.to_string()),
+                ..Default::default()
+            };
+            StoryRepository::update(&db, &story_id, input).unwrap();
+        };
+
+        save("One two three");
+        save("One two three four five");
+        save("One three four five");
+
+        let today = Local::now().date_naive();
+        let scope = WritingScope {
+            story_id: Some(story_id.clone()),
+            ..Default::default()
+        };
+        let history = SessionRepository::daily_history(&db, &scope, today, today).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].words_added, 5);
+        assert_eq!(history[0].words_removed, 1);
+        assert_eq!(history[0].net_words, 4);
+    }
+
+    #[test]
+    fn test_daily_history_filters_by_container_subtree() {
+        let (db, _temp_dir) = setup_test_db();
+        let series = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            None,
+            "series".to_string(),
+            "Series".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+        let novel = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            Some(series.id.clone()),
+            "novel".to_string(),
+            "Novel".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+        let in_novel = create_story(&db, Some(novel.id.clone()));
+        let standalone = create_story(&db, None);
+        let today = day("2024-03-10");
+        SessionRepository::record(&db, &in_novel, "universe-1", today, 300, 0).unwrap();
+        SessionRepository::record(&db, &standalone, "universe-1", today, 50, 10).unwrap();
+
+        let series_scope = WritingScope {
+            container_id: Some(series.id.clone()),
+            ..Default::default()
+        };
+        let universe_scope = WritingScope {
+            universe_id: Some("universe-1".to_string()),
+            ..Default::default()
+        };
+
+        let series_days = SessionRepository::daily_history(&db, &series_scope, today, today);
+        assert_eq!(series_days.unwrap()[0].words_added, 300);
+        let universe_days = SessionRepository::daily_history(&db, &universe_scope, today, today);
+        assert_eq!(universe_days.unwrap()[0].net_words, 340);
+    }
+
+    #[test]
+    fn test_streak_counts_consecutive_days_meeting_threshold() {
+        let (db, _temp_dir) = setup_test_db();
+        let story_id = create_story(&db, None);
+        for (d, words) in [
+            ("2024-03-01", 500),
+            ("2024-03-02", 600),
+            ("2024-03-03", 700),
+            ("2024-03-05", 500),
+            ("2024-03-06", 100),
+            ("2024-03-07", 800),
+            ("2024-03-08", 900),
+        ] {
+            SessionRepository::record(&db, &story_id, "universe-1", day(d), words, 0).unwrap();
+        }
+        let scope = WritingScope::default();
+
+        // Today (the 9th) has no writing yet, so the streak runs up to yesterday
+        let streak = SessionRepository::streak(&db, &scope, 1, day("2024-03-09")).unwrap();
+        assert_eq!(streak.current, 4);
+        assert_eq!(streak.longest, 4);
+        assert_eq!(streak.last_writing_day.as_deref(), Some("2024-03-08"));
+
+        // The 6th misses a 500-word goal
+        let streak = SessionRepository::streak(&db, &scope, 500, day("2024-03-08")).unwrap();
+        assert_eq!(streak.current, 2);
+        assert_eq!(streak.longest, 3);
+
+        let streak = SessionRepository::streak(&db, &scope, 1, day("2024-03-11")).unwrap();
+        assert_eq!(streak.current, 0);
+    }
+
+    #[test]
+    fn test_create_goal_validates_scope_and_deadline() {
+        let (db, _temp_dir) = setup_test_db();
+        let container = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            None,
+            "novel".to_string(),
+            "Novel".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+
+        assert!(SessionRepository::create_goal(&db, deadline_input(None, "2024-12-31")).is_err());
+        assert!(SessionRepository::create_goal(
+            &db,
+            deadline_input(Some(container.id.clone()), "end of year")
+        )
+        .is_err());
+
+        let goal =
+            SessionRepository::create_goal(&db, deadline_input(Some(container.id), "2024-12-31"))
+                .unwrap();
+        assert_eq!(goal.goal_type, GoalType::Deadline);
+        assert_eq!(goal.deadline.as_deref(), Some("2024-12-31"));
+        assert_eq!(SessionRepository::list_goals(&db).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_goal_progress_projects_completion_from_pace() {
+        let (db, _temp_dir) = setup_test_db();
+        let container = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            None,
+            "novel".to_string(),
+            "Novel".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+        let story_id = create_story(&db, Some(container.id.clone()));
+        let input = UpdateStoryInput {
+            content: Some("word ".repeat(40)),
+            ..Default::default()
+        };
+        StoryRepository::update(&db, &story_id, input).unwrap();
+
+        // 140 net words over the 14-day window: 10 words a day
+        let today = day("2024-03-14");
+        db.execute("DELETE FROM writing_sessions", &[]).unwrap();
+        SessionRepository::record(&db, &story_id, "universe-1", day("2024-03-01"), 100, 0).unwrap();
+        SessionRepository::record(&db, &story_id, "universe-1", day("2024-03-14"), 50, 10).unwrap();
+
+        let goal =
+            SessionRepository::create_goal(&db, deadline_input(Some(container.id), "2024-03-20"))
+                .unwrap();
+        db.execute(
+            "UPDATE writing_goals SET created_at = '2024-01-01T00:00:00Z'",
+            &[],
+        )
+        .unwrap();
+        let goal = SessionRepository::find_goal(&db, &goal.id).unwrap();
+
+        let progress = SessionRepository::goal_progress(&db, goal, today).unwrap();
+
+        assert_eq!(progress.current_words, 40);
+        assert_eq!(progress.remaining_words, 60);
+        assert_eq!(progress.average_daily_words, 10);
+        assert_eq!(progress.words_per_day_needed, Some(9));
+        assert_eq!(progress.projected_completion.as_deref(), Some("2024-03-20"));
+        assert!(progress.on_track);
+        assert!(!progress.completed);
+    }
+
+    #[test]
+    fn test_daily_goal_progress_counts_words_added_today() {
+        let (db, _temp_dir) = setup_test_db();
+        let story_id = create_story(&db, None);
+        let today = day("2024-03-14");
+        SessionRepository::record(&db, &story_id, "universe-1", today, 80, 30).unwrap();
+        let goal = SessionRepository::create_goal(
+            &db,
+            CreateWritingGoalInput {
+                goal_type: GoalType::Daily,
+                target_words: 100,
+                universe_id: Some("universe-1".to_string()),
+                container_id: None,
+                story_id: None,
+                deadline: None,
+            },
+        )
+        .unwrap();
+
+        let progress = SessionRepository::goal_progress(&db, goal, today).unwrap();
+
+        assert_eq!(progress.current_words, 80);
+        assert_eq!(progress.remaining_words, 20);
+        assert_eq!(progress.projected_completion, None);
+        assert_eq!(
+            SessionRepository::daily_target(&db, Some("universe-1")).unwrap(),
+            Some(100)
+        );
+        assert_eq!(SessionRepository::daily_target(&db, None).unwrap(), None);
+    }
+}
@@ -1,9 +1,13 @@
 use crate::db::Database;
 use crate::models::{
-    CreateStoryInput, Story, StoryStatus, StoryType, UpdateStoryInput, VariationType,
+    CreateStoryInput, JournalEntityType, Story, StoryStatus, StoryType, UpdateStoryInput,
+    VariationType,
 };
-use chrono::Utc;
-use rusqlite::{params, Result};
+use crate::repositories::{ContainerRepository, JournalRepository, SessionRepository};
+use crate::text_stats;
+use crate::word_diff::count_word_changes;
+use chrono::{Local, Utc};
+use rusqlite::{params, OptionalExtension, Result};
 use uuid::Uuid;
 
 pub struct StoryRepository;
@@ -51,6 +55,8 @@ impl StoryRepository {
             })
             .unwrap_or_else(|| "original".to_string());
         let tags_json = input.tags.map(|t| serde_json::to_string(&t).unwrap());
+        let content = input.content.unwrap_or_default();
+        let stats = text_stats::compute(&content);
 
         db.execute(
             "INSERT INTO stories (
@@ -58,8 +64,10 @@ impl StoryRepository {
                 content, variation_group_id, variation_type, parent_variation_id,
                 git_repo_path, current_branch, staged_changes, created_at, updated_at,
                 notes, outline, target_word_count, \"order\", tags, color, favorite,
-                related_element_ids, series_name, container_id, last_edited_at, version
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28)",
+                related_element_ids, series_name, container_id, last_edited_at, version,
+                character_count, character_count_no_spaces, paragraph_count, sentence_count,
+                reading_time_seconds
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33)",
             params![
                 &id,
                 &input.universe_id,
@@ -67,8 +75,8 @@ impl StoryRepository {
                 &input.description,
                 &story_type_str,
                 "draft",
-                0,
-                &input.content.unwrap_or_default(),
+                &stats.word_count,
+                &content,
                 &variation_group_id,
                 &variation_type_str,
                 &input.parent_variation_id,
@@ -89,10 +97,102 @@ impl StoryRepository {
                 &input.container_id,
                 &now,  // last_edited_at
                 1,  // version
+                &stats.character_count,
+                &stats.character_count_no_spaces,
+                &stats.paragraph_count,
+                &stats.sentence_count,
+                &stats.reading_time_seconds,
             ],
         )?;
 
-        Self::find_by_id(db, &id)
+        let story = Self::find_by_id(db, &id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Story,
+            &story.id,
+            &story.universe_id,
+            None,
+            Some(&story),
+        )?;
+        Ok(story)
+    }
+
+    /// Insert a fully-formed Story, keeping its ID and timestamps
+    ///
+    /// Used when restoring stories whose identity comes from elsewhere
+    /// (e.g. a git repository's metadata.json). No leaf protection is applied.
+    pub fn insert(db: &Database, story: &Story) -> Result<Story> {
+        let to_str = |value: String| value.trim_matches('"').to_string();
+        let story_type_str = to_str(serde_json::to_string(&story.story_type).unwrap());
+        let status_str = to_str(serde_json::to_string(&story.status).unwrap());
+        let variation_type_str = to_str(serde_json::to_string(&story.variation_type).unwrap());
+        let tags_json = story
+            .tags
+            .as_ref()
+            .map(|t| serde_json::to_string(t).unwrap());
+        let related_elements_json = story
+            .related_element_ids
+            .as_ref()
+            .map(|ids| serde_json::to_string(ids).unwrap());
+        let stats = text_stats::compute(&story.content);
+
+        db.execute(
+            "INSERT INTO stories (
+                id, universe_id, title, description, story_type, status, word_count,
+                content, variation_group_id, variation_type, parent_variation_id,
+                git_repo_path, current_branch, staged_changes, created_at, updated_at,
+                notes, outline, target_word_count, \"order\", tags, color, favorite,
+                related_element_ids, series_name, container_id, last_edited_at, version,
+                character_count, character_count_no_spaces, paragraph_count, sentence_count,
+                reading_time_seconds
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33)",
+            params![
+                &story.id,
+                &story.universe_id,
+                &story.title,
+                &story.description,
+                &story_type_str,
+                &status_str,
+                &stats.word_count,
+                &story.content,
+                &story.variation_group_id,
+                &variation_type_str,
+                &story.parent_variation_id,
+                &story.git_repo_path,
+                &story.current_branch,
+                &story.staged_changes,
+                &story.created_at,
+                &story.updated_at,
+                &story.notes,
+                &story.outline,
+                &story.target_word_count,
+                &story.order.unwrap_or(0),
+                &tags_json,
+                &story.color,
+                &story.favorite.unwrap_or(false),
+                &related_elements_json,
+                &story.series_name,
+                &story.container_id,
+                &story.last_edited_at,
+                &story.version,
+                &stats.character_count,
+                &stats.character_count_no_spaces,
+                &stats.paragraph_count,
+                &stats.sentence_count,
+                &stats.reading_time_seconds,
+            ],
+        )?;
+
+        let inserted = Self::find_by_id(db, &story.id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Story,
+            &inserted.id,
+            &inserted.universe_id,
+            None,
+            Some(&inserted),
+        )?;
+        Ok(inserted)
     }
 
     /// Find a Story by ID
@@ -105,7 +205,9 @@ impl StoryRepository {
                     content, variation_group_id, variation_type, parent_variation_id,
                     git_repo_path, current_branch, staged_changes, created_at, updated_at,
                     notes, outline, target_word_count, \"order\", tags, color, favorite,
-                    related_element_ids, series_name, container_id, last_edited_at, version
+                    related_element_ids, series_name, container_id, last_edited_at, version,
+                    character_count, character_count_no_spaces, paragraph_count,
+                    sentence_count, reading_time_seconds
              FROM stories WHERE id = ?1",
             params![id],
             Self::map_row_to_story,
@@ -122,9 +224,11 @@ impl StoryRepository {
                     content, variation_group_id, variation_type, parent_variation_id,
                     git_repo_path, current_branch, staged_changes, created_at, updated_at,
                     notes, outline, target_word_count, \"order\", tags, color, favorite,
-                    related_element_ids, series_name, container_id, last_edited_at, version
+                    related_element_ids, series_name, container_id, last_edited_at, version,
+                    character_count, character_count_no_spaces, paragraph_count,
+                    sentence_count, reading_time_seconds
              FROM stories
-             WHERE universe_id = ?1
+             WHERE universe_id = ?1 AND deleted_at IS NULL
              ORDER BY updated_at DESC",
         )?;
 
@@ -145,9 +249,11 @@ impl StoryRepository {
                     content, variation_group_id, variation_type, parent_variation_id,
                     git_repo_path, current_branch, staged_changes, created_at, updated_at,
                     notes, outline, target_word_count, \"order\", tags, color, favorite,
-                    related_element_ids, series_name, container_id, last_edited_at, version
+                    related_element_ids, series_name, container_id, last_edited_at, version,
+                    character_count, character_count_no_spaces, paragraph_count,
+                    sentence_count, reading_time_seconds
              FROM stories
-             WHERE variation_group_id = ?1
+             WHERE variation_group_id = ?1 AND deleted_at IS NULL
              ORDER BY created_at ASC",
         )?;
 
@@ -168,9 +274,11 @@ impl StoryRepository {
                     content, variation_group_id, variation_type, parent_variation_id,
                     git_repo_path, current_branch, staged_changes, created_at, updated_at,
                     notes, outline, target_word_count, \"order\", tags, color, favorite,
-                    related_element_ids, series_name, container_id, last_edited_at, version
+                    related_element_ids, series_name, container_id, last_edited_at, version,
+                    character_count, character_count_no_spaces, paragraph_count,
+                    sentence_count, reading_time_seconds
              FROM stories
-             WHERE container_id = ?1
+             WHERE container_id = ?1 AND deleted_at IS NULL
              ORDER BY \"order\" ASC, created_at ASC",
         )?;
 
@@ -191,9 +299,11 @@ impl StoryRepository {
                     content, variation_group_id, variation_type, parent_variation_id,
                     git_repo_path, current_branch, staged_changes, created_at, updated_at,
                     notes, outline, target_word_count, \"order\", tags, color, favorite,
-                    related_element_ids, series_name, container_id, last_edited_at, version
+                    related_element_ids, series_name, container_id, last_edited_at, version,
+                    character_count, character_count_no_spaces, paragraph_count,
+                    sentence_count, reading_time_seconds
              FROM stories
-             WHERE universe_id = ?1 AND container_id IS NULL
+             WHERE universe_id = ?1 AND container_id IS NULL AND deleted_at IS NULL
              ORDER BY updated_at DESC",
         )?;
 
@@ -211,14 +321,14 @@ impl StoryRepository {
         story_ids: Vec<String>,
     ) -> Result<()> {
         let conn = db.connection();
-        let conn = conn.lock().unwrap();
+        let mut conn = conn.lock().unwrap();
 
-        // Start transaction
-        conn.execute("BEGIN TRANSACTION", [])?;
+        // Start transaction (a savepoint, so it nests in `Database::transaction`)
+        let tx = conn.savepoint()?;
 
         // Validate that all story_ids belong to the container
         for story_id in &story_ids {
-            let container_check: Result<Option<String>, _> = conn.query_row(
+            let container_check: Result<Option<String>, _> = tx.query_row(
                 "SELECT container_id FROM stories WHERE id = ?1",
                 params![story_id],
                 |row| row.get(0),
@@ -226,35 +336,60 @@ impl StoryRepository {
 
             match container_check {
                 Ok(Some(cid)) if cid == container_id => {}
-                Ok(Some(_)) | Ok(None) => {
-                    conn.execute("ROLLBACK", [])?;
-                    return Err(rusqlite::Error::QueryReturnedNoRows);
-                }
-                Err(e) => {
-                    conn.execute("ROLLBACK", [])?;
-                    return Err(e);
-                }
+                Ok(Some(_)) | Ok(None) => return Err(rusqlite::Error::QueryReturnedNoRows),
+                Err(e) => return Err(e),
             }
         }
 
         // Update order for each story
         for (index, story_id) in story_ids.iter().enumerate() {
-            conn.execute(
+            tx.execute(
                 "UPDATE stories SET \"order\" = ?1 WHERE id = ?2",
                 params![index as u32, story_id],
             )?;
         }
 
         // Commit transaction
-        conn.execute("COMMIT", [])?;
+        tx.commit()?;
 
         Ok(())
     }
 
     /// Update a Story
+    ///
+    /// Content changes recompute the content statistics and add the words
+    /// added and removed to today's writing session.
     pub fn update(db: &Database, id: &str, input: UpdateStoryInput) -> Result<Story> {
+        Self::apply_update(db, id, input, true)
+    }
+
+    /// Update a Story without counting content changes as writing
+    ///
+    /// For text that moves between stories (splits and merges), which would
+    /// otherwise show up as words removed from one story in today's session.
+    pub fn update_without_session(
+        db: &Database,
+        id: &str,
+        input: UpdateStoryInput,
+    ) -> Result<Story> {
+        Self::apply_update(db, id, input, false)
+    }
+
+    fn apply_update(
+        db: &Database,
+        id: &str,
+        input: UpdateStoryInput,
+        record_session: bool,
+    ) -> Result<Story> {
+        let before = Self::find_by_id(db, id)?;
         let now = Utc::now().to_rfc3339();
 
+        let word_changes = input
+            .content
+            .as_ref()
+            .filter(|_| record_session)
+            .map(|content| count_word_changes(&before.content, content));
+
         // Use serde serialization to get correct kebab-case/lowercase format
         let story_type_str = input.story_type.map(|st| {
             serde_json::to_string(&st)
@@ -293,8 +428,19 @@ impl StoryRepository {
             params_vec.push(Box::new(status));
         }
         if let Some(content) = input.content {
+            let stats = text_stats::compute(&content);
             updates.push("content = ?");
             params_vec.push(Box::new(content));
+            updates.push(
+                "word_count = ?, character_count = ?, character_count_no_spaces = ?, \
+                 paragraph_count = ?, sentence_count = ?, reading_time_seconds = ?",
+            );
+            params_vec.push(Box::new(stats.word_count));
+            params_vec.push(Box::new(stats.character_count));
+            params_vec.push(Box::new(stats.character_count_no_spaces));
+            params_vec.push(Box::new(stats.paragraph_count));
+            params_vec.push(Box::new(stats.sentence_count));
+            params_vec.push(Box::new(stats.reading_time_seconds));
         }
         if let Some(notes) = input.notes {
             updates.push("notes = ?");
@@ -342,16 +488,141 @@ impl StoryRepository {
 
         db.execute(&query, &params_refs)?;
 
-        Self::find_by_id(db, id)
+        if let Some((added, removed)) = word_changes {
+            let today = Local::now().date_naive();
+            SessionRepository::record(db, id, &before.universe_id, today, added, removed)?;
+        }
+
+        let story = Self::find_by_id(db, id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Story,
+            id,
+            &story.universe_id,
+            Some(&before),
+            Some(&story),
+        )?;
+        Ok(story)
     }
 
     /// Delete a Story
     /// Note: Container hierarchy is managed by the Container repository
     pub fn delete(db: &Database, id: &str) -> Result<()> {
+        let story = Self::find_by_id(db, id).optional()?;
         db.execute("DELETE FROM stories WHERE id = ?1", params![id])?;
+        if let Some(story) = story {
+            JournalRepository::record(
+                db,
+                JournalEntityType::Story,
+                id,
+                &story.universe_id,
+                Some(&story),
+                None,
+            )?;
+        }
         Ok(())
     }
 
+    /// Move a Story into a container, or out of one to stand alone
+    ///
+    /// A target container must be in `universe_id`, outside the trash and
+    /// without child containers. Moving to another universe drops the
+    /// story's related elements, which belong to the old universe. Stories
+    /// moving into a container take the container's repo, so their own
+    /// repo path is cleared; the caller moves the content between repos.
+    ///
+    /// # Arguments
+    /// * `order` - Position in the target container (after the last story if
+    ///   None). Ignored for standalone stories, which keep their order.
+    pub fn move_to(
+        db: &Database,
+        id: &str,
+        universe_id: &str,
+        container_id: Option<&str>,
+        order: Option<u32>,
+    ) -> Result<Story> {
+        let before = Self::find_by_id(db, id)?;
+        if Self::is_trashed(db, id)? {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "Cannot move a story that is in the trash".to_string(),
+            ));
+        }
+
+        if let Some(container_id) = container_id {
+            let container = ContainerRepository::find_by_id(db, container_id)?;
+            if ContainerRepository::is_trashed(db, container_id)? {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Container is in the trash".to_string(),
+                ));
+            }
+            if container.universe_id != universe_id {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Container belongs to a different universe".to_string(),
+                ));
+            }
+            // Leaf Protection: Check if container has child containers
+            if Self::get_child_container_count(db, container_id)? > 0 {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Cannot add story to a container that has child containers".to_string(),
+                ));
+            }
+        }
+
+        {
+            let conn = db.connection();
+            let mut conn = conn.lock().unwrap();
+            let tx = conn.savepoint()?;
+
+            let order = match (container_id, order) {
+                (Some(_), Some(order)) => order,
+                (Some(container_id), None) => tx.query_row(
+                    "SELECT COALESCE(MAX(\"order\") + 1, 0) FROM stories
+                     WHERE container_id = ?1 AND id != ?2 AND deleted_at IS NULL",
+                    params![container_id, id],
+                    |row| row.get(0),
+                )?,
+                (None, _) => before.order.unwrap_or(0),
+            };
+            tx.execute(
+                "UPDATE stories SET universe_id = ?1, container_id = ?2, \"order\" = ?3,
+                        updated_at = ?4
+                 WHERE id = ?5",
+                params![
+                    universe_id,
+                    container_id,
+                    order,
+                    Utc::now().to_rfc3339(),
+                    id
+                ],
+            )?;
+            if before.universe_id != universe_id {
+                tx.execute(
+                    "UPDATE stories SET related_element_ids = NULL WHERE id = ?1",
+                    params![id],
+                )?;
+            }
+            if container_id.is_some() {
+                tx.execute(
+                    "UPDATE stories SET git_repo_path = '', current_branch = 'main' WHERE id = ?1",
+                    params![id],
+                )?;
+            }
+
+            tx.commit()?;
+        }
+
+        let story = Self::find_by_id(db, id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Story,
+            &story.id,
+            &story.universe_id,
+            Some(&before),
+            Some(&story),
+        )?;
+        Ok(story)
+    }
+
     /// Update the git repo path for a story (internal use)
     pub fn set_git_repo_path(db: &Database, id: &str, git_repo_path: &str) -> Result<()> {
         db.execute(
@@ -370,6 +641,40 @@ impl StoryRepository {
         Ok(())
     }
 
+    /// Get the git repository a story's content lives in
+    ///
+    /// Standalone stories have their own repo; stories in a container share
+    /// the container's repo. Returns None if that repo hasn't been initialized.
+    pub fn find_git_repo_path(db: &Database, story: &Story) -> Result<Option<String>> {
+        let repo_path = match story.container_id {
+            None => Some(story.git_repo_path.clone()),
+            Some(ref container_id) => {
+                let conn = db.connection();
+                let conn = conn.lock().unwrap();
+
+                conn.query_row(
+                    "SELECT git_repo_path FROM containers WHERE id = ?1",
+                    params![container_id],
+                    |row| row.get::<_, Option<String>>(0),
+                )?
+            }
+        };
+
+        Ok(repo_path.filter(|path| !path.is_empty()))
+    }
+
+    /// Check whether a story is in the trash
+    pub fn is_trashed(db: &Database, id: &str) -> Result<bool> {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT deleted_at IS NOT NULL FROM stories WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
     /// Get the count of child containers in a container (used for leaf protection)
     fn get_child_container_count(db: &Database, container_id: &str) -> Result<i32> {
         let conn = db.connection();
@@ -428,6 +733,11 @@ impl StoryRepository {
             container_id: row.get(25)?,
             last_edited_at: row.get(26)?,
             version: row.get(27)?,
+            character_count: row.get(28)?,
+            character_count_no_spaces: row.get(29)?,
+            paragraph_count: row.get(30)?,
+            sentence_count: row.get(31)?,
+            reading_time_seconds: row.get(32)?,
         })
     }
 }
@@ -493,6 +803,51 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_content_statistics_follow_content() {
+        let (db, _temp_dir) = setup_test_db();
+        let input = CreateStoryInput {
+            universe_id: "universe-1".to_string(),
+            title: "Counted".to_string(),
+            description: Some("Test".to_string()),
+            story_type: Some(StoryType::ShortStory),
+            content: Some("One **two** three.".to_string()),
+            notes: None,
+            outline: None,
+            target_word_count: None,
+            tags: None,
+            color: None,
+            series_name: None,
+            container_id: None,
+            variation_type: None,
+            parent_variation_id: None,
+        };
+        let story = StoryRepository::create(&db, input).unwrap();
+        assert_eq!(story.word_count, 3);
+        assert_eq!(story.sentence_count, 1);
+
+        let input = UpdateStoryInput {
+            content: Some("Four words, two sentences. Yes!\n\nAnother paragraph here.".to_string()),
+            ..Default::default()
+        };
+        let updated = StoryRepository::update(&db, &story.id, input).unwrap();
+
+        assert_eq!(updated.word_count, 8);
+        assert_eq!(updated.paragraph_count, 2);
+        assert_eq!(updated.sentence_count, 3);
+        let stats = crate::text_stats::compute(&updated.content);
+        assert_eq!(updated.character_count, stats.character_count);
+        assert_eq!(updated.reading_time_seconds, stats.reading_time_seconds);
+
+        // Updates that don't touch content keep the counts
+        let input = UpdateStoryInput {
+            title: Some("Renamed".to_string()),
+            ..Default::default()
+        };
+        let renamed = StoryRepository::update(&db, &story.id, input).unwrap();
+        assert_eq!(renamed.word_count, 8);
+    }
+
     #[test]
     fn test_list_by_container() {
         let (db, _temp_dir) = setup_test_db();
@@ -805,6 +1160,65 @@ mod tests {
         assert_eq!(updated.git_repo_path, "/path/to/repo");
     }
 
+    #[test]
+    fn test_find_git_repo_path_for_standalone_and_child_stories() {
+        let (db, _temp_dir) = setup_test_db();
+
+        let container = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            None,
+            "novel".to_string(),
+            "Test Container".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+
+        let make_input = |container_id: Option<String>| CreateStoryInput {
+            universe_id: "universe-1".to_string(),
+            title: "Story".to_string(),
+            description: Some("Test".to_string()),
+            story_type: Some(StoryType::Chapter),
+            content: None,
+            notes: None,
+            outline: None,
+            target_word_count: None,
+            tags: None,
+            color: None,
+            series_name: None,
+            container_id,
+            variation_type: None,
+            parent_variation_id: None,
+        };
+
+        let standalone = StoryRepository::create(&db, make_input(None)).unwrap();
+        let child = StoryRepository::create(&db, make_input(Some(container.id.clone()))).unwrap();
+
+        // Neither repo is initialized yet
+        assert_eq!(
+            StoryRepository::find_git_repo_path(&db, &standalone).unwrap(),
+            None
+        );
+        assert_eq!(
+            StoryRepository::find_git_repo_path(&db, &child).unwrap(),
+            None
+        );
+
+        StoryRepository::set_git_repo_path(&db, &standalone.id, "/repos/standalone").unwrap();
+        ContainerRepository::set_git_repo_path(&db, &container.id, "/repos/container").unwrap();
+        let standalone = StoryRepository::find_by_id(&db, &standalone.id).unwrap();
+
+        assert_eq!(
+            StoryRepository::find_git_repo_path(&db, &standalone).unwrap(),
+            Some("/repos/standalone".to_string())
+        );
+        assert_eq!(
+            StoryRepository::find_git_repo_path(&db, &child).unwrap(),
+            Some("/repos/container".to_string())
+        );
+    }
+
     #[test]
     fn test_set_current_branch() {
         let (db, _temp_dir) = setup_test_db();
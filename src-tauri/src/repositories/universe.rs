@@ -1,7 +1,10 @@
 use crate::db::Database;
-use crate::models::{CreateUniverseInput, Universe, UniverseStatus, UpdateUniverseInput};
+use crate::models::{
+    CreateUniverseInput, JournalEntityType, Universe, UniverseStatus, UpdateUniverseInput,
+};
+use crate::repositories::JournalRepository;
 use chrono::Utc;
-use rusqlite::{params, Result};
+use rusqlite::{params, OptionalExtension, Result};
 use uuid::Uuid;
 
 pub struct UniverseRepository;
@@ -51,7 +54,70 @@ impl UniverseRepository {
             ],
         )?;
 
-        Self::find_by_id(db, &id)
+        let universe = Self::find_by_id(db, &id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Universe,
+            &universe.id,
+            &universe.id,
+            None,
+            Some(&universe),
+        )?;
+        Ok(universe)
+    }
+
+    /// Insert a fully-formed Universe, keeping its ID and timestamps
+    pub fn insert(db: &Database, universe: &Universe) -> Result<Universe> {
+        let genre_str = universe
+            .genre
+            .as_ref()
+            .map(|g| serde_json::to_string(g).unwrap());
+        let tone_str = universe
+            .tone
+            .as_ref()
+            .map(|t| serde_json::to_string(t).unwrap());
+        let themes_json = universe
+            .themes
+            .as_ref()
+            .map(|t| serde_json::to_string(t).unwrap());
+        let tags_json = universe
+            .tags
+            .as_ref()
+            .map(|t| serde_json::to_string(t).unwrap());
+        let status_str = format!("{:?}", universe.status).to_lowercase();
+
+        db.execute(
+            "INSERT INTO universes (
+                id, name, description, created_at, updated_at, genre, tone,
+                worldbuilding_notes, themes, status, color, icon, tags
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                &universe.id,
+                &universe.name,
+                &universe.description,
+                &universe.created_at,
+                &universe.updated_at,
+                &genre_str,
+                &tone_str,
+                &universe.worldbuilding_notes,
+                &themes_json,
+                &status_str,
+                &universe.color,
+                &universe.icon,
+                &tags_json,
+            ],
+        )?;
+
+        let inserted = Self::find_by_id(db, &universe.id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Universe,
+            &inserted.id,
+            &inserted.id,
+            None,
+            Some(&inserted),
+        )?;
+        Ok(inserted)
     }
 
     /// Find a Universe by ID
@@ -106,6 +172,7 @@ impl UniverseRepository {
             "SELECT id, name, description, created_at, updated_at, genre, tone,
                     worldbuilding_notes, themes, status, color, icon, tags
              FROM universes
+             WHERE deleted_at IS NULL
              ORDER BY updated_at DESC",
         )?;
 
@@ -147,6 +214,7 @@ impl UniverseRepository {
 
     /// Update a Universe
     pub fn update(db: &Database, id: &str, input: UpdateUniverseInput) -> Result<Universe> {
+        let before = Self::find_by_id(db, id)?;
         let now = Utc::now().to_rfc3339();
 
         let genre_str = input.genre.map(|g| serde_json::to_string(&g).unwrap());
@@ -209,12 +277,32 @@ impl UniverseRepository {
 
         db.execute(&query, &params_refs)?;
 
-        Self::find_by_id(db, id)
+        let universe = Self::find_by_id(db, id)?;
+        JournalRepository::record(
+            db,
+            JournalEntityType::Universe,
+            id,
+            id,
+            Some(&before),
+            Some(&universe),
+        )?;
+        Ok(universe)
     }
 
     /// Delete a Universe
     pub fn delete(db: &Database, id: &str) -> Result<()> {
+        let universe = Self::find_by_id(db, id).optional()?;
         db.execute("DELETE FROM universes WHERE id = ?1", params![id])?;
+        if let Some(universe) = universe {
+            JournalRepository::record(
+                db,
+                JournalEntityType::Universe,
+                id,
+                id,
+                Some(&universe),
+                None,
+            )?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,820 @@
+//! Splitting and merging stories
+//!
+//! Cutting a long chapter in two or folding scenes together used to mean
+//! copying text between stories, which left no trace of where it came from.
+//! A split cuts a story at a paragraph into two consecutive stories; a merge
+//! joins sibling stories into the first of them. Both renumber the
+//! container's stories and commit everything, renames included, as a single
+//! commit naming the files involved, so the text's history can be followed
+//! across the restructure.
+//!
+//! Only stories in a container can be restructured, since their order only
+//! means something among siblings. Paragraphs are runs of non-blank lines,
+//! as in `GitService::blame_paragraphs`. Moving text between stories isn't
+//! new writing, so it's left out of the writing session counts.
+//!
+//! A restructure happens completely or not at all: the database changes run
+//! in one transaction, and if the files can't be committed they're put back
+//! as they were before the transaction is rolled back.
+
+use crate::db::Database;
+use crate::file_management::{
+    describe_renames, forget_chapter, record_chapter, rename_story_files, story_filename,
+    FileManagementError, StoryFileToReorder,
+};
+use crate::git::{GitService, GitServiceError};
+use crate::models::{CreateStoryInput, Story, UpdateStoryInput};
+use crate::repositories::StoryRepository;
+use crate::trash::{self, TrashEntityType, TrashError};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Error type for split and merge operations
+#[derive(Debug)]
+pub enum RestructureError {
+    /// Reading or writing the database failed
+    Database(rusqlite::Error),
+    /// Moving a merged story to the trash failed
+    Trash(TrashError),
+    /// Renaming or writing story files failed
+    Files(FileManagementError),
+    /// Committing the restructure failed
+    Git(GitServiceError),
+    /// The stories can't be split or merged as asked
+    Invalid(String),
+}
+
+impl fmt::Display for RestructureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestructureError::Database(e) => write!(f, "Database error: {e}"),
+            RestructureError::Trash(e) => write!(f, "{e}"),
+            RestructureError::Files(e) => write!(f, "{e}"),
+            RestructureError::Git(e) => write!(f, "Git error: {e}"),
+            RestructureError::Invalid(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for RestructureError {}
+
+impl From<rusqlite::Error> for RestructureError {
+    fn from(err: rusqlite::Error) -> Self {
+        RestructureError::Database(err)
+    }
+}
+
+impl From<TrashError> for RestructureError {
+    fn from(err: TrashError) -> Self {
+        RestructureError::Trash(err)
+    }
+}
+
+impl From<FileManagementError> for RestructureError {
+    fn from(err: FileManagementError) -> Self {
+        RestructureError::Files(err)
+    }
+}
+
+impl From<GitServiceError> for RestructureError {
+    fn from(err: GitServiceError) -> Self {
+        RestructureError::Git(err)
+    }
+}
+
+/// Result type alias for split and merge operations
+pub type RestructureResult<T> = Result<T, RestructureError>;
+
+/// Split a story in two at a paragraph
+///
+/// The story keeps the paragraphs before `paragraph_index`; the rest becomes
+/// a new story right after it, with the same type, status, tags, related
+/// elements, color and series. Later stories move down one place.
+///
+/// # Arguments
+/// * `paragraph_index` - First paragraph of the new story (0-based, so at
+///   least 1)
+/// * `new_title` - Title of the new story
+///
+/// # Returns
+/// The shortened story and the new one
+pub fn split_story(
+    db: &Database,
+    id: &str,
+    paragraph_index: usize,
+    new_title: &str,
+) -> RestructureResult<(Story, Story)> {
+    let story = StoryRepository::find_by_id(db, id)?;
+    let Some(container_id) = story.container_id.clone() else {
+        return Err(RestructureError::Invalid(
+            "Only stories in a container can be split".to_string(),
+        ));
+    };
+    let new_title = new_title.trim();
+    if new_title.is_empty() {
+        return Err(RestructureError::Invalid(
+            "The new story needs a title".to_string(),
+        ));
+    }
+    let Some((head, tail)) = split_at_paragraph(&story.content, paragraph_index) else {
+        return Err(RestructureError::Invalid(format!(
+            "Can't split at paragraph {paragraph_index}; both parts need at least one paragraph"
+        )));
+    };
+    let previous = StoryRepository::list_by_container(db, &container_id)?;
+    if !previous.iter().any(|sibling| sibling.id == story.id) {
+        return Err(RestructureError::Invalid(
+            "Stories in the trash can't be split".to_string(),
+        ));
+    }
+
+    db.transaction(|| {
+        let input = UpdateStoryInput {
+            content: Some(head),
+            ..Default::default()
+        };
+        StoryRepository::update_without_session(db, id, input)?;
+        let created = StoryRepository::create(
+        db,
+        CreateStoryInput {
+            universe_id: story.universe_id.clone(),
+            title: new_title.to_string(),
+            description: Some(String::new()),
+            story_type: Some(story.story_type.clone()),
+            content: Some(tail),
+            notes: None,
+            outline: None,
+            target_word_count: None,
+            tags: story.tags.clone(),
+            color: story.color.clone(),
+            series_name: story.series_name.clone(),
+            container_id: Some(container_id.clone()),
+            variation_type: None,
+            parent_variation_id: None,
+        },
+    )?;
+    let input = UpdateStoryInput {
+        status: Some(story.status.clone()),
+        related_element_ids: story.related_element_ids.clone(),
+        ..Default::default()
+    };
+    StoryRepository::update_without_session(db, &created.id, input)?;
+
+    let mut order: Vec<String> = previous.iter().map(|sibling| sibling.id.clone()).collect();
+    let position = order
+        .iter()
+        .position(|sibling| *sibling == story.id)
+        .unwrap();
+    order.insert(position + 1, created.id.clone());
+    StoryRepository::reorder_by_container(db, &container_id, order)?;
+
+    let original = StoryRepository::find_by_id(db, id)?;
+    let created = StoryRepository::find_by_id(db, &created.id)?;
+    let summary = format!(
+        "Split {} into {} and {}: {}",
+        story_filename(&story),
+        story_filename(&original),
+        story_filename(&created),
+        story.title
+    );
+    commit_restructure(
+        db,
+        &container_id,
+        &previous,
+        &[],
+        &[&original, &created],
+        &summary,
+    )?;
+
+    Ok((original, created))
+    })
+}
+
+/// Merge sibling stories into the first of them
+///
+/// The stories' text is joined in container order, separated by a blank
+/// line, and their tags and related elements are combined. The first story
+/// keeps its title and other fields; the others go to the trash, so they
+/// can still be brought back.
+///
+/// # Arguments
+/// * `app_data_dir` - App data directory, which holds the trash
+/// * `ids` - At least two stories of the same container, in any order
+///
+/// # Returns
+/// The merged story
+pub fn merge_stories(
+    db: &Database,
+    app_data_dir: &Path,
+    ids: &[String],
+) -> RestructureResult<Story> {
+    let ids: HashSet<&String> = ids.iter().collect();
+    if ids.len() < 2 {
+        return Err(RestructureError::Invalid(
+            "Select at least two stories to merge".to_string(),
+        ));
+    }
+    let mut container_ids = HashSet::new();
+    for id in &ids {
+        container_ids.insert(StoryRepository::find_by_id(db, id)?.container_id);
+    }
+    let container_id = match container_ids.into_iter().collect::<Vec<_>>().as_slice() {
+        [Some(container_id)] => container_id.clone(),
+        [None] => {
+            return Err(RestructureError::Invalid(
+                "Only stories in a container can be merged".to_string(),
+            ))
+        }
+        _ => {
+            return Err(RestructureError::Invalid(
+                "Only stories in the same container can be merged".to_string(),
+            ))
+        }
+    };
+
+    let previous = StoryRepository::list_by_container(db, &container_id)?;
+    let merged: Vec<&Story> = previous
+        .iter()
+        .filter(|story| ids.contains(&story.id))
+        .collect();
+    if merged.len() != ids.len() {
+        return Err(RestructureError::Invalid(
+            "Stories in the trash can't be merged".to_string(),
+        ));
+    }
+    let (first, rest) = merged.split_first().unwrap();
+
+    let content = merged
+        .iter()
+        .map(|story| story.content.trim_end())
+        .filter(|content| !content.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let trash_dir = trash::trash_dir(app_data_dir);
+    db.transaction(|| {
+        let input = UpdateStoryInput {
+            content: Some(format!("{content}\n")),
+            tags: combine(merged.iter().map(|story| story.tags.as_ref())),
+            related_element_ids: combine(
+                merged
+                    .iter()
+                    .map(|story| story.related_element_ids.as_ref()),
+            ),
+            ..Default::default()
+        };
+        StoryRepository::update_without_session(db, &first.id, input)?;
+        for story in rest {
+            trash::move_to_trash(db, &trash_dir, TrashEntityType::Story, &story.id)?;
+        }
+
+    let order = previous
+        .iter()
+        .filter(|story| !rest.iter().any(|merged| merged.id == story.id))
+        .map(|story| story.id.clone())
+        .collect();
+    StoryRepository::reorder_by_container(db, &container_id, order)?;
+
+    let survivor = StoryRepository::find_by_id(db, &first.id)?;
+    let summary = format!(
+        "Merge {} into {}: {}",
+        rest.iter()
+            .map(|story| story_filename(story))
+            .collect::<Vec<_>>()
+            .join(", "),
+        story_filename(&survivor),
+        survivor.title
+    );
+    commit_restructure(db, &container_id, &previous, rest, &[&survivor], &summary)?;
+
+    Ok(survivor)
+    })
+}
+
+/// Split text before its `index`-th paragraph
+///
+/// The first part ends with a single newline; the second starts at the
+/// paragraph. Returns None unless both parts have at least one paragraph.
+fn split_at_paragraph(content: &str, index: usize) -> Option<(String, String)> {
+    let mut offset = 0;
+    let mut paragraph = 0;
+    let mut in_paragraph = false;
+    for line in content.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        if !blank && !in_paragraph {
+            if paragraph == index && index > 0 {
+                let head = format!("{}\n", content[..offset].trim_end());
+                return Some((head, content[offset..].to_string()));
+            }
+            paragraph += 1;
+        }
+        in_paragraph = !blank;
+        offset += line.len();
+    }
+    None
+}
+
+/// Combine lists, keeping the first occurrence of each value
+///
+/// Returns None if none of the lists were set.
+fn combine<'a>(lists: impl Iterator<Item = Option<&'a Vec<String>>>) -> Option<Vec<String>> {
+    let mut combined: Option<Vec<String>> = None;
+    for list in lists.flatten() {
+        let combined = combined.get_or_insert_with(Vec::new);
+        for value in list {
+            if !combined.contains(value) {
+                combined.push(value.clone());
+            }
+        }
+    }
+    combined
+}
+
+/// Bring a container's repository in line with a split or merge and commit it
+///
+/// Removes the files of `removed` stories, renames the files of the
+/// remaining `previous` stories to their new positions and writes the
+/// content of `written` stories, updating their records in metadata.json
+/// to match, all in one commit headed by `summary`.
+/// If any of that fails, the files are put back as they were. Containers
+/// without a repository are left alone.
+fn commit_restructure(
+    db: &Database,
+    container_id: &str,
+    previous: &[Story],
+    removed: &[&Story],
+    written: &[&Story],
+    summary: &str,
+) -> RestructureResult<()> {
+    let Some(repo_path) = StoryRepository::find_git_repo_path(db, written[0])?
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+    else {
+        return Ok(());
+    };
+
+    let originals: Vec<(PathBuf, Vec<u8>)> = previous
+        .iter()
+        .map(|story| repo_path.join(story_filename(story)))
+        .chain(std::iter::once(repo_path.join("metadata.json")))
+        .filter_map(|path| fs::read(&path).ok().map(|content| (path, content)))
+        .collect();
+    let current = StoryRepository::list_by_container(db, container_id)?;
+
+    let result = rewrite_files(&repo_path, previous, &current, removed, written, summary);
+    if result.is_err() {
+        // Renaming goes through `temp-` names, which may be left behind
+        let touched = previous
+            .iter()
+            .map(|story| format!("temp-{}", story_filename(story)))
+            .chain(current.iter().map(story_filename));
+        restore_files(&repo_path, touched, &originals);
+    }
+    result
+}
+
+/// Remove, rename, write and commit the files for `commit_restructure`
+fn rewrite_files(
+    repo_path: &Path,
+    previous: &[Story],
+    current: &[Story],
+    removed: &[&Story],
+    written: &[&Story],
+    summary: &str,
+) -> RestructureResult<()> {
+    for story in removed {
+        let file_path = repo_path.join(story_filename(story));
+        if file_path.exists() {
+            fs::remove_file(file_path).map_err(FileManagementError::from)?;
+        }
+        forget_chapter(repo_path, &story.id)?;
+    }
+
+    let files: Vec<StoryFileToReorder> = previous
+        .iter()
+        .filter_map(|before| {
+            let after = current.iter().find(|story| story.id == before.id)?;
+            let current_filename = story_filename(before);
+            repo_path
+                .join(&current_filename)
+                .exists()
+                .then(|| StoryFileToReorder {
+                    current_filename,
+                    new_order: after.order.unwrap_or(0) as usize + 1,
+                    title: before.title.clone(),
+                })
+        })
+        .collect();
+    let renames = rename_story_files(repo_path, &files)?;
+
+    for story in written {
+        fs::write(repo_path.join(story_filename(story)), &story.content)
+            .map_err(FileManagementError::from)?;
+        record_chapter(repo_path, story)?;
+    }
+
+    let mut message = vec![summary.to_string()];
+    if !renames.is_empty() {
+        message.push(String::new());
+        message.extend(describe_renames(&renames));
+    }
+    GitService::commit_all(repo_path, &message.join("\n"))?;
+
+    Ok(())
+}
+
+/// Put a repository's story files and metadata.json back after a failed
+/// restructure
+///
+/// Deletes the `touched` files, then writes the `originals` back. Failures
+/// are logged, since the restructure's own error is the one to report.
+fn restore_files(
+    repo_path: &Path,
+    touched: impl Iterator<Item = String>,
+    originals: &[(PathBuf, Vec<u8>)],
+) {
+    for filename in touched {
+        let path = repo_path.join(filename);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                log::error!("Failed to remove {}: {e}", path.display());
+            }
+        }
+    }
+    for (path, content) in originals {
+        if let Err(e) = fs::write(path, content) {
+            log::error!("Failed to restore {}: {e}", path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_management::sync_story_file;
+    use crate::models::{StoryType, WritingScope};
+    use crate::repositories::{ContainerRepository, SessionRepository};
+    use chrono::Local;
+    use tempfile::TempDir;
+
+    struct Fixture {
+        db: Database,
+        container_id: String,
+        repo_path: PathBuf,
+        temp_dir: TempDir,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+             VALUES ('universe-1', 'Test Universe', 'A test universe', datetime('now'), datetime('now'), 'active')",
+            &[],
+        )
+        .unwrap();
+
+        let container_id = ContainerRepository::create(
+            &db,
+            "universe-1".to_string(),
+            None,
+            "novel".to_string(),
+            "Novel".to_string(),
+            None,
+            0,
+        )
+        .unwrap()
+        .id;
+        let repo_path = GitService::init_repo(temp_dir.path(), &container_id).unwrap();
+        ContainerRepository::set_git_repo_path(&db, &container_id, &repo_path.to_string_lossy())
+            .unwrap();
+
+        Fixture {
+            db,
+            container_id,
+            repo_path,
+            temp_dir,
+        }
+    }
+
+    /// Add stories to the container in order, with their files committed
+    fn add_stories(fixture: &Fixture, stories: &[(&str, &str)]) -> Vec<String> {
+        let ids: Vec<String> = stories
+            .iter()
+            .map(|(title, content)| {
+                StoryRepository::create(
+                    &fixture.db,
+                    CreateStoryInput {
+                        universe_id: "universe-1".to_string(),
+                        title: title.to_string(),
+                        description: Some(String::new()),
+                        story_type: Some(StoryType::Chapter),
+                        content: Some(content.to_string()),
+                        notes: None,
+                        outline: None,
+                        target_word_count: None,
+                        tags: None,
+                        color: None,
+                        series_name: None,
+                        container_id: Some(fixture.container_id.clone()),
+                        variation_type: None,
+                        parent_variation_id: None,
+                    },
+                )
+                .unwrap()
+                .id
+            })
+            .collect();
+        StoryRepository::reorder_by_container(&fixture.db, &fixture.container_id, ids.clone())
+            .unwrap();
+        for story in StoryRepository::list_by_container(&fixture.db, &fixture.container_id).unwrap()
+        {
+            sync_story_file(&fixture.repo_path, None, &story, None).unwrap();
+        }
+        ids
+    }
+
+    fn titles(fixture: &Fixture) -> Vec<String> {
+        StoryRepository::list_by_container(&fixture.db, &fixture.container_id)
+            .unwrap()
+            .into_iter()
+            .map(|story| story.title)
+            .collect()
+    }
+
+    fn commit_count(fixture: &Fixture) -> usize {
+        GitService::get_history(&fixture.repo_path, "original")
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_split_at_paragraph() {
+        let text = "One.\n\nTwo,\nstill two.\n\n\nThree.\n";
+        assert_eq!(
+            split_at_paragraph(text, 1),
+            Some((
+                "One.\n".to_string(),
+                "Two,\nstill two.\n\n\nThree.\n".to_string()
+            ))
+        );
+        assert_eq!(
+            split_at_paragraph(text, 2),
+            Some((
+                "One.\n\nTwo,\nstill two.\n".to_string(),
+                "Three.\n".to_string()
+            ))
+        );
+        assert_eq!(split_at_paragraph(text, 0), None);
+        assert_eq!(split_at_paragraph(text, 3), None);
+    }
+
+    #[test]
+    fn test_split_story_inserts_new_story_after_it() {
+        let fixture = setup();
+        let ids = add_stories(
+            &fixture,
+            &[
+                ("Opening", "Dawn.\n\nNoon.\n\nDusk.\n"),
+                ("Ending", "Night.\n"),
+            ],
+        );
+        let input = UpdateStoryInput {
+            tags: Some(vec!["draft".to_string()]),
+            related_element_ids: Some(vec!["element-1".to_string()]),
+            ..Default::default()
+        };
+        StoryRepository::update(&fixture.db, &ids[0], input).unwrap();
+        let commits = commit_count(&fixture);
+
+        let (original, created) = split_story(&fixture.db, &ids[0], 2, "Evening").unwrap();
+
+        assert_eq!(original.content, "Dawn.\n\nNoon.\n");
+        assert_eq!(created.content, "Dusk.\n");
+        assert_eq!(created.tags, Some(vec!["draft".to_string()]));
+        assert_eq!(
+            created.related_element_ids,
+            Some(vec!["element-1".to_string()])
+        );
+        assert_eq!(titles(&fixture), ["Opening", "Evening", "Ending"]);
+
+        assert_eq!(
+            fs::read_to_string(fixture.repo_path.join("001-opening.md")).unwrap(),
+            "Dawn.\n\nNoon.\n"
+        );
+        assert_eq!(
+            fs::read_to_string(fixture.repo_path.join("002-evening.md")).unwrap(),
+            "Dusk.\n"
+        );
+        assert!(fixture.repo_path.join("003-ending.md").exists());
+        assert!(!fixture.repo_path.join("002-ending.md").exists());
+
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history.len(), commits + 1);
+        assert!(history[0]
+            .message
+            .starts_with("Split 001-opening.md into 001-opening.md and 002-evening.md"));
+        assert!(history[0].message.contains("002-ending.md → 003-ending.md"));
+
+        // Moving text isn't writing
+        let scope = WritingScope {
+            container_id: Some(fixture.container_id.clone()),
+            ..Default::default()
+        };
+        let today = Local::now().date_naive();
+        let days = SessionRepository::daily_history(&fixture.db, &scope, today, today).unwrap();
+        assert!(days.iter().all(|day| day.words_removed == 0));
+    }
+
+    #[test]
+    fn test_split_story_rejects_bad_offsets_and_standalone_stories() {
+        let fixture = setup();
+        let ids = add_stories(&fixture, &[("Single", "Only paragraph.\n")]);
+
+        assert!(matches!(
+            split_story(&fixture.db, &ids[0], 1, "Second"),
+            Err(RestructureError::Invalid(_))
+        ));
+        assert_eq!(titles(&fixture), ["Single"]);
+    }
+
+    #[test]
+    fn test_merge_stories_joins_into_first_and_trashes_the_rest() {
+        let fixture = setup();
+        let ids = add_stories(
+            &fixture,
+            &[
+                ("One", "First scene.\n"),
+                ("Two", "Second scene.\n"),
+                ("Three", "Third scene.\n"),
+                ("Four", "Fourth scene.\n"),
+            ],
+        );
+        for (id, tag) in [(&ids[1], "a"), (&ids[2], "b")] {
+            let input = UpdateStoryInput {
+                tags: Some(vec![tag.to_string(), "shared".to_string()]),
+                ..Default::default()
+            };
+            StoryRepository::update(&fixture.db, id, input).unwrap();
+        }
+        let commits = commit_count(&fixture);
+
+        // Given out of order, merged in container order
+        let merged = merge_stories(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &[ids[2].clone(), ids[1].clone()],
+        )
+        .unwrap();
+
+        assert_eq!(merged.id, ids[1]);
+        assert_eq!(merged.content, "Second scene.\n\nThird scene.\n");
+        assert_eq!(
+            merged.tags,
+            Some(vec!["a".to_string(), "shared".to_string(), "b".to_string()])
+        );
+        assert_eq!(titles(&fixture), ["One", "Two", "Four"]);
+        assert!(trash::list_trash(&fixture.db, Some("universe-1"))
+            .unwrap()
+            .iter()
+            .any(|item| item.id == ids[2]));
+
+        assert_eq!(
+            fs::read_to_string(fixture.repo_path.join("002-two.md")).unwrap(),
+            "Second scene.\n\nThird scene.\n"
+        );
+        assert!(!fixture.repo_path.join("003-three.md").exists());
+        assert!(fixture.repo_path.join("003-four.md").exists());
+
+        let history = GitService::get_history(&fixture.repo_path, "original").unwrap();
+        assert_eq!(history.len(), commits + 1);
+        assert!(history[0]
+            .message
+            .starts_with("Merge 003-three.md into 002-two.md: Two"));
+    }
+
+    /// Make the next commit in the fixture's repository fail
+    fn lock_index(fixture: &Fixture) {
+        fs::write(fixture.repo_path.join(".git").join("index.lock"), "").unwrap();
+    }
+
+    #[test]
+    fn test_failed_split_leaves_stories_and_files_unchanged() {
+        let fixture = setup();
+        let ids = add_stories(
+            &fixture,
+            &[("Opening", "Dawn.\n\nDusk.\n"), ("Ending", "Night.\n")],
+        );
+        let commits = commit_count(&fixture);
+        lock_index(&fixture);
+
+        assert!(matches!(
+            split_story(&fixture.db, &ids[0], 1, "Evening"),
+            Err(RestructureError::Git(_))
+        ));
+
+        assert_eq!(titles(&fixture), ["Opening", "Ending"]);
+        assert_eq!(
+            StoryRepository::find_by_id(&fixture.db, &ids[0])
+                .unwrap()
+                .content,
+            "Dawn.\n\nDusk.\n"
+        );
+        assert_eq!(
+            fs::read_to_string(fixture.repo_path.join("001-opening.md")).unwrap(),
+            "Dawn.\n\nDusk.\n"
+        );
+        assert!(fixture.repo_path.join("002-ending.md").exists());
+        assert!(!fixture.repo_path.join("002-evening.md").exists());
+        assert!(!fixture.repo_path.join("003-ending.md").exists());
+        assert_eq!(commit_count(&fixture), commits);
+    }
+
+    #[test]
+    fn test_failed_merge_leaves_stories_and_files_unchanged() {
+        let fixture = setup();
+        let ids = add_stories(
+            &fixture,
+            &[
+                ("One", "First scene.\n"),
+                ("Two", "Second scene.\n"),
+                ("Three", "Third scene.\n"),
+            ],
+        );
+        lock_index(&fixture);
+
+        assert!(merge_stories(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            &[ids[0].clone(), ids[1].clone()],
+        )
+        .is_err());
+
+        assert_eq!(titles(&fixture), ["One", "Two", "Three"]);
+        assert_eq!(
+            StoryRepository::find_by_id(&fixture.db, &ids[0])
+                .unwrap()
+                .content,
+            "First scene.\n"
+        );
+        assert!(trash::list_trash(&fixture.db, Some("universe-1"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            fs::read_to_string(fixture.repo_path.join("001-one.md")).unwrap(),
+            "First scene.\n"
+        );
+        assert!(fixture.repo_path.join("002-two.md").exists());
+        assert!(fixture.repo_path.join("003-three.md").exists());
+        assert!(!fixture.repo_path.join("002-three.md").exists());
+    }
+
+    #[test]
+    fn test_merge_stories_requires_siblings() {
+        let fixture = setup();
+        let ids = add_stories(&fixture, &[("One", "First.\n")]);
+        let other = ContainerRepository::create(
+            &fixture.db,
+            "universe-1".to_string(),
+            None,
+            "novel".to_string(),
+            "Other".to_string(),
+            None,
+            1,
+        )
+        .unwrap();
+        let input = CreateStoryInput {
+            universe_id: "universe-1".to_string(),
+            title: "Elsewhere".to_string(),
+            description: Some(String::new()),
+            story_type: Some(StoryType::Chapter),
+            content: Some("Far away.\n".to_string()),
+            notes: None,
+            outline: None,
+            target_word_count: None,
+            tags: None,
+            color: None,
+            series_name: None,
+            container_id: Some(other.id),
+            variation_type: None,
+            parent_variation_id: None,
+        };
+        let elsewhere = StoryRepository::create(&fixture.db, input).unwrap();
+        let app_data_dir = fixture.temp_dir.path();
+
+        assert!(matches!(
+            merge_stories(&fixture.db, app_data_dir, &[ids[0].clone(), elsewhere.id]),
+            Err(RestructureError::Invalid(_))
+        ));
+        assert!(matches!(
+            merge_stories(&fixture.db, app_data_dir, &[ids[0].clone(), ids[0].clone()]),
+            Err(RestructureError::Invalid(_))
+        ));
+    }
+}
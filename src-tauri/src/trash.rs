@@ -0,0 +1,915 @@
+//! Trash: soft delete for universes, containers and stories
+//!
+//! Deleting an entity stamps `deleted_at` on it and on everything below it
+//! that isn't already in the trash, all with the same timestamp. The stamp
+//! marks one batch: restoring the entity brings back exactly the rows that
+//! were trashed with it, while things deleted earlier stay in the trash as
+//! entries of their own.
+//!
+//! Repositories of standalone stories and leaf containers are moved from
+//! `git-repos` into the trash directory under the same directory name. The
+//! rows keep their original `git_repo_path`, which is where restore moves
+//! the repositories back to. Stories inside a container keep their files in
+//! the container's repository.
+
+use crate::db::Database;
+use crate::models::{FieldChange, JournalAction, JournalEntityType};
+use crate::repositories::{
+    ContainerRepository, JournalRepository, StoryRepository, UniverseRepository,
+};
+use chrono::{Duration, Utc};
+use log::warn;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Error type for trash operations
+#[derive(Debug)]
+pub enum TrashError {
+    /// Reading or writing the database failed
+    Database(rusqlite::Error),
+    /// Moving or removing a repository failed
+    Io(std::io::Error),
+    /// The entity is already in the trash
+    AlreadyInTrash(String),
+    /// The entity is not in the trash
+    NotInTrash(String),
+    /// The entity's parent is still in the trash, so it has nowhere to go back to
+    ParentInTrash(String),
+    /// A repository can't be moved because its destination is taken
+    DestinationExists(PathBuf),
+}
+
+impl fmt::Display for TrashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrashError::Database(e) => write!(f, "Database error: {e}"),
+            TrashError::Io(e) => write!(f, "IO error: {e}"),
+            TrashError::AlreadyInTrash(id) => write!(f, "{id} is already in the trash"),
+            TrashError::NotInTrash(id) => write!(f, "{id} is not in the trash"),
+            TrashError::ParentInTrash(id) => {
+                write!(f, "The parent of {id} is in the trash; restore it first")
+            }
+            TrashError::DestinationExists(path) => {
+                write!(f, "{} already exists", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TrashError {}
+
+impl From<rusqlite::Error> for TrashError {
+    fn from(err: rusqlite::Error) -> Self {
+        TrashError::Database(err)
+    }
+}
+
+impl From<std::io::Error> for TrashError {
+    fn from(err: std::io::Error) -> Self {
+        TrashError::Io(err)
+    }
+}
+
+/// Result type alias for trash operations
+pub type TrashResult<T> = Result<T, TrashError>;
+
+/// The kind of entity in the trash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum TrashEntityType {
+    Universe,
+    Container,
+    Story,
+}
+
+/// An entity that was deleted, together with everything trashed with it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub entity_type: TrashEntityType,
+    pub id: String,
+    pub title: String,
+    pub universe_id: String,
+    /// Container the entity was in, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_container_id: Option<String>,
+    pub deleted_at: String,
+    /// Containers and stories below this one that were trashed with it
+    pub descendant_count: u32,
+}
+
+/// A universe, container or story row within a subtree
+struct SubtreeRow {
+    entity_type: TrashEntityType,
+    id: String,
+    universe_id: String,
+    /// Repository the row owns, if any
+    git_repo_path: Option<String>,
+    deleted_at: Option<String>,
+}
+
+/// Directory trashed repositories are kept in
+pub fn trash_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("trash")
+}
+
+/// Move an entity and everything below it to the trash
+///
+/// # Arguments
+/// * `db` - The application database
+/// * `trash_dir` - Directory trashed repositories are moved to
+/// * `entity_type` - What kind of entity `id` is
+/// * `id` - The entity to delete
+///
+/// # Returns
+/// The new trash entry
+pub fn move_to_trash(
+    db: &Database,
+    trash_dir: &Path,
+    entity_type: TrashEntityType,
+    id: &str,
+) -> TrashResult<TrashItem> {
+    {
+        let conn = db.connection();
+        let mut conn = conn.lock().unwrap();
+
+        let rows = load_subtree(&conn, entity_type, id)?;
+        if root_deleted_at(&rows, id)?.is_some() {
+            return Err(TrashError::AlreadyInTrash(id.to_string()));
+        }
+        let batch: Vec<&SubtreeRow> = rows.iter().filter(|r| r.deleted_at.is_none()).collect();
+
+        let moves = repo_moves(&batch, |path| {
+            (path.to_path_buf(), trashed_path(trash_dir, path))
+        });
+        move_repos(&moves)?;
+
+        let deleted_at = Utc::now().to_rfc3339();
+        if let Err(e) = set_deleted_at(&mut conn, &batch, Some(&deleted_at)) {
+            undo_moves(&moves);
+            return Err(e.into());
+        }
+    }
+
+    trash_item(db, entity_type, id)
+}
+
+/// List the trash
+///
+/// # Arguments
+/// * `universe_id` - List containers and stories deleted from this universe,
+///   or None to list deleted universes
+///
+/// # Returns
+/// One entry per deletion, most recent first. Entities trashed together with
+/// their parent are counted in the parent's entry rather than listed.
+pub fn list_trash(db: &Database, universe_id: Option<&str>) -> TrashResult<Vec<TrashItem>> {
+    let roots: Vec<(TrashEntityType, String)> = {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        match universe_id {
+            None => conn
+                .prepare(
+                    "SELECT id FROM universes WHERE deleted_at IS NOT NULL
+                     ORDER BY deleted_at DESC",
+                )?
+                .query_map([], |row| Ok((TrashEntityType::Universe, row.get(0)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?,
+            Some(universe_id) => conn
+                .prepare(
+                    "SELECT 'container', c.id, c.deleted_at FROM containers c
+                     JOIN universes u ON u.id = c.universe_id
+                     WHERE c.universe_id = ?1 AND c.deleted_at IS NOT NULL
+                       AND c.deleted_at IS NOT u.deleted_at
+                       AND c.deleted_at IS NOT
+                           (SELECT deleted_at FROM containers WHERE id = c.parent_container_id)
+                     UNION ALL
+                     SELECT 'story', s.id, s.deleted_at FROM stories s
+                     JOIN universes u ON u.id = s.universe_id
+                     WHERE s.universe_id = ?1 AND s.deleted_at IS NOT NULL
+                       AND s.deleted_at IS NOT u.deleted_at
+                       AND s.deleted_at IS NOT
+                           (SELECT deleted_at FROM containers WHERE id = s.container_id)
+                     ORDER BY 3 DESC",
+                )?
+                .query_map(params![universe_id], |row| {
+                    let entity_type = if row.get::<_, String>(0)? == "container" {
+                        TrashEntityType::Container
+                    } else {
+                        TrashEntityType::Story
+                    };
+                    Ok((entity_type, row.get(1)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?,
+        }
+    };
+
+    roots
+        .into_iter()
+        .map(|(entity_type, id)| trash_item(db, entity_type, &id))
+        .collect()
+}
+
+/// Bring an entity and everything trashed with it back out of the trash
+///
+/// Repositories are moved back to their original paths. Anything below the
+/// entity that was deleted separately, earlier, stays in the trash.
+///
+/// # Returns
+/// The trash entry that was restored
+pub fn restore(
+    db: &Database,
+    trash_dir: &Path,
+    entity_type: TrashEntityType,
+    id: &str,
+) -> TrashResult<TrashItem> {
+    let item = trash_item(db, entity_type, id)?;
+
+    let conn = db.connection();
+    let mut conn = conn.lock().unwrap();
+
+    if parent_in_trash(&conn, &item)? {
+        return Err(TrashError::ParentInTrash(id.to_string()));
+    }
+
+    let rows = load_subtree(&conn, entity_type, id)?;
+    let batch: Vec<&SubtreeRow> = rows
+        .iter()
+        .filter(|r| r.deleted_at.as_deref() == Some(item.deleted_at.as_str()))
+        .collect();
+
+    let moves = repo_moves(&batch, |path| {
+        (trashed_path(trash_dir, path), path.to_path_buf())
+    });
+    move_repos(&moves)?;
+
+    if let Err(e) = set_deleted_at(&mut conn, &batch, None) {
+        undo_moves(&moves);
+        return Err(e.into());
+    }
+
+    Ok(item)
+}
+
+/// Permanently delete an entity that is in the trash, with everything below
+/// it and their trashed repositories
+pub fn purge(
+    db: &Database,
+    trash_dir: &Path,
+    entity_type: TrashEntityType,
+    id: &str,
+) -> TrashResult<()> {
+    let rows = {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+        load_subtree(&conn, entity_type, id)?
+    };
+    if root_deleted_at(&rows, id)?.is_none() {
+        return Err(TrashError::NotInTrash(id.to_string()));
+    }
+
+    // Child containers and stories go with their parent through ON DELETE CASCADE
+    match entity_type {
+        TrashEntityType::Universe => UniverseRepository::delete(db, id)?,
+        TrashEntityType::Container => {
+            ContainerRepository::delete(db, id)?;
+        }
+        TrashEntityType::Story => StoryRepository::delete(db, id)?,
+    }
+
+    for row in &rows {
+        let Some(ref git_repo_path) = row.git_repo_path else {
+            continue;
+        };
+        let path = trashed_path(trash_dir, Path::new(git_repo_path));
+        if path.exists() {
+            if let Err(e) = fs::remove_dir_all(&path) {
+                warn!("Failed to remove trashed repo {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Purge every trash entry deleted more than `retention_days` ago
+///
+/// # Returns
+/// The entries that were purged
+pub fn purge_expired(
+    db: &Database,
+    trash_dir: &Path,
+    retention_days: u32,
+) -> TrashResult<Vec<TrashItem>> {
+    let cutoff = (Utc::now() - Duration::days(i64::from(retention_days))).to_rfc3339();
+
+    let mut expired: Vec<TrashItem> = list_trash(db, None)?;
+    let universe_ids: Vec<String> = {
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id FROM universes ORDER BY id")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        ids
+    };
+    for universe_id in universe_ids {
+        expired.extend(list_trash(db, Some(&universe_id))?);
+    }
+    expired.retain(|item| item.deleted_at < cutoff);
+
+    let mut purged = Vec::new();
+    for item in expired {
+        // An earlier purge may already have removed this entry with its parent
+        match purge(db, trash_dir, item.entity_type, &item.id) {
+            Ok(()) => purged.push(item),
+            Err(TrashError::Database(rusqlite::Error::QueryReturnedNoRows)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(purged)
+}
+
+/// Build the trash entry for a deleted entity
+fn trash_item(db: &Database, entity_type: TrashEntityType, id: &str) -> TrashResult<TrashItem> {
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    let sql = match entity_type {
+        TrashEntityType::Universe => {
+            "SELECT name, id, NULL, deleted_at FROM universes WHERE id = ?1"
+        }
+        TrashEntityType::Container => {
+            "SELECT title, universe_id, parent_container_id, deleted_at FROM containers
+             WHERE id = ?1"
+        }
+        TrashEntityType::Story => {
+            "SELECT title, universe_id, container_id, deleted_at FROM stories WHERE id = ?1"
+        }
+    };
+    let (title, universe_id, parent_container_id, deleted_at): (
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+    ) = conn.query_row(sql, params![id], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+    })?;
+    let Some(deleted_at) = deleted_at else {
+        return Err(TrashError::NotInTrash(id.to_string()));
+    };
+
+    let descendant_count = load_subtree(&conn, entity_type, id)?
+        .iter()
+        .filter(|r| r.id != id && r.deleted_at.as_deref() == Some(deleted_at.as_str()))
+        .count() as u32;
+
+    Ok(TrashItem {
+        entity_type,
+        id: id.to_string(),
+        title,
+        universe_id,
+        parent_container_id,
+        deleted_at,
+        descendant_count,
+    })
+}
+
+/// Load an entity and every container and story below it, trashed or not
+fn load_subtree(
+    conn: &Connection,
+    entity_type: TrashEntityType,
+    id: &str,
+) -> rusqlite::Result<Vec<SubtreeRow>> {
+    // Ids of the containers in the subtree; a story has none below it
+    let tree = match entity_type {
+        TrashEntityType::Universe => {
+            "WITH tree(id) AS (SELECT id FROM containers WHERE universe_id = ?1)"
+        }
+        TrashEntityType::Container => {
+            "WITH RECURSIVE tree(id) AS (
+                SELECT id FROM containers WHERE id = ?1
+                UNION ALL
+                SELECT c.id FROM containers c JOIN tree t ON c.parent_container_id = t.id
+            )"
+        }
+        TrashEntityType::Story => "",
+    };
+
+    let mut rows = Vec::new();
+
+    if entity_type == TrashEntityType::Universe {
+        rows.push(conn.query_row(
+            "SELECT id, id, deleted_at FROM universes WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(SubtreeRow {
+                    entity_type: TrashEntityType::Universe,
+                    id: row.get(0)?,
+                    universe_id: row.get(1)?,
+                    git_repo_path: None,
+                    deleted_at: row.get(2)?,
+                })
+            },
+        )?);
+    }
+
+    if entity_type != TrashEntityType::Story {
+        let mut stmt = conn.prepare(&format!(
+            "{tree}
+             SELECT id, universe_id, NULLIF(git_repo_path, ''), deleted_at FROM containers
+             WHERE id IN (SELECT id FROM tree)"
+        ))?;
+        rows.extend(
+            stmt.query_map(params![id], |row| {
+                Ok(SubtreeRow {
+                    entity_type: TrashEntityType::Container,
+                    id: row.get(0)?,
+                    universe_id: row.get(1)?,
+                    git_repo_path: row.get(2)?,
+                    deleted_at: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?,
+        );
+    }
+
+    let story_filter = match entity_type {
+        TrashEntityType::Universe => "universe_id = ?1",
+        TrashEntityType::Container => "container_id IN (SELECT id FROM tree)",
+        TrashEntityType::Story => "id = ?1",
+    };
+    let mut stmt = conn.prepare(&format!(
+        "{tree}
+         SELECT id, universe_id,
+                CASE WHEN container_id IS NULL THEN NULLIF(git_repo_path, '') END, deleted_at
+         FROM stories
+         WHERE {story_filter}"
+    ))?;
+    rows.extend(
+        stmt.query_map(params![id], |row| {
+            Ok(SubtreeRow {
+                entity_type: TrashEntityType::Story,
+                id: row.get(0)?,
+                universe_id: row.get(1)?,
+                git_repo_path: row.get(2)?,
+                deleted_at: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?,
+    );
+
+    Ok(rows)
+}
+
+/// The `deleted_at` of the subtree's root, or NotFound if it doesn't exist
+fn root_deleted_at<'a>(rows: &'a [SubtreeRow], id: &str) -> rusqlite::Result<Option<&'a str>> {
+    rows.iter()
+        .find(|r| r.id == id)
+        .map(|r| r.deleted_at.as_deref())
+        .ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// Whether the universe or container a trash entry was in is itself trashed
+fn parent_in_trash(conn: &Connection, item: &TrashItem) -> rusqlite::Result<bool> {
+    if item.entity_type == TrashEntityType::Universe {
+        return Ok(false);
+    }
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM universes WHERE id = ?1 AND deleted_at IS NOT NULL)
+             OR EXISTS (SELECT 1 FROM containers WHERE id = ?2 AND deleted_at IS NOT NULL)",
+        params![item.universe_id, item.parent_container_id],
+        |row| row.get(0),
+    )
+}
+
+/// Set or clear `deleted_at` on a batch of rows in one transaction
+fn set_deleted_at(
+    conn: &mut Connection,
+    rows: &[&SubtreeRow],
+    deleted_at: Option<&str>,
+) -> rusqlite::Result<()> {
+    let tx = conn.savepoint()?;
+    for row in rows {
+        let (table, journal_type) = match row.entity_type {
+            TrashEntityType::Universe => ("universes", JournalEntityType::Universe),
+            TrashEntityType::Container => ("containers", JournalEntityType::Container),
+            TrashEntityType::Story => ("stories", JournalEntityType::Story),
+        };
+        tx.execute(
+            &format!("UPDATE {table} SET deleted_at = ?1 WHERE id = ?2"),
+            params![deleted_at, row.id],
+        )?;
+
+        let change = FieldChange {
+            before: row.deleted_at.clone().into(),
+            after: deleted_at.into(),
+        };
+        JournalRepository::append(
+            &tx,
+            journal_type,
+            &row.id,
+            &row.universe_id,
+            JournalAction::Update,
+            &BTreeMap::from([("deletedAt".to_string(), change)]),
+        )?;
+    }
+    tx.commit()
+}
+
+/// Where a repository is kept while its owner is in the trash
+fn trashed_path(trash_dir: &Path, git_repo_path: &Path) -> PathBuf {
+    match git_repo_path.file_name() {
+        Some(name) => trash_dir.join(name),
+        None => trash_dir.join(git_repo_path),
+    }
+}
+
+/// The repository moves for a batch, skipping repositories that don't exist
+fn repo_moves(
+    rows: &[&SubtreeRow],
+    paths: impl Fn(&Path) -> (PathBuf, PathBuf),
+) -> Vec<(PathBuf, PathBuf)> {
+    rows.iter()
+        .filter_map(|row| row.git_repo_path.as_deref())
+        .map(|git_repo_path| paths(Path::new(git_repo_path)))
+        .filter(|(from, _)| from.exists())
+        .collect()
+}
+
+/// Move repositories, putting back the ones already moved if any move fails
+fn move_repos(moves: &[(PathBuf, PathBuf)]) -> TrashResult<()> {
+    if let Some((_, to)) = moves.iter().find(|(_, to)| to.exists()) {
+        return Err(TrashError::DestinationExists(to.clone()));
+    }
+
+    for (index, (from, to)) in moves.iter().enumerate() {
+        let result = match to.parent() {
+            Some(parent) => fs::create_dir_all(parent).and_then(|_| fs::rename(from, to)),
+            None => fs::rename(from, to),
+        };
+        if let Err(e) = result {
+            undo_moves(&moves[..index]);
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Move repositories back to where they came from
+fn undo_moves(moves: &[(PathBuf, PathBuf)]) {
+    for (from, to) in moves {
+        if let Err(e) = fs::rename(to, from) {
+            warn!(
+                "Failed to move {} back to {}: {}",
+                to.display(),
+                from.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateStoryInput, StoryType};
+    use tempfile::TempDir;
+
+    struct Fixture {
+        db: Database,
+        repos_dir: PathBuf,
+        trash_dir: PathBuf,
+        _temp_dir: TempDir,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+             VALUES ('universe-1', 'Test Universe', 'A test universe', datetime('now'), datetime('now'), 'active')",
+            &[],
+        )
+        .unwrap();
+
+        Fixture {
+            db,
+            repos_dir: temp_dir.path().join("git-repos"),
+            trash_dir: temp_dir.path().join("trash"),
+            _temp_dir: temp_dir,
+        }
+    }
+
+    /// Create a directory standing in for an entity's repository
+    fn make_repo(fixture: &Fixture, id: &str) -> String {
+        let path = fixture.repos_dir.join(id);
+        fs::create_dir_all(&path).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn create_story(fixture: &Fixture, title: &str, container_id: Option<String>) -> String {
+        let story = StoryRepository::create(
+            &fixture.db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: title.to_string(),
+                description: Some(String::new()),
+                story_type: Some(StoryType::Chapter),
+                content: None,
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: container_id.clone(),
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap();
+        if container_id.is_none() {
+            let path = make_repo(fixture, &story.id);
+            StoryRepository::set_git_repo_path(&fixture.db, &story.id, &path).unwrap();
+        }
+        story.id
+    }
+
+    /// A novel with one chapter in it, under a series
+    fn create_tree(fixture: &Fixture) -> (String, String, String) {
+        let series = ContainerRepository::create(
+            &fixture.db,
+            "universe-1".to_string(),
+            None,
+            "series".to_string(),
+            "Series".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+        let novel = ContainerRepository::create(
+            &fixture.db,
+            "universe-1".to_string(),
+            Some(series.id.clone()),
+            "novel".to_string(),
+            "Novel".to_string(),
+            None,
+            0,
+        )
+        .unwrap();
+        let path = make_repo(fixture, &novel.id);
+        ContainerRepository::set_git_repo_path(&fixture.db, &novel.id, &path).unwrap();
+        let chapter = create_story(fixture, "Chapter", Some(novel.id.clone()));
+        (series.id, novel.id, chapter)
+    }
+
+    #[test]
+    fn test_trash_and_restore_container_subtree() {
+        let fixture = setup();
+        let (series, novel, chapter) = create_tree(&fixture);
+
+        let item = move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Container,
+            &series,
+        )
+        .unwrap();
+
+        assert_eq!(item.descendant_count, 2);
+        assert!(
+            ContainerRepository::list_by_universe(&fixture.db, "universe-1")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(StoryRepository::list_by_universe(&fixture.db, "universe-1")
+            .unwrap()
+            .is_empty());
+        assert!(!fixture.repos_dir.join(&novel).exists());
+        assert!(fixture.trash_dir.join(&novel).exists());
+
+        let listed = list_trash(&fixture.db, Some("universe-1")).unwrap();
+        assert_eq!(listed, vec![item]);
+
+        restore(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Container,
+            &series,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ContainerRepository::list_by_universe(&fixture.db, "universe-1")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            StoryRepository::list_by_container(&fixture.db, &novel).unwrap()[0].id,
+            chapter
+        );
+        assert!(fixture.repos_dir.join(&novel).exists());
+        assert!(!fixture.trash_dir.join(&novel).exists());
+        assert!(list_trash(&fixture.db, Some("universe-1"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_earlier_deletions_stay_in_trash() {
+        let fixture = setup();
+        let (series, _novel, chapter) = create_tree(&fixture);
+
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &chapter,
+        )
+        .unwrap();
+        // Make sure the second deletion gets a different stamp
+        fixture
+            .db
+            .execute(
+                "UPDATE stories SET deleted_at = '2000-01-01T00:00:00+00:00' WHERE id = ?1",
+                &[&chapter],
+            )
+            .unwrap();
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Container,
+            &series,
+        )
+        .unwrap();
+
+        let listed = list_trash(&fixture.db, Some("universe-1")).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, series);
+        assert_eq!(listed[0].descendant_count, 1);
+        assert_eq!(listed[1].id, chapter);
+
+        // The chapter can't come back before its novel does
+        let result = restore(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &chapter,
+        );
+        assert!(matches!(result, Err(TrashError::ParentInTrash(_))));
+
+        restore(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Container,
+            &series,
+        )
+        .unwrap();
+        let listed = list_trash(&fixture.db, Some("universe-1")).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, chapter);
+    }
+
+    #[test]
+    fn test_trash_universe_hides_it_from_listings() {
+        let fixture = setup();
+        let story = create_story(&fixture, "Standalone", None);
+
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Universe,
+            "universe-1",
+        )
+        .unwrap();
+
+        assert!(UniverseRepository::list_all(&fixture.db)
+            .unwrap()
+            .is_empty());
+        assert!(fixture.trash_dir.join(&story).exists());
+        let listed = list_trash(&fixture.db, None).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].descendant_count, 1);
+        // The story went with its universe, so it has no entry of its own
+        assert!(list_trash(&fixture.db, Some("universe-1"))
+            .unwrap()
+            .is_empty());
+
+        let result = move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Universe,
+            "universe-1",
+        );
+        assert!(matches!(result, Err(TrashError::AlreadyInTrash(_))));
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite_repo() {
+        let fixture = setup();
+        let story = create_story(&fixture, "Standalone", None);
+
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &story,
+        )
+        .unwrap();
+        make_repo(&fixture, &story);
+
+        let result = restore(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &story,
+        );
+        assert!(matches!(result, Err(TrashError::DestinationExists(_))));
+        assert!(fixture.trash_dir.join(&story).exists());
+        assert_eq!(
+            list_trash(&fixture.db, Some("universe-1")).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_purge_and_retention() {
+        let fixture = setup();
+        let (series, _novel, _chapter) = create_tree(&fixture);
+        let old = create_story(&fixture, "Old", None);
+        let recent = create_story(&fixture, "Recent", None);
+
+        // Purging requires the entity to be in the trash
+        let result = purge(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &old,
+        );
+        assert!(matches!(result, Err(TrashError::NotInTrash(_))));
+
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Container,
+            &series,
+        )
+        .unwrap();
+        purge(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Container,
+            &series,
+        )
+        .unwrap();
+        assert!(ContainerRepository::find_by_id(&fixture.db, &series).is_err());
+        assert_eq!(fs::read_dir(&fixture.trash_dir).unwrap().count(), 0);
+
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &old,
+        )
+        .unwrap();
+        move_to_trash(
+            &fixture.db,
+            &fixture.trash_dir,
+            TrashEntityType::Story,
+            &recent,
+        )
+        .unwrap();
+        fixture
+            .db
+            .execute(
+                "UPDATE stories SET deleted_at = '2000-01-01T00:00:00+00:00' WHERE id = ?1",
+                &[&old],
+            )
+            .unwrap();
+
+        let purged = purge_expired(&fixture.db, &fixture.trash_dir, 30).unwrap();
+
+        assert_eq!(purged.len(), 1);
+        assert_eq!(purged[0].id, old);
+        assert!(StoryRepository::find_by_id(&fixture.db, &old).is_err());
+        assert!(!fixture.trash_dir.join(&old).exists());
+        assert!(fixture.trash_dir.join(&recent).exists());
+    }
+}
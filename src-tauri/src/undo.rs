@@ -0,0 +1,1044 @@
+//! Undo and redo for structural changes
+//!
+//! Content has git history and field edits are in the change journal, but
+//! reordering, deleting and relinking things had no way back. Commands that
+//! make such changes go through `perform`, which applies a list of steps and
+//! logs each one together with the operation that reverses it. Everything
+//! logged by one call is one undoable unit, however many steps it took.
+//!
+//! The log is kept in the database per universe, so it survives restarts,
+//! and only the last `MAX_UNDO_ENTRIES` entries are kept. Entries that were
+//! undone stay in the log for redo until a new change is logged.
+
+use crate::db::Database;
+use crate::file_management::{
+    reorder_story_files, story_filename, FileManagementError, StoryFileToReorder,
+};
+use crate::models::{Element, ElementRelationship, UpdateElementInput};
+use crate::moves::{self, MoveError};
+use crate::repositories::{ContainerRepository, ElementRepository, StoryRepository};
+use crate::trash::{self, TrashEntityType, TrashError};
+use chrono::Utc;
+use log::warn;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use ts_rs::TS;
+
+/// Number of entries kept in each universe's undo log
+pub const MAX_UNDO_ENTRIES: u32 = 100;
+
+/// Error type for undo operations
+#[derive(Debug)]
+pub enum UndoError {
+    /// Reading or writing the database failed
+    Database(rusqlite::Error),
+    /// Moving an entity in or out of the trash failed
+    Trash(TrashError),
+    /// Renaming story files failed
+    Files(FileManagementError),
+    /// Moving a story or container failed
+    Move(MoveError),
+    /// Replaying an entry failed and some of its operations could not be
+    /// rolled back; the entry stays in the log
+    PartiallyApplied {
+        /// Operations still applied
+        applied: usize,
+        /// Why the replay failed
+        source: Box<UndoError>,
+    },
+}
+
+impl fmt::Display for UndoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UndoError::Database(e) => write!(f, "Database error: {e}"),
+            UndoError::Trash(e) => write!(f, "{e}"),
+            UndoError::Files(e) => write!(f, "{e}"),
+            UndoError::Move(e) => write!(f, "{e}"),
+            UndoError::PartiallyApplied { applied, source } => write!(
+                f,
+                "{source} ({applied} operation(s) were applied and could not be rolled back)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UndoError {}
+
+impl From<rusqlite::Error> for UndoError {
+    fn from(err: rusqlite::Error) -> Self {
+        UndoError::Database(err)
+    }
+}
+
+impl From<TrashError> for UndoError {
+    fn from(err: TrashError) -> Self {
+        UndoError::Trash(err)
+    }
+}
+
+impl From<FileManagementError> for UndoError {
+    fn from(err: FileManagementError) -> Self {
+        UndoError::Files(err)
+    }
+}
+
+impl From<MoveError> for UndoError {
+    fn from(err: MoveError) -> Self {
+        UndoError::Move(err)
+    }
+}
+
+/// Result type alias for undo operations
+pub type UndoResult<T> = Result<T, UndoError>;
+
+/// A structural change that can be applied to a universe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum UndoOperation {
+    /// Put a container's child containers in the given order
+    ReorderContainers {
+        parent_id: String,
+        container_ids: Vec<String>,
+    },
+    /// Put a container's stories in the given order, renaming their files
+    ReorderStories {
+        container_id: String,
+        story_ids: Vec<String>,
+    },
+    /// Replace an element's outgoing relationships
+    SetRelationships {
+        element_id: String,
+        relationships: Vec<ElementRelationship>,
+    },
+    /// Delete an element and the relationships to and from it
+    DeleteElement { element_id: String },
+    /// Bring back a deleted element, with the relationships other elements
+    /// had to it as (source element ID, relationship) pairs
+    RestoreElement {
+        element: Box<Element>,
+        incoming: Vec<(String, ElementRelationship)>,
+    },
+    /// Move a container or story to the trash, with everything below it
+    Trash {
+        entity_type: TrashEntityType,
+        id: String,
+    },
+    /// Restore a container or story from the trash
+    Restore {
+        entity_type: TrashEntityType,
+        id: String,
+    },
+    /// Move a story into a container (or out of one) and its files along
+    MoveStory {
+        story_id: String,
+        universe_id: Option<String>,
+        container_id: Option<String>,
+        order: Option<u32>,
+    },
+    /// Move a container, with everything below it, to a new parent
+    MoveContainer {
+        container_id: String,
+        universe_id: Option<String>,
+        parent_id: Option<String>,
+        order: Option<i32>,
+    },
+}
+
+/// One step of an undoable change and the operation that reverses it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoStep {
+    pub redo: UndoOperation,
+    pub undo: UndoOperation,
+}
+
+impl UndoStep {
+    pub fn new(redo: UndoOperation, undo: UndoOperation) -> Self {
+        Self { redo, undo }
+    }
+}
+
+/// A logged change, as shown to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct UndoEntry {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub universe_id: String,
+    /// Short description, e.g. "Delete Mira"
+    pub label: String,
+    pub created_at: String,
+}
+
+/// What undo and redo would do next in a universe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+#[serde(rename_all = "camelCase")]
+pub struct UndoState {
+    pub undo: Option<UndoEntry>,
+    pub redo: Option<UndoEntry>,
+}
+
+/// Apply a change and log it for undo
+///
+/// Steps are applied in order, as one unit: if one fails, the steps applied
+/// before it are undone, nothing is logged and the error is returned. If
+/// undoing them fails too, the steps still applied are logged so they can
+/// be undone later, and `PartiallyApplied` is returned.
+pub fn perform(
+    db: &Database,
+    app_data_dir: &Path,
+    universe_id: &str,
+    label: &str,
+    steps: Vec<UndoStep>,
+) -> UndoResult<()> {
+    let result = apply_all(
+        db,
+        app_data_dir,
+        label,
+        steps.iter().map(|step| (&step.redo, &step.undo)),
+    );
+    match result {
+        Ok(()) => record(db, universe_id, label, &steps),
+        Err(UndoError::PartiallyApplied { applied, source }) => {
+            record(db, universe_id, label, &steps[..applied])?;
+            Err(UndoError::PartiallyApplied { applied, source })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Log a change that was already applied
+///
+/// Clears the universe's redo entries and drops the oldest entries beyond
+/// `MAX_UNDO_ENTRIES`.
+pub fn record(db: &Database, universe_id: &str, label: &str, steps: &[UndoStep]) -> UndoResult<()> {
+    let conn = db.connection();
+    let mut conn = conn.lock().unwrap();
+    let tx = conn.savepoint()?;
+
+    tx.execute(
+        "DELETE FROM undo_log WHERE universe_id = ?1 AND undone = 1",
+        params![universe_id],
+    )?;
+    tx.execute(
+        "INSERT INTO undo_log (universe_id, label, steps, undone, created_at)
+         VALUES (?1, ?2, ?3, 0, ?4)",
+        params![
+            universe_id,
+            label,
+            serde_json::to_string(steps).unwrap(),
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    tx.execute(
+        "DELETE FROM undo_log
+         WHERE universe_id = ?1 AND id NOT IN (
+             SELECT id FROM undo_log WHERE universe_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        params![universe_id, MAX_UNDO_ENTRIES],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Undo the latest change in a universe
+///
+/// If an operation fails, the ones already applied are rolled back. An entry
+/// that can no longer be undone (e.g. its container was purged from the
+/// trash) is then dropped from the log and the error returned, so the next
+/// undo moves on to the entry before it.
+///
+/// # Returns
+/// The undone entry, or None if there was nothing to undo
+pub fn undo(
+    db: &Database,
+    app_data_dir: &Path,
+    universe_id: &str,
+) -> UndoResult<Option<UndoEntry>> {
+    let Some((entry, steps)) = next_entry(db, universe_id, false)? else {
+        return Ok(None);
+    };
+    replay(
+        db,
+        app_data_dir,
+        &entry,
+        steps.iter().rev().map(|step| (&step.undo, &step.redo)),
+    )?;
+    set_undone(db, entry.id, true)?;
+    Ok(Some(entry))
+}
+
+/// Redo the latest undone change in a universe
+///
+/// # Returns
+/// The redone entry, or None if there was nothing to redo
+pub fn redo(
+    db: &Database,
+    app_data_dir: &Path,
+    universe_id: &str,
+) -> UndoResult<Option<UndoEntry>> {
+    let Some((entry, steps)) = next_entry(db, universe_id, true)? else {
+        return Ok(None);
+    };
+    replay(
+        db,
+        app_data_dir,
+        &entry,
+        steps.iter().map(|step| (&step.redo, &step.undo)),
+    )?;
+    set_undone(db, entry.id, false)?;
+    Ok(Some(entry))
+}
+
+/// Get the entries undo and redo would apply next
+pub fn undo_state(db: &Database, universe_id: &str) -> UndoResult<UndoState> {
+    Ok(UndoState {
+        undo: next_entry(db, universe_id, false)?.map(|(entry, _)| entry),
+        redo: next_entry(db, universe_id, true)?.map(|(entry, _)| entry),
+    })
+}
+
+/// Load the entry undo (`undone` false) or redo (`undone` true) applies next
+///
+/// Undone entries always follow the ones that weren't, so undo takes the
+/// newest entry that isn't undone and redo the oldest one that is.
+fn next_entry(
+    db: &Database,
+    universe_id: &str,
+    undone: bool,
+) -> UndoResult<Option<(UndoEntry, Vec<UndoStep>)>> {
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    let order = if undone { "ASC" } else { "DESC" };
+    let row = conn
+        .query_row(
+            &format!(
+                "SELECT id, universe_id, label, created_at, steps FROM undo_log
+                 WHERE universe_id = ?1 AND undone = ?2
+                 ORDER BY id {order} LIMIT 1"
+            ),
+            params![universe_id, undone],
+            |row| {
+                let steps_json: String = row.get(4)?;
+                Ok((
+                    UndoEntry {
+                        id: row.get(0)?,
+                        universe_id: row.get(1)?,
+                        label: row.get(2)?,
+                        created_at: row.get(3)?,
+                    },
+                    serde_json::from_str(&steps_json).unwrap_or_default(),
+                ))
+            },
+        )
+        .optional()?;
+
+    Ok(row)
+}
+
+/// Apply an entry's operations, each paired with the one that reverses it
+///
+/// If an operation fails, the ones applied before it are reversed and the
+/// entry is dropped. If reversing fails too, the entry is kept and
+/// `PartiallyApplied` returned.
+fn replay<'a>(
+    db: &Database,
+    app_data_dir: &Path,
+    entry: &UndoEntry,
+    operations: impl Iterator<Item = (&'a UndoOperation, &'a UndoOperation)>,
+) -> UndoResult<()> {
+    match apply_all(db, app_data_dir, &entry.label, operations) {
+        Err(e @ UndoError::PartiallyApplied { .. }) => Err(e),
+        Err(e) => {
+            db.execute("DELETE FROM undo_log WHERE id = ?1", params![entry.id])?;
+            Err(e)
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Apply operations in order, each paired with the one that reverses it
+///
+/// If an operation fails, the ones applied before it are reversed, newest
+/// first, and its error returned. If reversing fails too,
+/// `PartiallyApplied` is returned with the number of operations, from the
+/// first, that are still applied.
+fn apply_all<'a>(
+    db: &Database,
+    app_data_dir: &Path,
+    label: &str,
+    operations: impl Iterator<Item = (&'a UndoOperation, &'a UndoOperation)>,
+) -> UndoResult<()> {
+    let mut applied = Vec::new();
+    for (operation, reverse) in operations {
+        let Err(e) = apply(db, app_data_dir, operation) else {
+            applied.push(reverse);
+            continue;
+        };
+
+        while let Some(reverse) = applied.last() {
+            if let Err(rollback) = apply(db, app_data_dir, reverse) {
+                warn!("Rolling back '{label}' failed: {rollback}");
+                return Err(UndoError::PartiallyApplied {
+                    applied: applied.len(),
+                    source: Box::new(e),
+                });
+            }
+            applied.pop();
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn set_undone(db: &Database, id: i64, undone: bool) -> UndoResult<()> {
+    db.execute(
+        "UPDATE undo_log SET undone = ?1 WHERE id = ?2",
+        params![undone, id],
+    )?;
+    Ok(())
+}
+
+/// Apply a single operation
+fn apply(db: &Database, app_data_dir: &Path, operation: &UndoOperation) -> UndoResult<()> {
+    match operation {
+        UndoOperation::ReorderContainers {
+            parent_id,
+            container_ids,
+        } => {
+            ContainerRepository::reorder_children(db, parent_id, container_ids.clone())?;
+        }
+        UndoOperation::ReorderStories {
+            container_id,
+            story_ids,
+        } => reorder_stories(db, container_id, story_ids)?,
+        UndoOperation::SetRelationships {
+            element_id,
+            relationships,
+        } => {
+            ElementRepository::update(db, element_id, relationships_input(relationships))?;
+        }
+        UndoOperation::DeleteElement { element_id } => {
+            ElementRepository::delete(db, element_id)?;
+        }
+        UndoOperation::RestoreElement { element, incoming } => {
+            ElementRepository::insert(db, element, incoming)?;
+        }
+        UndoOperation::Trash { entity_type, id } => {
+            trash::move_to_trash(db, &trash::trash_dir(app_data_dir), *entity_type, id)?;
+        }
+        UndoOperation::Restore { entity_type, id } => {
+            trash::restore(db, &trash::trash_dir(app_data_dir), *entity_type, id)?;
+        }
+        UndoOperation::MoveStory {
+            story_id,
+            universe_id,
+            container_id,
+            order,
+        } => {
+            moves::move_story(
+                db,
+                app_data_dir,
+                story_id,
+                universe_id.as_deref(),
+                container_id.as_deref(),
+                *order,
+            )?;
+        }
+        UndoOperation::MoveContainer {
+            container_id,
+            universe_id,
+            parent_id,
+            order,
+        } => {
+            moves::move_container(
+                db,
+                container_id,
+                universe_id.as_deref(),
+                parent_id.as_deref(),
+                *order,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Reorder a container's stories and rename their files to match
+///
+/// Stories whose file isn't in the container's repository (yet) only have
+/// their order updated.
+fn reorder_stories(db: &Database, container_id: &str, story_ids: &[String]) -> UndoResult<()> {
+    let previous = StoryRepository::list_by_container(db, container_id)?;
+    StoryRepository::reorder_by_container(db, container_id, story_ids.to_vec())?;
+
+    let container = ContainerRepository::find_by_id(db, container_id)?;
+    let Some(repo_path) = container.git_repo_path.filter(|path| !path.is_empty()) else {
+        return Ok(());
+    };
+    let repo_path = Path::new(&repo_path);
+    if !repo_path.exists() {
+        return Ok(());
+    }
+
+    let files: Vec<StoryFileToReorder> = previous
+        .iter()
+        .filter_map(|story| {
+            let position = story_ids.iter().position(|id| *id == story.id)?;
+            let current_filename = story_filename(story);
+            repo_path
+                .join(&current_filename)
+                .exists()
+                .then(|| StoryFileToReorder {
+                    current_filename,
+                    new_order: position + 1,
+                    title: story.title.clone(),
+                })
+        })
+        .collect();
+    reorder_story_files(repo_path, &files)?;
+
+    Ok(())
+}
+
+/// Build an element update that only replaces its relationships
+fn relationships_input(relationships: &[ElementRelationship]) -> UpdateElementInput {
+    UpdateElementInput {
+        name: None,
+        description: None,
+        element_type: None,
+        custom_type_name: None,
+        details: None,
+        attributes: None,
+        image_url: None,
+        tags: None,
+        relationships: Some(relationships.to_vec()),
+        related_story_ids: None,
+        color: None,
+        icon: None,
+        favorite: None,
+        order: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_management::sync_story_file;
+    use crate::git::GitService;
+    use crate::models::{CreateElementInput, CreateStoryInput, StoryType};
+    use std::fs;
+    use tempfile::TempDir;
+
+    struct Fixture {
+        db: Database,
+        temp_dir: TempDir,
+    }
+
+    fn setup() -> Fixture {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        {
+            let conn = db.connection();
+            let conn = conn.lock().unwrap();
+            crate::db::migrations::run_migrations(&conn).unwrap();
+        }
+        db.execute(
+            "INSERT INTO universes (id, name, description, created_at, updated_at, status)
+             VALUES ('universe-1', 'Test Universe', 'A test universe', datetime('now'), datetime('now'), 'active')",
+            &[],
+        )
+        .unwrap();
+
+        Fixture { db, temp_dir }
+    }
+
+    fn create_container(fixture: &Fixture, title: &str, parent: Option<String>) -> String {
+        ContainerRepository::create(
+            &fixture.db,
+            "universe-1".to_string(),
+            parent,
+            "book".to_string(),
+            title.to_string(),
+            None,
+            0,
+        )
+        .unwrap()
+        .id
+    }
+
+    fn create_story(fixture: &Fixture, title: &str, container_id: &str) -> String {
+        StoryRepository::create(
+            &fixture.db,
+            CreateStoryInput {
+                universe_id: "universe-1".to_string(),
+                title: title.to_string(),
+                description: Some(String::new()),
+                story_type: Some(StoryType::Chapter),
+                content: Some(format!("{title} text")),
+                notes: None,
+                outline: None,
+                target_word_count: None,
+                tags: None,
+                color: None,
+                series_name: None,
+                container_id: Some(container_id.to_string()),
+                variation_type: None,
+                parent_variation_id: None,
+            },
+        )
+        .unwrap()
+        .id
+    }
+
+    fn create_element(
+        fixture: &Fixture,
+        name: &str,
+        relationships: Vec<ElementRelationship>,
+    ) -> Element {
+        ElementRepository::create(
+            &fixture.db,
+            CreateElementInput {
+                universe_id: "universe-1".to_string(),
+                name: name.to_string(),
+                description: Some(String::new()),
+                element_type: None,
+                custom_type_name: None,
+                details: None,
+                attributes: None,
+                image_url: None,
+                tags: None,
+                relationships: Some(relationships),
+                color: None,
+                icon: None,
+            },
+        )
+        .unwrap()
+    }
+
+    fn relationship(target_element_id: &str, label: &str) -> ElementRelationship {
+        ElementRelationship {
+            target_element_id: target_element_id.to_string(),
+            label: label.to_string(),
+            inverse_label: None,
+            description: None,
+        }
+    }
+
+    fn child_order(fixture: &Fixture, parent_id: &str) -> Vec<String> {
+        ContainerRepository::list_children(&fixture.db, parent_id)
+            .unwrap()
+            .into_iter()
+            .map(|container| container.title)
+            .collect()
+    }
+
+    fn story_order(fixture: &Fixture, container_id: &str) -> Vec<String> {
+        StoryRepository::list_by_container(&fixture.db, container_id)
+            .unwrap()
+            .into_iter()
+            .map(|story| story.title)
+            .collect()
+    }
+
+    #[test]
+    fn test_undo_and_redo_element_delete() {
+        let fixture = setup();
+        let mira = create_element(&fixture, "Mira", vec![]);
+        let ship = create_element(&fixture, "Ship", vec![relationship(&mira.id, "flown by")]);
+        let mira = ElementRepository::update(
+            &fixture.db,
+            &mira.id,
+            relationships_input(&[relationship(&ship.id, "pilots")]),
+        )
+        .unwrap();
+        let incoming =
+            ElementRepository::list_incoming_relationships(&fixture.db, &mira.id).unwrap();
+
+        perform(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Delete Mira",
+            vec![UndoStep::new(
+                UndoOperation::DeleteElement {
+                    element_id: mira.id.clone(),
+                },
+                UndoOperation::RestoreElement {
+                    element: Box::new(mira.clone()),
+                    incoming,
+                },
+            )],
+        )
+        .unwrap();
+        assert!(ElementRepository::find_by_id(&fixture.db, &mira.id).is_err());
+        assert!(ElementRepository::find_by_id(&fixture.db, &ship.id)
+            .unwrap()
+            .relationships
+            .is_none());
+
+        let undone = undo(&fixture.db, fixture.temp_dir.path(), "universe-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(undone.label, "Delete Mira");
+        let restored = ElementRepository::find_by_id(&fixture.db, &mira.id).unwrap();
+        assert_eq!(restored.name, "Mira");
+        assert_eq!(restored.created_at, mira.created_at);
+        assert_eq!(restored.relationships.unwrap()[0].label, "pilots");
+        let ship_relationships = ElementRepository::find_by_id(&fixture.db, &ship.id)
+            .unwrap()
+            .relationships
+            .unwrap();
+        assert_eq!(ship_relationships[0].target_element_id, mira.id);
+
+        let state = undo_state(&fixture.db, "universe-1").unwrap();
+        assert!(state.undo.is_none());
+        assert_eq!(state.redo.unwrap().label, "Delete Mira");
+
+        redo(&fixture.db, fixture.temp_dir.path(), "universe-1")
+            .unwrap()
+            .unwrap();
+        assert!(ElementRepository::find_by_id(&fixture.db, &mira.id).is_err());
+        assert!(redo(&fixture.db, fixture.temp_dir.path(), "universe-1")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_reorder_is_undone_as_one_unit() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let part_one = create_container(&fixture, "Part One", Some(book.clone()));
+        let part_two = create_container(&fixture, "Part Two", Some(book.clone()));
+        ContainerRepository::reorder_children(
+            &fixture.db,
+            &book,
+            vec![part_one.clone(), part_two.clone()],
+        )
+        .unwrap();
+
+        let chapters = create_container(&fixture, "Chapters", None);
+        let repo_path = GitService::init_repo(fixture.temp_dir.path(), &chapters).unwrap();
+        ContainerRepository::set_git_repo_path(
+            &fixture.db,
+            &chapters,
+            &repo_path.to_string_lossy(),
+        )
+        .unwrap();
+        let first = create_story(&fixture, "First", &chapters);
+        let second = create_story(&fixture, "Second", &chapters);
+        StoryRepository::reorder_by_container(
+            &fixture.db,
+            &chapters,
+            vec![first.clone(), second.clone()],
+        )
+        .unwrap();
+        for story in StoryRepository::list_by_container(&fixture.db, &chapters).unwrap() {
+            sync_story_file(&repo_path, None, &story, None).unwrap();
+        }
+
+        perform(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Reorder",
+            vec![
+                UndoStep::new(
+                    UndoOperation::ReorderContainers {
+                        parent_id: book.clone(),
+                        container_ids: vec![part_two.clone(), part_one.clone()],
+                    },
+                    UndoOperation::ReorderContainers {
+                        parent_id: book.clone(),
+                        container_ids: vec![part_one, part_two],
+                    },
+                ),
+                UndoStep::new(
+                    UndoOperation::ReorderStories {
+                        container_id: chapters.clone(),
+                        story_ids: vec![second.clone(), first.clone()],
+                    },
+                    UndoOperation::ReorderStories {
+                        container_id: chapters.clone(),
+                        story_ids: vec![first, second],
+                    },
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(child_order(&fixture, &book), ["Part Two", "Part One"]);
+        assert_eq!(story_order(&fixture, &chapters), ["Second", "First"]);
+        assert!(repo_path.join("001-second.md").exists());
+        assert!(repo_path.join("002-first.md").exists());
+
+        undo(&fixture.db, fixture.temp_dir.path(), "universe-1").unwrap();
+
+        assert_eq!(child_order(&fixture, &book), ["Part One", "Part Two"]);
+        assert_eq!(story_order(&fixture, &chapters), ["First", "Second"]);
+        assert_eq!(
+            fs::read_to_string(repo_path.join("001-first.md")).unwrap(),
+            "First text"
+        );
+        assert!(repo_path.join("002-second.md").exists());
+        assert!(undo(&fixture.db, fixture.temp_dir.path(), "universe-1")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_failed_undo_rolls_back_applied_operations() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let part_one = create_container(&fixture, "Part One", Some(book.clone()));
+        let part_two = create_container(&fixture, "Part Two", Some(book.clone()));
+        let in_order = vec![part_one.clone(), part_two.clone()];
+        let swapped = vec![part_two, part_one];
+        ContainerRepository::reorder_children(&fixture.db, &book, in_order.clone()).unwrap();
+
+        // Undo replays the steps backwards: the reorder applies, then the
+        // restore of a story that was never trashed fails
+        record(
+            &fixture.db,
+            "universe-1",
+            "Broken",
+            &[
+                UndoStep::new(
+                    UndoOperation::Trash {
+                        entity_type: TrashEntityType::Story,
+                        id: "missing-story".to_string(),
+                    },
+                    UndoOperation::Restore {
+                        entity_type: TrashEntityType::Story,
+                        id: "missing-story".to_string(),
+                    },
+                ),
+                UndoStep::new(
+                    UndoOperation::ReorderContainers {
+                        parent_id: book.clone(),
+                        container_ids: in_order,
+                    },
+                    UndoOperation::ReorderContainers {
+                        parent_id: book.clone(),
+                        container_ids: swapped,
+                    },
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert!(undo(&fixture.db, fixture.temp_dir.path(), "universe-1").is_err());
+
+        assert_eq!(child_order(&fixture, &book), ["Part One", "Part Two"]);
+        assert!(undo_state(&fixture.db, "universe-1")
+            .unwrap()
+            .undo
+            .is_none());
+    }
+
+    #[test]
+    fn test_failed_change_is_rolled_back_and_not_logged() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let part = create_container(&fixture, "Part", Some(book.clone()));
+
+        // The subtree goes to the trash, then trashing a missing story fails
+        let result = perform(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Delete Book",
+            vec![
+                UndoStep::new(
+                    UndoOperation::Trash {
+                        entity_type: TrashEntityType::Container,
+                        id: book.clone(),
+                    },
+                    UndoOperation::Restore {
+                        entity_type: TrashEntityType::Container,
+                        id: book.clone(),
+                    },
+                ),
+                UndoStep::new(
+                    UndoOperation::Trash {
+                        entity_type: TrashEntityType::Story,
+                        id: "missing-story".to_string(),
+                    },
+                    UndoOperation::Restore {
+                        entity_type: TrashEntityType::Story,
+                        id: "missing-story".to_string(),
+                    },
+                ),
+            ],
+        );
+
+        assert!(result.is_err());
+        assert!(!matches!(result, Err(UndoError::PartiallyApplied { .. })));
+        assert_eq!(child_order(&fixture, &book), ["Part"]);
+        assert!(!ContainerRepository::is_trashed(&fixture.db, &part).unwrap());
+        assert!(trash::list_trash(&fixture.db, Some("universe-1"))
+            .unwrap()
+            .is_empty());
+        let entries: i64 = fixture
+            .db
+            .query_one("SELECT COUNT(*) FROM undo_log", &[], |row| row.get(0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(entries, 0);
+    }
+
+    #[test]
+    fn test_undo_story_move_brings_file_back() {
+        let fixture = setup();
+        let mut repos = Vec::new();
+        for title in ["First", "Second"] {
+            let container = create_container(&fixture, title, None);
+            let repo_path = GitService::init_repo(fixture.temp_dir.path(), &container).unwrap();
+            ContainerRepository::set_git_repo_path(
+                &fixture.db,
+                &container,
+                &repo_path.to_string_lossy(),
+            )
+            .unwrap();
+            repos.push((container, repo_path));
+        }
+        let (first, first_repo) = &repos[0];
+        let (second, second_repo) = &repos[1];
+        let story = create_story(&fixture, "Wanderer", first);
+        let story = StoryRepository::find_by_id(&fixture.db, &story).unwrap();
+        sync_story_file(first_repo, None, &story, None).unwrap();
+
+        perform(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Move Wanderer",
+            vec![UndoStep::new(
+                UndoOperation::MoveStory {
+                    story_id: story.id.clone(),
+                    universe_id: None,
+                    container_id: Some(second.clone()),
+                    order: None,
+                },
+                UndoOperation::MoveStory {
+                    story_id: story.id.clone(),
+                    universe_id: None,
+                    container_id: Some(first.clone()),
+                    order: story.order,
+                },
+            )],
+        )
+        .unwrap();
+        assert!(!first_repo.join("001-wanderer.md").exists());
+        assert!(second_repo.join("001-wanderer.md").exists());
+
+        undo(&fixture.db, fixture.temp_dir.path(), "universe-1").unwrap();
+
+        let story = StoryRepository::find_by_id(&fixture.db, &story.id).unwrap();
+        assert_eq!(story.container_id.as_deref(), Some(first.as_str()));
+        assert_eq!(
+            fs::read_to_string(first_repo.join("001-wanderer.md")).unwrap(),
+            "Wanderer text"
+        );
+        assert!(!second_repo.join("001-wanderer.md").exists());
+    }
+
+    #[test]
+    fn test_undo_container_delete_restores_subtree() {
+        let fixture = setup();
+        let book = create_container(&fixture, "Book", None);
+        let part = create_container(&fixture, "Part", Some(book.clone()));
+        let trash_step = UndoStep::new(
+            UndoOperation::Trash {
+                entity_type: TrashEntityType::Container,
+                id: book.clone(),
+            },
+            UndoOperation::Restore {
+                entity_type: TrashEntityType::Container,
+                id: book.clone(),
+            },
+        );
+
+        perform(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            "universe-1",
+            "Delete Book",
+            vec![trash_step],
+        )
+        .unwrap();
+        assert!(ContainerRepository::list_children(&fixture.db, &book)
+            .unwrap()
+            .is_empty());
+
+        undo(&fixture.db, fixture.temp_dir.path(), "universe-1").unwrap();
+        assert_eq!(child_order(&fixture, &book), ["Part"]);
+        assert!(trash::list_trash(&fixture.db, Some("universe-1"))
+            .unwrap()
+            .is_empty());
+
+        // Purging the container behind the log's back leaves nothing to redo
+        redo(&fixture.db, fixture.temp_dir.path(), "universe-1").unwrap();
+        trash::purge(
+            &fixture.db,
+            fixture.temp_dir.path(),
+            TrashEntityType::Container,
+            &book,
+        )
+        .unwrap();
+        assert!(undo(&fixture.db, fixture.temp_dir.path(), "universe-1").is_err());
+        assert_eq!(
+            undo_state(&fixture.db, "universe-1").unwrap(),
+            UndoState {
+                undo: None,
+                redo: None
+            }
+        );
+        assert!(ContainerRepository::find_by_id(&fixture.db, &part).is_err());
+    }
+
+    #[test]
+    fn test_log_is_bounded_and_new_changes_clear_redo() {
+        let fixture = setup();
+        let element = create_element(&fixture, "Mira", vec![]);
+        let step = UndoStep::new(
+            UndoOperation::SetRelationships {
+                element_id: element.id.clone(),
+                relationships: vec![],
+            },
+            UndoOperation::SetRelationships {
+                element_id: element.id.clone(),
+                relationships: vec![],
+            },
+        );
+
+        for i in 0..MAX_UNDO_ENTRIES + 5 {
+            record(
+                &fixture.db,
+                "universe-1",
+                &format!("Change {i}"),
+                std::slice::from_ref(&step),
+            )
+            .unwrap();
+        }
+        let count: u32 = {
+            let conn = fixture.db.connection();
+            let conn = conn.lock().unwrap();
+            conn.query_row("SELECT COUNT(*) FROM undo_log", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count, MAX_UNDO_ENTRIES);
+
+        undo(&fixture.db, fixture.temp_dir.path(), "universe-1").unwrap();
+        undo(&fixture.db, fixture.temp_dir.path(), "universe-1").unwrap();
+        record(&fixture.db, "universe-1", "Fresh change", &[step]).unwrap();
+
+        let state = undo_state(&fixture.db, "universe-1").unwrap();
+        assert_eq!(state.undo.unwrap().label, "Fresh change");
+        assert!(state.redo.is_none());
+    }
+}
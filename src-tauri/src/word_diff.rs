@@ -0,0 +1,218 @@
+//! Word-level diffing for prose
+//!
+//! Story files keep each paragraph on a single line, so a line-level diff only
+//! says "this paragraph changed". These helpers split a pair of lines into
+//! words and report which words were added or removed, so the compare view can
+//! highlight the actual edit inside a paragraph.
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::time::Duration;
+use ts_rs::TS;
+
+/// Longest `count_word_changes` spends diffing before settling for a
+/// coarser (over-counting) result
+const CHANGE_COUNT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// A run of text within a changed line
+///
+/// On a removed line, `changed` segments are words that were deleted.
+/// On an added line, `changed` segments are words that were inserted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/")]
+pub struct WordSegment {
+    pub text: String,
+    pub changed: bool,
+}
+
+/// Result of comparing two lines word by word
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordDiff {
+    /// Segments of the old line (changed = removed)
+    pub old_segments: Vec<WordSegment>,
+    /// Segments of the new line (changed = added)
+    pub new_segments: Vec<WordSegment>,
+    /// Number of words inserted
+    pub words_added: u32,
+    /// Number of words deleted
+    pub words_removed: u32,
+}
+
+/// Compare two pieces of text word by word
+///
+/// Whitespace is kept in the segments so that concatenating the segments of
+/// one side reproduces the original text exactly.
+///
+/// # Arguments
+/// * `old` - The previous text
+/// * `new` - The updated text
+///
+/// # Returns
+/// WordDiff with segments for both sides and added/removed word counts
+pub fn diff_words(old: &str, new: &str) -> WordDiff {
+    let diff = TextDiff::from_words(old, new);
+
+    let mut old_segments = Vec::new();
+    let mut new_segments = Vec::new();
+    let mut words_added = 0;
+    let mut words_removed = 0;
+
+    for change in diff.iter_all_changes() {
+        let value = change.value();
+        let is_word = !value.trim().is_empty();
+
+        match change.tag() {
+            ChangeTag::Equal => {
+                push_segment(&mut old_segments, value, false);
+                push_segment(&mut new_segments, value, false);
+            }
+            ChangeTag::Delete => {
+                push_segment(&mut old_segments, value, is_word);
+                if is_word {
+                    words_removed += 1;
+                }
+            }
+            ChangeTag::Insert => {
+                push_segment(&mut new_segments, value, is_word);
+                if is_word {
+                    words_added += 1;
+                }
+            }
+        }
+    }
+
+    WordDiff {
+        old_segments,
+        new_segments,
+        words_added,
+        words_removed,
+    }
+}
+
+/// Count whitespace-separated words in a piece of text
+pub fn count_words(text: &str) -> u32 {
+    text.split_whitespace().count() as u32
+}
+
+/// Count the words inserted and deleted between two versions of a text
+///
+/// Cheaper than `diff_words` for whole documents: no segments are built and
+/// very large rewrites give up on a minimal diff after a short timeout.
+///
+/// # Returns
+/// `(words_added, words_removed)`
+pub fn count_word_changes(old: &str, new: &str) -> (u32, u32) {
+    let diff = TextDiff::configure()
+        .timeout(CHANGE_COUNT_TIMEOUT)
+        .diff_words(old, new);
+
+    let mut added = 0;
+    let mut removed = 0;
+    for change in diff.iter_all_changes() {
+        if change.value().trim().is_empty() {
+            continue;
+        }
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    (added, removed)
+}
+
+/// Append text to the segment list, merging with the previous segment when
+/// both have the same `changed` flag
+fn push_segment(segments: &mut Vec<WordSegment>, text: &str, changed: bool) {
+    if let Some(last) = segments.last_mut() {
+        // Whitespace between two changed words stays part of the changed run
+        if last.changed == changed || (last.changed && text.trim().is_empty()) {
+            last.text.push_str(text);
+            return;
+        }
+    }
+
+    segments.push(WordSegment {
+        text: text.to_string(),
+        changed,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join(segments: &[WordSegment]) -> String {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_diff_words_identical_text() {
+        let diff = diff_words("The gate was open.", "The gate was open.");
+
+        assert_eq!(diff.words_added, 0);
+        assert_eq!(diff.words_removed, 0);
+        assert!(diff.old_segments.iter().all(|s| !s.changed));
+        assert!(diff.new_segments.iter().all(|s| !s.changed));
+    }
+
+    #[test]
+    fn test_diff_words_single_word_replaced() {
+        let diff = diff_words("The gate was open.", "The gate was shut.");
+
+        assert_eq!(diff.words_added, 1);
+        assert_eq!(diff.words_removed, 1);
+
+        let removed: Vec<_> = diff.old_segments.iter().filter(|s| s.changed).collect();
+        let added: Vec<_> = diff.new_segments.iter().filter(|s| s.changed).collect();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(added.len(), 1);
+        assert_eq!(removed[0].text, "open.");
+        assert_eq!(added[0].text, "shut.");
+    }
+
+    #[test]
+    fn test_diff_words_segments_reproduce_text() {
+        let old = "She walked slowly to the Obsidian Gate and waited.";
+        let new = "She ran to the Obsidian Gate, then waited for hours.";
+        let diff = diff_words(old, new);
+
+        assert_eq!(join(&diff.old_segments), old);
+        assert_eq!(join(&diff.new_segments), new);
+    }
+
+    #[test]
+    fn test_diff_words_insertion_only() {
+        let diff = diff_words("A quiet night.", "A very quiet night.");
+
+        assert_eq!(diff.words_added, 1);
+        assert_eq!(diff.words_removed, 0);
+    }
+
+    #[test]
+    fn test_diff_words_from_empty() {
+        let diff = diff_words("", "Three new words");
+
+        assert_eq!(diff.words_added, 3);
+        assert_eq!(diff.words_removed, 0);
+        assert_eq!(join(&diff.new_segments), "Three new words");
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words(""), 0);
+        assert_eq!(count_words("   "), 0);
+        assert_eq!(count_words("one two  three\nfour"), 4);
+    }
+
+    #[test]
+    fn test_count_word_changes() {
+        assert_eq!(
+            count_word_changes("The old grey cat sat", "The young cat sat down"),
+            (2, 2)
+        );
+        assert_eq!(count_word_changes("", "Three new words"), (3, 0));
+        assert_eq!(count_word_changes("Same text", "Same text"), (0, 0));
+    }
+}
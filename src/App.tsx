@@ -1,4 +1,7 @@
+import { useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useNavigationStore } from "@/stores/useNavigationStore";
+import { useSettingsStore } from "@/stores/useSettingsStore";
 import { useToastStore } from "@/stores/useToastStore";
 import { UniverseSelection } from "./views/UniverseSelection";
 import { StoriesList } from "./views/StoriesList";
@@ -15,6 +18,7 @@ import { ContainerView } from "./views/ContainerView";
 import { ContainerSettings } from "./views/ContainerSettings";
 import { ErrorBoundary } from "./components/ErrorBoundary";
 import { ToastContainer } from "./components/Toast";
+import type { AutosaveConfig } from "@/types";
 import "./App.css";
 
 function AppContent() {
@@ -136,6 +140,34 @@ function AppContent() {
 function App() {
   const toasts = useToastStore((state) => state.toasts);
   const dismissToast = useToastStore((state) => state.dismissToast);
+  const trashRetentionDays = useSettingsStore((state) => state.trashRetentionDays);
+  const autoCommitEnabled = useSettingsStore((state) => state.autoCommitEnabled);
+  const autoCommitMode = useSettingsStore((state) => state.autoCommitMode);
+  const autoCommitDelay = useSettingsStore((state) => state.autoCommitDelay);
+  const autoCommitMaxWords = useSettingsStore((state) => state.autoCommitMaxWords);
+
+  // Backend autosave commits edits to Git: after the idle delay or a large
+  // edit in 'timed' mode, or only when leaving the editor in 'on-close' mode.
+  // Sent on startup too, since the backend doesn't keep the settings.
+  useEffect(() => {
+    const timed = autoCommitMode === 'timed';
+    const config: AutosaveConfig = {
+      enabled: autoCommitEnabled,
+      idleMs: timed ? autoCommitDelay : 0,
+      maxWords: timed ? autoCommitMaxWords : 0,
+    };
+    invoke('set_autosave_config', { config }).catch((error) => {
+      console.error('[Autosave] Failed to update config:', error);
+    });
+  }, [autoCommitEnabled, autoCommitMode, autoCommitDelay, autoCommitMaxWords]);
+
+  // Empty expired items from the trash when a retention period is set
+  useEffect(() => {
+    if (trashRetentionDays === null) return;
+    invoke('purge_expired_trash', { retentionDays: trashRetentionDays }).catch((error) => {
+      console.error('Failed to purge expired trash:', error);
+    });
+  }, [trashRetentionDays]);
 
   return (
     <ErrorBoundary name="Application">
@@ -16,6 +16,11 @@ const mockStory: Story = {
   storyType: 'chapter',
   status: 'inprogress',
   wordCount: 1000,
+  characterCount: 0,
+  characterCountNoSpaces: 0,
+  paragraphCount: 0,
+  sentenceCount: 0,
+  readingTimeSeconds: 0,
   targetWordCount: 5000,
   content: 'Test content',
   notes: null,
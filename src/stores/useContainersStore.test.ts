@@ -192,6 +192,11 @@ describe('useContainersStore - Optimistic Reordering', () => {
         status: 'draft' as const,
         content: '',
         wordCount: 0,
+        characterCount: 0,
+        characterCountNoSpaces: 0,
+        paragraphCount: 0,
+        sentenceCount: 0,
+        readingTimeSeconds: 0,
         order: 0,
         tags: [],
         relatedElementIds: [],
@@ -211,6 +216,11 @@ describe('useContainersStore - Optimistic Reordering', () => {
         status: 'draft' as const,
         content: '',
         wordCount: 0,
+        characterCount: 0,
+        characterCountNoSpaces: 0,
+        paragraphCount: 0,
+        sentenceCount: 0,
+        readingTimeSeconds: 0,
         order: 1,
         tags: [],
         relatedElementIds: [],
@@ -297,6 +307,11 @@ describe('useContainersStore - Optimistic Reordering', () => {
         status: 'draft' as const,
         content: '',
         wordCount: 0,
+        characterCount: 0,
+        characterCountNoSpaces: 0,
+        paragraphCount: 0,
+        sentenceCount: 0,
+        readingTimeSeconds: 0,
         order: 0,
         tags: [],
         relatedElementIds: [],
@@ -7,8 +7,9 @@
 
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Container, Story, CreateContainerInput, UpdateContainerInput, ContainerChildren } from '@/types';
+import type { Container, Story, CreateContainerInput, UpdateContainerInput, ContainerChildren, HistoryMode } from '@/types';
 import { LRUCache } from '@/utils/LRUCache';
+import { getErrorMessage } from '@/utils';
 
 // Cache configuration
 const CHILDREN_CACHE_SIZE = 100; // Maximum number of container children to cache
@@ -40,6 +41,8 @@ interface ContainersState {
   getContainer: (id: string) => Promise<Container>;
   updateContainer: (id: string, input: UpdateContainerInput) => Promise<Container>;
   deleteContainer: (id: string) => Promise<void>;
+  moveContainer: (id: string, parentContainerId: string | null, universeId?: string) => Promise<Container>;
+  duplicateContainer: (id: string, history: HistoryMode) => Promise<Container>;
 
   // Child container actions
   loadContainerChildren: (containerId: string) => Promise<ContainerChildren>;
@@ -84,7 +87,7 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
       const containers = await invoke<Container[]>('list_containers', { universeId });
       set({ containers, isLoading: false });
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to load containers';
+      const errorMessage = getErrorMessage(error, 'Failed to load containers');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -105,7 +108,7 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
       }));
       return container;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to create container';
+      const errorMessage = getErrorMessage(error, 'Failed to create container');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -118,7 +121,7 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
       set({ isLoading: false });
       return container;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to get container';
+      const errorMessage = getErrorMessage(error, 'Failed to get container');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -135,7 +138,7 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
       }));
       return container;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to update container';
+      const errorMessage = getErrorMessage(error, 'Failed to update container');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -175,7 +178,53 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
         };
       });
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to delete container';
+      const errorMessage = getErrorMessage(error, 'Failed to delete container');
+      set({ error: errorMessage, isLoading: false });
+      throw error;
+    }
+  },
+
+  moveContainer: async (id, parentContainerId, universeId) => {
+    set({ isLoading: true, error: null });
+    try {
+      const previousParentId = get().containers.find((c) => c.id === id)?.parentContainerId;
+      const container = await invoke<Container>('move_container', { id, parentContainerId, universeId });
+
+      // Both parents list different children now
+      if (previousParentId) get().invalidateChildren(previousParentId);
+      if (parentContainerId) get().invalidateChildren(parentContainerId);
+
+      set((state) => {
+        // Containers moved to another universe leave the current list
+        const movedAway = state.containers.some((c) => c.id === id && c.universeId !== container.universeId);
+        return {
+          containers: movedAway
+            ? state.containers.filter((c) => c.id !== id)
+            : state.containers.map((c) => (c.id === id ? container : c)),
+          selectedContainer: state.selectedContainer?.id === id ? container : state.selectedContainer,
+          isLoading: false,
+        };
+      });
+      return container;
+    } catch (error) {
+      const errorMessage = getErrorMessage(error, 'Failed to move container');
+      set({ error: errorMessage, isLoading: false });
+      throw error;
+    }
+  },
+
+  duplicateContainer: async (id, history) => {
+    set({ isLoading: true, error: null });
+    try {
+      const container = await invoke<Container>('duplicate_container', { id, history });
+      if (container.parentContainerId) get().invalidateChildren(container.parentContainerId);
+
+      // The copy brings containers below it, so list the universe again
+      const containers = await invoke<Container[]>('list_containers', { universeId: container.universeId });
+      set({ containers, isLoading: false });
+      return container;
+    } catch (error) {
+      const errorMessage = getErrorMessage(error, 'Failed to duplicate container');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -197,7 +246,7 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
       });
       return children;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to load container children';
+      const errorMessage = getErrorMessage(error, 'Failed to load container children');
       set((state) => ({
         error: errorMessage,
         childrenLoading: { ...state.childrenLoading, [containerId]: false },
@@ -219,7 +268,7 @@ export const useContainersStore = create<ContainersState>((set, get) => ({
       // Success - reload to ensure sync with backend
       await get().loadContainerChildren(containerId);
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to reorder children';
+      const errorMessage = getErrorMessage(error, 'Failed to reorder children');
 
       // Rollback to original state
       if (originalChildren) {
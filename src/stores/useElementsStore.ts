@@ -8,6 +8,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { Element, CreateElementInput, UpdateElementInput, ElementType } from '@/types';
+import { getErrorMessage } from '@/utils';
 
 interface ElementFilters {
   type: ElementType | null;
@@ -61,7 +62,7 @@ export const useElementsStore = create<ElementsState>((set, get) => ({
       const elements = await invoke<Element[]>('list_elements_by_universe', { universeId });
       set({ elements, isLoading: false });
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to load elements';
+      const errorMessage = getErrorMessage(error, 'Failed to load elements');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -82,7 +83,7 @@ export const useElementsStore = create<ElementsState>((set, get) => ({
       }));
       return element;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to create element';
+      const errorMessage = getErrorMessage(error, 'Failed to create element');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -95,7 +96,7 @@ export const useElementsStore = create<ElementsState>((set, get) => ({
       set({ isLoading: false });
       return element;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to get element';
+      const errorMessage = getErrorMessage(error, 'Failed to get element');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -112,7 +113,7 @@ export const useElementsStore = create<ElementsState>((set, get) => ({
       }));
       return element;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to update element';
+      const errorMessage = getErrorMessage(error, 'Failed to update element');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -128,7 +129,7 @@ export const useElementsStore = create<ElementsState>((set, get) => ({
         isLoading: false,
       }));
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to delete element';
+      const errorMessage = getErrorMessage(error, 'Failed to delete element');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
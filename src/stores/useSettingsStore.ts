@@ -15,11 +15,17 @@ interface SettingsState {
   autoCommitEnabled: boolean;
   autoCommitMode: AutoCommitMode;
   autoCommitDelay: number; // milliseconds (only used when mode is 'timed')
+  autoCommitMaxWords: number; // words changed before committing early, 0 = never (only used when mode is 'timed')
+
+  // Trash settings
+  trashRetentionDays: number | null; // null keeps trashed items until emptied by hand
 
   // Actions
   setAutoCommitEnabled: (enabled: boolean) => void;
   setAutoCommitMode: (mode: AutoCommitMode) => void;
   setAutoCommitDelay: (delay: number) => void;
+  setAutoCommitMaxWords: (maxWords: number) => void;
+  setTrashRetentionDays: (days: number | null) => void;
   resetToDefaults: () => void;
 }
 
@@ -27,6 +33,8 @@ const DEFAULT_SETTINGS = {
   autoCommitEnabled: true,
   autoCommitMode: 'on-close' as AutoCommitMode, // Default: commit when leaving editor
   autoCommitDelay: 30000, // 30 seconds (only used when mode is 'timed')
+  autoCommitMaxWords: 500, // Commit early after 500 changed words (only used when mode is 'timed')
+  trashRetentionDays: null as number | null, // Default: never purge automatically
 };
 
 export const useSettingsStore = create<SettingsState>()(
@@ -48,6 +56,14 @@ export const useSettingsStore = create<SettingsState>()(
         set({ autoCommitDelay: delay });
       },
 
+      setAutoCommitMaxWords: (maxWords) => {
+        set({ autoCommitMaxWords: maxWords });
+      },
+
+      setTrashRetentionDays: (days) => {
+        set({ trashRetentionDays: days });
+      },
+
       resetToDefaults: () => {
         set(DEFAULT_SETTINGS);
       },
@@ -19,6 +19,11 @@ describe('useStoriesStore - Filter and Sort Functionality', () => {
     storyType: 'chapter',
     status: 'draft',
     wordCount: 1000,
+    characterCount: 0,
+    characterCountNoSpaces: 0,
+    paragraphCount: 0,
+    sentenceCount: 0,
+    readingTimeSeconds: 0,
     targetWordCount: null,
     content: '',
     notes: null,
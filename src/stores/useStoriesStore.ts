@@ -7,7 +7,8 @@
 
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Story, CreateStoryInput, UpdateStoryInput, StoryStatus, StoryType, StoryUpdate } from '@/types';
+import type { Story, CreateStoryInput, UpdateStoryInput, StoryStatus, StoryType, StoryUpdate, ChangeOrigin, HistoryMode } from '@/types';
+import { getErrorMessage } from '@/utils';
 
 /**
  * Convert partial update to full UpdateStoryInput with null values for missing fields.
@@ -58,8 +59,12 @@ interface StoriesState {
   selectStory: (story: Story | null) => void;
   createStory: (input: CreateStoryInput) => Promise<Story>;
   getStory: (id: string) => Promise<Story>;
-  updateStory: (id: string, input: StoryUpdate) => Promise<Story>;
+  updateStory: (id: string, input: StoryUpdate, origin?: ChangeOrigin) => Promise<Story>;
   deleteStory: (id: string) => Promise<void>;
+  moveStory: (id: string, containerId: string | null, universeId?: string) => Promise<Story>;
+  splitStory: (id: string, paragraphIndex: number, newTitle: string) => Promise<Story[]>;
+  mergeStories: (storyIds: string[]) => Promise<Story>;
+  duplicateStory: (id: string, history: HistoryMode) => Promise<Story>;
 
   // Filtering and sorting
   setFilter: (key: keyof StoryFilters, value: StoryType | StoryStatus | string | null) => void;
@@ -94,7 +99,7 @@ export const useStoriesStore = create<StoriesState>((set, get) => ({
       const stories = await invoke<Story[]>('list_stories_by_universe', { universeId });
       set({ stories, isLoading: false });
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to load stories';
+      const errorMessage = getErrorMessage(error, 'Failed to load stories');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -115,7 +120,7 @@ export const useStoriesStore = create<StoriesState>((set, get) => ({
       }));
       return story;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to create story';
+      const errorMessage = getErrorMessage(error, 'Failed to create story');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -128,18 +133,18 @@ export const useStoriesStore = create<StoriesState>((set, get) => ({
       set({ isLoading: false });
       return story;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to get story';
+      const errorMessage = getErrorMessage(error, 'Failed to get story');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
   },
 
-  updateStory: async (id, input) => {
+  updateStory: async (id, input, origin) => {
     set({ isLoading: true, error: null });
     try {
       // Convert partial update to full input with null for missing fields
       const fullInput = toUpdateStoryInput(input);
-      const story = await invoke<Story>('update_story', { id, input: fullInput });
+      const story = await invoke<Story>('update_story', { id, input: fullInput, origin });
       set((state) => ({
         stories: state.stories.map((s) => (s.id === id ? story : s)),
         selectedStory: state.selectedStory?.id === id ? story : state.selectedStory,
@@ -147,7 +152,88 @@ export const useStoriesStore = create<StoriesState>((set, get) => ({
       }));
       return story;
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to update story';
+      const errorMessage = getErrorMessage(error, 'Failed to update story');
+      set({ error: errorMessage, isLoading: false });
+      throw error;
+    }
+  },
+
+  moveStory: async (id, containerId, universeId) => {
+    set({ isLoading: true, error: null });
+    try {
+      const story = await invoke<Story>('move_story', { id, containerId, universeId });
+      set((state) => {
+        // Stories moved to another universe leave the current list
+        const movedAway = state.stories.some((s) => s.id === id && s.universeId !== story.universeId);
+        return {
+          stories: movedAway
+            ? state.stories.filter((s) => s.id !== id)
+            : state.stories.map((s) => (s.id === id ? story : s)),
+          selectedStory: state.selectedStory?.id === id ? story : state.selectedStory,
+          isLoading: false,
+        };
+      });
+      return story;
+    } catch (error) {
+      const errorMessage = getErrorMessage(error, 'Failed to move story');
+      set({ error: errorMessage, isLoading: false });
+      throw error;
+    }
+  },
+
+  splitStory: async (id, paragraphIndex, newTitle) => {
+    set({ isLoading: true, error: null });
+    try {
+      const [original, created] = await invoke<Story[]>('split_story', { id, paragraphIndex, newTitle });
+      set((state) => ({
+        stories: [...state.stories.map((s) => (s.id === id ? original : s)), created],
+        selectedStory: state.selectedStory?.id === id ? original : state.selectedStory,
+        isLoading: false,
+      }));
+      return [original, created];
+    } catch (error) {
+      const errorMessage = getErrorMessage(error, 'Failed to split story');
+      set({ error: errorMessage, isLoading: false });
+      throw error;
+    }
+  },
+
+  mergeStories: async (storyIds) => {
+    set({ isLoading: true, error: null });
+    try {
+      const merged = await invoke<Story>('merge_stories', { storyIds });
+      set((state) => {
+        // Every story but the merged one went to the trash
+        const trashedIds = storyIds.filter((id) => id !== merged.id);
+        return {
+          stories: state.stories
+            .filter((s) => !trashedIds.includes(s.id))
+            .map((s) => (s.id === merged.id ? merged : s)),
+          selectedStory: state.selectedStory && trashedIds.includes(state.selectedStory.id)
+            ? merged
+            : state.selectedStory?.id === merged.id ? merged : state.selectedStory,
+          isLoading: false,
+        };
+      });
+      return merged;
+    } catch (error) {
+      const errorMessage = getErrorMessage(error, 'Failed to merge stories');
+      set({ error: errorMessage, isLoading: false });
+      throw error;
+    }
+  },
+
+  duplicateStory: async (id, history) => {
+    set({ isLoading: true, error: null });
+    try {
+      const story = await invoke<Story>('duplicate_story', { id, history });
+      set((state) => ({
+        stories: [...state.stories, story],
+        isLoading: false,
+      }));
+      return story;
+    } catch (error) {
+      const errorMessage = getErrorMessage(error, 'Failed to duplicate story');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
@@ -175,7 +261,7 @@ export const useStoriesStore = create<StoriesState>((set, get) => ({
         };
       });
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : 'Failed to delete story';
+      const errorMessage = getErrorMessage(error, 'Failed to delete story');
       set({ error: errorMessage, isLoading: false });
       throw error;
     }
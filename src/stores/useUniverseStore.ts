@@ -9,7 +9,8 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { invoke } from '@tauri-apps/api/core';
-import type { Universe, CreateUniverseInput } from '@/types';
+import type { Universe, CreateUniverseInput, UniverseStatus, HistoryMode } from '@/types';
+import { getErrorMessage } from '@/utils';
 
 interface UniverseState {
   // State
@@ -25,6 +26,8 @@ interface UniverseState {
   getUniverse: (id: string) => Promise<Universe>;
   updateUniverse: (id: string, input: Partial<CreateUniverseInput>) => Promise<Universe>;
   deleteUniverse: (id: string) => Promise<void>;
+  duplicateUniverse: (id: string, history: HistoryMode, name?: string, status?: UniverseStatus) => Promise<Universe>;
+  createUniverseFromTemplate: (templateId: string, name: string) => Promise<Universe>;
   clearError: () => void;
 }
 
@@ -48,7 +51,7 @@ export const useUniverseStore = create<UniverseState>()(
           const universes = await invoke<Universe[]>('list_universes');
           set({ universes, isLoading: false });
         } catch (error) {
-          const errorMessage = error instanceof Error ? error.message : 'Failed to load universes';
+          const errorMessage = getErrorMessage(error, 'Failed to load universes');
           set({ error: errorMessage, isLoading: false });
           throw error;
         }
@@ -65,7 +68,7 @@ export const useUniverseStore = create<UniverseState>()(
           }));
           return universe;
         } catch (error) {
-          const errorMessage = error instanceof Error ? error.message : 'Failed to create universe';
+          const errorMessage = getErrorMessage(error, 'Failed to create universe');
           set({ error: errorMessage, isLoading: false });
           throw error;
         }
@@ -78,7 +81,7 @@ export const useUniverseStore = create<UniverseState>()(
           set({ isLoading: false });
           return universe;
         } catch (error) {
-          const errorMessage = error instanceof Error ? error.message : 'Failed to get universe';
+          const errorMessage = getErrorMessage(error, 'Failed to get universe');
           set({ error: errorMessage, isLoading: false });
           throw error;
         }
@@ -95,7 +98,7 @@ export const useUniverseStore = create<UniverseState>()(
           }));
           return universe;
         } catch (error) {
-          const errorMessage = error instanceof Error ? error.message : 'Failed to update universe';
+          const errorMessage = getErrorMessage(error, 'Failed to update universe');
           set({ error: errorMessage, isLoading: false });
           throw error;
         }
@@ -111,7 +114,40 @@ export const useUniverseStore = create<UniverseState>()(
             isLoading: false,
           }));
         } catch (error) {
-          const errorMessage = error instanceof Error ? error.message : 'Failed to delete universe';
+          const errorMessage = getErrorMessage(error, 'Failed to delete universe');
+          set({ error: errorMessage, isLoading: false });
+          throw error;
+        }
+      },
+
+      duplicateUniverse: async (id, history, name, status) => {
+        set({ isLoading: true, error: null });
+        try {
+          const universe = await invoke<Universe>('duplicate_universe', { id, name, status, history });
+          set((state) => ({
+            universes: [...state.universes, universe],
+            isLoading: false,
+          }));
+          return universe;
+        } catch (error) {
+          const errorMessage = getErrorMessage(error, 'Failed to duplicate universe');
+          set({ error: errorMessage, isLoading: false });
+          throw error;
+        }
+      },
+
+      createUniverseFromTemplate: async (templateId, name) => {
+        set({ isLoading: true, error: null });
+        try {
+          const universe = await invoke<Universe>('create_universe_from_template', { templateId, name });
+          set((state) => ({
+            universes: [...state.universes, universe],
+            currentUniverse: universe,
+            isLoading: false,
+          }));
+          return universe;
+        } catch (error) {
+          const errorMessage = getErrorMessage(error, 'Failed to create universe from template');
           set({ error: errorMessage, isLoading: false });
           throw error;
         }
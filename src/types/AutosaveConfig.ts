@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When pending edits get committed
+ *
+ * The default only commits when the editor closes, like the frontend's
+ * default settings, so nothing else is committed before the app sends the
+ * user's own.
+ */
+export type AutosaveConfig = { 
+/**
+ * Whether edits are committed automatically at all (false = only on flush)
+ */
+enabled: boolean, 
+/**
+ * Commit after this many milliseconds without edits (0 = only on flush)
+ */
+idleMs: number, 
+/**
+ * Commit once the word count moved this far from the last commit (0 = never)
+ */
+maxWords: number, };
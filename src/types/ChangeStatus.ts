@@ -3,4 +3,4 @@
 /**
  * Status of a file change
  */
-export type ChangeStatus = "Added" | "Modified" | "Deleted";
+export type ChangeStatus = "Added" | "Modified" | "Deleted" | "Renamed";
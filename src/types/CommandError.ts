@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+
+/**
+ * Error returned by Tauri commands
+ */
+export type CommandError = { 
+/**
+ * Stable code the frontend can branch on
+ */
+code: ErrorCode, 
+/**
+ * Human-readable description, suitable for display
+ */
+message: string, 
+/**
+ * Structured context, e.g. `path`, `branch`, `git_code`
+ */
+details: { [key in string]?: string }, };
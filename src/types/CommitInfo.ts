@@ -3,4 +3,8 @@
 /**
  * Information about a commit in the history
  */
-export type CommitInfo = { hash: string, message: string, author: string, timestamp: string, };
+export type CommitInfo = { hash: string, message: string, author: string, timestamp: string, 
+/**
+ * Names of the tags (milestones) pointing at this commit
+ */
+tags: Array<string>, };
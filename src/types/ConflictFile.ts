@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConflictHunk } from "./ConflictHunk";
+
+/**
+ * A conflicted file with its conflict hunks in document order
+ */
+export type ConflictFile = { path: string, hunks: Array<ConflictHunk>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A conflicting passage within a conflicted file
+ */
+export type ConflictHunk = { id: number, base: string, ours: string, theirs: string, };
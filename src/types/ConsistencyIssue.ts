@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConsistencyEntity } from "./ConsistencyEntity";
+import type { ConsistencyIssueKind } from "./ConsistencyIssueKind";
+
+/**
+ * A single inconsistency between the database and the filesystem
+ */
+export type ConsistencyIssue = { kind: ConsistencyIssueKind, entityType: ConsistencyEntity | null, entityId: string | null, 
+/**
+ * Repository path the issue is about, if any
+ */
+path: string | null, message: string, 
+/**
+ * What `apply_fix` would do, or None if the issue needs manual attention
+ */
+fix: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The kind of inconsistency found
+ */
+export type ConsistencyIssueKind = "OrphanedRepo" | "MissingRepo" | "CorruptedRepo" | "BranchMismatch" | "DanglingElementReference" | "DanglingStoryReference";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConsistencyIssue } from "./ConsistencyIssue";
+
+/**
+ * Result of a consistency check
+ */
+export type ConsistencyReport = { issues: Array<ConsistencyIssue>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TextStats } from "./TextStats";
+
+/**
+ * Content totals for a container and everything nested under it
+ */
+export type ContainerStats = { containerId: string, 
+/**
+ * Number of stories counted (variations are not included)
+ */
+storyCount: number, stats: TextStats, };
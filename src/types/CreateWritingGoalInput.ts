@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoalType } from "./GoalType";
+
+/**
+ * Input for creating a writing goal
+ */
+export type CreateWritingGoalInput = { goalType: GoalType, targetWords: number, universeId: string | null, containerId: string | null, storyId: string | null, deadline: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffLine } from "./DiffLine";
+
+/**
+ * A contiguous region of changes within a file
+ */
+export type DiffHunk = { 
+/**
+ * Hunk header, e.g. "@@ -1,3 +1,4 @@"
+ */
+header: string, old_start: number, old_lines: number, new_start: number, new_lines: number, lines: Array<DiffLine>, };
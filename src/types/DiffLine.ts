@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffLineKind } from "./DiffLineKind";
+import type { WordSegment } from "./WordSegment";
+
+/**
+ * A single line within a diff hunk
+ */
+export type DiffLine = { kind: DiffLineKind, 
+/**
+ * Line content without the trailing newline
+ */
+content: string, old_lineno: number | null, new_lineno: number | null, 
+/**
+ * Word-level breakdown of a changed line, paired against the line it replaced.
+ * Empty for context lines and for lines that were purely added or removed.
+ */
+segments: Array<WordSegment>, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stable error codes exposed to the frontend
+ *
+ * Codes are part of the IPC contract: add new ones freely, but never rename
+ * or repurpose existing ones.
+ */
+export type ErrorCode = "NotFound" | "AlreadyExists" | "InvalidInput" | "InvalidOperation" | "UncommittedChanges" | "Conflict" | "RepositoryCorrupted" | "ConstraintViolation" | "Database" | "Git" | "Io";
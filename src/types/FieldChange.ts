@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The value of one field before and after a change
+ *
+ * Values are the field's JSON form as the frontend sees it; `null` stands
+ * for a field that wasn't set.
+ */
+export type FieldChange = { before: unknown, after: unknown, };
@@ -1,7 +1,28 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ChangeStatus } from "./ChangeStatus";
+import type { DiffHunk } from "./DiffHunk";
 
 /**
  * Represents a file change in a diff
  */
-export type FileChange = { path: string, status: ChangeStatus, diff: string | null, };
+export type FileChange = { path: string, 
+/**
+ * Previous path of the file, set only for renamed files
+ */
+old_path: string | null, status: ChangeStatus, 
+/**
+ * Unified patch text for the file (None for binary files)
+ */
+diff: string | null, 
+/**
+ * Changed regions of the file with line- and word-level detail
+ */
+hunks: Array<DiffHunk>, 
+/**
+ * Number of words added to the file
+ */
+words_added: number, 
+/**
+ * Number of words removed from the file
+ */
+words_removed: number, };
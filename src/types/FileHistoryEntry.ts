@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitInfo } from "./CommitInfo";
+
+/**
+ * A commit that changed a file, with the file's path at that commit
+ */
+export type FileHistoryEntry = { path: string, commit: CommitInfo, };
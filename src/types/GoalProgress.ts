@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WritingGoal } from "./WritingGoal";
+
+/**
+ * How far along a goal is
+ */
+export type GoalProgress = { goal: WritingGoal, 
+/**
+ * Words added today (daily goals) or total words (deadline goals)
+ */
+currentWords: number, remainingWords: number, completed: boolean, 
+/**
+ * Average net words per day over the recent window
+ */
+averageDailyWords: number, 
+/**
+ * Words per day needed to meet the deadline (deadline goals only)
+ */
+wordsPerDayNeeded: number | null, 
+/**
+ * Day the target will be reached at the current pace, if it is being
+ * approached at all (deadline goals only)
+ */
+projectedCompletion: string | null, 
+/**
+ * Whether the projected completion is on or before the deadline
+ */
+onTrack: boolean, };
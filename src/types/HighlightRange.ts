@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A highlighted range within a search hit's text
+ *
+ * Offsets are in UTF-16 code units so they can be used directly with
+ * JavaScript string methods.
+ */
+export type HighlightRange = { start: number, end: number, };
@@ -0,0 +1,44 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitInfo } from "./CommitInfo";
+
+/**
+ * A passage found by searching a repository's history
+ */
+export type HistoryMatch = { 
+/**
+ * Newest commit in which the passage had this text
+ */
+commit: CommitInfo, 
+/**
+ * Branch (variation slug) the commit belongs to
+ */
+branch: string, 
+/**
+ * User-facing name of the branch; same as `branch` unless filled in
+ * from the variation mapping
+ */
+branch_display_name: string, 
+/**
+ * Path of the file at that commit
+ */
+file_path: string, 
+/**
+ * Position of the paragraph in the file at that commit (0-based)
+ */
+paragraph_index: number, 
+/**
+ * The whole paragraph containing the match
+ */
+passage: string, 
+/**
+ * Text around the match, with "…" where the paragraph was cut
+ */
+snippet: string, 
+/**
+ * Start of the match within `snippet`, in UTF-16 code units
+ */
+match_start: number, 
+/**
+ * End of the match within `snippet`, in UTF-16 code units
+ */
+match_end: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HunkResolution } from "./HunkResolution";
+
+/**
+ * The resolution chosen for a conflict hunk
+ */
+export type HunkChoice = { hunk_id: number, resolution: HunkResolution, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How to resolve a single conflict hunk
+ */
+export type HunkResolution = { "kind": "ours" } | { "kind": "theirs" } | { "kind": "ours_then_theirs" } | { "kind": "theirs_then_ours" } | { "kind": "custom", text: string, };
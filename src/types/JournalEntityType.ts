@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The kind of entity a journal entry is about
+ */
+export type JournalEntityType = "universe" | "container" | "element" | "story";
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeOrigin } from "./ChangeOrigin";
+import type { FieldChange } from "./FieldChange";
+import type { JournalAction } from "./JournalAction";
+import type { JournalEntityType } from "./JournalEntityType";
+
+/**
+ * One change to a universe, container, element or story's metadata
+ *
+ * Story content is versioned in git and isn't journaled.
+ */
+export type JournalEntry = { id: number, entityType: JournalEntityType, entityId: string, universeId: string, action: JournalAction, 
+/**
+ * Changed fields by their camelCase name
+ */
+changes: { [key in string]?: FieldChange }, origin: ChangeOrigin, createdAt: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConflictFile } from "./ConflictFile";
+import type { MergeOutcome } from "./MergeOutcome";
+
+/**
+ * Preview of a merge, computed without touching the repository
+ */
+export type MergePreview = { outcome: MergeOutcome, 
+/**
+ * Files that would conflict, with their conflict hunks
+ */
+conflicts: Array<ConflictFile>, 
+/**
+ * Files changed on the source branch since the branches diverged
+ */
+files_changed: number, words_added: number, words_removed: number, };
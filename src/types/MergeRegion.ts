@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A region of a three-way merge result
+ */
+export type MergeRegion = { "kind": "resolved", text: string, } | { "kind": "conflict", base: string, ours: string, theirs: string, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Information about a milestone (annotated Git tag with display name)
+ *
+ * Milestones mark points in a story's history such as "Draft 1 sent to editor"
+ * or "Beta-reader version".
+ */
+export type MilestoneInfo = { 
+/**
+ * Git tag name (slug), e.g., "draft-1-sent-to-editor"
+ */
+slug: string, 
+/**
+ * User-facing display name, e.g., "Draft 1 sent to editor"
+ */
+display_name: string, 
+/**
+ * Free-form note attached to the milestone
+ */
+note: string, 
+/**
+ * Hash of the commit the milestone points to
+ */
+commit_hash: string, 
+/**
+ * Unix timestamp of when the milestone was created
+ */
+timestamp: string, };
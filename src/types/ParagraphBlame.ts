@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitInfo } from "./CommitInfo";
+
+/**
+ * The commit that last changed a paragraph
+ */
+export type ParagraphBlame = { 
+/**
+ * Position of the paragraph in the file (0-based)
+ */
+index: number, 
+/**
+ * First line of the paragraph (1-based)
+ */
+start_line: number, 
+/**
+ * Last line of the paragraph (1-based, inclusive)
+ */
+end_line: number, text: string, commit: CommitInfo, };
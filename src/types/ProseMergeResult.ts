@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MergeRegion } from "./MergeRegion";
+
+/**
+ * Result of merging a prose file
+ */
+export type ProseMergeResult = { 
+/**
+ * True when every region was resolved automatically
+ */
+clean: boolean, 
+/**
+ * Ordered regions that together make up the merged file
+ */
+regions: Array<MergeRegion>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A universe, container or story recreated by the import
+ */
+export type RecoveredEntity = { id: string, title: string, universeId: string, 
+/**
+ * Repository the entity was read from, if it has one of its own
+ */
+gitRepoPath: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A repository the import couldn't (or wouldn't) restore
+ */
+export type RecoveryIssue = { 
+/**
+ * Path of the repository directory
+ */
+path: string, 
+/**
+ * Entity ID, when it could be determined
+ */
+id: string | null, reason: string, };
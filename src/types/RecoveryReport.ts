@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RecoveredEntity } from "./RecoveredEntity";
+import type { RecoveryIssue } from "./RecoveryIssue";
+
+/**
+ * Outcome of rebuilding the database from git repositories
+ */
+export type RecoveryReport = { universes: Array<RecoveredEntity>, containers: Array<RecoveredEntity>, stories: Array<RecoveredEntity>, 
+/**
+ * Repos whose entity already exists in the database, or whose metadata
+ * disagrees with their location
+ */
+conflicts: Array<RecoveryIssue>, 
+/**
+ * Directories that aren't valid repos or have no readable metadata.json
+ */
+orphanedRepos: Array<RecoveryIssue>, 
+/**
+ * Entities that were restored with missing information filled in
+ */
+warnings: Array<RecoveryIssue>, };
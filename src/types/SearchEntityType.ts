@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Kind of entity a search hit refers to
+ */
+export type SearchEntityType = "story" | "element" | "universe";
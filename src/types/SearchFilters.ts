@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchEntityType } from "./SearchEntityType";
+import type { StoryStatus } from "./StoryStatus";
+
+/**
+ * Optional filters for a search
+ */
+export type SearchFilters = { 
+/**
+ * Only return hits from this universe
+ */
+universeId: string | null, 
+/**
+ * Only return these kinds of entity (all kinds if empty or absent)
+ */
+entityTypes: Array<SearchEntityType> | null, 
+/**
+ * Only return stories, elements and universes carrying this tag
+ */
+tag: string | null, 
+/**
+ * Only return stories with this status
+ */
+status: StoryStatus | null, 
+/**
+ * Maximum number of hits (default 50)
+ */
+limit: number | null, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HighlightRange } from "./HighlightRange";
+import type { SearchEntityType } from "./SearchEntityType";
+
+/**
+ * A single ranked search result
+ */
+export type SearchHit = { entityType: SearchEntityType, entityId: string, universeId: string, title: string, 
+/**
+ * Matched terms within `title`
+ */
+titleHighlights: Array<HighlightRange>, 
+/**
+ * Excerpt around the best match, with "…" where text was cut
+ */
+snippet: string, 
+/**
+ * Matched terms within `snippet`
+ */
+snippetHighlights: Array<HighlightRange>, 
+/**
+ * Relevance score; higher is better
+ */
+score: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file as it was in a snapshot
+ */
+export type SnapshotFile = { path: string, content: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Counts for a piece of text
+ */
+export type TextStats = { wordCount: number, 
+/**
+ * Characters including spaces, excluding line breaks
+ */
+characterCount: number, characterCountNoSpaces: number, paragraphCount: number, sentenceCount: number, 
+/**
+ * Estimated at `WORDS_PER_MINUTE`, rounded up to whole seconds
+ */
+readingTimeSeconds: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TrashEntityType } from "./TrashEntityType";
+
+/**
+ * An entity that was deleted, together with everything trashed with it
+ */
+export type TrashItem = { entityType: TrashEntityType, id: string, title: string, universeId: string, 
+/**
+ * Container the entity was in, if any
+ */
+parentContainerId: string | null, deletedAt: string, 
+/**
+ * Containers and stories below this one that were trashed with it
+ */
+descendantCount: number, };
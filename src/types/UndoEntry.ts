@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A logged change, as shown to the frontend
+ */
+export type UndoEntry = { id: number, universeId: string, 
+/**
+ * Short description, e.g. "Delete Mira"
+ */
+label: string, createdAt: string, };
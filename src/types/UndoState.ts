@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UndoEntry } from "./UndoEntry";
+
+/**
+ * What undo and redo would do next in a universe
+ */
+export type UndoState = { undo: UndoEntry | null, redo: UndoEntry | null, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A run of text within a changed line
+ *
+ * On a removed line, `changed` segments are words that were deleted.
+ * On an added line, `changed` segments are words that were inserted.
+ */
+export type WordSegment = { text: string, changed: boolean, };
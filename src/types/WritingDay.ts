@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Words written on one day
+ *
+ * Recorded from content saves: each save compares the new content with the
+ * previous one word by word. Days are local calendar days (`YYYY-MM-DD`).
+ */
+export type WritingDay = { day: string, wordsAdded: number, wordsRemoved: number, 
+/**
+ * `words_added - words_removed`
+ */
+netWords: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoalType } from "./GoalType";
+
+/**
+ * A writing goal
+ *
+ * Daily goals apply to all writing, or to one universe when `universe_id` is
+ * set. Deadline goals apply to exactly one container or story.
+ */
+export type WritingGoal = { id: string, goalType: GoalType, targetWords: number, universeId: string | null, containerId: string | null, storyId: string | null, 
+/**
+ * Local date (`YYYY-MM-DD`) the target should be reached by
+ */
+deadline: string | null, createdAt: string, updatedAt: string, };
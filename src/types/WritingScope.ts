@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which writing a history or streak query covers
+ *
+ * The most specific id wins: a story, else a container (including everything
+ * nested under it), else a universe. With no ids set, all writing counts.
+ */
+export type WritingScope = { universeId: string | null, containerId: string | null, storyId: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Consecutive days of writing
+ *
+ * A day counts when at least one word was added, or at least the daily
+ * goal's target when the streak is measured against one. Today doesn't
+ * break the current streak until it's over.
+ */
+export type WritingStreak = { current: number, longest: number, lastWritingDay: string | null, };
@@ -26,6 +26,7 @@ export type { StoryType } from './StoryType';
 export type { VariationType } from './VariationType';
 export type { CreateStoryInput } from './CreateStoryInput';
 export type { UpdateStoryInput } from './UpdateStoryInput';
+export type { TextStats } from './TextStats';
 
 // Element types
 export type { ElementType } from './ElementType';
@@ -37,14 +38,83 @@ export type { UpdateElementInput } from './UpdateElementInput';
 export type { CreateContainerInput } from './CreateContainerInput';
 export type { UpdateContainerInput } from './UpdateContainerInput';
 export type { ContainerChildren } from './ContainerChildren';
+export type { ContainerStats } from './ContainerStats';
 
 // Git types
 export type { CommitInfo } from './CommitInfo';
+export type { FileHistoryEntry } from './FileHistoryEntry';
+export type { ParagraphBlame } from './ParagraphBlame';
+export type { HistoryMatch } from './HistoryMatch';
+export type { SnapshotFile } from './SnapshotFile';
 export type { DiffResult } from './DiffResult';
 export type { FileChange } from './FileChange';
 export type { ChangeStatus } from './ChangeStatus';
+export type { DiffHunk } from './DiffHunk';
+export type { DiffLine } from './DiffLine';
+export type { DiffLineKind } from './DiffLineKind';
+export type { WordSegment } from './WordSegment';
 export type { MergeResult } from './MergeResult';
+export type { MergeOutcome } from './MergeOutcome';
+export type { MergePreview } from './MergePreview';
+export type { MergeRegion } from './MergeRegion';
+export type { ProseMergeResult } from './ProseMergeResult';
+export type { ConflictFile } from './ConflictFile';
+export type { ConflictHunk } from './ConflictHunk';
+export type { HunkResolution } from './HunkResolution';
+export type { HunkChoice } from './HunkChoice';
 export type { VariationInfo } from './VariationInfo';
+export type { MilestoneInfo } from './MilestoneInfo';
+
+// Autosave types
+export type { AutosaveConfig } from './AutosaveConfig';
+
+// Search types
+export type { SearchHit } from './SearchHit';
+export type { SearchFilters } from './SearchFilters';
+export type { SearchEntityType } from './SearchEntityType';
+export type { HighlightRange } from './HighlightRange';
+
+// Writing session types
+export type { WritingDay } from './WritingDay';
+export type { WritingScope } from './WritingScope';
+export type { WritingStreak } from './WritingStreak';
+export type { WritingGoal } from './WritingGoal';
+export type { GoalType } from './GoalType';
+export type { CreateWritingGoalInput } from './CreateWritingGoalInput';
+export type { GoalProgress } from './GoalProgress';
+
+// Trash types
+export type { TrashItem } from './TrashItem';
+export type { TrashEntityType } from './TrashEntityType';
+
+// Change journal types
+export type { JournalEntry } from './JournalEntry';
+export type { JournalEntityType } from './JournalEntityType';
+export type { JournalAction } from './JournalAction';
+export type { FieldChange } from './FieldChange';
+export type { ChangeOrigin } from './ChangeOrigin';
+
+// Undo types
+export type { UndoEntry } from './UndoEntry';
+export type { UndoState } from './UndoState';
+
+// Duplicate types
+export type { HistoryMode } from './HistoryMode';
+
+// Recovery types
+export type { RecoveryReport } from './RecoveryReport';
+export type { RecoveredEntity } from './RecoveredEntity';
+export type { RecoveryIssue } from './RecoveryIssue';
+
+// Consistency check types
+export type { ConsistencyReport } from './ConsistencyReport';
+export type { ConsistencyIssue } from './ConsistencyIssue';
+export type { ConsistencyIssueKind } from './ConsistencyIssueKind';
+export type { ConsistencyEntity } from './ConsistencyEntity';
+
+// Command error types
+export type { CommandError } from './CommandError';
+export type { ErrorCode } from './ErrorCode';
 
 // Utility types for partial updates
 // These convert `field: T | null` types to `field?: T | null` for easier usage
@@ -0,0 +1,31 @@
+import { describe, it, expect } from 'vitest';
+import { getErrorMessage, hasErrorCode, isCommandError } from './commandError';
+import type { CommandError } from '../types';
+
+const notFound: CommandError = {
+  code: 'NotFound',
+  message: "Branch 'draft' not found",
+  details: { branch: 'draft' },
+};
+
+describe('commandError', () => {
+  it('should recognize command errors', () => {
+    expect(isCommandError(notFound)).toBe(true);
+    expect(isCommandError(new Error('boom'))).toBe(false);
+    expect(isCommandError('boom')).toBe(false);
+    expect(isCommandError(null)).toBe(false);
+  });
+
+  it('should match error codes', () => {
+    expect(hasErrorCode(notFound, 'NotFound')).toBe(true);
+    expect(hasErrorCode(notFound, 'Conflict')).toBe(false);
+    expect(hasErrorCode('NotFound', 'NotFound')).toBe(false);
+  });
+
+  it('should extract a displayable message', () => {
+    expect(getErrorMessage(notFound, 'fallback')).toBe("Branch 'draft' not found");
+    expect(getErrorMessage(new Error('boom'), 'fallback')).toBe('boom');
+    expect(getErrorMessage('plain', 'fallback')).toBe('plain');
+    expect(getErrorMessage(42, 'fallback')).toBe('fallback');
+  });
+});
@@ -0,0 +1,40 @@
+/**
+ * Command error helpers
+ *
+ * Tauri commands reject with a CommandError ({ code, message, details }).
+ * Use these helpers to branch on the error code instead of matching messages.
+ */
+
+import type { CommandError, ErrorCode } from '../types';
+
+/**
+ * Check whether a rejected invoke() value is a CommandError.
+ */
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as CommandError).code === 'string' &&
+    typeof (error as CommandError).message === 'string'
+  );
+}
+
+/**
+ * Check whether an error is a CommandError with the given code.
+ */
+export function hasErrorCode(error: unknown, code: ErrorCode): boolean {
+  return isCommandError(error) && error.code === code;
+}
+
+/**
+ * Get a displayable message from any rejected value.
+ */
+export function getErrorMessage(error: unknown, fallback: string): string {
+  if (isCommandError(error) || error instanceof Error) {
+    return error.message;
+  }
+  if (typeof error === 'string') {
+    return error;
+  }
+  return fallback;
+}
@@ -7,5 +7,4 @@
  * export { sanitizeInput } from './stringUtils';
  */
 
-// Utils will be added here as they are created
-export {};
+export { getErrorMessage, hasErrorCode, isCommandError } from './commandError';
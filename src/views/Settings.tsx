@@ -26,6 +26,14 @@ const DELAY_PRESETS = [
   { label: '5 minutes', value: 300000 },
 ];
 
+// Preset edit volume thresholds in changed words
+const MAX_WORDS_PRESETS = [
+  { label: '250 words', value: 250 },
+  { label: '500 words', value: 500 },
+  { label: '1,000 words', value: 1000 },
+  { label: 'Never', value: 0 },
+];
+
 export function Settings() {
   const goBack = useNavigationStore((state) => state.goBack);
   const canGoBack = useNavigationStore((state) => state.canGoBack);
@@ -36,9 +44,11 @@ export function Settings() {
     autoCommitEnabled,
     autoCommitMode,
     autoCommitDelay,
+    autoCommitMaxWords,
     setAutoCommitEnabled,
     setAutoCommitMode,
     setAutoCommitDelay,
+    setAutoCommitMaxWords,
     resetToDefaults,
   } = useSettingsStore();
 
@@ -75,6 +85,16 @@ export function Settings() {
     showSuccess(`Save frequency set to ${formatDelay(newDelay)}`);
   };
 
+  const handleMaxWordsChange = (event: React.ChangeEvent<HTMLSelectElement>) => {
+    const newMaxWords = parseInt(event.target.value, 10);
+    setAutoCommitMaxWords(newMaxWords);
+    showSuccess(
+      newMaxWords === 0
+        ? 'Early saves after large edits disabled'
+        : `Save early after ${newMaxWords} changed words`
+    );
+  };
+
   const handleResetToDefaults = () => {
     resetToDefaults();
     showSuccess('Settings reset to defaults');
@@ -201,6 +221,35 @@ export function Settings() {
               </div>
             </div>
           )}
+
+          {/* Edit volume threshold (only visible when mode is 'timed') */}
+          {autoCommitMode === 'timed' && (
+            <div className={`setting-item ${!autoCommitEnabled ? 'setting-item--disabled' : ''}`}>
+              <div className="setting-info">
+                <label htmlFor="auto-commit-max-words" className="setting-label">
+                  Save after large edits
+                </label>
+                <p className="setting-description">
+                  Save early once this many words have changed
+                </p>
+              </div>
+              <div className="setting-control">
+                <select
+                  id="auto-commit-max-words"
+                  className="input-filled delay-select"
+                  value={autoCommitMaxWords}
+                  onChange={handleMaxWordsChange}
+                  disabled={!autoCommitEnabled}
+                >
+                  {MAX_WORDS_PRESETS.map((preset) => (
+                    <option key={preset.value} value={preset.value}>
+                      {preset.label}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            </div>
+          )}
         </div>
 
         {/* Actions */}
@@ -5,7 +5,7 @@
  * Provides a distraction-free writing experience.
  */
 
-import { useEffect, useState, useMemo, useCallback, useRef } from 'react';
+import { useEffect, useState, useMemo, useCallback } from 'react';
 import { ArrowLeft, FloppyDisk, Check, WarningCircle, Clock, GitBranch, Gear } from '@phosphor-icons/react';
 import { invoke } from '@tauri-apps/api/core';
 import { useNavigationStore } from '@/stores/useNavigationStore';
@@ -13,7 +13,6 @@ import { useStoriesStore } from '@/stores/useStoriesStore';
 import { useToastStore } from '@/stores/useToastStore';
 import { RichTextEditor } from '@/components/editor/RichTextEditor';
 import { useAutoSave } from '@/hooks/useAutoSave';
-import { useAutoCommit } from '@/hooks/useAutoCommit';
 import { useSettingsStore } from '@/stores/useSettingsStore';
 import type { Story } from '@/types';
 import '@/design-system/tokens/colors/modern-indigo.css';
@@ -31,9 +30,6 @@ export function StoryEditor() {
   const updateStory = useStoriesStore((state) => state.updateStory);
   const getStory = useStoriesStore((state) => state.getStory);
   const showError = useToastStore((state) => state.error);
-  const autoCommitEnabled = useSettingsStore((state) => state.autoCommitEnabled);
-  const autoCommitMode = useSettingsStore((state) => state.autoCommitMode);
-  const autoCommitDelay = useSettingsStore((state) => state.autoCommitDelay);
 
   const [story, setStory] = useState<Story | null>(null);
   const [content, setContent] = useState<string>('');
@@ -41,9 +37,6 @@ export function StoryEditor() {
   const [isLoadingStory, setIsLoadingStory] = useState(true);
   const [isEditingTitle, setIsEditingTitle] = useState(false);
 
-  // Track initial content for commit-on-close comparison
-  const initialContentRef = useRef<string>('');
-
   // Extract story ID from route
   const storyId =
     currentRoute.screen === 'story-editor' ? currentRoute.storyId : null;
@@ -59,8 +52,6 @@ export function StoryEditor() {
         setStory(loadedStory);
         setContent(loadedStory.content || '');
         setTitle(loadedStory.title);
-        // Store initial content to track changes for commit-on-close
-        initialContentRef.current = loadedStory.content || '';
       } catch (error) {
         const message = error instanceof Error ? error.message : 'Failed to load story';
         showError(message);
@@ -75,7 +66,7 @@ export function StoryEditor() {
   // Memoized save callback to prevent unnecessary re-renders
   const handleSaveContent = useCallback(async (newContent: string) => {
     if (!storyId) return;
-    await updateStory(storyId, { content: newContent });
+    await updateStory(storyId, { content: newContent }, 'autosave');
   }, [storyId, updateStory]);
 
   // Auto-save content changes
@@ -86,51 +77,19 @@ export function StoryEditor() {
     enabled: !isLoadingStory && !!storyId,
   });
 
-  // Auto-commit content changes to Git (only when mode is 'timed')
-  useAutoCommit({
-    storyId: storyId || '',
-    gitRepoPath: story?.gitRepoPath || null,
-    filePath: 'content.md',
-    content,
-    delay: autoCommitDelay,
-    enabled: autoCommitEnabled && autoCommitMode === 'timed' && !isLoadingStory && !!storyId && !!story?.gitRepoPath,
-  });
-
-  // Commit on close: create a Git commit when leaving the editor
-  // Uses refs to access latest values in cleanup function
-  const contentRef = useRef(content);
-  const storyRef = useRef(story);
-  contentRef.current = content;
-  storyRef.current = story;
-
+  // Commit on close: flush pending autosave edits when leaving the editor,
+  // unless auto-commit is off and the user commits by hand
   useEffect(() => {
-    // Only setup commit-on-close when mode is 'on-close'
-    if (!autoCommitEnabled || autoCommitMode !== 'on-close') return;
+    if (!storyId) return;
 
     return () => {
-      // Cleanup function runs when component unmounts (user leaves editor)
-      const currentStory = storyRef.current;
-      const currentContent = contentRef.current;
-
-      // Only commit if there's a Git repo and content has changed
-      if (currentStory?.gitRepoPath && currentContent !== initialContentRef.current) {
-        const timestamp = new Date().toISOString();
-        const message = `Auto-save on close: ${timestamp}`;
-
-        // Fire and forget - we're unmounting so we can't await
-        invoke('git_commit_file', {
-          repoPath: currentStory.gitRepoPath,
-          filePath: 'content.md',
-          content: currentContent,
-          message,
-        }).then(() => {
-          console.log(`[CommitOnClose] Committed story ${currentStory.id}`);
-        }).catch((error) => {
-          console.error('[CommitOnClose] Failed to commit:', error);
-        });
-      }
+      if (!useSettingsStore.getState().autoCommitEnabled) return;
+      // Fire and forget - we're unmounting so we can't await
+      invoke('flush_autosave', { storyId }).catch((error) => {
+        console.error('[Autosave] Failed to commit on close:', error);
+      });
     };
-  }, [autoCommitEnabled, autoCommitMode]);
+  }, [storyId]);
 
   // Handle title changes
   const handleTitleBlur = async () => {
@@ -33,6 +33,11 @@ const mockStory: Story = {
   notes: '',
   outline: '',
   wordCount: 0,
+  characterCount: 0,
+  characterCountNoSpaces: 0,
+  paragraphCount: 0,
+  sentenceCount: 0,
+  readingTimeSeconds: 0,
   targetWordCount: null,
   order: 0,
   tags: [],
@@ -65,18 +70,21 @@ const mockCommits: CommitInfo[] = [
     author: 'Test Author',
     message: 'Updated chapter 3',
     timestamp: twoHoursAgo,
+    tags: [],
   },
   {
     hash: 'def456ghi789',
     author: 'Test Author',
     message: 'auto-save',
     timestamp: yesterdayTimestamp,
+    tags: [],
   },
   {
     hash: 'ghi789jkl012',
     author: 'Test Author',
     message: 'Added new character',
     timestamp: lastWeekTimestamp,
+    tags: [],
   },
 ];
 
@@ -310,7 +318,7 @@ describe('StoryHistory', () => {
       const user = userEvent.setup();
       mockTauriInvoke('ensure_story_git_repo', mockStory);
       mockTauriInvoke('git_get_history', mockCommits);
-      mockTauriInvoke('git_restore_commit', undefined);
+      mockTauriInvoke('git_restore_snapshot', 'restored123');
 
       renderWithProviders(<StoryHistory />);
 
@@ -184,7 +184,7 @@ export function StoryHistory() {
 
     setIsRestoring(true);
     try {
-      await invoke('git_restore_commit', {
+      await invoke('git_restore_snapshot', {
         repoPath: story.gitRepoPath,
         commitHash: confirmRestore.hash,
       });
@@ -34,6 +34,11 @@ const mockStory: Story = {
   notes: '',
   outline: '',
   wordCount: 0,
+  characterCount: 0,
+  characterCountNoSpaces: 0,
+  paragraphCount: 0,
+  sentenceCount: 0,
+  readingTimeSeconds: 0,
   targetWordCount: null,
   order: 0,
   tags: [],